@@ -0,0 +1,46 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{
+    io::{self, Read as _, Write as _},
+    path::PathBuf,
+};
+
+use clap::Parser;
+
+use link_canonical::{json::Value, Canonical as _};
+
+/// Read JSON and print its canonical form
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// the file to read the JSON from, defaults to stdin
+    pub file: Option<PathBuf>,
+    /// print an indented rendering instead of the canonical form. The output
+    /// is NOT canonical and must not be signed or hashed
+    #[clap(long)]
+    pub pretty: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    let Args { file, pretty } = Args::parse();
+    let input = match file {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)?;
+            buf
+        },
+    };
+    let value = input.parse::<Value>()?;
+
+    let mut stdout = io::stdout();
+    if pretty {
+        writeln!(stdout, "{}", value.to_pretty(2))?;
+    } else {
+        stdout.write_all(&value.canonical_form()?)?;
+    }
+
+    Ok(())
+}
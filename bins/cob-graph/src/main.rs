@@ -0,0 +1,112 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{
+    fmt,
+    io::{self, Write as _},
+    str::FromStr,
+};
+
+use anyhow::anyhow;
+use clap::Parser;
+
+use librad::{
+    collaborative_objects::{ChangeGraphs, ObjectId, TypeName},
+    git::Urn,
+    profile::{LnkHome, Profile},
+};
+use lnk_clib::storage;
+use lnk_exe::cli::args::Global;
+
+/// Print the change graph of a collaborative object
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(flatten)]
+    pub global: Global,
+    /// the identity the object lives under
+    pub urn: Urn,
+    /// the type of the object
+    pub typename: TypeName,
+    /// the id of the object
+    pub object_id: ObjectId,
+    /// the output format, either `dot` for Graphviz or `json`
+    #[clap(long, default_value_t)]
+    pub format: Format,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Dot,
+    Json,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self::Dot
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = match self {
+            Self::Dot => "dot",
+            Self::Json => "json",
+        };
+        write!(f, "{}", format)
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "dot" => Ok(Self::Dot),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unsupported format `{}`", input)),
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let Args {
+        global,
+        urn,
+        typename,
+        object_id,
+        format,
+    } = Args::parse();
+    let home = LnkHome::default();
+    let profile = Profile::from_home(&home, global.lnk_profile)?;
+    let storage = storage::read_only(&profile)?;
+    let cobs = ChangeGraphs::new(&storage);
+
+    let not_found = || {
+        anyhow!(
+            "object `{}` of type `{}` not found under `{}`",
+            object_id,
+            typename,
+            urn
+        )
+    };
+    let mut stdout = io::stdout();
+    match format {
+        Format::Dot => {
+            let info = cobs
+                .changegraph_info_for_object(&urn, &typename, &object_id)?
+                .ok_or_else(not_found)?;
+            writeln!(stdout, "{}", info.dotviz)?;
+        },
+        Format::Json => {
+            let graph = cobs
+                .changegraph_json_for_object(&urn, &typename, &object_id)?
+                .ok_or_else(not_found)?;
+            serde_json::to_writer_pretty(&mut stdout, &graph)?;
+            writeln!(stdout)?;
+        },
+    }
+
+    Ok(())
+}
@@ -9,6 +9,7 @@ use clap::Parser;
 
 use librad::{
     crypto::BoxedSigner,
+    net::peer::client::config::AddrFamily,
     profile::{LnkHome, Profile},
 };
 
@@ -30,13 +31,27 @@ pub struct Args {
     /// indefinitely.
     pub linger_timeout: Option<LingerTimeout>,
     #[clap(long)]
-    /// The linkd RPC socket address to use for any RPC calls.
-    pub linkd_rpc_socket: Option<PathBuf>,
+    /// The socket address to serve HTTP health checks on. `/healthz` responds
+    /// once the server is listening, `/readyz` once it is ready to serve git
+    /// requests.
+    pub health_addr: Option<SocketAddr>,
+    #[clap(long)]
+    /// Address of the graphite collector to send stats to. If it is not set,
+    /// no stats are sent.
+    pub graphite_addr: Option<SocketAddr>,
+    #[clap(long)]
+    /// The linkd RPC socket address to use for any RPC calls. May be given
+    /// more than once, in which case announcements are sent to each socket.
+    pub linkd_rpc_socket: Vec<PathBuf>,
     #[clap(long)]
     /// Announce any changes when the gitd server is processing a
     /// `receive-pack`.
     pub announce_on_push: bool,
     #[clap(long)]
+    /// Abort startup if `--announce-on-push` is set and the linkd RPC socket
+    /// cannot be connected to. By default, only a warning is logged.
+    pub require_linkd: bool,
+    #[clap(long)]
     /// Push any changes to configured seeds when the gitd server is processing
     /// a `receive-pack`.
     pub push_seeds: bool,
@@ -44,6 +59,37 @@ pub struct Args {
     /// Fetch any changes from configured seeds when the gitd server is
     /// processing a `upload-pack`.
     pub fetch_seeds: bool,
+    #[clap(long)]
+    /// Update the collaborative object references of the pushed URN when the
+    /// gitd server is processing a `receive-pack`.
+    pub update_cobs: bool,
+    #[clap(long, default_value_t)]
+    /// Which address families to use when connecting to configured seeds, one
+    /// of `any`, `prefer-v4`, `prefer-v6`, `v4` or `v6`. The `prefer-*`
+    /// options fall back to the other family if no connection could be made.
+    pub addr_family: AddrFamily,
+    #[clap(long)]
+    /// The time (in seconds) a git subprocess may run for before it is
+    /// killed. If it is not set, git subprocesses may run indefinitely.
+    pub git_timeout: Option<u64>,
+    #[clap(long)]
+    /// The number of bytes a git subprocess may write to the client on each of
+    /// stdout and stderr before it is killed. If it is not set, the output is
+    /// unbounded.
+    pub git_max_output: Option<usize>,
+    #[clap(long)]
+    /// The time (in seconds) to wait for storage to become available before
+    /// rejecting a request as busy. If it is not set, requests wait
+    /// indefinitely.
+    pub pool_timeout: Option<u64>,
+    #[clap(long, conflicts_with = "deny_urns")]
+    /// Only accept pushes to the URNs listed in this file, one per line. The
+    /// file is read again on SIGHUP.
+    pub allow_urns: Option<PathBuf>,
+    #[clap(long)]
+    /// Reject pushes to the URNs listed in this file, one per line. The file
+    /// is read again on SIGHUP.
+    pub deny_urns: Option<PathBuf>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -73,23 +119,45 @@ impl Args {
             })
             .await?;
         let announce = match (self.announce_on_push, self.linkd_rpc_socket) {
-            (true, Some(path)) => Ok(Some(hooks::Announce {
-                rpc_socket_path: path,
+            (true, paths) if !paths.is_empty() => Ok(Some(hooks::Announce {
+                rpc_socket_paths: paths,
+                on_unreachable: if self.require_linkd {
+                    hooks::OnUnreachable::Fatal
+                } else {
+                    hooks::OnUnreachable::Warn
+                },
             })),
             (false, _) => Ok(None),
-            (true, None) => Err(Error::AnnounceWithoutRpc),
+            (true, _) => Err(Error::AnnounceWithoutRpc),
         }?;
         let network = config::Network {
             announce,
             request_pull: self.push_seeds,
             replicate: self.fetch_seeds,
+            update_cobs: self.update_cobs,
+            addr_family: self.addr_family,
+        };
+        let urn_policy = match (self.allow_urns, self.deny_urns) {
+            (Some(path), _) => config::UrnPolicy::Allow(path),
+            (None, Some(path)) => config::UrnPolicy::Deny(path),
+            (None, None) => config::UrnPolicy::AllowAll,
         };
         Ok(Config {
             paths: profile.paths().clone(),
             signer,
             addr: self.addr,
             linger_timeout: self.linger_timeout.map(|l| l.into()),
+            health_addr: self.health_addr,
+            graphite_addr: self.graphite_addr,
             network,
+            urn_policy,
+            git_limits: config::GitLimits {
+                timeout: self.git_timeout.map(Duration::from_secs),
+                max_output: self.git_max_output,
+                pool_timeout: self.pool_timeout.map(Duration::from_secs),
+            },
+            authorizer: None,
+            host_key_dir: None,
         })
     }
 }
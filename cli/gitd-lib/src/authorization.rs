@@ -0,0 +1,160 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+//! Decide which peers may fetch from, and push to, which URNs.
+
+use std::{collections::BTreeSet, fmt, iter, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use librad::{
+    git::{
+        identities::{self, SomeIdentity},
+        storage,
+        tracking,
+        Urn,
+    },
+    PeerId,
+};
+use link_async::Spawner;
+use thiserror::Error;
+
+use crate::pool;
+
+/// The kind of access a git service requires.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Access {
+    /// `git upload-pack`, i.e. a fetch or clone.
+    Read,
+    /// `git receive-pack`, i.e. a push.
+    Write,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read => f.write_str("read"),
+            Self::Write => f.write_str("write"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Decision {
+    Allow,
+    Deny { reason: String },
+}
+
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Decide whether an authenticated peer may access a URN.
+///
+/// This is consulted for every `exec` request, after the SSH connection has
+/// been authenticated and before the git subprocess is started. It is called
+/// on the async runtime, so implementations must not block on storage access.
+#[async_trait]
+pub trait Authorizer: Send + Sync {
+    async fn authorize(
+        &self,
+        peer: &PeerId,
+        access: Access,
+        urn: &Urn,
+    ) -> Result<Decision, BoxedError>;
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Pool(#[from] pool::Error),
+    #[error(transparent)]
+    IsTracked(#[from] tracking::error::IsTracked),
+    #[error(transparent)]
+    Identities(#[from] identities::Error),
+}
+
+/// The default [`Authorizer`].
+///
+/// Anyone may read. Writes are allowed for the local peer, for peers which are
+/// tracked for the URN, and for delegates of the URN's identity.
+///
+/// Storage is taken from the pool waiting at most `pool_timeout`, see
+/// [`pool::get`].
+#[derive(Clone)]
+pub struct TrackedWriters {
+    local: PeerId,
+    spawner: Arc<Spawner>,
+    pool: Arc<storage::Pool<storage::Storage>>,
+    pool_timeout: Option<Duration>,
+}
+
+impl TrackedWriters {
+    pub fn new(
+        local: PeerId,
+        spawner: Arc<Spawner>,
+        pool: Arc<storage::Pool<storage::Storage>>,
+        pool_timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            local,
+            spawner,
+            pool,
+            pool_timeout,
+        }
+    }
+
+    async fn may_write(&self, peer: &PeerId, urn: &Urn) -> Result<bool, Error> {
+        if *peer == self.local {
+            return Ok(true);
+        }
+
+        let storage = pool::get(&self.pool, self.pool_timeout).await?;
+        let peer = *peer;
+        let urn = urn.clone();
+        self.spawner
+            .blocking::<_, Result<_, Error>>(move || {
+                let storage: &storage::Storage = storage.as_ref();
+                if tracking::is_tracked(storage, &urn, Some(peer))? {
+                    return Ok(true);
+                }
+
+                let key = peer.as_public_key();
+                Ok(match identities::any::get(storage, &urn)? {
+                    Some(SomeIdentity::Project(project)) => project
+                        .delegations()
+                        .eligible(iter::once(key).collect::<BTreeSet<_>>())
+                        .map(|eligible| !eligible.is_empty())
+                        .unwrap_or(false),
+                    Some(SomeIdentity::Person(person)) => person.delegations().contains(key),
+                    _ => false,
+                })
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Authorizer for TrackedWriters {
+    async fn authorize(
+        &self,
+        peer: &PeerId,
+        access: Access,
+        urn: &Urn,
+    ) -> Result<Decision, BoxedError> {
+        match access {
+            Access::Read => Ok(Decision::Allow),
+            Access::Write => {
+                if self.may_write(peer, urn).await? {
+                    Ok(Decision::Allow)
+                } else {
+                    Ok(Decision::Deny {
+                        reason: format!(
+                            "{} is neither tracked for, nor a delegate of, {}",
+                            peer, urn
+                        ),
+                    })
+                }
+            },
+        }
+    }
+}
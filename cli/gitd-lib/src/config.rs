@@ -3,16 +3,37 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{net::SocketAddr, time::Duration};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
-pub use crate::hooks;
+use librad::net::peer::client::config::AddrFamily;
+
+pub use crate::{
+    authorization::Authorizer,
+    git_subprocess::Limits as GitLimits,
+    hooks,
+    urn_policy::UrnPolicy,
+};
 
 pub struct Config<S> {
     pub paths: librad::paths::Paths,
     pub signer: S,
     pub addr: Option<SocketAddr>,
     pub linger_timeout: Option<Duration>,
+    /// The address to serve health and readiness probes on, if any.
+    pub health_addr: Option<SocketAddr>,
+    /// The address of the graphite collector to send stats to, if any.
+    pub graphite_addr: Option<SocketAddr>,
     pub network: Network,
+    /// Which URNs may be pushed to.
+    pub urn_policy: UrnPolicy,
+    /// Bounds on each git subprocess.
+    pub git_limits: GitLimits,
+    /// Which peers may access which URNs. If not set,
+    /// [`crate::authorization::TrackedWriters`] is used.
+    pub authorizer: Option<Arc<dyn Authorizer>>,
+    /// The directory to store the SSH host key in. If not set, the key is
+    /// stored in the `linkd-git` XDG state directory.
+    pub host_key_dir: Option<PathBuf>,
 }
 
 pub struct Network {
@@ -23,6 +44,11 @@ pub struct Network {
     pub request_pull: bool,
     /// Replicate to the configured seeds on a `git upload-pack`.
     pub replicate: bool,
+    /// Update the collaborative object references of the pushed URN on a
+    /// `git receive-pack`.
+    pub update_cobs: bool,
+    /// Which address families to use when connecting to the configured seeds.
+    pub addr_family: AddrFamily,
 }
 
 impl From<&Network> for hooks::PostReceive {
@@ -30,6 +56,7 @@ impl From<&Network> for hooks::PostReceive {
         Self {
             announce: net.announce.clone(),
             request_pull: net.request_pull,
+            update_cobs: net.update_cobs,
         }
     }
 }
@@ -8,6 +8,7 @@ use std::{
     os::unix::process::ExitStatusExt,
     process::{ExitStatus, Stdio},
     sync::Arc,
+    time::Duration,
 };
 
 use futures::{
@@ -25,18 +26,35 @@ use link_async::Spawner;
 
 use crate::{
     hooks::{self, Hooks},
-    processes::ProcessReply,
+    pool,
     ssh_service,
 };
 
+pub use crate::processes::ProcessReply;
+
 pub mod command;
 
-pub(crate) enum Message {
+pub enum Message {
     Signal(nix::sys::signal::Signal),
     Data(Vec<u8>),
     Eof,
 }
 
+/// Bounds on a single git subprocess.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Limits {
+    /// How long the subprocess may run before it is killed. If `None`, it may
+    /// run indefinitely.
+    pub timeout: Option<Duration>,
+    /// How many bytes of output the subprocess may send to the client on each
+    /// of standard output and standard error before it is killed. If `None`,
+    /// the output is unbounded.
+    pub max_output: Option<usize>,
+    /// How long to wait for storage from the pool before rejecting the request
+    /// as busy. If `None`, wait indefinitely.
+    pub pool_timeout: Option<Duration>,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error<ReplyError> {
     #[error("unexpected error when running git subprocess: {0}")]
@@ -45,6 +63,7 @@ pub enum Error<ReplyError> {
     Reply(ReplyError),
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(level = "trace", skip(spawner, pool, incoming, out, hooks))]
 pub(crate) async fn run_git_subprocess<Replier, S>(
     spawner: Arc<Spawner>,
@@ -53,12 +72,14 @@ pub(crate) async fn run_git_subprocess<Replier, S>(
     mut out: Replier,
     service: ssh_service::SshService,
     hooks: Hooks<S>,
+    limits: Limits,
 ) -> Result<(), Error<Replier::Error>>
 where
     Replier: ProcessReply + Clone,
     S: librad::Signer + Clone,
 {
-    let result = run_git_subprocess_inner(spawner, pool, incoming, &mut out, service, hooks).await;
+    let result =
+        run_git_subprocess_inner(spawner, pool, incoming, &mut out, service, hooks, limits).await;
     match out.close().await {
         Ok(()) => {},
         Err(e) => {
@@ -68,6 +89,7 @@ where
     result
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(level = "trace", skip(spawner, pool, incoming, out, hooks))]
 async fn run_git_subprocess_inner<Replier, S>(
     spawner: Arc<Spawner>,
@@ -76,6 +98,7 @@ async fn run_git_subprocess_inner<Replier, S>(
     out: &mut Replier,
     service: ssh_service::SshService,
     hooks: Hooks<S>,
+    limits: Limits,
 ) -> Result<(), Error<Replier::Error>>
 where
     Replier: ProcessReply + Clone,
@@ -98,14 +121,32 @@ where
         }
     }
 
-    let mut git = {
-        let storage = pool.get().await.map_err(|e| {
-            tracing::error!(err=?e, "error opening storage pool");
-            Error::Unexpected(Box::new(e))
-        })?;
+    let (mut git, pre_receive) = {
+        let storage = match pool::get(&pool, limits.pool_timeout).await {
+            Ok(storage) => storage,
+            Err(e @ pool::Error::Busy { .. }) => {
+                return report_limited(out, e.to_string()).await.map(|_| ());
+            },
+            Err(e) => {
+                tracing::error!(err=?e, "error opening storage pool");
+                return Err(Error::Unexpected(Box::new(e)));
+            },
+        };
         let service = service.clone();
+        let update_cobs = hooks.updates_cobs();
         spawner
-            .blocking::<_, Result<_, _>>(move || command::create_command(&storage, service))
+            .blocking::<_, Result<_, _>>(move || {
+                let pre_receive = if service.service == GitService::ReceivePack.into() {
+                    hooks::PreReceive::load(&storage, service.path.as_ref(), update_cobs)
+                        .unwrap_or_else(|e| {
+                            tracing::warn!(err=?e, "error loading pre-receive state");
+                            hooks::PreReceive::default()
+                        })
+                } else {
+                    hooks::PreReceive::default()
+                };
+                command::create_command(&storage, service).map(|git| (git, pre_receive))
+            })
             .await
             .map_err(|e| {
                 tracing::error!(err=?e, "error creating git subcommand");
@@ -134,14 +175,152 @@ where
         },
     };
 
+    let exit_status = match communicate(&mut child, &mut incoming, out, &limits).await? {
+        Some(status) => status,
+        None => return Ok(()),
+    };
+
+    if !exit_status.success() {
+        tracing::error!(
+            exit_status=?exit_status.code(),
+            "non-successful exit status received whilst executing git subprocess"
+        );
+        out.exit_status(exit_status).await.map_err(Error::Reply)?;
+        return Ok(());
+    }
+
+    // Run hooks
+    if service.service == GitService::ReceivePack.into() {
+        if let Err(e) = hooks
+            .post_receive(&mut progress_reporter, service.path.into(), pre_receive)
+            .await
+        {
+            match e {
+                hooks::error::PostReceive::Progress(_)
+                | hooks::error::PostReceive::Announce(hooks::error::Announce::Progress(_))
+                | hooks::error::PostReceive::Update(hooks::error::UpdateSignedRefs::Progress(_))
+                | hooks::error::PostReceive::RequestPull(hooks::error::RequestPull::Progress(_))
+                | hooks::error::PostReceive::UpdateCobs(hooks::error::UpdateCobs::Progress(_)) => {
+                    tracing::error!("client went away whilst executing post receive hook");
+                },
+                other => {
+                    tracing::error!(err=?other, "error executing post receive hook");
+                    out.stderr_data(
+                        format!("error executing post receive hook: {}\n", other).into_bytes(),
+                    )
+                    .await
+                    .map_err(Error::Reply)?;
+                },
+            }
+        }
+    };
+
+    out.exit_status(ExitStatus::from_raw(0))
+        .await
+        .map_err(Error::Reply)?;
+
+    Ok(())
+}
+
+/// The raw wait status reported to the client when a request is rejected or
+/// its subprocess killed because of its [`Limits`], i.e. an exit code of 1.
+const LIMITED_STATUS: i32 = 1 << 8;
+
+/// The number of output bytes a subprocess may still send to the client on a
+/// single channel.
+struct OutputBudget {
+    remaining: Option<usize>,
+}
+
+impl OutputBudget {
+    fn new(limits: &Limits) -> Self {
+        Self {
+            remaining: limits.max_output,
+        }
+    }
+
+    /// Spend the result of a read from the subprocess against the budget.
+    /// Returns the number of bytes which may be forwarded and whether the
+    /// budget has been exceeded, in which case the remainder must be dropped.
+    fn spend(
+        &mut self,
+        bytes_read: Result<usize, std::io::Error>,
+    ) -> (Result<usize, std::io::Error>, bool) {
+        match (bytes_read, self.remaining.as_mut()) {
+            (Ok(n), Some(remaining)) if n > *remaining => {
+                let allowed = *remaining;
+                *remaining = 0;
+                (Ok(allowed), true)
+            },
+            (Ok(n), Some(remaining)) => {
+                *remaining -= n;
+                (Ok(n), false)
+            },
+            (bytes_read, _) => (bytes_read, false),
+        }
+    }
+}
+
+/// Tell the client that the request was stopped for `reason`.
+async fn report_limited<Replier>(
+    out: &mut Replier,
+    reason: String,
+) -> Result<Option<ExitStatus>, Error<Replier::Error>>
+where
+    Replier: ProcessReply,
+{
+    out.stderr_data(format!("{}\n", reason).into_bytes())
+        .await
+        .map_err(Error::Reply)?;
+    out.exit_status(ExitStatus::from_raw(LIMITED_STATUS))
+        .await
+        .map_err(Error::Reply)?;
+    Ok(None)
+}
+
+fn truncation_notice(limits: &Limits) -> String {
+    format!(
+        "git subprocess output exceeded {} bytes, truncated",
+        limits.max_output.unwrap_or_default()
+    )
+}
+
+/// Forward `incoming` messages to the standard input of `child`, and its
+/// standard output and error to `out`, until `child` exits.
+///
+/// Returns the exit status of `child`, or `None` if it was killed, either
+/// because `out` went away or because it exceeded `limits`. In the latter
+/// case the client has already been told why; output beyond
+/// [`Limits::max_output`] on either channel is dropped.
+pub async fn communicate<Replier>(
+    child: &mut Child,
+    incoming: &mut tokio::sync::mpsc::Receiver<Message>,
+    out: &mut Replier,
+    limits: &Limits,
+) -> Result<Option<ExitStatus>, Error<Replier::Error>>
+where
+    Replier: ProcessReply,
+{
     let mut child_stdin = Some(child.stdin.take().unwrap());
     let mut child_stdout = child.stdout.take().unwrap();
     let mut child_stderr = child.stderr.take().unwrap();
 
     let mut stdout_buffer = [0; 1000];
     let mut stderr_buffer = [0; 1000];
+    let mut stdout_budget = OutputBudget::new(limits);
+    let mut stderr_budget = OutputBudget::new(limits);
+    let deadline = match limits.timeout {
+        Some(timeout) => link_async::sleep(timeout).boxed().fuse(),
+        None => Fuse::terminated(),
+    };
+    futures::pin_mut!(deadline);
     let exit_status = loop {
         futures::select! {
+            () = deadline => {
+                tracing::warn!(timeout=?limits.timeout, "git subprocess timed out, killing it");
+                kill_child(child).await?;
+                return report_limited(out, "git subprocess timed out".to_string()).await;
+            },
             input = incoming.recv().fuse() => {
                 match input {
                     Some(Message::Data(bytes)) => {
@@ -183,15 +362,27 @@ where
                 }
             },
             bytes_read = child_stdout.read(&mut stdout_buffer).fuse() => {
+                let (bytes_read, exhausted) = stdout_budget.spend(bytes_read);
                 if !forward_input(bytes_read, &stdout_buffer, |d| out.stdout_data(d.to_vec())).await {
-                    kill_child(&mut child).await?;
-                    return Ok(());
+                    kill_child(child).await?;
+                    return Ok(None);
+                }
+                if exhausted {
+                    tracing::warn!(max_output=?limits.max_output, "git subprocess exceeded its output limit, killing it");
+                    kill_child(child).await?;
+                    return report_limited(out, truncation_notice(limits)).await;
                 }
             },
             err_bytes_read = child_stderr.read(&mut stderr_buffer).fuse() => {
+                let (err_bytes_read, exhausted) = stderr_budget.spend(err_bytes_read);
                 if !forward_input(err_bytes_read, &stderr_buffer, |d| out.stderr_data(d.to_vec())).await {
-                    kill_child(&mut child).await?;
-                    return Ok(());
+                    kill_child(child).await?;
+                    return Ok(None);
+                }
+                if exhausted {
+                    tracing::warn!(max_output=?limits.max_output, "git subprocess exceeded its output limit, killing it");
+                    kill_child(child).await?;
+                    return report_limited(out, truncation_notice(limits)).await;
                 }
             },
             status = child.wait().fuse() => {
@@ -230,64 +421,35 @@ where
         }
         futures::pin_mut!(stdout_bytes);
         futures::pin_mut!(stderr_bytes);
-        futures::select! {
+        let exhausted = futures::select! {
             bytes_read = stdout_bytes => {
                 if let Ok(0) = bytes_read {
                     child_stdout = None;
+                    false
                 } else {
+                    let (bytes_read, exhausted) = stdout_budget.spend(bytes_read);
                     forward_input(bytes_read, &stdout_buffer, |d| out.stdout_data(d.to_vec())).await;
+                    exhausted
                 }
             },
             err_bytes_read = stderr_bytes => {
                 if let Ok(0) = err_bytes_read {
                     child_stderr = None;
+                    false
                 } else {
+                    let (err_bytes_read, exhausted) = stderr_budget.spend(err_bytes_read);
                     forward_input(err_bytes_read, &stderr_buffer, |d| out.stderr_data(d.to_vec())).await;
+                    exhausted
                 }
             },
+        };
+        if exhausted {
+            tracing::warn!(max_output=?limits.max_output, "git subprocess exceeded its output limit");
+            return report_limited(out, truncation_notice(limits)).await;
         }
     }
 
-    if !exit_status.success() {
-        tracing::error!(
-            exit_status=?exit_status.code(),
-            "non-successful exit status received whilst executing git subprocess"
-        );
-        out.exit_status(exit_status).await.map_err(Error::Reply)?;
-        return Ok(());
-    }
-
-    // Run hooks
-    if service.service == GitService::ReceivePack.into() {
-        if let Err(e) = hooks
-            .post_receive(&mut progress_reporter, service.path.into())
-            .await
-        {
-            match e {
-                hooks::error::PostReceive::Progress(_)
-                | hooks::error::PostReceive::Announce(hooks::error::Announce::Progress(_))
-                | hooks::error::PostReceive::Update(hooks::error::UpdateSignedRefs::Progress(_))
-                | hooks::error::PostReceive::RequestPull(hooks::error::RequestPull::Progress(_)) =>
-                {
-                    tracing::error!("client went away whilst executing post receive hook");
-                },
-                other => {
-                    tracing::error!(err=?other, "error executing post receive hook");
-                    out.stderr_data(
-                        format!("error executing post receive hook: {}\n", other).into_bytes(),
-                    )
-                    .await
-                    .map_err(Error::Reply)?;
-                },
-            }
-        }
-    };
-
-    out.exit_status(ExitStatus::from_raw(0))
-        .await
-        .map_err(Error::Reply)?;
-
-    Ok(())
+    Ok(Some(exit_status))
 }
 
 struct Reporter<R> {
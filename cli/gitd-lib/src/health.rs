@@ -0,0 +1,118 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+//! A minimal HTTP server for liveness and readiness probes.
+//!
+//! * `GET /healthz` responds with `200 OK` as long as the server is listening.
+//! * `GET /readyz` responds with `200 OK` once every [`Component`] is running,
+//!   and `503 Service Unavailable` otherwise.
+//!
+//! Any other path responds with `404 Not Found`.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use link_async::Spawner;
+use tokio::{
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+    net::{TcpListener, TcpStream},
+};
+
+/// The parts of gitd which must be running for it to be ready.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Component {
+    /// The storage pool can hand out storage.
+    Storage,
+    /// The loop running git subprocesses.
+    Processes,
+    /// The SSH server accepting connections.
+    Ssh,
+}
+
+/// The readiness of each [`Component`], shared between the tasks of gitd and
+/// the health server.
+#[derive(Clone, Debug, Default)]
+pub struct Readiness(Arc<State>);
+
+#[derive(Debug, Default)]
+struct State {
+    storage: AtomicBool,
+    processes: AtomicBool,
+    ssh: AtomicBool,
+}
+
+impl Readiness {
+    pub fn set(&self, component: Component, ready: bool) {
+        let flag = match component {
+            Component::Storage => &self.0.storage,
+            Component::Processes => &self.0.processes,
+            Component::Ssh => &self.0.ssh,
+        };
+        flag.store(ready, Ordering::SeqCst)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.0.storage.load(Ordering::SeqCst)
+            && self.0.processes.load(Ordering::SeqCst)
+            && self.0.ssh.load(Ordering::SeqCst)
+    }
+}
+
+/// The largest request head we read before giving up on a connection.
+const MAX_REQUEST_HEAD: usize = 4096;
+
+/// Serve probes on `listener` until accepting a connection fails.
+pub async fn serve(
+    spawner: Arc<Spawner>,
+    listener: TcpListener,
+    readiness: Readiness,
+) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let readiness = readiness.clone();
+        spawner
+            .spawn(async move {
+                if let Err(e) = respond(stream, &readiness).await {
+                    tracing::debug!(err=%e, "failed to respond to health probe");
+                }
+            })
+            .detach();
+    }
+}
+
+async fn respond(mut stream: TcpStream, readiness: &Readiness) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0; 512];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let request_line = head
+        .split(|b| *b == b'\n')
+        .next()
+        .map(String::from_utf8_lossy)
+        .unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let status = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/healthz")) => "200 OK",
+        (Some("GET"), Some("/readyz")) if readiness.is_ready() => "200 OK",
+        (Some("GET"), Some("/readyz")) => "503 Service Unavailable",
+        _ => "404 Not Found",
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}\n",
+        status,
+        status.len() + 1,
+        status
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -3,16 +3,25 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{fmt, path::PathBuf, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use futures::StreamExt as _;
 use lnk_clib::seed::Seeds;
 use tracing::instrument;
 
 use librad::{
+    collaborative_objects::{CollaborativeObjects, ObjectId, TypeName},
     git::{
         refs::{self, Refs},
-        storage,
+        storage::{self, ReadOnlyStorage as _},
+        types::{Namespace, Reference},
         Urn,
     },
     git_ext as ext,
@@ -21,9 +30,42 @@ use librad::{
 use link_async::Spawner;
 use linkd_lib::api::client::Reply;
 
+use crate::pool;
+
 pub mod error;
 mod progress;
-pub(crate) use progress::{report, Progress, ProgressReporter};
+pub(crate) use progress::report;
+pub use progress::{Progress, ProgressReporter};
+
+/// The state of a URN before a `git receive-pack`, which the post-receive
+/// hook compares against to determine what the push changed.
+#[derive(Clone, Debug, Default)]
+pub struct PreReceive {
+    signed_refs: Option<ext::Oid>,
+    cobs: BTreeMap<(TypeName, ObjectId), git2::Oid>,
+}
+
+impl PreReceive {
+    /// Load the state of `urn`. The tips of its collaborative objects are only
+    /// recorded if `cobs` is set, as they are only needed if they are updated
+    /// after the push, see [`PostReceive::update_cobs`].
+    pub fn load(
+        storage: &storage::Storage,
+        urn: &Urn,
+        cobs: bool,
+    ) -> Result<Self, error::PreReceive> {
+        let signed_refs = storage
+            .reference(&Reference::rad_signed_refs(Namespace::from(urn), None))?
+            .and_then(|reference| reference.target())
+            .map(ext::Oid::from);
+        let cobs = if cobs {
+            CollaborativeObjects::new(storage.signer().clone(), storage, None).local_tips(urn)?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self { signed_refs, cobs })
+    }
+}
 
 #[derive(Clone)]
 pub(crate) struct Hooks<Signer> {
@@ -31,6 +73,7 @@ pub(crate) struct Hooks<Signer> {
     client: Client<Signer, quic::SendOnly>,
     seeds: Seeds,
     pool: Arc<storage::Pool<storage::Storage>>,
+    pool_timeout: Option<Duration>,
     post_receive: PostReceive,
     pre_upload: PreUpload,
 }
@@ -54,6 +97,7 @@ where
         client: Client<S, quic::SendOnly>,
         seeds: Seeds,
         pool: Arc<storage::Pool<storage::Storage>>,
+        pool_timeout: Option<Duration>,
         post_receive: PostReceive,
         pre_upload: PreUpload,
     ) -> Self {
@@ -62,16 +106,24 @@ where
             client,
             seeds,
             pool,
+            pool_timeout,
             post_receive,
             pre_upload,
         }
     }
 
+    /// Whether the collaborative object references are updated after a push,
+    /// see [`PostReceive::update_cobs`].
+    pub(crate) fn updates_cobs(&self) -> bool {
+        self.post_receive.update_cobs
+    }
+
     #[instrument(skip(self, reporter), err)]
     pub(crate) async fn post_receive<P, E>(
         &self,
         reporter: &mut P,
         urn: Urn,
+        pre_receive: PreReceive,
     ) -> Result<(), error::PostReceive<E>>
     where
         E: std::error::Error + Send + 'static,
@@ -87,10 +139,22 @@ where
             )
             .await?;
         }
+        if self.post_receive.update_cobs {
+            update_cobs(
+                reporter,
+                self.spawner.clone(),
+                self.pool.clone(),
+                self.pool_timeout,
+                urn.clone(),
+                &pre_receive,
+            )
+            .await?;
+        }
         let at = match update_signed_refs(
             reporter,
             self.spawner.clone(),
             self.pool.clone(),
+            self.pool_timeout,
             urn.clone(),
         )
         .await?
@@ -99,7 +163,7 @@ where
             None => return Ok(()),
         };
         if let Some(ann) = &self.post_receive.announce {
-            announce(reporter, ann, urn, at).await?;
+            announce(reporter, ann, urn, at, pre_receive.signed_refs).await?;
         } else {
             report(
                 reporter,
@@ -168,11 +232,18 @@ where
     Ok(())
 }
 
-async fn announce<P, E>(
+/// Announce `at` for `urn` to each of the linkd nodes in `announce`, along
+/// with the `previous` tip of the signed refs if there was one.
+///
+/// A failure to announce to one node does not prevent announcing to the
+/// others. The outcome for each node is reported to `reporter`, and an error
+/// is returned if any of them failed.
+pub async fn announce<P, E>(
     reporter: &mut P,
-    Announce { rpc_socket_path }: &Announce,
+    announce: &Announce,
     urn: Urn,
     at: ext::Oid,
+    previous: Option<ext::Oid>,
 ) -> Result<(), error::Announce<E>>
 where
     P: ProgressReporter<Error = E>,
@@ -180,11 +251,62 @@ where
 {
     tracing::info!("running post receive announcement hook");
     report(reporter, "announcing new refs").await?;
+    let total = announce.rpc_socket_paths.len();
+    let mut failed = Vec::new();
+    for rpc_socket_path in &announce.rpc_socket_paths {
+        match announce_to(reporter, rpc_socket_path, urn.clone(), at, previous).await {
+            Ok(()) => {},
+            Err(error::Announce::Progress(e)) => return Err(error::Announce::Progress(e)),
+            Err(e) => {
+                tracing::warn!(path=%rpc_socket_path.display(), err=%e, "failed to announce");
+                report(
+                    reporter,
+                    format!(
+                        "failed to announce to `{}`: {}",
+                        rpc_socket_path.display(),
+                        e
+                    ),
+                )
+                .await?;
+                failed.push(rpc_socket_path.clone());
+            },
+        }
+    }
+    report(
+        reporter,
+        format!(
+            "announced to {} of {} linkd nodes",
+            total - failed.len(),
+            total
+        ),
+    )
+    .await?;
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(error::Announce::Failed { failed, total })
+    }
+}
+
+async fn announce_to<P, E>(
+    reporter: &mut P,
+    rpc_socket_path: &Path,
+    urn: Urn,
+    at: ext::Oid,
+    previous: Option<ext::Oid>,
+) -> Result<(), error::Announce<E>>
+where
+    P: ProgressReporter<Error = E>,
+    E: std::error::Error + Send + 'static,
+{
     tracing::trace!(?rpc_socket_path, "attempting to send announcement");
     let conn = linkd_lib::api::client::Connection::connect(LINKD_CLIENT_NAME, rpc_socket_path)
         .await
         .map_err(error::Announce::LinkdConnect)?;
-    let cmd = linkd_lib::api::client::Command::announce(urn.clone(), at);
+    let mut cmd = linkd_lib::api::client::Command::announce(urn.clone(), at);
+    if let Some(previous) = previous {
+        cmd = cmd.previous(previous);
+    }
     let mut replies = cmd
         .execute_with_reply(conn)
         .await
@@ -201,7 +323,14 @@ where
             },
             Ok(Reply::Success { .. }) => {
                 tracing::trace!("got success from linkd node");
-                report(reporter, "successfully announced refs").await?;
+                report(
+                    reporter,
+                    format!(
+                        "successfully announced refs to `{}`",
+                        rpc_socket_path.display()
+                    ),
+                )
+                .await?;
                 return Ok(());
             },
             Ok(Reply::Error { msg, .. }) => {
@@ -220,6 +349,7 @@ async fn update_signed_refs<P, E>(
     reporter: &mut P,
     spawner: Arc<Spawner>,
     pool: Arc<storage::Pool<storage::Storage>>,
+    pool_timeout: Option<Duration>,
     urn: Urn,
 ) -> Result<Option<ext::Oid>, error::UpdateSignedRefs<E>>
 where
@@ -229,7 +359,7 @@ where
     // Update `rad/signed_refs`
     report(reporter, "updating signed refs").await?;
     let update_result = {
-        let storage = pool.get().await?;
+        let storage = pool::get(&pool, pool_timeout).await?;
         spawner
             .blocking::<_, Result<_, refs::stored::Error>>(move || {
                 Refs::update(storage.as_ref(), &urn)
@@ -253,6 +383,43 @@ where
     Ok(Some(at.into()))
 }
 
+/// Refresh the local collaborative object references of `urn` which the push
+/// changed or which do not point directly at a change commit, see
+/// [`CollaborativeObjects::update_local_refs`].
+pub async fn update_cobs<P, E>(
+    reporter: &mut P,
+    spawner: Arc<Spawner>,
+    pool: Arc<storage::Pool<storage::Storage>>,
+    pool_timeout: Option<Duration>,
+    urn: Urn,
+    pre_receive: &PreReceive,
+) -> Result<(), error::UpdateCobs<E>>
+where
+    P: ProgressReporter<Error = E>,
+    E: std::error::Error + Send + 'static,
+{
+    report(reporter, "updating collaborative object references").await?;
+    let updated = {
+        let storage = pool::get(&pool, pool_timeout).await?;
+        let before = pre_receive.cobs.clone();
+        spawner
+            .blocking(move || {
+                let cobs = CollaborativeObjects::new(storage.signer().clone(), &storage, None);
+                cobs.update_local_refs(&urn, &before)
+            })
+            .await
+    }?;
+    for (typename, oid) in &updated {
+        tracing::debug!(%typename, %oid, "updated collaborative object reference");
+    }
+    report(
+        reporter,
+        format!("updated {} collaborative object references", updated.len()),
+    )
+    .await?;
+    Ok(())
+}
+
 #[instrument(skip(client, reporter))]
 async fn request_pull<S, E, P>(
     reporter: &mut P,
@@ -316,7 +483,45 @@ where
 
 #[derive(Debug, Clone)]
 pub struct Announce {
-    pub rpc_socket_path: PathBuf,
+    /// The RPC sockets of the linkd nodes to announce to.
+    pub rpc_socket_paths: Vec<PathBuf>,
+    /// What to do if an RPC socket cannot be connected to at startup.
+    pub on_unreachable: OnUnreachable,
+}
+
+/// The action taken when a linkd RPC socket is unreachable at startup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnUnreachable {
+    /// Log a warning and continue. Announcements will fail until the socket
+    /// becomes available.
+    Warn,
+    /// Abort startup.
+    Fatal,
+}
+
+impl Default for OnUnreachable {
+    fn default() -> Self {
+        Self::Warn
+    }
+}
+
+/// How long to wait for a connection to the linkd RPC socket when checking
+/// that it is reachable.
+const LINKD_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Check that the linkd RPC socket at `rpc_socket_path` can be connected to.
+///
+/// The connection is dropped immediately, no commands are issued.
+#[instrument]
+pub async fn check_linkd(rpc_socket_path: &Path) -> Result<(), io::Error> {
+    let conn = linkd_lib::api::client::Connection::connect(LINKD_CLIENT_NAME, rpc_socket_path);
+    match link_async::timeout(LINKD_CHECK_TIMEOUT, conn).await {
+        Ok(conn) => conn.map(|_| ()),
+        Err(link_async::Elapsed) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "timed out connecting to linkd",
+        )),
+    }
 }
 
 /// Actions to be taken after a `git receive-pack`.
@@ -326,6 +531,8 @@ pub struct PostReceive {
     pub announce: Option<Announce>,
     /// Make a request-pull to configured seeds.
     pub request_pull: bool,
+    /// Update the local collaborative object references of the pushed URN.
+    pub update_cobs: bool,
 }
 
 /// Actions to be taken after a `git receive-pack`.
@@ -3,13 +3,26 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::io;
+use std::{io, path::PathBuf};
 
 use thiserror::Error;
 
-use librad::git::{refs, storage};
+use librad::{
+    collaborative_objects::RefsError,
+    git::{refs, storage},
+};
 use linkd_lib::api;
 
+use crate::pool;
+
+#[derive(Debug, Error)]
+pub enum PreReceive {
+    #[error("error reading signed refs: {0}")]
+    SignedRefs(#[from] storage::Error),
+    #[error("error reading collaborative object references: {0}")]
+    Cobs(#[from] RefsError),
+}
+
 #[derive(Debug, Error)]
 pub enum PostReceive<E: std::error::Error + Send + 'static> {
     #[error(transparent)]
@@ -19,6 +32,8 @@ pub enum PostReceive<E: std::error::Error + Send + 'static> {
     #[error(transparent)]
     RequestPull(#[from] RequestPull<E>),
     #[error(transparent)]
+    UpdateCobs(#[from] UpdateCobs<E>),
+    #[error(transparent)]
     Progress(#[from] Progress<E>),
 }
 
@@ -32,18 +47,30 @@ pub enum Announce<E: std::error::Error + Send + 'static> {
     LinkdTransport(#[source] api::client::ReplyError<api::io::SocketTransportError>),
     #[error("the linkd node reported an error: {0}")]
     Linkd(String),
+    #[error("failed to announce to {} of {total} linkd nodes", .failed.len())]
+    Failed { failed: Vec<PathBuf>, total: usize },
 }
 
 #[derive(Debug, Error)]
 pub enum UpdateSignedRefs<E: std::error::Error + Send + 'static> {
     #[error(transparent)]
     Progress(#[from] Progress<E>),
-    #[error("could not open storage: {0}")]
-    OpenStorage(#[from] storage::pool::PoolError),
+    #[error(transparent)]
+    OpenStorage(#[from] pool::Error),
     #[error("error updating refs: {0}")]
     UpdateRefs(#[from] refs::stored::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum UpdateCobs<E: std::error::Error + Send + 'static> {
+    #[error(transparent)]
+    Progress(#[from] Progress<E>),
+    #[error(transparent)]
+    OpenStorage(#[from] pool::Error),
+    #[error("error updating collaborative object references: {0}")]
+    UpdateRefs(#[from] RefsError),
+}
+
 #[derive(Debug, Error)]
 pub enum RequestPull<E: std::error::Error + Send + 'static> {
     #[error(transparent)]
@@ -13,7 +13,7 @@ use librad::{
 
 use super::error;
 
-pub(crate) struct Progress(String);
+pub struct Progress(String);
 
 impl fmt::Display for Progress {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -33,7 +33,7 @@ impl From<String> for Progress {
     }
 }
 
-pub(crate) trait ProgressReporter {
+pub trait ProgressReporter {
     type Error;
     fn report(&mut self, progress: Progress)
         -> futures::future::BoxFuture<Result<(), Self::Error>>;
@@ -3,7 +3,7 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{sync::Arc, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
 
 use clap::Parser;
 use futures::{FutureExt, StreamExt};
@@ -26,12 +26,17 @@ use tokio::net::TcpListener;
 use tracing::instrument;
 
 mod args;
+pub mod authorization;
 pub mod config;
 pub mod git_subprocess;
+pub mod health;
 pub mod hooks;
+pub mod metrics;
+pub mod pool;
 mod processes;
 mod server;
 mod ssh_service;
+pub mod urn_policy;
 
 #[derive(thiserror::Error, Debug)]
 pub enum RunError {
@@ -49,6 +54,14 @@ pub enum RunError {
     UnableToLoadKey(Box<dyn std::error::Error>),
     #[error("error loading socket activation environment variables: {0}")]
     SocketActivation(std::io::Error),
+    #[error("unable to connect to linkd RPC socket at {path}: {source}")]
+    LinkdUnreachable {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    UrnPolicy(#[from] urn_policy::error::Load),
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
@@ -73,6 +86,18 @@ pub async fn run<S: librad::Signer + Clone>(
     config: config::Config<S>,
     spawner: Arc<link_async::Spawner>,
 ) -> Result<(), RunError> {
+    let readiness = health::Readiness::default();
+    let _health_task = match config.health_addr {
+        Some(addr) => {
+            let listener = TcpListener::bind(addr)
+                .await
+                .map_err(RunError::CouldNotBind)?;
+            tracing::info!(%addr, "serving health checks");
+            Some(spawner.spawn(health::serve(spawner.clone(), listener, readiness.clone())))
+        },
+        None => None,
+    };
+
     // Load storage pool
     let storage_pool = Arc::new(librad::git::storage::Pool::new(
         librad::git::storage::pool::ReadWriteConfig::new(
@@ -82,20 +107,63 @@ pub async fn run<S: librad::Signer + Clone>(
         ),
         librad::net::peer::config::UserStorage::default().pool_size,
     ));
+    if let Err(e) = storage_pool.get().await {
+        tracing::error!(err=?e, "unable to get storage from the pool");
+        return Err(RunError::CouldNotOpenStorage);
+    }
+    readiness.set(health::Component::Storage, true);
+
+    if let Some(announce) = &config.network.announce {
+        check_linkd(announce).await?;
+    }
+
+    let urns = Arc::new(urn_policy::UrnFilter::load(config.urn_policy.clone())?);
+    let _reload_urns = match urns.policy() {
+        urn_policy::UrnPolicy::AllowAll => None,
+        _ => Some(spawner.spawn(reload_on_sighup(urns.clone())?)),
+    };
 
     let peer_id = PeerId::from_signer(&config.signer);
+    let stats = metrics::Stats::default();
+    let _graphite_task = config.graphite_addr.map(|addr| {
+        spawner.spawn({
+            let stats = stats.clone();
+            async move {
+                if let Err(e) = metrics::graphite(stats, peer_id, addr).await {
+                    tracing::error!(err=%e, "graphite stats routine failed");
+                }
+            }
+        })
+    });
+    let authorizer = config.authorizer.clone().unwrap_or_else(|| {
+        Arc::new(authorization::TrackedWriters::new(
+            peer_id,
+            spawner.clone(),
+            storage_pool.clone(),
+            config.git_limits.pool_timeout,
+        ))
+    });
 
     // Create thrussh config from stored key or create a new one
-    let server_key = create_or_load_key(peer_id)?;
+    let server_key = create_or_load_key(peer_id, config.host_key_dir.as_deref())?;
     let mut thrussh_config = thrussh::server::Config::default();
     thrussh_config.keys.push(server_key);
     let thrussh_config = Arc::new(thrussh_config);
 
     // Processes thread which handles git subprocesses
-    let (processes, handle) = processes::Processes::new(spawner.clone(), storage_pool.clone());
+    let (processes, handle) =
+        processes::Processes::new(spawner.clone(), storage_pool.clone(), config.git_limits);
 
     let socket = bind_sockets(&config).await?;
-    let processes_task = spawner.spawn(processes.run());
+    let processes_task = spawner.spawn({
+        let readiness = readiness.clone();
+        async move {
+            readiness.set(health::Component::Processes, true);
+            let result = processes.run().await;
+            readiness.set(health::Component::Processes, false);
+            result
+        }
+    });
     let client = {
         let network = Network::default();
         let config = client::Config {
@@ -104,6 +172,10 @@ pub async fn run<S: librad::Signer + Clone>(
             replication: replication::Config::default(),
             user_storage: client::config::Storage::default(),
             network: network.clone(),
+            connections: client::config::Connections {
+                family: config.network.addr_family,
+                ..Default::default()
+            },
         };
         let endpoint = quic::SendOnly::new(config.signer.clone(), network).await?;
         Client::new(config, spawner.clone(), endpoint)?
@@ -125,12 +197,22 @@ pub async fn run<S: librad::Signer + Clone>(
         client,
         seeds,
         storage_pool.clone(),
+        config.git_limits.pool_timeout,
         (&config.network).into(),
         (&config.network).into(),
     );
 
-    let sh = server::Server::new(spawner.clone(), peer_id, handle.clone(), hooks);
+    let sh = server::Server::new(
+        spawner.clone(),
+        peer_id,
+        handle.clone(),
+        hooks,
+        urns,
+        authorizer,
+        stats,
+    );
     let ssh_tasks = sh.serve(&socket, thrussh_config).await;
+    readiness.set(health::Component::Ssh, true);
     let server_complete = match config.linger_timeout {
         Some(d) => link_async::tasks::run_until_idle(ssh_tasks.boxed(), d).boxed(),
         None => link_async::tasks::run_forever(ssh_tasks.boxed()).boxed(),
@@ -203,17 +285,73 @@ async fn bind_sockets<S: librad::Signer + Clone>(
     }
 }
 
+/// Read the URN file of `urns` again whenever SIGHUP is received.
+fn reload_on_sighup(
+    urns: Arc<urn_policy::UrnFilter>,
+) -> Result<impl futures::Future<Output = ()>, RunError> {
+    let mut sighup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+    Ok(async move {
+        while sighup.recv().await.is_some() {
+            match urns.reload() {
+                Ok(()) => tracing::info!(policy=?urns.policy(), "reloaded URN policy"),
+                Err(e) => {
+                    tracing::error!(err=%e, "failed to reload URN policy, keeping the previous URNs")
+                },
+            }
+        }
+    })
+}
+
+async fn check_linkd(announce: &hooks::Announce) -> Result<(), RunError> {
+    for path in &announce.rpc_socket_paths {
+        match hooks::check_linkd(path).await {
+            Ok(()) => {},
+            Err(source) => match announce.on_unreachable {
+                hooks::OnUnreachable::Warn => {
+                    tracing::warn!(
+                        path=%path.display(),
+                        err=%source,
+                        "linkd RPC socket is unreachable, announcements will fail until it is available"
+                    );
+                },
+                hooks::OnUnreachable::Fatal => {
+                    return Err(RunError::LinkdUnreachable {
+                        path: path.clone(),
+                        source,
+                    })
+                },
+            },
+        }
+    }
+    Ok(())
+}
+
 #[instrument]
-fn create_or_load_key(peer_id: PeerId) -> Result<thrussh_keys::key::KeyPair, RunError> {
-    let dirs = xdg::BaseDirectories::new().map_err(|e| RunError::UnableToLoadKey(Box::new(e)))?;
-    let key_filename = format!("linkd-git/{}-ssh-key", peer_id);
-    let key_path = dirs.place_state_file(&key_filename).map_err(|e| {
-        tracing::error!(
-            ?key_filename,
-            "unable to get state file path for linkd ssh key"
-        );
-        RunError::UnableToLoadKey(Box::new(e))
-    })?;
+fn create_or_load_key(
+    peer_id: PeerId,
+    dir: Option<&Path>,
+) -> Result<thrussh_keys::key::KeyPair, RunError> {
+    let key_path = match dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                tracing::error!(?dir, "unable to create directory for linkd ssh key");
+                RunError::UnableToLoadKey(Box::new(e))
+            })?;
+            dir.join(format!("{}-ssh-key", peer_id))
+        },
+        None => {
+            let dirs =
+                xdg::BaseDirectories::new().map_err(|e| RunError::UnableToLoadKey(Box::new(e)))?;
+            let key_filename = format!("linkd-git/{}-ssh-key", peer_id);
+            dirs.place_state_file(&key_filename).map_err(|e| {
+                tracing::error!(
+                    ?key_filename,
+                    "unable to get state file path for linkd ssh key"
+                );
+                RunError::UnableToLoadKey(Box::new(e))
+            })?
+        },
+    };
     if key_path.exists() {
         tracing::info!("found server key");
         let raw = std::fs::read(key_path).map_err(|e| {
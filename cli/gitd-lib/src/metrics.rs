@@ -0,0 +1,91 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+//! Statistics about the requests served by gitd, reported using the metric
+//! names shared with the other binaries in [`lnk_clib::metrics`].
+
+use std::{
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use librad::PeerId;
+use lnk_clib::metrics::{gitd, graphite_line, Label};
+use tokio::{net::UdpSocket, time};
+use tracing::{debug, info, instrument};
+
+/// Counters of the requests served, shared between the SSH server and the
+/// metrics routine.
+#[derive(Clone, Debug, Default)]
+pub struct Stats(Arc<Counters>);
+
+#[derive(Debug, Default)]
+struct Counters {
+    connections: AtomicUsize,
+    git_requests: AtomicUsize,
+    git_requests_denied: AtomicUsize,
+}
+
+impl Stats {
+    /// Record an accepted SSH connection.
+    pub fn connection(&self) {
+        self.0.connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a git request, and whether it was denied.
+    pub fn git_request(&self, denied: bool) {
+        self.0.git_requests.fetch_add(1, Ordering::Relaxed);
+        if denied {
+            self.0.git_requests_denied.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn connections_total(&self) -> usize {
+        self.0.connections.load(Ordering::Relaxed)
+    }
+
+    pub fn git_requests_total(&self) -> usize {
+        self.0.git_requests.load(Ordering::Relaxed)
+    }
+
+    pub fn git_requests_denied_total(&self) -> usize {
+        self.0.git_requests_denied.load(Ordering::Relaxed)
+    }
+}
+
+#[instrument(name = "graphite subroutine", skip(stats))]
+pub async fn graphite(stats: Stats, peer: PeerId, graphite_addr: SocketAddr) -> io::Result<()> {
+    info!("starting graphite stats routine");
+
+    debug!("connecting to graphite at {}", graphite_addr);
+    let sock = UdpSocket::bind("0.0.0.0:0").await?;
+    sock.connect(graphite_addr).await?;
+    debug!("connected to graphite at {}", graphite_addr);
+
+    let labels = [Label::Peer(peer)];
+    loop {
+        time::sleep(Duration::from_secs(10)).await;
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        for (metric, value) in &[
+            (gitd::CONNECTIONS_TOTAL, stats.connections_total()),
+            (gitd::GIT_REQUESTS_TOTAL, stats.git_requests_total()),
+            (
+                gitd::GIT_REQUESTS_DENIED_TOTAL,
+                stats.git_requests_denied_total(),
+            ),
+        ] {
+            sock.send(graphite_line(metric, &labels, *value as f32, now).as_bytes())
+                .await?;
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::time::Duration;
+
+use librad::git::storage::{self, Pooled as _, PooledRef, Storage};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("server busy, no storage available after {waited:?}, retry later")]
+    Busy { waited: Duration },
+    #[error("could not open storage: {0}")]
+    Pool(#[from] storage::PoolError),
+}
+
+/// Get a storage from `pool`, waiting at most `timeout` for one to become
+/// available. If `timeout` is `None`, wait indefinitely.
+pub async fn get(
+    pool: &storage::Pool<Storage>,
+    timeout: Option<Duration>,
+) -> Result<PooledRef<Storage>, Error> {
+    match timeout {
+        None => Ok(pool.get().await?),
+        Some(waited) => match link_async::timeout(waited, pool.get()).await {
+            Ok(storage) => Ok(storage?),
+            Err(link_async::Elapsed) => {
+                tracing::warn!(?waited, "storage pool exhausted, rejecting request");
+                Err(Error::Busy { waited })
+            },
+        },
+    }
+}
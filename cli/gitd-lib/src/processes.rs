@@ -36,7 +36,7 @@ const MAX_IN_FLIGHT_GITS: usize = 10;
 /// A trait representing a channel for data to be sent from a running process to
 /// the user.
 #[async_trait]
-pub(crate) trait ProcessReply {
+pub trait ProcessReply {
     type Error: std::error::Error + Send + 'static;
     /// Data to be delivered to the users standard input
     async fn stdout_data(&mut self, data: Vec<u8>) -> Result<(), Self::Error>;
@@ -162,6 +162,8 @@ type GitProcessResult<Id, E> = (Id, Result<(), git_subprocess::Error<E>>);
 pub(crate) struct Processes<Id, Reply: ProcessReply, Signer> {
     spawner: Arc<Spawner>,
     pool: Arc<Pool<Storage>>,
+    /// Bounds on each git subprocess
+    limits: git_subprocess::Limits,
     /// Incoming control messages
     incoming: tokio::sync::mpsc::Receiver<Message<Id>>,
     /// Incoming exec git requests
@@ -191,12 +193,14 @@ where
     pub(crate) fn new(
         spawner: Arc<Spawner>,
         pool: Arc<Pool<Storage>>,
+        limits: git_subprocess::Limits,
     ) -> (Processes<Id, Reply, S>, ProcessesHandle<Id, Reply, S>) {
         let (tx, rx) = tokio::sync::mpsc::channel(1);
         let (exec_git_tx, exec_git_rx) = tokio::sync::mpsc::channel(1);
         let processes = Processes {
             spawner,
             pool,
+            limits,
             incoming: rx,
             exec_git_incoming: exec_git_rx,
             process_sends: HashMap::new(),
@@ -222,11 +226,13 @@ where
         let task = self.spawner.spawn({
             let spawner = self.spawner.clone();
             let pool = self.pool.clone();
+            let limits = self.limits;
             let id = id.clone();
             async move {
-                let result =
-                    git_subprocess::run_git_subprocess(spawner, pool, rx, handle, service, hooks)
-                        .await;
+                let result = git_subprocess::run_git_subprocess(
+                    spawner, pool, rx, handle, service, hooks, limits,
+                )
+                .await;
                 (id, result)
             }
         });
@@ -13,12 +13,15 @@ use rand::Rng;
 use tokio::net::{TcpListener, TcpStream};
 use tracing::instrument;
 
-use librad::PeerId;
+use librad::{git::Urn, PeerId, PublicKey};
 use link_async::{incoming::TcpListenerExt, Spawner};
 
 use crate::{
+    authorization::{Access, Authorizer, Decision},
     hooks::Hooks,
+    metrics::Stats,
     processes::{ProcessReply, ProcessesHandle},
+    urn_policy::UrnFilter,
 };
 
 #[derive(Clone)]
@@ -27,6 +30,9 @@ pub(crate) struct Server<Signer> {
     peer: PeerId,
     processes_handle: ProcessesHandle<ChannelAndSessionId, ChannelHandle, Signer>,
     hooks: Hooks<Signer>,
+    urns: Arc<UrnFilter>,
+    authorizer: Arc<dyn Authorizer>,
+    stats: Stats,
 }
 
 /// The ID of the "extended data" channel in the SSH protocol which corresponds
@@ -42,12 +48,18 @@ where
         peer: PeerId,
         processes_handle: ProcessesHandle<ChannelAndSessionId, ChannelHandle, S>,
         hooks: Hooks<S>,
+        urns: Arc<UrnFilter>,
+        authorizer: Arc<dyn Authorizer>,
+        stats: Stats,
     ) -> Self {
         Self {
             spawner,
             peer,
             processes_handle,
             hooks,
+            urns,
+            authorizer,
+            stats,
         }
     }
 
@@ -60,14 +72,20 @@ where
         let incoming = socket.incoming();
         incoming
             .map(move |stream| match stream {
-                Ok(stream) => Some(run_stream(
-                    conf.clone(),
-                    self.spawner.clone(),
-                    self.peer,
-                    self.hooks.clone(),
-                    self.processes_handle.clone(),
-                    stream,
-                )),
+                Ok(stream) => {
+                    self.stats.connection();
+                    Some(run_stream(
+                        conf.clone(),
+                        self.spawner.clone(),
+                        self.peer,
+                        self.hooks.clone(),
+                        self.urns.clone(),
+                        self.authorizer.clone(),
+                        self.stats.clone(),
+                        self.processes_handle.clone(),
+                        stream,
+                    ))
+                },
                 Err(e) => {
                     tracing::error!(err=?e, "error accepting incoming connection");
                     None
@@ -78,12 +96,16 @@ where
     }
 }
 
-#[instrument(skip(conf, spawner, handle, stream, hooks))]
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(conf, spawner, handle, stream, hooks, urns, authorizer, stats))]
 fn run_stream<S>(
     conf: Arc<thrussh::server::Config>,
     spawner: Arc<link_async::Spawner>,
     peer: librad::PeerId,
     hooks: Hooks<S>,
+    urns: Arc<UrnFilter>,
+    authorizer: Arc<dyn Authorizer>,
+    stats: Stats,
     handle: ProcessesHandle<ChannelAndSessionId, ChannelHandle, S>,
     stream: TcpStream,
 ) -> link_async::Task<()>
@@ -95,10 +117,13 @@ where
             conf.clone(),
             stream,
             SshHandler {
-                peer,
+                authenticated: None,
                 id: SessionId::random(),
                 handle: handle.clone(),
                 hooks,
+                urns,
+                authorizer,
+                stats,
             },
         );
         match handler_stream.await {
@@ -116,10 +141,14 @@ where
 }
 
 struct SshHandler<Signer> {
-    peer: librad::PeerId,
+    /// The peer the connection was authenticated as, if any
+    authenticated: Option<librad::PeerId>,
     id: SessionId,
     handle: crate::processes::ProcessesHandle<ChannelAndSessionId, ChannelHandle, Signer>,
     hooks: Hooks<Signer>,
+    urns: Arc<UrnFilter>,
+    authorizer: Arc<dyn Authorizer>,
+    stats: Stats,
 }
 
 impl<S> SshHandler<S> {
@@ -174,17 +203,18 @@ where
 
     #[tracing::instrument(level = "debug", skip(self))]
     fn auth_publickey(
-        self,
+        mut self,
         _user: &str,
         public_key: &thrussh_keys::key::PublicKey,
     ) -> Self::FutureAuth {
         let thrussh_keys::key::PublicKey::Ed25519(k) = public_key;
-        let client_key_bytes: &[u8] = &k.key;
-        let peer_key_bytes: &[u8] = self.peer.as_ref();
-        let auth = if client_key_bytes == peer_key_bytes {
-            thrussh::server::Auth::Accept
-        } else {
-            thrussh::server::Auth::Reject
+        // Any peer may authenticate, the `Authorizer` decides what it may access
+        let auth = match PublicKey::from_slice(&k.key) {
+            Some(key) => {
+                self.authenticated = Some(PeerId::from(key));
+                thrussh::server::Auth::Accept
+            },
+            None => thrussh::server::Auth::Reject,
         };
         self.finished_auth(auth)
     }
@@ -282,9 +312,44 @@ where
         };
         tracing::debug!(%ssh_service.service, %ssh_service.path, "parsed exec_request");
 
+        if !ssh_service.is_upload() && !self.urns.allows(ssh_service.path.as_ref()) {
+            tracing::warn!(%ssh_service.path, "rejecting push to a URN which is not allowed");
+            self.stats.git_request(true);
+            session.extended_data(
+                channel,
+                STDERR_ID,
+                format!(
+                    "ERROR: pushing to {} is not allowed by this server\n",
+                    ssh_service.path
+                )
+                .into(),
+            );
+            session.close(channel);
+            return self.finished(session);
+        }
+
         let id = self.channel_id(channel);
         let handle = ChannelHandle::new(session.handle(), channel);
         async move {
+            let access = if ssh_service.is_upload() {
+                Access::Read
+            } else {
+                Access::Write
+            };
+            let authorized = authorize(
+                self.authorizer.as_ref(),
+                self.authenticated,
+                access,
+                ssh_service.path.as_ref().clone(),
+            )
+            .await;
+            self.stats.git_request(authorized.is_err());
+            if let Err(reason) = authorized {
+                session.extended_data(channel, STDERR_ID, format!("ERROR: {}\n", reason).into());
+                session.close(channel);
+                return Ok((self, session));
+            }
+
             match self
                 .handle
                 .exec_git(id, handle, ssh_service, self.hooks.clone())
@@ -301,6 +366,31 @@ where
     }
 }
 
+/// Consult the [`Authorizer`] on whether the `authenticated` peer may access
+/// `urn`, returning the reason if it may not.
+async fn authorize(
+    authorizer: &dyn Authorizer,
+    authenticated: Option<PeerId>,
+    access: Access,
+    urn: Urn,
+) -> Result<(), String> {
+    let peer = match authenticated {
+        Some(peer) => peer,
+        None => return Err("the connection is not authenticated".to_string()),
+    };
+    match authorizer.authorize(&peer, access, &urn).await {
+        Ok(Decision::Allow) => Ok(()),
+        Ok(Decision::Deny { reason }) => {
+            tracing::warn!(%peer, %access, %urn, %reason, "access denied");
+            Err(reason)
+        },
+        Err(e) => {
+            tracing::error!(err=%e, %peer, %access, %urn, "failed to authorize access");
+            Err(format!("unable to authorize {} access to {}", access, urn))
+        },
+    }
+}
+
 #[derive(Copy, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct SessionId([u8; 32]);
 
@@ -0,0 +1,135 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+//! Restrict the URNs which may be pushed to the gitd server.
+//!
+//! The URN files list one URN per line. Blank lines and lines starting with
+//! `#` are ignored.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr as _,
+    sync::RwLock,
+};
+
+use librad::git::Urn;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum UrnPolicy {
+    /// Accept pushes to any URN.
+    AllowAll,
+    /// Only accept pushes to the URNs listed in the file.
+    Allow(PathBuf),
+    /// Accept pushes to any URN, except the ones listed in the file.
+    Deny(PathBuf),
+}
+
+impl Default for UrnPolicy {
+    fn default() -> Self {
+        Self::AllowAll
+    }
+}
+
+impl UrnPolicy {
+    fn path(&self) -> Option<&Path> {
+        match self {
+            Self::AllowAll => None,
+            Self::Allow(path) | Self::Deny(path) => Some(path),
+        }
+    }
+}
+
+pub mod error {
+    use std::{io, path::PathBuf};
+
+    use librad::{git_ext, identities::urn};
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    pub enum Load {
+        #[error("failed to read URN list {path}")]
+        Io {
+            path: PathBuf,
+            #[source]
+            source: io::Error,
+        },
+        #[error("invalid URN on line {line} of {path}")]
+        Urn {
+            path: PathBuf,
+            line: usize,
+            #[source]
+            source: urn::error::FromStr<git_ext::oid::FromMultihashError>,
+        },
+    }
+}
+
+/// A [`UrnPolicy`] along with the URNs loaded from its file.
+#[derive(Debug)]
+pub struct UrnFilter {
+    policy: UrnPolicy,
+    urns: RwLock<BTreeSet<Urn>>,
+}
+
+impl UrnFilter {
+    pub fn load(policy: UrnPolicy) -> Result<Self, error::Load> {
+        let urns = match policy.path() {
+            None => BTreeSet::new(),
+            Some(path) => read(path)?,
+        };
+        Ok(Self {
+            policy,
+            urns: RwLock::new(urns),
+        })
+    }
+
+    pub fn policy(&self) -> &UrnPolicy {
+        &self.policy
+    }
+
+    /// Read the URN file again. If this fails, the previously loaded URNs are
+    /// kept.
+    pub fn reload(&self) -> Result<(), error::Load> {
+        if let Some(path) = self.policy.path() {
+            let urns = read(path)?;
+            *self.urns.write().unwrap() = urns;
+        }
+        Ok(())
+    }
+
+    /// Whether pushes to `urn` are accepted. Only the identity of `urn` is
+    /// considered, not its path.
+    pub fn allows(&self, urn: &Urn) -> bool {
+        let urn = Urn::new(urn.id);
+        match self.policy {
+            UrnPolicy::AllowAll => true,
+            UrnPolicy::Allow(_) => self.urns.read().unwrap().contains(&urn),
+            UrnPolicy::Deny(_) => !self.urns.read().unwrap().contains(&urn),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<BTreeSet<Urn>, error::Load> {
+    let contents = fs::read_to_string(path).map_err(|source| error::Load::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, urn)| {
+            Urn::from_str(urn)
+                .map(|urn| Urn::new(urn.id))
+                .map_err(|source| error::Load::Urn {
+                    path: path.to_path_buf(),
+                    line,
+                    source,
+                })
+        })
+        .collect()
+}
@@ -1,4 +1,11 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod authorization;
 mod git_subprocess;
+mod health;
+mod hooks;
+mod metrics;
+mod pool;
+mod server;
+mod urn_policy;
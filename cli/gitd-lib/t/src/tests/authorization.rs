@@ -0,0 +1,128 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{sync::Arc, time::Duration};
+
+use gitd_lib::authorization::{Access, Authorizer as _, Decision, TrackedWriters};
+use it_helpers::fixed::TestProject;
+use librad::{
+    git::{
+        storage::{self, pool, Pooled as _, Storage},
+        tracking,
+        Urn,
+    },
+    paths::Paths,
+    PeerId,
+    SecretKey,
+};
+use link_async::Spawner;
+
+struct Fixture {
+    _tmp: tempfile::TempDir,
+    storage: Storage,
+    local: PeerId,
+    urn: Urn,
+    pool: Arc<storage::Pool<Storage>>,
+    authorizer: TrackedWriters,
+}
+
+impl Fixture {
+    /// Must be called within a tokio runtime.
+    fn new() -> Self {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = Paths::from_root(tmp.path()).unwrap();
+        let signer = SecretKey::new();
+        let local = PeerId::from(signer.clone());
+        let storage = Storage::open(&paths, signer.clone()).unwrap();
+        let urn = TestProject::create(&storage).unwrap().project.urn();
+        let pool = Arc::new(storage::Pool::new(
+            pool::ReadWriteConfig::new(paths, signer, pool::Initialised::no()),
+            1,
+        ));
+        let spawner = Arc::new(Spawner::from_current().unwrap());
+
+        Self {
+            _tmp: tmp,
+            storage,
+            local,
+            urn,
+            pool: pool.clone(),
+            authorizer: TrackedWriters::new(local, spawner, pool, Some(Duration::from_millis(100))),
+        }
+    }
+
+    async fn authorize(&self, peer: &PeerId, access: Access) -> Decision {
+        self.authorizer
+            .authorize(peer, access, &self.urn)
+            .await
+            .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn anyone_may_read() {
+    let fixture = Fixture::new();
+    let stranger = PeerId::from(SecretKey::new());
+
+    assert_eq!(
+        fixture.authorize(&stranger, Access::Read).await,
+        Decision::Allow
+    );
+    assert_eq!(
+        fixture.authorize(&fixture.local, Access::Read).await,
+        Decision::Allow
+    );
+}
+
+#[tokio::test]
+async fn local_peer_may_write() {
+    let fixture = Fixture::new();
+    assert_eq!(
+        fixture.authorize(&fixture.local, Access::Write).await,
+        Decision::Allow
+    );
+}
+
+#[tokio::test]
+async fn untracked_peer_may_not_write() {
+    let fixture = Fixture::new();
+    let stranger = PeerId::from(SecretKey::new());
+
+    assert!(matches!(
+        fixture.authorize(&stranger, Access::Write).await,
+        Decision::Deny { .. }
+    ));
+}
+
+#[tokio::test]
+async fn tracked_peer_may_write() {
+    let fixture = Fixture::new();
+    let peer = PeerId::from(SecretKey::new());
+    tracking::track(
+        &fixture.storage,
+        &fixture.urn,
+        Some(peer),
+        tracking::Config::default(),
+        tracking::policy::Track::Any,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        fixture.authorize(&peer, Access::Write).await,
+        Decision::Allow
+    );
+}
+
+#[tokio::test]
+async fn busy_storage_is_an_error() {
+    let fixture = Fixture::new();
+    let stranger = PeerId::from(SecretKey::new());
+    let _held = fixture.pool.get().await.unwrap();
+
+    assert!(fixture
+        .authorizer
+        .authorize(&stranger, Access::Write, &fixture.urn)
+        .await
+        .is_err());
+}
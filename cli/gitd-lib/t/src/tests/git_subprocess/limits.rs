@@ -0,0 +1,119 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    process::{ExitStatus, Stdio},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use gitd_lib::git_subprocess::{communicate, Limits, ProcessReply};
+
+#[derive(Clone, Default)]
+struct Recorder {
+    stdout: Arc<Mutex<Vec<u8>>>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+    exit_status: Arc<Mutex<Option<ExitStatus>>>,
+}
+
+#[async_trait::async_trait]
+impl ProcessReply for Recorder {
+    type Error = std::convert::Infallible;
+
+    async fn stdout_data(&mut self, data: Vec<u8>) -> Result<(), Self::Error> {
+        self.stdout.lock().unwrap().extend(data);
+        Ok(())
+    }
+
+    async fn stderr_data(&mut self, data: Vec<u8>) -> Result<(), Self::Error> {
+        self.stderr.lock().unwrap().extend(data);
+        Ok(())
+    }
+
+    async fn exit_status(&mut self, status: ExitStatus) -> Result<(), Self::Error> {
+        *self.exit_status.lock().unwrap() = Some(status);
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn kills_slow_subprocess() {
+    let mut child = tokio::process::Command::new("sleep")
+        .arg("60")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (_tx, mut incoming) = tokio::sync::mpsc::channel(1);
+    let mut out = Recorder::default();
+    let limits = Limits {
+        timeout: Some(Duration::from_millis(100)),
+        ..Limits::default()
+    };
+
+    let status = communicate(&mut child, &mut incoming, &mut out, &limits)
+        .await
+        .unwrap();
+    assert!(status.is_none());
+
+    let exit_status = out.exit_status.lock().unwrap().unwrap();
+    assert!(!exit_status.success());
+    let stderr = String::from_utf8(out.stderr.lock().unwrap().clone()).unwrap();
+    assert!(stderr.contains("timed out"));
+}
+
+#[tokio::test]
+async fn truncates_noisy_subprocess() {
+    let mut child = tokio::process::Command::new("yes")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (_tx, mut incoming) = tokio::sync::mpsc::channel(1);
+    let mut out = Recorder::default();
+    let limits = Limits {
+        max_output: Some(4096),
+        ..Limits::default()
+    };
+
+    let status = communicate(&mut child, &mut incoming, &mut out, &limits)
+        .await
+        .unwrap();
+    assert!(status.is_none());
+
+    assert_eq!(out.stdout.lock().unwrap().len(), 4096);
+    let exit_status = out.exit_status.lock().unwrap().unwrap();
+    assert!(!exit_status.success());
+    let stderr = String::from_utf8(out.stderr.lock().unwrap().clone()).unwrap();
+    assert!(stderr.contains("exceeded 4096 bytes"));
+}
+
+#[tokio::test]
+async fn budgets_each_channel_separately() {
+    let mut child = tokio::process::Command::new("sh")
+        .args(["-c", "head -c 3000 /dev/zero >&2; head -c 3000 /dev/zero"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (_tx, mut incoming) = tokio::sync::mpsc::channel(1);
+    let mut out = Recorder::default();
+    let limits = Limits {
+        max_output: Some(4096),
+        ..Limits::default()
+    };
+
+    let status = communicate(&mut child, &mut incoming, &mut out, &limits)
+        .await
+        .unwrap();
+    assert!(status.unwrap().success());
+    assert_eq!(out.stdout.lock().unwrap().len(), 3000);
+    assert_eq!(out.stderr.lock().unwrap().len(), 3000);
+}
@@ -0,0 +1,54 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{net::SocketAddr, sync::Arc};
+
+use gitd_lib::health::{self, Component, Readiness};
+use tokio::{
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+    net::{TcpListener, TcpStream},
+};
+
+async fn get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response.lines().next().unwrap().to_owned()
+}
+
+#[tokio::test]
+async fn probes() {
+    let spawner = Arc::new(link_async::Spawner::from_current().unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let readiness = Readiness::default();
+    let _server = spawner.spawn(health::serve(spawner.clone(), listener, readiness.clone()));
+
+    assert_eq!(get(addr, "/healthz").await, "HTTP/1.1 200 OK");
+    assert_eq!(
+        get(addr, "/readyz").await,
+        "HTTP/1.1 503 Service Unavailable"
+    );
+    assert_eq!(get(addr, "/nope").await, "HTTP/1.1 404 Not Found");
+
+    readiness.set(Component::Storage, true);
+    readiness.set(Component::Ssh, true);
+    assert_eq!(
+        get(addr, "/readyz").await,
+        "HTTP/1.1 503 Service Unavailable"
+    );
+
+    readiness.set(Component::Processes, true);
+    assert_eq!(get(addr, "/readyz").await, "HTTP/1.1 200 OK");
+    assert_eq!(get(addr, "/healthz").await, "HTTP/1.1 200 OK");
+
+    readiness.set(Component::Processes, false);
+    assert_eq!(
+        get(addr, "/readyz").await,
+        "HTTP/1.1 503 Service Unavailable"
+    );
+}
@@ -0,0 +1,312 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::convert::Infallible;
+
+use futures::{future::BoxFuture, FutureExt as _};
+use gitd_lib::hooks::{self, Progress, ProgressReporter};
+
+#[derive(Default)]
+struct Messages(Vec<String>);
+
+impl ProgressReporter for Messages {
+    type Error = Infallible;
+
+    fn report(&mut self, progress: Progress) -> BoxFuture<Result<(), Self::Error>> {
+        self.0.push(progress.to_string());
+        futures::future::ready(Ok(())).boxed()
+    }
+}
+
+#[tokio::test]
+async fn check_linkd_present() {
+    let tmp = tempfile::tempdir().unwrap();
+    let socket = tmp.path().join("linkd.sock");
+    let _listener = tokio::net::UnixListener::bind(&socket).unwrap();
+
+    assert!(hooks::check_linkd(&socket).await.is_ok())
+}
+
+#[tokio::test]
+async fn check_linkd_absent() {
+    let tmp = tempfile::tempdir().unwrap();
+    let socket = tmp.path().join("linkd.sock");
+
+    assert!(hooks::check_linkd(&socket).await.is_err())
+}
+
+mod announce {
+    use std::path::Path;
+
+    use gitd_lib::hooks;
+    use librad::git::Urn;
+    use linkd_lib::api::{
+        announce,
+        io::{SocketTransport, Transport as _},
+        messages::{RequestId, RequestMode, RequestPayload, Response, ResponsePayload},
+    };
+    use radicle_git_ext as ext;
+
+    use super::Messages;
+
+    /// Accept a single announcement of `rev` replacing `previous` for `urn` on
+    /// `socket` and report success.
+    ///
+    /// Requests carry no id, so like linkd a fresh id is assigned to the
+    /// request and every response to it carries that id.
+    async fn linkd(socket: &Path, urn: Urn, rev: ext::Oid, previous: ext::Oid) {
+        let listener = tokio::net::UnixListener::bind(socket).unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut transport = SocketTransport::from(stream);
+        let request = transport.recv_request().await.unwrap().unwrap();
+        assert_eq!(request.mode, RequestMode::ReportProgress);
+        match request.payload {
+            RequestPayload::Announce(announce) => {
+                assert_eq!(announce.urn, urn);
+                assert_eq!(announce.rev, rev);
+                assert_eq!(announce.previous, Some(previous));
+            },
+            other => panic!("expected an announcement, got {:?}", other),
+        }
+        let request_id = RequestId::default();
+        for payload in [
+            ResponsePayload::Ack,
+            ResponsePayload::Success(announce::Response),
+        ] {
+            transport
+                .send_response(Response {
+                    request_id: request_id.clone(),
+                    payload,
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn continues_past_failing_socket() {
+        let tmp = tempfile::tempdir().unwrap();
+        let missing = tmp.path().join("missing.sock");
+        let present = tmp.path().join("present.sock");
+        let oid = git2::Oid::hash_object(git2::ObjectType::Blob, b"announce").unwrap();
+        let previous = git2::Oid::hash_object(git2::ObjectType::Blob, b"previous").unwrap();
+        let urn = Urn::new(oid.into());
+        let server = tokio::spawn({
+            let present = present.clone();
+            let urn = urn.clone();
+            async move { linkd(&present, urn, oid.into(), previous.into()).await }
+        });
+        while !present.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let config = hooks::Announce {
+            rpc_socket_paths: vec![missing.clone(), present.clone()],
+            on_unreachable: hooks::OnUnreachable::Warn,
+        };
+        let mut messages = Messages::default();
+        let result = hooks::announce(
+            &mut messages,
+            &config,
+            urn,
+            oid.into(),
+            Some(previous.into()),
+        )
+        .await;
+        server.await.unwrap();
+
+        assert!(matches!(
+            result,
+            Err(hooks::error::Announce::Failed { ref failed, total: 2 }) if failed == &[missing.clone()]
+        ));
+        let messages = messages.0;
+        assert!(messages
+            .iter()
+            .any(|m| m.starts_with(&format!("failed to announce to `{}`", missing.display()))));
+        assert!(messages.contains(&format!(
+            "successfully announced refs to `{}`",
+            present.display()
+        )));
+        assert!(messages.contains(&"announced to 1 of 2 linkd nodes".to_string()));
+    }
+}
+
+mod update_cobs {
+    use std::{str::FromStr as _, sync::Arc};
+
+    use gitd_lib::hooks;
+    use it_helpers::fixed::TestProject;
+    use librad::{
+        collaborative_objects::{ObjectId, TypeName},
+        git::{
+            refs::Refs,
+            storage::{self, pool, Storage},
+            types::{Namespace, Reference},
+            Urn,
+        },
+        paths::Paths,
+        SecretKey,
+    };
+
+    use super::Messages;
+
+    fn cob_reference(urn: &Urn, commit: git2::Oid) -> String {
+        Reference::rad_collaborative_object(
+            Namespace::from(urn),
+            None,
+            TypeName::from_str("xyz.rad.issue").unwrap(),
+            ObjectId::from(commit),
+        )
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn peels_pushed_tags() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = Paths::from_root(tmp.path()).unwrap();
+        let signer = SecretKey::new();
+        let storage = Storage::open(&paths, signer.clone()).unwrap();
+        let urn = TestProject::create(&storage).unwrap().project.urn();
+        let pool = Arc::new(storage::Pool::new(
+            pool::ReadWriteConfig::new(paths.clone(), signer, pool::Initialised::no()),
+            1,
+        ));
+
+        let pre_receive = hooks::PreReceive::load(&storage, &urn, true).unwrap();
+
+        // Simulate a push which wrote an annotated tag of the change commit to
+        // the object's reference.
+        let repo = git2::Repository::open(paths.git_dir()).unwrap();
+        let sig = git2::Signature::now("gitd", "gitd@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let commit = repo.commit(None, &sig, &sig, "change", &tree, &[]).unwrap();
+        let tag = repo
+            .tag(
+                "change",
+                &repo.find_object(commit, None).unwrap(),
+                &sig,
+                "change",
+                false,
+            )
+            .unwrap();
+        let reference = cob_reference(&urn, commit);
+        repo.reference(&reference, tag, true, "push").unwrap();
+
+        let spawner = Arc::new(link_async::Spawner::from_current().unwrap());
+        let mut messages = Messages::default();
+        hooks::update_cobs(&mut messages, spawner, pool, None, urn, &pre_receive)
+            .await
+            .unwrap();
+
+        assert_eq!(repo.refname_to_id(&reference).unwrap(), commit);
+        assert!(messages
+            .0
+            .contains(&"updated 1 collaborative object references".to_string()));
+    }
+
+    #[tokio::test]
+    async fn replaces_pushed_symbolic_refs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = Paths::from_root(tmp.path()).unwrap();
+        let signer = SecretKey::new();
+        let storage = Storage::open(&paths, signer.clone()).unwrap();
+        let urn = TestProject::create(&storage).unwrap().project.urn();
+        let pool = Arc::new(storage::Pool::new(
+            pool::ReadWriteConfig::new(paths.clone(), signer, pool::Initialised::no()),
+            1,
+        ));
+
+        let pre_receive = hooks::PreReceive::load(&storage, &urn, true).unwrap();
+
+        // Simulate a push which made the object's reference a symbolic
+        // reference to another reference pointing at the change commit.
+        let repo = git2::Repository::open(paths.git_dir()).unwrap();
+        let sig = git2::Signature::now("gitd", "gitd@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let commit = repo.commit(None, &sig, &sig, "change", &tree, &[]).unwrap();
+        let target = format!("refs/namespaces/{}/refs/heads/change", urn.encode_id());
+        repo.reference(&target, commit, true, "push").unwrap();
+        let reference = cob_reference(&urn, commit);
+        repo.reference_symbolic(&reference, &target, true, "push")
+            .unwrap();
+
+        let spawner = Arc::new(link_async::Spawner::from_current().unwrap());
+        let mut messages = Messages::default();
+        hooks::update_cobs(
+            &mut messages,
+            spawner,
+            pool,
+            None,
+            urn.clone(),
+            &pre_receive,
+        )
+        .await
+        .unwrap();
+
+        let updated = repo.find_reference(&reference).unwrap();
+        assert_eq!(updated.kind(), Some(git2::ReferenceType::Direct));
+        assert_eq!(updated.target(), Some(commit));
+        assert_eq!(repo.refname_to_id(&target).unwrap(), commit);
+        let signed = Refs::load(&storage, &urn, None).unwrap().unwrap();
+        assert!(signed.cobs().any(|(_, oid)| git2::Oid::from(oid) == commit));
+    }
+
+    #[tokio::test]
+    async fn signs_pushed_commits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = Paths::from_root(tmp.path()).unwrap();
+        let signer = SecretKey::new();
+        let storage = Storage::open(&paths, signer.clone()).unwrap();
+        let urn = TestProject::create(&storage).unwrap().project.urn();
+        let pool = Arc::new(storage::Pool::new(
+            pool::ReadWriteConfig::new(paths.clone(), signer, pool::Initialised::no()),
+            1,
+        ));
+
+        let repo = git2::Repository::open(paths.git_dir()).unwrap();
+        let sig = git2::Signature::now("gitd", "gitd@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let root = repo.commit(None, &sig, &sig, "create", &tree, &[]).unwrap();
+        let reference = cob_reference(&urn, root);
+        repo.reference(&reference, root, true, "create").unwrap();
+        let pre_receive = hooks::PreReceive::load(&storage, &urn, true).unwrap();
+
+        // Simulate a normal push of a new change on top of the object.
+        let change = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "change",
+                &tree,
+                &[&repo.find_commit(root).unwrap()],
+            )
+            .unwrap();
+        repo.reference(&reference, change, true, "push").unwrap();
+
+        let spawner = Arc::new(link_async::Spawner::from_current().unwrap());
+        let mut messages = Messages::default();
+        hooks::update_cobs(
+            &mut messages,
+            spawner,
+            pool,
+            None,
+            urn.clone(),
+            &pre_receive,
+        )
+        .await
+        .unwrap();
+
+        assert!(messages
+            .0
+            .contains(&"updated 1 collaborative object references".to_string()));
+        let signed = Refs::load(&storage, &urn, None).unwrap().unwrap();
+        assert!(signed.cobs().any(|(_, oid)| git2::Oid::from(oid) == change));
+    }
+}
@@ -0,0 +1,17 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gitd_lib::metrics::Stats;
+
+#[test]
+fn counts_requests_and_denials() {
+    let stats = Stats::default();
+    let shared = stats.clone();
+    shared.connection();
+    shared.git_request(false);
+    shared.git_request(true);
+
+    assert_eq!(stats.connections_total(), 1);
+    assert_eq!(stats.git_requests_total(), 2);
+    assert_eq!(stats.git_requests_denied_total(), 1);
+}
@@ -0,0 +1,31 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::time::Duration;
+
+use gitd_lib::pool;
+use librad::{
+    git::storage::{self, pool::ReadWriteConfig, Storage},
+    paths::Paths,
+    SecretKey,
+};
+
+#[tokio::test]
+async fn busy_when_exhausted() {
+    let tmp = tempfile::tempdir().unwrap();
+    let paths = Paths::from_root(tmp.path()).unwrap();
+    let signer = SecretKey::new();
+    let pool = storage::Pool::<Storage>::new(
+        ReadWriteConfig::new(paths, signer, storage::pool::Initialised::no()),
+        1,
+    );
+    let timeout = Some(Duration::from_millis(50));
+
+    let first = pool::get(&pool, timeout).await.unwrap();
+    let second = pool::get(&pool, timeout).await;
+    assert!(matches!(second, Err(pool::Error::Busy { .. })));
+    assert!(second.unwrap_err().to_string().contains("server busy"));
+
+    drop(first);
+    assert!(pool::get(&pool, timeout).await.is_ok());
+}
@@ -0,0 +1,202 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{fs, net::SocketAddr, sync::Arc, time::Duration};
+
+use gitd_lib::config::{Config, GitLimits, Network, UrnPolicy};
+use it_helpers::fixed::TestProject;
+use librad::{
+    git::{storage::Storage, Urn},
+    paths::Paths,
+    PeerId,
+    SecretKey,
+};
+use link_async::Spawner;
+use lnk_thrussh as thrussh;
+use lnk_thrussh_keys as thrussh_keys;
+
+struct Client;
+
+impl thrussh::client::Handler for Client {
+    type Error = thrussh::Error;
+    type FutureBool = futures::future::Ready<Result<(Self, bool), Self::Error>>;
+    type FutureUnit = futures::future::Ready<Result<(Self, thrussh::client::Session), Self::Error>>;
+
+    fn finished_bool(self, b: bool) -> Self::FutureBool {
+        futures::future::ready(Ok((self, b)))
+    }
+
+    fn finished(self, session: thrussh::client::Session) -> Self::FutureUnit {
+        futures::future::ready(Ok((self, session)))
+    }
+
+    fn check_server_key(self, _key: &thrussh_keys::key::PublicKey) -> Self::FutureBool {
+        self.finished_bool(true)
+    }
+}
+
+/// What the server replied to an exec request before either side gave up.
+#[derive(Debug, Default)]
+struct Reply {
+    stdout: Vec<u8>,
+    stderr: String,
+}
+
+/// Start a gitd server for `key`, only accepting pushes to `allowed`.
+async fn gitd(
+    tmp: &tempfile::TempDir,
+    key: SecretKey,
+    allowed: &Urn,
+) -> (SocketAddr, link_async::Task<()>) {
+    let urns = tmp.path().join("allowed");
+    fs::write(&urns, format!("{}\n", allowed)).unwrap();
+
+    let addr = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    let config = Config {
+        paths: Paths::from_root(tmp.path()).unwrap(),
+        signer: key,
+        addr: Some(addr),
+        linger_timeout: None,
+        health_addr: None,
+        graphite_addr: None,
+        network: Network {
+            announce: None,
+            request_pull: false,
+            replicate: false,
+            update_cobs: false,
+            addr_family: Default::default(),
+        },
+        urn_policy: UrnPolicy::Allow(urns),
+        git_limits: GitLimits::default(),
+        authorizer: None,
+        host_key_dir: Some(tmp.path().join("host-keys")),
+    };
+    let spawner = Arc::new(Spawner::from_current().unwrap());
+    let task = spawner.spawn({
+        let spawner = spawner.clone();
+        async move { gitd_lib::run(config, spawner).await.unwrap() }
+    });
+    (addr, task)
+}
+
+/// Connect to `addr` as `key` and exec `command`, collecting the reply until
+/// the channel closes or some output arrives on stdout.
+async fn exec(addr: SocketAddr, key: &SecretKey, command: String) -> Reply {
+    let mut session = loop {
+        let config = Arc::new(thrussh::client::Config::default());
+        match thrussh::client::connect(config, addr, Client).await {
+            Ok(session) => break session,
+            Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+        }
+    };
+    let keypair = {
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(key.as_ref());
+        bytes[32..].copy_from_slice(PeerId::from(key).as_ref());
+        thrussh_keys::key::KeyPair::Ed25519(thrussh_keys::key::ed25519::SecretKey { key: bytes })
+    };
+    assert!(session
+        .authenticate_publickey("git", Arc::new(keypair))
+        .await
+        .unwrap());
+
+    let mut channel = session.channel_open_session().await.unwrap();
+    channel.exec(true, command).await.unwrap();
+    let mut reply = Reply::default();
+    while let Some(msg) = channel.wait().await {
+        match msg {
+            thrussh::ChannelMsg::Data { data } => {
+                reply.stdout.extend_from_slice(&data);
+                break;
+            },
+            thrussh::ChannelMsg::ExtendedData { data, .. } => {
+                reply.stderr.push_str(&String::from_utf8_lossy(&data))
+            },
+            thrussh::ChannelMsg::Close => break,
+            _ => {},
+        }
+    }
+    reply
+}
+
+fn receive_pack(urn: &Urn) -> String {
+    format!("git-receive-pack '{}.git'", urn)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn push_allowed_urn() {
+    let tmp = tempfile::tempdir().unwrap();
+    let key = SecretKey::new();
+    let urn = {
+        let storage = Storage::open(&Paths::from_root(tmp.path()).unwrap(), key.clone()).unwrap();
+        TestProject::create(&storage).unwrap().project.urn()
+    };
+    let (addr, _gitd) = gitd(&tmp, key.clone(), &urn).await;
+
+    let reply = exec(addr, &key, receive_pack(&urn)).await;
+    assert!(
+        reply.stderr.is_empty(),
+        "unexpected error: {}",
+        reply.stderr
+    );
+    assert!(!reply.stdout.is_empty(), "expected a ref advertisement");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn push_denied_urn() {
+    let tmp = tempfile::tempdir().unwrap();
+    let key = SecretKey::new();
+    let (allowed, denied) = {
+        let storage = Storage::open(&Paths::from_root(tmp.path()).unwrap(), key.clone()).unwrap();
+        let allowed = TestProject::create(&storage).unwrap().project.urn();
+        let denied = Urn::new(
+            git2::Oid::hash_object(git2::ObjectType::Blob, b"denied")
+                .unwrap()
+                .into(),
+        );
+        (allowed, denied)
+    };
+    let (addr, _gitd) = gitd(&tmp, key.clone(), &allowed).await;
+
+    let reply = exec(addr, &key, receive_pack(&denied)).await;
+    assert!(reply.stdout.is_empty());
+    assert!(
+        reply.stderr.contains("is not allowed by this server"),
+        "unexpected reply: {}",
+        reply.stderr
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn remote_peer_is_authorized_as_itself() {
+    let tmp = tempfile::tempdir().unwrap();
+    let key = SecretKey::new();
+    let urn = {
+        let storage = Storage::open(&Paths::from_root(tmp.path()).unwrap(), key.clone()).unwrap();
+        TestProject::create(&storage).unwrap().project.urn()
+    };
+    let (addr, _gitd) = gitd(&tmp, key, &urn).await;
+    let stranger = SecretKey::new();
+
+    let reply = exec(addr, &stranger, format!("git-upload-pack '{}.git'", urn)).await;
+    assert!(
+        reply.stderr.is_empty(),
+        "unexpected error: {}",
+        reply.stderr
+    );
+    assert!(!reply.stdout.is_empty(), "expected a ref advertisement");
+
+    let reply = exec(addr, &stranger, receive_pack(&urn)).await;
+    assert!(reply.stdout.is_empty());
+    assert!(
+        reply.stderr.contains(&format!(
+            "{} is neither tracked for",
+            PeerId::from(&stranger)
+        )),
+        "unexpected reply: {}",
+        reply.stderr
+    );
+}
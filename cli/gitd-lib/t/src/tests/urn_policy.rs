@@ -0,0 +1,62 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::fs;
+
+use gitd_lib::urn_policy::{UrnFilter, UrnPolicy};
+use librad::git::Urn;
+
+fn urn(name: &str) -> Urn {
+    Urn::new(
+        git2::Oid::hash_object(git2::ObjectType::Blob, name.as_bytes())
+            .unwrap()
+            .into(),
+    )
+}
+
+#[test]
+fn allow_all() {
+    let filter = UrnFilter::load(UrnPolicy::AllowAll).unwrap();
+    assert!(filter.allows(&urn("a")));
+}
+
+#[test]
+fn allow_list() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("allowed");
+    fs::write(&path, format!("# allowed projects\n{}\n\n", urn("a"))).unwrap();
+
+    let filter = UrnFilter::load(UrnPolicy::Allow(path)).unwrap();
+    assert!(filter.allows(&urn("a")));
+    assert!(filter.allows(&urn("a").with_path(librad::reflike!("refs/heads/main"))));
+    assert!(!filter.allows(&urn("b")));
+}
+
+#[test]
+fn deny_list() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("denied");
+    fs::write(&path, format!("{}\n", urn("a"))).unwrap();
+
+    let filter = UrnFilter::load(UrnPolicy::Deny(path)).unwrap();
+    assert!(!filter.allows(&urn("a")));
+    assert!(filter.allows(&urn("b")));
+}
+
+#[test]
+fn reload() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("denied");
+    fs::write(&path, format!("{}\n", urn("a"))).unwrap();
+
+    let filter = UrnFilter::load(UrnPolicy::Deny(path.clone())).unwrap();
+    fs::write(&path, format!("{}\n", urn("b"))).unwrap();
+    filter.reload().unwrap();
+    assert!(filter.allows(&urn("a")));
+    assert!(!filter.allows(&urn("b")));
+
+    // An invalid file keeps the previous list
+    fs::write(&path, "not a urn\n").unwrap();
+    assert!(filter.reload().is_err());
+    assert!(!filter.allows(&urn("b")));
+}
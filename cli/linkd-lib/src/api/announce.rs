@@ -7,6 +7,10 @@ pub struct Request {
     pub urn: Urn,
     #[n(1)]
     pub rev: Oid,
+    /// The tip `rev` replaced, if any. Requests from clients which predate
+    /// this field decode with `None`.
+    #[n(2)]
+    pub previous: Option<Oid>,
 }
 
 impl Request {
@@ -15,6 +19,7 @@ impl Request {
             urn: self.urn,
             rev: Some(self.rev.into()),
             origin: Some(peer),
+            previous: self.previous.map(Into::into),
         }
     }
 }
@@ -259,10 +259,21 @@ where
 impl Command<announce::Request, announce::Response> {
     pub fn announce(urn: Urn, rev: Oid) -> Self {
         Self {
-            payload: announce::Request { urn, rev },
+            payload: announce::Request {
+                urn,
+                rev,
+                previous: None,
+            },
             _marker: PhantomData,
         }
     }
+
+    /// Also announce the tip which `rev` replaced, so that receivers can
+    /// determine what changed.
+    pub fn previous(mut self, previous: Oid) -> Self {
+        self.payload.previous = Some(previous);
+        self
+    }
 }
 
 impl Command<request_pull::Request, request_pull::Response> {
@@ -327,7 +327,7 @@ impl Listener<request_pull::Response> {
                                 .await;
                             break;
                         },
-                        Ok(Response::Error(Error { message })) => {
+                        Ok(Response::Error(Error { message, .. })) => {
                             self.error(format!("request-pull failed: {message}")).await;
                             break;
                         },
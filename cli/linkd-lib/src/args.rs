@@ -17,11 +17,15 @@ use librad::{
     profile::{LnkHome, ProfileId},
     PeerId,
 };
-use lnk_clib::{keys::ssh::SshAuthSock, seed::Seed};
+use lnk_clib::{
+    keys::ssh::SshAuthSock,
+    seed::{self, Seed},
+};
 
 use crate::tracking;
 
 #[derive(Debug, Default, Eq, PartialEq, Parser)]
+#[clap(subcommand_negates_reqs = true)]
 pub struct Args {
     /// Usage: `--bootstrap <peer1>@<addr1>[,<label1>] --bootstrap
     /// <peer2>@<addr2>[,<label2>]`
@@ -68,6 +72,9 @@ pub struct Args {
     #[clap(long)]
     pub tmp_root: bool,
 
+    #[clap(flatten)]
+    pub seeds: SeedsArgs,
+
     #[clap(flatten)]
     pub tracking: TrackingArgs,
 
@@ -79,6 +86,24 @@ pub struct Args {
     /// shutdown.
     #[clap(long)]
     pub linger_timeout: Option<LingerTimeout>,
+
+    /// Run a one-off command against the profile instead of starting the
+    /// node.
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Eq, PartialEq, clap::Subcommand)]
+pub enum Command {
+    /// Print the tracking graph of the profile's storage as canonical JSON.
+    DumpTracking(DumpTrackingArgs),
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Parser)]
+pub struct DumpTrackingArgs {
+    /// Only include the tracking entries of this urn.
+    #[clap(long)]
+    pub urn: Option<Urn>,
 }
 
 #[derive(Debug, Eq, PartialEq, Parser)]
@@ -320,6 +345,29 @@ fn parse_protocol_network(src: &str) -> Result<Network, String> {
     }
 }
 
+/// Policy for the seeds loaded on startup. By default the node will start with
+/// whichever seeds could be loaded.
+#[derive(Debug, Default, Eq, PartialEq, Parser)]
+pub struct SeedsArgs {
+    /// Refuse to start if fewer than this number of seeds could be loaded.
+    #[clap(long, default_value_t)]
+    pub min_seeds: usize,
+
+    /// Refuse to start if the seed with this peer id could not be loaded.
+    /// Argument can be repeated.
+    #[clap(long = "critical-seed", name = "critical-seed")]
+    pub critical: Vec<PeerId>,
+}
+
+impl From<&SeedsArgs> for seed::Policy {
+    fn from(args: &SeedsArgs) -> Self {
+        Self {
+            min_seeds: args.min_seeds,
+            critical: args.critical.iter().copied().collect(),
+        }
+    }
+}
+
 #[derive(Debug, Default, Eq, PartialEq, Parser)]
 pub struct TrackingArgs {
     /// Instruct the node to automatically track either everything it observes
@@ -392,12 +440,19 @@ pub struct RequestPullStorage {
     /// Number of [`librad::git::storage::Storage`] instances to reserve.
     #[clap(long = "request-pull-pool-size", default_value_t = num_cpus::get_physical())]
     pub pool_size: usize,
+
+    /// Only allow request-pulls from peers which are tracked for the requested
+    /// urn, or for urns which are tracked. In the latter case, the requesting
+    /// peer will be tracked. Takes precedence over `--track`.
+    #[clap(long = "request-pull-tracked-only")]
+    pub tracked_only: bool,
 }
 
 impl Default for RequestPullStorage {
     fn default() -> Self {
         Self {
             pool_size: num_cpus::get_physical(),
+            tracked_only: false,
         }
     }
 }
@@ -75,6 +75,9 @@ pub enum Error {
     #[error(transparent)]
     Seed(#[from] seed::error::Load),
 
+    #[error(transparent)]
+    SeedsPolicy(#[from] seed::error::Policy),
+
     #[error(transparent)]
     Timeout(#[from] Elapsed),
 }
@@ -129,6 +132,7 @@ impl Cfg<discovery::Static, BoxedSigner, request_pull::State> {
 
             seeds
         };
+        seed::Policy::from(&args.seeds).check(&seeds)?;
         let disco = discovery::Static::try_from(seeds)?;
         let signer = construct_signer(args, &profile).await?;
 
@@ -178,6 +182,7 @@ impl Cfg<discovery::Static, BoxedSigner, request_pull::State> {
                 args.request_pull.pool_size,
             ),
             tracker.clone(),
+            args.request_pull.tracked_only,
         );
 
         Ok(Self {
@@ -194,6 +199,7 @@ impl Cfg<discovery::Static, BoxedSigner, request_pull::State> {
                     replication: Default::default(),
                     rate_limits: Default::default(),
                     request_pull,
+                    caches: Default::default(),
                 },
                 storage: Default::default(),
             },
@@ -15,11 +15,7 @@ use librad::{
     net::{peer::Peer, protocol::RequestPullGuard},
     Signer,
 };
-
-const CONNECTIONS_TOTAL: &str = "connections_total";
-const CONNECTED_PEERS: &str = "connected_peers";
-const MEMBERSHIP_ACTIVE: &str = "membership_active";
-const MEMBERSHIP_PASSIVE: &str = "membership_passive";
+use lnk_clib::metrics::{graphite_line, linkd, Label};
 
 #[instrument(name = "graphite subroutine", skip(peer))]
 pub async fn routine<S, G>(peer: Peer<S, G>, graphite_addr: SocketAddr) -> anyhow::Result<()>
@@ -34,7 +30,7 @@ where
     sock.connect(graphite_addr).await?;
     debug!("connected to graphite at {}", graphite_addr);
 
-    let peer_id = peer.peer_id().to_string();
+    let labels = [Label::Peer(peer.peer_id())];
     loop {
         time::sleep(Duration::from_secs(10)).await;
 
@@ -42,23 +38,13 @@ where
         let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
 
         for (metric, value) in &[
-            (CONNECTED_PEERS, stats.connected_peers.len()),
-            (CONNECTIONS_TOTAL, stats.connections_total),
-            (MEMBERSHIP_ACTIVE, stats.membership_active),
-            (MEMBERSHIP_PASSIVE, stats.membership_passive),
+            (linkd::CONNECTED_PEERS, stats.connected_peers.len()),
+            (linkd::CONNECTIONS_TOTAL, stats.connections_total),
+            (linkd::MEMBERSHIP_ACTIVE, stats.membership_active),
+            (linkd::MEMBERSHIP_PASSIVE, stats.membership_passive),
         ] {
-            sock.send(line(peer_id.clone(), metric, *value as f32, now).as_bytes())
+            sock.send(graphite_line(metric, &labels, *value as f32, now).as_bytes())
                 .await?;
         }
     }
 }
-
-fn line(peer_id: String, metric: &str, value: f32, time: Duration) -> String {
-    format!(
-        "linkd_{};peer={} {:?} {}",
-        metric,
-        peer_id,
-        value,
-        time.as_secs()
-    )
-}
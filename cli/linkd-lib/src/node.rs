@@ -3,7 +3,7 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{panic, sync::Arc, time::Duration};
+use std::{convert::TryFrom as _, panic, sync::Arc, time::Duration};
 
 use clap::Parser as _;
 use futures::{future::FutureExt as _, stream::FuturesUnordered, StreamExt};
@@ -11,13 +11,16 @@ use tokio::sync::mpsc;
 use tracing::info;
 
 use librad::{
+    canonical::Canonical as _,
     crypto::BoxedSigner,
+    git::storage::ReadOnly,
     net::{discovery, peer::Peer},
+    profile::Profile,
 };
 
 use crate::{
     api,
-    args::Args,
+    args::{Args, Command, DumpTrackingArgs},
     cfg::{self, Cfg, RunMode},
     logging,
     metrics::graphite,
@@ -36,6 +39,9 @@ pub async fn run() -> anyhow::Result<()> {
     let spawner = Arc::new(link_async::Spawner::from_current().unwrap());
 
     let args = Args::parse();
+    if let Some(Command::DumpTracking(dump)) = &args.command {
+        return dump_tracking(&args, dump);
+    }
     let cfg: Cfg<discovery::Static, BoxedSigner, request_pull::State> = cfg(&args).await?;
 
     let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
@@ -99,6 +105,14 @@ pub async fn run() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn dump_tracking(args: &Args, dump: &DumpTrackingArgs) -> anyhow::Result<()> {
+    let profile = Profile::try_from(args)?;
+    let storage = ReadOnly::open(profile.paths())?;
+    let graph = tracking::graph(&storage, dump.urn.as_ref())?;
+    println!("{}", String::from_utf8(graph.canonical_form()?)?);
+    Ok(())
+}
+
 #[cfg(unix)]
 async fn cfg(
     args: &Args,
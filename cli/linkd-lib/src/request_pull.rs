@@ -1,13 +1,15 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::fmt;
-
 use thiserror::Error;
 
 use librad::{
     git::{storage, tracking, Urn},
-    net::protocol::request_pull::Guard,
+    net::protocol::request_pull::{
+        guard::{self, Tracked},
+        Guard,
+        TrackingGuard,
+    },
     PeerId,
 };
 
@@ -17,14 +19,20 @@ use crate::tracking::Tracker;
 pub struct State {
     storage: storage::Pool<storage::Storage>,
     tracker: Option<Tracker>,
+    tracked_only: Option<TrackingGuard<storage::Pool<storage::Storage>>>,
 }
 
 impl State {
+    /// If `tracked_only` is `true`, request-pulls are only allowed if the
+    /// requesting peer or the URN is already tracked, see [`TrackingGuard`].
+    /// Otherwise, the `tracker` decides.
     pub fn new(
         storage: storage::Pool<storage::Storage>,
         tracker: impl Into<Option<Tracker>>,
+        tracked_only: bool,
     ) -> Self {
         State {
+            tracked_only: tracked_only.then(|| TrackingGuard::new(storage.clone())),
             storage,
             tracker: tracker.into(),
         }
@@ -39,31 +47,22 @@ pub enum Error {
     IsTracked(#[from] tracking::error::IsTracked),
     #[error(transparent)]
     Track(#[from] tracking::error::Track),
+    #[error(transparent)]
+    Tracking(#[from] guard::Error),
     #[error("`{0}` was rejected")]
     Rejected(Urn),
 }
 
-pub struct Tracked {
-    tracked: Option<Result<tracking::Ref, tracking::PreviousError>>,
-    urn: Urn,
-}
-
-impl fmt::Display for Tracked {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.tracked {
-            None => write!(f, "Already tracking `{}`", self.urn),
-            Some(Ok(reference)) => write!(f, "Tracked `{}`", reference.name),
-            Some(Err(previous)) => write!(f, "Attempted to track `{}`: {}", self.urn, previous),
-        }
-    }
-}
-
 impl Guard for State {
     type Error = Error;
 
     type Output = Tracked;
 
     fn guard(&self, peer: &PeerId, urn: &Urn) -> Result<Self::Output, Self::Error> {
+        if let Some(guard) = &self.tracked_only {
+            return Ok(guard.guard(peer, urn)?);
+        }
+
         match &self.tracker {
             Some(tracker) => {
                 if tracker.guard(peer, urn).unwrap() {
@@ -76,15 +75,9 @@ impl Guard for State {
                             tracking::Config::default(),
                             tracking::policy::Track::MustNotExist,
                         )?;
-                        Ok(Tracked {
-                            tracked: Some(tracked),
-                            urn: urn.clone(),
-                        })
+                        Ok(Tracked::new(urn.clone(), Some(tracked)))
                     } else {
-                        Ok(Tracked {
-                            tracked: None,
-                            urn: urn.clone(),
-                        })
+                        Ok(Tracked::new(urn.clone(), None))
                     }
                 } else {
                     Err(Error::Rejected(urn.clone()))
@@ -93,4 +86,11 @@ impl Guard for State {
             None => Err(Error::Rejected(urn.clone())),
         }
     }
+
+    fn is_internal(&self, error: &Self::Error) -> bool {
+        !matches!(
+            error,
+            Error::Rejected(_) | Error::Tracking(guard::Error::Untracked { .. })
+        )
+    }
 }
@@ -3,7 +3,11 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{collections::BTreeSet, convert::Infallible, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    str::FromStr,
+};
 
 use futures::{pin_mut, StreamExt as _};
 use radicle_git_ext::FromMultihashError;
@@ -11,7 +15,8 @@ use thiserror::Error;
 use tracing::{error, info, instrument, trace};
 
 use librad::{
-    git::{tracking, Urn},
+    canonical::json::{ToCjson, Value},
+    git::{storage::ReadOnly, tracking, Urn},
     net::{
         peer::{event::upstream::Gossip, Peer, PeerInfo, ProtocolEvent},
         protocol::{
@@ -215,6 +220,44 @@ impl Tracker {
     }
 }
 
+/// Snapshot the tracking graph of `storage`, grouped by URN.
+///
+/// The result is an object keyed by URN, where each entry holds the
+/// `default` config for the URN, or `null` if there is none, and a `peers`
+/// object mapping each tracked peer to its config. If `filter_by` is given,
+/// only that URN is included.
+pub fn graph(
+    storage: &ReadOnly,
+    filter_by: Option<&Urn>,
+) -> Result<Value, tracking::error::Tracked> {
+    let mut graph =
+        BTreeMap::<Urn, (Option<tracking::Config>, BTreeMap<PeerId, tracking::Config>)>::new();
+    for tracked in tracking::tracked(storage, filter_by)? {
+        match tracked? {
+            tracking::Tracked::Default { urn, config } => {
+                graph.entry(urn).or_default().0 = Some(config);
+            },
+            tracking::Tracked::Peer { urn, peer, config } => {
+                graph.entry(urn).or_default().1.insert(peer, config);
+            },
+        }
+    }
+
+    Ok(graph
+        .into_iter()
+        .map(|(urn, (default, peers))| {
+            let peers = peers
+                .into_iter()
+                .map(|(peer, config)| (peer.to_string(), config))
+                .collect::<Value>();
+            let entry = vec![("default", default.into_cjson()), ("peers", peers)]
+                .into_iter()
+                .collect::<Value>();
+            (urn.to_string(), entry)
+        })
+        .collect())
+}
+
 #[instrument(name = "tracking subroutine", skip(peer, tracker))]
 pub async fn routine<S, G>(peer: Peer<S, G>, tracker: Tracker) -> anyhow::Result<()>
 where
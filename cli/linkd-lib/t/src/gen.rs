@@ -28,8 +28,12 @@ pub fn request_mode() -> impl Strategy<Value = messages::RequestMode> {
 }
 
 pub fn announce() -> impl Strategy<Value = announce::Request> {
-    gen_oid(git2::ObjectType::Commit)
-        .prop_flat_map(move |rev| gen_urn().prop_map(move |urn| announce::Request { urn, rev }))
+    (
+        gen_urn(),
+        gen_oid(git2::ObjectType::Commit),
+        proptest::option::of(gen_oid(git2::ObjectType::Commit)),
+    )
+        .prop_map(|(urn, rev, previous)| announce::Request { urn, rev, previous })
 }
 
 pub fn request_pull(addrs: Vec<SocketAddr>) -> impl Strategy<Value = request_pull::Request> {
@@ -0,0 +1,94 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use librad::{
+    git::Urn,
+    git_ext::Oid,
+    net::protocol::gossip::{self, Rev},
+    PeerId,
+    SecretKey,
+};
+use linkd_lib::api::announce;
+use proptest::prelude::*;
+
+use crate::gen;
+
+/// An announce request as encoded before `previous` was added.
+#[derive(Debug, PartialEq, minicbor::Decode, minicbor::Encode)]
+struct Legacy {
+    #[n(0)]
+    urn: Urn,
+    #[n(1)]
+    rev: Oid,
+}
+
+fn oid(name: &str) -> Oid {
+    git2::Oid::hash_object(git2::ObjectType::Commit, name.as_bytes())
+        .unwrap()
+        .into()
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(request in gen::announce()) {
+        let bytes = minicbor::to_vec(&request).unwrap();
+        let decoded: announce::Request = minicbor::decode(&bytes).unwrap();
+        prop_assert_eq!(request, decoded);
+    }
+}
+
+#[test]
+fn decodes_legacy_request() {
+    let legacy = Legacy {
+        urn: Urn::new(oid("urn")),
+        rev: oid("rev"),
+    };
+    let request: announce::Request = minicbor::decode(&minicbor::to_vec(&legacy).unwrap()).unwrap();
+    assert_eq!(
+        request,
+        announce::Request {
+            urn: legacy.urn,
+            rev: legacy.rev,
+            previous: None,
+        }
+    );
+}
+
+#[test]
+fn legacy_decodes_request() {
+    let request = announce::Request {
+        urn: Urn::new(oid("urn")),
+        rev: oid("rev"),
+        previous: Some(oid("previous")),
+    };
+    let legacy: Legacy = minicbor::decode(&minicbor::to_vec(&request).unwrap()).unwrap();
+    assert_eq!(
+        legacy,
+        Legacy {
+            urn: request.urn,
+            rev: request.rev,
+        }
+    );
+}
+
+#[test]
+fn previous_is_gossiped() {
+    let peer = PeerId::from(SecretKey::new());
+    let request = announce::Request {
+        urn: Urn::new(oid("urn")),
+        rev: oid("rev"),
+        previous: Some(oid("previous")),
+    };
+    let payload = request.clone().into_gossip(peer);
+    assert_eq!(
+        payload,
+        gossip::Payload {
+            urn: request.urn,
+            rev: Some(Rev::Git(oid("rev").into())),
+            origin: Some(peer),
+            previous: Some(Rev::Git(oid("previous").into())),
+        }
+    );
+}
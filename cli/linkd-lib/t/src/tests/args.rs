@@ -25,6 +25,7 @@ use linkd_lib::args::{
     MetricsProvider,
     ProtocolArgs,
     ProtocolListen,
+    SeedsArgs,
     Signer,
     TrackingArgs,
     TrackingMode,
@@ -419,3 +420,26 @@ fn tracking() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn seeds_policy() -> Result<()> {
+    #[rustfmt::skip]
+    let parsed = Args::try_parse_from(vec![
+        "linkd",
+            "--protocol-listen", "localhost",
+            "--min-seeds", "2",
+            "--critical-seed", "hynkyndc6w3p8urucakobzna7sxwgcqny7xxtw88dtx3pkf7m3nrzc",
+    ])?;
+    assert_eq!(
+        parsed,
+        Args {
+            seeds: SeedsArgs {
+                min_seeds: 2,
+                critical: vec!["hynkyndc6w3p8urucakobzna7sxwgcqny7xxtw88dtx3pkf7m3nrzc".parse()?],
+            },
+            ..Default::default()
+        }
+    );
+
+    Ok(())
+}
@@ -1,7 +1,18 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use linkd_lib::tracking::{Pair, Selected};
+use librad::{
+    canonical::json::{ToCjson as _, Value},
+    git::{
+        storage::Storage,
+        tracking::{policy, track, Config},
+        Urn,
+    },
+    paths::Paths,
+    PeerId,
+    SecretKey,
+};
+use linkd_lib::tracking::{graph, Pair, Selected};
 
 #[test]
 pub fn selected_dedups() {
@@ -17,3 +28,73 @@ pub fn selected_dedups() {
     assert!(selected.urns().next().is_none());
     assert_eq!(selected.pairs().next(), Some(&pair));
 }
+
+#[test]
+pub fn graph_groups_by_urn() {
+    let tmp = tempfile::tempdir().unwrap();
+    let paths = Paths::from_root(tmp.path()).unwrap();
+    let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+
+    let peer1 = PeerId::from(SecretKey::new());
+    let peer2 = PeerId::from(SecretKey::new());
+    let urn1 = Urn::new(
+        git2::Oid::hash_object(git2::ObjectType::Blob, b"urn1")
+            .unwrap()
+            .into(),
+    );
+    let urn2 = Urn::new(
+        git2::Oid::hash_object(git2::ObjectType::Blob, b"urn2")
+            .unwrap()
+            .into(),
+    );
+    for (urn, peer) in [
+        (&urn1, None),
+        (&urn1, Some(peer1)),
+        (&urn1, Some(peer2)),
+        (&urn2, Some(peer2)),
+    ] {
+        track(&storage, urn, peer, Config::default(), policy::Track::Any)
+            .unwrap()
+            .unwrap();
+    }
+
+    let config = || Config::default().into_cjson();
+    let urn1_entry = vec![
+        ("default", config()),
+        (
+            "peers",
+            vec![(peer1.to_string(), config()), (peer2.to_string(), config())]
+                .into_iter()
+                .collect::<Value>(),
+        ),
+    ]
+    .into_iter()
+    .collect::<Value>();
+    let urn2_entry = vec![
+        ("default", Value::Null),
+        (
+            "peers",
+            vec![(peer2.to_string(), config())]
+                .into_iter()
+                .collect::<Value>(),
+        ),
+    ]
+    .into_iter()
+    .collect::<Value>();
+
+    assert_eq!(
+        graph(storage.read_only(), None).unwrap(),
+        vec![
+            (urn1.to_string(), urn1_entry.clone()),
+            (urn2.to_string(), urn2_entry),
+        ]
+        .into_iter()
+        .collect::<Value>()
+    );
+    assert_eq!(
+        graph(storage.read_only(), Some(&urn1)).unwrap(),
+        vec![(urn1.to_string(), urn1_entry)]
+            .into_iter()
+            .collect::<Value>()
+    );
+}
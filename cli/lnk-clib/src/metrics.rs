@@ -0,0 +1,136 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+//! Metric definitions shared between the binaries, so that dashboards can be
+//! built across them using the same vocabulary.
+//!
+//! # Naming
+//!
+//! A metric name is `<namespace>_<name>`, e.g. `linkd_connected_peers`, where
+//! the namespace identifies the binary emitting the metric. Both components
+//! consist of lower-case ascii letters, digits, and underscores, and start
+//! with a letter. Counters which only ever increase are suffixed with
+//! `_total`.
+//!
+//! # Labels
+//!
+//! Labels are attached to a metric as `key=value` pairs. Every distinct set
+//! of label values results in a new time series, so labels MUST be of low
+//! cardinality. Labelling by the [`PeerId`] of the emitting node is fine,
+//! while labelling by something which grows with the data stored, such as a
+//! label per URN, is not.
+
+use std::{fmt, time::Duration};
+
+use librad::PeerId;
+
+/// The binary a [`Metric`] is emitted by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Namespace {
+    Linkd,
+    Gitd,
+}
+
+impl Namespace {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Linkd => "linkd",
+            Self::Gitd => "gitd",
+        }
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Metric {
+    pub namespace: Namespace,
+    pub name: &'static str,
+}
+
+impl Metric {
+    pub const fn new(namespace: Namespace, name: &'static str) -> Self {
+        Self { namespace, name }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.namespace, self.name)
+    }
+}
+
+/// The labels which may be attached to a [`Metric`].
+///
+/// See the [module documentation][self] for why there is no label for URNs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Label {
+    /// The peer emitting the metric.
+    Peer(PeerId),
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Peer(peer) => write!(f, "peer={}", peer),
+        }
+    }
+}
+
+pub mod linkd {
+    use super::{Metric, Namespace::Linkd};
+
+    pub const CONNECTIONS_TOTAL: Metric = Metric::new(Linkd, "connections_total");
+    pub const CONNECTED_PEERS: Metric = Metric::new(Linkd, "connected_peers");
+    pub const MEMBERSHIP_ACTIVE: Metric = Metric::new(Linkd, "membership_active");
+    pub const MEMBERSHIP_PASSIVE: Metric = Metric::new(Linkd, "membership_passive");
+
+    pub const ALL: &[Metric] = &[
+        CONNECTIONS_TOTAL,
+        CONNECTED_PEERS,
+        MEMBERSHIP_ACTIVE,
+        MEMBERSHIP_PASSIVE,
+    ];
+}
+
+pub mod gitd {
+    use super::{Metric, Namespace::Gitd};
+
+    pub const CONNECTIONS_TOTAL: Metric = Metric::new(Gitd, "connections_total");
+    pub const GIT_REQUESTS_TOTAL: Metric = Metric::new(Gitd, "git_requests_total");
+    pub const GIT_REQUESTS_DENIED_TOTAL: Metric = Metric::new(Gitd, "git_requests_denied_total");
+
+    pub const ALL: &[Metric] = &[
+        CONNECTIONS_TOTAL,
+        GIT_REQUESTS_TOTAL,
+        GIT_REQUESTS_DENIED_TOTAL,
+    ];
+}
+
+/// Check that `name` follows the naming convention for the name component of
+/// a [`Metric`], or a full metric name.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !name.ends_with('_')
+        && !name.contains("__")
+}
+
+/// Render a [graphite plaintext] line for `metric`.
+///
+/// [graphite plaintext]: https://graphite.readthedocs.io/en/latest/feeding-carbon.html#the-plaintext-protocol
+pub fn graphite_line(metric: &Metric, labels: &[Label], value: f32, time: Duration) -> String {
+    let mut line = metric.to_string();
+    for label in labels {
+        line.push(';');
+        line.push_str(&label.to_string());
+    }
+    format!("{} {:?} {}", line, value, time.as_secs())
+}
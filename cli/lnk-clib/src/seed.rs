@@ -3,9 +3,9 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{convert::TryFrom, fmt, io, net::SocketAddr, str::FromStr};
+use std::{collections::BTreeSet, convert::TryFrom, fmt, io, net::SocketAddr, str::FromStr};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use librad::{net::discovery, PeerId};
 use tokio::net::{lookup_host, ToSocketAddrs};
@@ -13,7 +13,7 @@ use tokio::net::{lookup_host, ToSocketAddrs};
 pub mod store;
 pub use store::Store;
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Seed<Addrs> {
     /// The identifier for the `Seed`.
     pub peer: PeerId,
@@ -111,6 +111,57 @@ impl Seeds {
         self.0.len()
     }
 
+    /// Add `seed`, merging it with any existing [`Seed`] for the same peer.
+    ///
+    /// When merged, the addresses of `seed` which are not already known are
+    /// appended, and the existing label is kept unless it is `None`. A
+    /// conflicting label is logged as a warning.
+    ///
+    /// Returns `true` if `seed` was for a peer not seen before.
+    pub fn merge(&mut self, seed: Seed<Vec<SocketAddr>>) -> bool {
+        match self
+            .0
+            .iter_mut()
+            .find(|existing| existing.peer == seed.peer)
+        {
+            None => {
+                self.0.push(seed);
+                true
+            },
+            Some(existing) => {
+                for addr in seed.addrs {
+                    if !existing.addrs.contains(&addr) {
+                        existing.addrs.push(addr);
+                    }
+                }
+                match (&existing.label, seed.label) {
+                    (None, label) => existing.label = label,
+                    (Some(ours), Some(theirs)) if ours != &theirs => {
+                        tracing::warn!(
+                            peer = %existing.peer,
+                            label = %ours,
+                            conflicting = %theirs,
+                            "conflicting labels for seed, keeping the first"
+                        )
+                    },
+                    _ => {},
+                }
+                false
+            },
+        }
+    }
+
+    /// Merge any [`Seed`]s for the same peer, see [`Seeds::merge`].
+    ///
+    /// The order in which peers first appear is preserved.
+    pub fn dedup(self) -> Self {
+        let mut deduped = Self(Vec::with_capacity(self.len()));
+        for seed in self.0 {
+            deduped.merge(seed);
+        }
+        deduped
+    }
+
     /// Load and resolve the [`Seeds`] from the given `store`.
     ///
     /// If `cutoff` is given then only that number of seeds will be retrieved
@@ -118,6 +169,9 @@ impl Seeds {
     ///
     /// If any seeds failed to be resolved they will be returned alongside the
     /// successful seeds.
+    ///
+    /// Seeds for the same peer are merged, see [`Seeds::merge`], and count
+    /// once towards the `cutoff`.
     pub async fn load<S, T>(
         store: &S,
         cutoff: impl Into<Option<usize>>,
@@ -128,7 +182,7 @@ impl Seeds {
         T: Clone + fmt::Display + FromStr + ToSocketAddrs,
         T::Err: std::error::Error + Send + Sync + 'static,
     {
-        let mut resolved = Vec::new();
+        let mut resolved = Self(Vec::new());
         let mut failures = Vec::new();
         let cutoff = cutoff.into();
 
@@ -137,9 +191,8 @@ impl Seeds {
                 Err(err) => failures.push(error::Load::MalformedSeed(Box::new(err))),
                 Ok(seed) => match seed.resolve().await {
                     Ok(r) => {
-                        resolved.push(r);
-                        if Some(resolved.len()) == cutoff {
-                            return Ok((Self(resolved), failures));
+                        if resolved.merge(r) && Some(resolved.len()) == cutoff {
+                            return Ok((resolved, failures));
                         }
                     },
                     Err(err) => failures.push(err.into()),
@@ -147,27 +200,30 @@ impl Seeds {
             }
         }
 
-        Ok((Self(resolved), failures))
+        Ok((resolved, failures))
     }
 
     /// Build up the list of [`Seed`]s, resolving their network addresses.
     ///
     /// If any seeds failed to be resolved they will be returned alongside the
-    /// successful seeds.
+    /// successful seeds. Seeds for the same peer are merged, see
+    /// [`Seeds::merge`].
     pub async fn resolve(
         seeds: impl ExactSizeIterator<Item = &Seed<String>>,
     ) -> (Self, Vec<error::Resolve>) {
-        let mut resolved = Vec::with_capacity(seeds.len());
+        let mut resolved = Self(Vec::with_capacity(seeds.len()));
         let mut failures = Vec::new();
 
         for seed in seeds {
             match seed.resolve().await {
-                Ok(r) => resolved.push(r),
+                Ok(r) => {
+                    resolved.merge(r);
+                },
                 Err(err) => failures.push(err),
             }
         }
 
-        (Self(resolved), failures)
+        (resolved, failures)
     }
 }
 
@@ -200,6 +256,51 @@ impl TryFrom<Seeds> for discovery::Static {
     }
 }
 
+/// A policy for deciding whether the [`Seeds`] that were loaded are
+/// sufficient to start a node with.
+///
+/// The [`Default`] policy accepts any set of seeds.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Policy {
+    /// The minimum number of seeds that must have been loaded.
+    pub min_seeds: usize,
+    /// Seeds that must have been loaded. Note that if the seeds were loaded
+    /// with a cutoff, a critical seed past the cutoff is considered missing.
+    pub critical: BTreeSet<PeerId>,
+}
+
+impl Policy {
+    /// Check the loaded `seeds` against this policy.
+    ///
+    /// # Errors
+    ///
+    /// * If fewer than [`Policy::min_seeds`] were loaded.
+    /// * If any of the [`Policy::critical`] seeds were not loaded.
+    pub fn check(&self, seeds: &Seeds) -> Result<(), error::Policy> {
+        if seeds.len() < self.min_seeds {
+            return Err(error::Policy::TooFew {
+                expected: self.min_seeds,
+                loaded: seeds.len(),
+            });
+        }
+
+        let loaded = seeds
+            .into_iter()
+            .map(|seed| seed.peer)
+            .collect::<BTreeSet<_>>();
+        let missing = self
+            .critical
+            .difference(&loaded)
+            .copied()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(error::Policy::CriticalMissing { missing });
+        }
+
+        Ok(())
+    }
+}
+
 pub mod error {
     use std::io;
     use thiserror::Error;
@@ -230,6 +331,23 @@ pub mod error {
         Unexpected(String),
     }
 
+    #[derive(Debug, Error)]
+    pub enum Policy {
+        #[error("expected at least {expected} seeds, but only {loaded} could be loaded")]
+        TooFew { expected: usize, loaded: usize },
+
+        #[error("critical seeds could not be loaded: {}", display_peers(.missing))]
+        CriticalMissing { missing: Vec<PeerId> },
+    }
+
+    fn display_peers(peers: &[PeerId]) -> String {
+        peers
+            .iter()
+            .map(|peer| peer.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     #[derive(Debug, Error)]
     pub enum Resolve {
         #[error("address `{addr}` for peer `{peer}` could be not be resolved")]
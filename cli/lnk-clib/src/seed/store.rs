@@ -4,7 +4,7 @@
 use super::Seed;
 
 pub mod file;
-pub use file::{FileStore, Iter};
+pub use file::{FileStore, Import, Iter};
 
 /// Get an iterator of the [`Seed`] in the [`Store`].
 pub trait Store {
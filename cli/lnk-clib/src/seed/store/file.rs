@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use std::{
+    fmt,
     fs,
-    io::{self, BufRead as _},
+    io::{self, BufRead as _, Write as _},
     marker::PhantomData,
     path::{Path, PathBuf},
     str::FromStr,
@@ -51,6 +52,75 @@ impl<T> FileStore<T> {
             _marker: self._marker,
         })
     }
+
+    /// Write `seeds` into the store, combining them with the existing seeds
+    /// according to `mode`.
+    ///
+    /// Every seed is checked to survive being written to, and read back from,
+    /// the store before any are written. If any seed fails this check, the
+    /// store is left untouched.
+    pub fn import(&self, seeds: Vec<Seed<T>>, mode: Import) -> Result<(), error::Import>
+    where
+        T: Clone + fmt::Display + FromStr + PartialEq,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        for seed in &seeds {
+            let line = seed.to_string();
+            match line.parse::<Seed<T>>() {
+                Ok(parsed) if parsed == *seed => {},
+                Ok(_) => return Err(error::Import::Ambiguous { seed: line }),
+                Err(source) => return Err(error::Import::Invalid { seed: line, source }),
+            }
+        }
+
+        let seeds = match mode {
+            Import::Replace => seeds,
+            Import::Merge => {
+                let mut existing = self.iter()?.collect::<Result<Vec<_>, _>>()?;
+                for seed in seeds {
+                    match existing.iter_mut().find(|s| s.peer == seed.peer) {
+                        Some(s) => *s = seed,
+                        None => existing.push(seed),
+                    }
+                }
+                existing
+            },
+        };
+
+        self.write(&seeds)?;
+        Ok(())
+    }
+
+    /// Overwrite the store with `seeds`.
+    ///
+    /// The seeds are first written to a temporary file alongside the store,
+    /// which then replaces the store, so that readers never observe a
+    /// partially written store.
+    fn write(&self, seeds: &[Seed<T>]) -> Result<(), io::Error>
+    where
+        T: fmt::Display,
+    {
+        let tmp = self.path.with_extension("tmp");
+        {
+            let mut file = io::BufWriter::new(fs::File::create(&tmp)?);
+            for seed in seeds {
+                writeln!(file, "{}", seed)?;
+            }
+            file.into_inner()?.sync_all()?;
+        }
+        fs::rename(tmp, &self.path)
+    }
+}
+
+/// How [`FileStore::import`] combines the imported seeds with the seeds already
+/// in the store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Import {
+    /// Keep the existing seeds, adding the imported ones. An imported seed
+    /// replaces an existing seed with the same peer.
+    Merge,
+    /// Discard the existing seeds, keeping only the imported ones.
+    Replace,
 }
 
 pub struct Iter<T> {
@@ -107,4 +177,20 @@ pub mod error {
         #[error(transparent)]
         Parse(#[from] error::Parse),
     }
+
+    #[derive(Debug, Error)]
+    pub enum Import {
+        #[error("seed `{seed}` would not be read back as the same seed")]
+        Ambiguous { seed: String },
+        #[error("seed `{seed}` is invalid")]
+        Invalid {
+            seed: String,
+            #[source]
+            source: error::Parse,
+        },
+        #[error(transparent)]
+        Io(#[from] io::Error),
+        #[error(transparent)]
+        Iter(#[from] Iter),
+    }
 }
@@ -5,4 +5,5 @@
 
 #[cfg(unix)]
 mod keys;
+mod metrics;
 mod seed;
@@ -0,0 +1,58 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::time::Duration;
+
+use librad::{PeerId, SecretKey};
+use lnk_clib::metrics::{self, graphite_line, is_valid_name, Label, Metric, Namespace};
+
+#[test]
+fn metric_names_follow_convention() {
+    for metric in metrics::linkd::ALL {
+        assert!(
+            is_valid_name(metric.name),
+            "invalid metric name: {}",
+            metric
+        );
+        assert!(
+            is_valid_name(&metric.to_string()),
+            "invalid metric name: {}",
+            metric
+        );
+        assert!(metric.to_string().starts_with("linkd_"));
+    }
+}
+
+#[test]
+fn gitd_metric_names_follow_convention() {
+    for metric in metrics::gitd::ALL {
+        assert!(
+            is_valid_name(&metric.to_string()),
+            "invalid metric name: {}",
+            metric
+        );
+        assert!(metric.to_string().starts_with("gitd_"));
+    }
+}
+
+#[test]
+fn invalid_names() {
+    assert!(!is_valid_name(""));
+    assert!(!is_valid_name("Connected"));
+    assert!(!is_valid_name("1_connected"));
+    assert!(!is_valid_name("connected-peers"));
+    assert!(!is_valid_name("connected__peers"));
+    assert!(!is_valid_name("connected_"));
+}
+
+#[test]
+fn graphite_line_format() {
+    let peer = PeerId::from(SecretKey::new());
+    let metric = Metric::new(Namespace::Gitd, "processes");
+    assert_eq!(
+        graphite_line(&metric, &[Label::Peer(peer)], 3.0, Duration::from_secs(42)),
+        format!("gitd_processes;peer={} 3.0 42", peer)
+    );
+}
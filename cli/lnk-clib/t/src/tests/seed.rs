@@ -3,12 +3,13 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::net;
+use std::{collections::BTreeSet, io::Write as _, net};
 
 use anyhow::Result;
 use pretty_assertions::assert_eq;
 
-use lnk_clib::seed::{Seed, Seeds};
+use librad::{PeerId, SecretKey};
+use lnk_clib::seed::{error, store::FileStore, Policy, Seed, Seeds};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_resolve_seeds() -> Result<()> {
@@ -37,3 +38,97 @@ async fn test_resolve_seeds() -> Result<()> {
 
     Ok(())
 }
+
+fn seeds(n: usize) -> Seeds {
+    Seeds(
+        (0..n)
+            .map(|i| Seed {
+                peer: PeerId::from(SecretKey::new()),
+                addrs: vec![([127, 0, 0, 1], 9000 + i as u16).into()],
+                label: None,
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn policy_default_is_permissive() {
+    assert!(Policy::default().check(&seeds(0)).is_ok());
+    assert!(Policy::default().check(&seeds(3)).is_ok());
+}
+
+#[test]
+fn policy_min_seeds() {
+    let policy = Policy {
+        min_seeds: 2,
+        ..Default::default()
+    };
+
+    assert!(policy.check(&seeds(2)).is_ok());
+    assert!(policy.check(&seeds(3)).is_ok());
+    assert!(matches!(
+        policy.check(&seeds(1)),
+        Err(error::Policy::TooFew {
+            expected: 2,
+            loaded: 1
+        })
+    ));
+}
+
+#[test]
+fn policy_critical_seeds() {
+    let loaded = seeds(2);
+    let present = loaded.0[0].peer;
+    let absent = PeerId::from(SecretKey::new());
+
+    let policy = Policy {
+        critical: Some(present).into_iter().collect(),
+        ..Default::default()
+    };
+    assert!(policy.check(&loaded).is_ok());
+
+    let policy = Policy {
+        critical: vec![present, absent].into_iter().collect::<BTreeSet<_>>(),
+        ..Default::default()
+    };
+    match policy.check(&loaded) {
+        Err(error::Policy::CriticalMissing { missing }) => assert_eq!(missing, vec![absent]),
+        res => panic!("expected missing critical seed, got {:?}", res),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn load_merges_duplicate_peers() -> Result<()> {
+    let peer = PeerId::from(SecretKey::new());
+    let other = PeerId::from(SecretKey::new());
+    let mut file = tempfile::NamedTempFile::new()?;
+    writeln!(file, "{}@127.0.0.1:9000", peer)?;
+    writeln!(file, "{}@127.0.0.1:9001,seedling", other)?;
+    writeln!(file, "{}@127.0.0.1:9002,seed", peer)?;
+    writeln!(file, "{}@127.0.0.1:9000", peer)?;
+
+    let store = FileStore::<String>::new(file.path())?;
+    let (seeds, failures) = Seeds::load(&store, None).await?;
+    assert!(failures.is_empty());
+    assert_eq!(
+        seeds,
+        Seeds(vec![
+            Seed {
+                peer,
+                addrs: vec![([127, 0, 0, 1], 9000).into(), ([127, 0, 0, 1], 9002).into()],
+                label: Some("seed".to_owned()),
+            },
+            Seed {
+                peer: other,
+                addrs: vec![([127, 0, 0, 1], 9001).into()],
+                label: Some("seedling".to_owned()),
+            },
+        ])
+    );
+
+    // Duplicates count once towards the cutoff
+    let (seeds, _) = Seeds::load(&store, 2).await?;
+    assert_eq!(seeds.len(), 2);
+
+    Ok(())
+}
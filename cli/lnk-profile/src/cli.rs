@@ -4,6 +4,7 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 pub mod args;
+pub mod json;
 pub mod main;
 
 pub use main::main;
@@ -3,14 +3,19 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
+use std::path::PathBuf;
+
 use clap::Parser;
 
-use librad::profile::ProfileId;
+use librad::profile::{Alias, ProfileId};
 
 /// Management of Radicle profiles and their associated configuration data.
 #[derive(Debug, Parser)]
 #[clap(author, version, about)]
 pub struct Args {
+    /// print the output of the command as JSON
+    #[clap(long, global = true)]
+    pub json: bool,
     #[clap(subcommand)]
     pub command: Command,
 }
@@ -21,9 +26,11 @@ pub enum Command {
     Get(Get),
     Set(Set),
     List(List),
+    Alias(SetAlias),
     Peer(GetPeerId),
     Paths(GetPaths),
     Ssh(Ssh),
+    Seeds(Seeds),
 }
 
 /// Create a new profile, generating a new secret key and initialising
@@ -51,6 +58,20 @@ pub struct Set {
 #[derive(Debug, Parser)]
 pub struct List {}
 
+/// Get or set the alias of a profile. The alias can be used in place of the
+/// profile identifier in any other command. If no profile was provided, then
+/// the active one is used.
+#[derive(Debug, Parser)]
+pub struct SetAlias {
+    /// the identifier to look up
+    #[clap(long)]
+    pub id: Option<ProfileId>,
+    /// the alias to set, which must be unique amongst all profiles. If it is
+    /// not provided, the current alias is printed.
+    #[clap(long)]
+    pub name: Option<Alias>,
+}
+
 /// Get the peer identifier associated with the provided profile identfier. If
 /// no profile was provided, then the active one is used.
 #[derive(Debug, Parser)]
@@ -84,6 +105,7 @@ pub mod ssh {
         Add(Add),
         Rm(Rm),
         Ready(Ready),
+        Ls(Ls),
         Sign(Sign),
         Verify(Verify),
     }
@@ -121,6 +143,11 @@ pub mod ssh {
         pub id: Option<ProfileId>,
     }
 
+    /// List all profiles, and whether their associated secret keys are present
+    /// in the ssh-agent.
+    #[derive(Debug, Parser)]
+    pub struct Ls {}
+
     /// Sign a payload with the profile's associated secret key. If no profile
     /// was provided, then the active one is used.
     #[derive(Debug, Parser)]
@@ -129,8 +156,14 @@ pub mod ssh {
         #[clap(long)]
         pub id: Option<ProfileId>,
         /// the payload to sign
+        #[clap(long, conflicts_with = "file", required_unless_present = "file")]
+        pub payload: Option<String>,
+        /// the file whose contents are signed, or `-` to read them from stdin
         #[clap(long)]
-        pub payload: String,
+        pub file: Option<PathBuf>,
+        /// the file to write the signature to, instead of printing it
+        #[clap(long)]
+        pub output: Option<PathBuf>,
     }
 
     /// Verify a signature of a payload with the profile's associated public
@@ -149,3 +182,47 @@ pub mod ssh {
         pub signature: String,
     }
 }
+
+/// Manage the profile's seeds
+#[derive(Debug, Parser)]
+pub struct Seeds {
+    #[clap(subcommand)]
+    pub options: seeds::Options,
+}
+
+pub mod seeds {
+    use super::*;
+
+    #[derive(Debug, Parser)]
+    pub enum Options {
+        Export(Export),
+        Import(Import),
+    }
+
+    /// Write all of the profile's seeds to stdout as canonical JSON. If no
+    /// profile was provided, then the active one is used.
+    #[derive(Debug, Parser)]
+    pub struct Export {
+        /// the identifier to look up
+        #[clap(long)]
+        pub id: Option<ProfileId>,
+    }
+
+    /// Read seeds, in the format written by `export`, into the profile's seeds.
+    /// By default the existing seeds are kept, and any which share a peer with
+    /// an imported seed are replaced. No seeds are written if any of them are
+    /// invalid. If no profile was provided, then the active one is used.
+    #[derive(Debug, Parser)]
+    pub struct Import {
+        /// the identifier to look up
+        #[clap(long)]
+        pub id: Option<ProfileId>,
+        /// the file to read the seeds from. If it is not provided, the seeds
+        /// are read from stdin.
+        #[clap(long)]
+        pub file: Option<PathBuf>,
+        /// discard the existing seeds, keeping only the imported seeds.
+        #[clap(long)]
+        pub replace: bool,
+    }
+}
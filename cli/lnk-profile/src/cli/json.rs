@@ -0,0 +1,114 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+//! The output of each command when `--json` is given.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use librad::{
+    paths,
+    profile::{Alias, ProfileId},
+    PeerId,
+    Signature,
+};
+
+#[derive(Debug, Serialize)]
+pub struct Id {
+    pub id: String,
+}
+
+impl From<&ProfileId> for Id {
+    fn from(id: &ProfileId) -> Self {
+        Self { id: id.to_string() }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Profile {
+    pub id: String,
+    pub alias: Option<String>,
+}
+
+impl Profile {
+    pub fn new(id: &ProfileId, alias: Option<&Alias>) -> Self {
+        Self {
+            id: id.to_string(),
+            alias: alias.map(ToString::to_string),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Created {
+    pub id: String,
+    pub peer_id: PeerId,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Peer {
+    pub peer_id: PeerId,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Paths {
+    pub git: PathBuf,
+    pub git_includes: PathBuf,
+    pub keys: PathBuf,
+}
+
+impl From<&paths::Paths> for Paths {
+    fn from(paths: &paths::Paths) -> Self {
+        Self {
+            git: paths.git_dir().to_path_buf(),
+            git_includes: paths.git_includes_dir().to_path_buf(),
+            keys: paths.keys_dir().to_path_buf(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SshStatus {
+    pub id: String,
+    pub alias: Option<String>,
+    pub peer_id: PeerId,
+    pub present: bool,
+}
+
+impl From<crate::SshStatus> for SshStatus {
+    fn from(status: crate::SshStatus) -> Self {
+        Self {
+            id: status.id.to_string(),
+            alias: status.alias.as_ref().map(ToString::to_string),
+            peer_id: status.peer_id,
+            present: status.present,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Signed {
+    pub id: String,
+    pub signature: Signature,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Verified {
+    pub id: String,
+    pub verified: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Imported {
+    pub id: String,
+    pub imported: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Ready {
+    pub id: String,
+    pub present: bool,
+}
@@ -3,44 +3,73 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{convert::TryInto as _, process::exit};
+use std::{
+    convert::TryInto as _,
+    fs,
+    io::{self, Read as _, Write as _},
+    process::exit,
+};
 
 use lnk_thrussh_agent::Constraint;
+use serde::Serialize;
 
 use librad::crypto::keystore::sign;
-use lnk_clib::keys::{self, ssh::SshAuthSock};
+use lnk_clib::{
+    keys::{self, ssh::SshAuthSock},
+    seed::store::Import,
+};
 
 use crate::{
+    alias,
     create,
     get,
-    list,
+    list_aliased,
     paths,
     peer_id,
+    read_payload,
+    seeds_export,
+    seeds_import,
     set,
+    set_alias,
     ssh_add,
+    ssh_list,
     ssh_ready,
     ssh_remove,
     ssh_sign,
     ssh_verify,
 };
 
-use super::args::*;
+use super::{args::*, json as out};
 
-pub fn main(Args { command }: Args, sock: SshAuthSock) -> anyhow::Result<()> {
-    eval(sock, command)
+pub fn main(Args { json, command }: Args, sock: SshAuthSock) -> anyhow::Result<()> {
+    eval(sock, json, command)
+}
+
+fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
 }
 
-fn eval(sock: SshAuthSock, command: Command) -> anyhow::Result<()> {
+fn eval(sock: SshAuthSock, json: bool, command: Command) -> anyhow::Result<()> {
     match command {
         Command::Create(Create {}) => {
             let (profile, peer_id) = create(None, keys::prompt::new())?;
-            println!("profile id: {}", profile.id());
-            println!("peer id: {}", peer_id);
+            if json {
+                print_json(&out::Created {
+                    id: profile.id().to_string(),
+                    peer_id,
+                })?;
+            } else {
+                println!("profile id: {}", profile.id());
+                println!("peer id: {}", peer_id);
+            }
         },
         Command::Get(Get { id }) => {
             let profile = get(None, id)?;
             match profile {
+                Some(profile) if json => print_json(&out::Id::from(profile.id()))?,
                 Some(profile) => println!("{}", profile.id()),
+                None if json => print_json(&None::<out::Id>)?,
                 None => println!(
                     "no active profile found, perhaps you want to run `lnk profile create`?"
                 ),
@@ -48,48 +77,217 @@ fn eval(sock: SshAuthSock, command: Command) -> anyhow::Result<()> {
         },
         Command::Set(Set { id }) => {
             set(None, id.clone())?;
-            println!("successfully set active profile id to {}", id);
+            if json {
+                print_json(&out::Id::from(&id))?;
+            } else {
+                println!("successfully set active profile id to {}", id);
+            }
         },
         Command::List(List {}) => {
-            let profiles = list(None)?;
-            for profile in profiles {
-                println!("{}", profile.id());
+            let profiles = list_aliased(None)?;
+            if json {
+                print_json(
+                    &profiles
+                        .iter()
+                        .map(|(profile, alias)| out::Profile::new(profile.id(), alias.as_ref()))
+                        .collect::<Vec<_>>(),
+                )?;
+            } else {
+                for (profile, alias) in profiles {
+                    match alias {
+                        Some(alias) => println!("{} ({})", profile.id(), alias),
+                        None => println!("{}", profile.id()),
+                    }
+                }
             }
         },
+        Command::Alias(SetAlias { id, name }) => match name {
+            Some(name) => {
+                let id = set_alias(None, id, name.clone())?;
+                if json {
+                    print_json(&out::Profile::new(&id, Some(&name)))?;
+                } else {
+                    println!(
+                        "successfully set alias of profile id `{}` to `{}`",
+                        id, name
+                    );
+                }
+            },
+            None => match alias(None, id)? {
+                (id, alias) if json => print_json(&out::Profile::new(&id, alias.as_ref()))?,
+                (_, Some(alias)) => println!("{}", alias),
+                (id, None) => println!("no alias set for profile id `{}`", id),
+            },
+        },
         Command::Peer(GetPeerId { id }) => {
             let peer_id = peer_id(None, id)?;
-            println!("{}", peer_id);
+            if json {
+                print_json(&out::Peer { peer_id })?;
+            } else {
+                println!("{}", peer_id);
+            }
         },
         Command::Paths(GetPaths { id }) => {
             let paths = paths(None, id)?;
-            println!("git: {}", paths.git_dir().display());
-            println!("git includes: {}", paths.git_includes_dir().display());
-            println!("keys: {}", paths.keys_dir().display());
+            if json {
+                print_json(&out::Paths::from(&paths))?;
+            } else {
+                println!("git: {}", paths.git_dir().display());
+                println!("git includes: {}", paths.git_includes_dir().display());
+                println!("keys: {}", paths.keys_dir().display());
+            }
+        },
+        Command::Seeds(Seeds { options }) => match options {
+            // The export is always JSON
+            seeds::Options::Export(seeds::Export { id }) => {
+                let (_, json) = seeds_export(None, id)?;
+                let mut stdout = io::stdout();
+                stdout.write_all(&json)?;
+                writeln!(stdout)?;
+            },
+            seeds::Options::Import(seeds::Import { id, file, replace }) => {
+                let seeds = match file {
+                    Some(file) => fs::read(file)?,
+                    None => {
+                        let mut seeds = Vec::new();
+                        io::stdin().read_to_end(&mut seeds)?;
+                        seeds
+                    },
+                };
+                let mode = if replace {
+                    Import::Replace
+                } else {
+                    Import::Merge
+                };
+                let (id, imported) = seeds_import(None, id, &seeds, mode)?;
+                if json {
+                    print_json(&out::Imported {
+                        id: id.to_string(),
+                        imported,
+                    })?;
+                } else {
+                    println!("imported {} seeds for profile id `{}`", imported, id);
+                }
+            },
         },
         Command::Ssh(Ssh { options }) => match options {
             ssh::Options::Add(ssh::Add { id, time }) => {
                 let constraints =
                     time.map_or(vec![], |seconds| vec![Constraint::KeyLifetime { seconds }]);
                 let id = ssh_add(None, id, sock, keys::prompt::new(), constraints)?;
-                println!("added key for profile id `{}`", id);
+                if json {
+                    print_json(&out::Id::from(&id))?;
+                } else {
+                    println!("added key for profile id `{}`", id);
+                }
             },
             ssh::Options::Rm(ssh::Rm { id }) => {
                 let id = ssh_remove(None, id, sock, keys::prompt::new())?;
-                println!("removed key for profile id `{}`", id);
+                if json {
+                    print_json(&out::Id::from(&id))?;
+                } else {
+                    println!("removed key for profile id `{}`", id);
+                }
             },
-            ssh::Options::Sign(ssh::Sign { id, payload }) => {
+            ssh::Options::Sign(ssh::Sign {
+                id,
+                payload,
+                file,
+                output,
+            }) => {
+                let payload = match (payload, file) {
+                    (Some(payload), _) => payload.into_bytes(),
+                    (None, Some(file)) => read_payload(&file)?,
+                    (None, None) => unreachable!("clap requires one of `--payload` or `--file`"),
+                };
                 let (id, sig) = ssh_sign(None, id, sock, payload)?;
-                println!("`{}` signature for profile id `{}`", sig, id);
+                match output {
+                    Some(output) => {
+                        fs::write(&output, sig.to_string())?;
+                        if json {
+                            print_json(&out::Id::from(&id))?;
+                        } else {
+                            println!(
+                                "wrote signature for profile id `{}` to `{}`",
+                                id,
+                                output.display()
+                            );
+                        }
+                    },
+                    None if json => print_json(&out::Signed {
+                        id: id.to_string(),
+                        signature: sig,
+                    })?,
+                    None => println!("`{}` signature for profile id `{}`", sig, id),
+                }
             },
             ssh::Options::Ready(ssh::Ready { id }) => {
                 let (id, present) = ssh_ready(None, id, sock)?;
-                if present {
+                if json {
+                    print_json(&out::Ready {
+                        id: id.to_string(),
+                        present,
+                    })?;
+                } else if present {
                     println!("key is on ssh-agent for profile id `{}`", id);
                 } else {
                     println!("key is *not* on ssh-agent for profile id `{}`", id);
+                }
+                if !present {
                     exit(1);
                 }
             },
+            ssh::Options::Ls(ssh::Ls {}) => {
+                let statuses = ssh_list(None, sock)?;
+                if json {
+                    print_json(
+                        &statuses
+                            .into_iter()
+                            .map(out::SshStatus::from)
+                            .collect::<Vec<_>>(),
+                    )?;
+                    return Ok(());
+                }
+                let rows = statuses
+                    .iter()
+                    .map(|status| {
+                        (
+                            status.id.to_string(),
+                            status
+                                .alias
+                                .as_ref()
+                                .map(ToString::to_string)
+                                .unwrap_or_else(|| "-".to_string()),
+                            status.peer_id.to_string(),
+                            if status.present { "yes" } else { "no" },
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let id_width = column_width("PROFILE", rows.iter().map(|row| &row.0));
+                let alias_width = column_width("ALIAS", rows.iter().map(|row| &row.1));
+                let peer_width = column_width("PEER", rows.iter().map(|row| &row.2));
+                println!(
+                    "{:<id_width$}  {:<alias_width$}  {:<peer_width$}  LOADED",
+                    "PROFILE",
+                    "ALIAS",
+                    "PEER",
+                    id_width = id_width,
+                    alias_width = alias_width,
+                    peer_width = peer_width,
+                );
+                for (id, alias, peer, present) in rows {
+                    println!(
+                        "{:<id_width$}  {:<alias_width$}  {:<peer_width$}  {}",
+                        id,
+                        alias,
+                        peer,
+                        present,
+                        id_width = id_width,
+                        alias_width = alias_width,
+                        peer_width = peer_width,
+                    );
+                }
+            },
             ssh::Options::Verify(ssh::Verify {
                 id,
                 payload,
@@ -99,7 +297,12 @@ fn eval(sock: SshAuthSock, command: Command) -> anyhow::Result<()> {
                 let signature = sign::Signature(signature);
                 let (id, verified) = ssh_verify(None, id, payload, signature.into())?;
 
-                if verified {
+                if json {
+                    print_json(&out::Verified {
+                        id: id.to_string(),
+                        verified,
+                    })?;
+                } else if verified {
                     println!("payload verified for profile id `{}`", id);
                 } else {
                     println!("payload *not* verified for profile id `{}`", id);
@@ -110,3 +313,12 @@ fn eval(sock: SshAuthSock, command: Command) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// The width of a column with the given `header` and `values`.
+fn column_width<'a>(header: &str, values: impl Iterator<Item = &'a String>) -> usize {
+    values
+        .map(String::len)
+        .chain(Some(header.len()))
+        .max()
+        .unwrap_or_default()
+}
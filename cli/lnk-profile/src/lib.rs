@@ -3,13 +3,20 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{error, fmt};
+use std::{
+    error,
+    fmt,
+    fs,
+    io::{self, Read as _},
+    path::Path,
+};
 
 use lnk_thrussh_agent::Constraint;
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
 use librad::{
+    canonical::{Cjson, CjsonError},
     crypto::{
         keystore::{crypto::Crypto, file, FileStorage, Keystore as _},
         IntoSecretKeyError,
@@ -19,10 +26,16 @@ use librad::{
     },
     git::storage::{self, read, ReadOnly, Storage},
     paths::Paths,
-    profile::{self, LnkHome, Profile, ProfileId},
+    profile::{self, Alias, LnkHome, Profile, ProfileId},
     Signature,
 };
-use lnk_clib::keys::{self, ssh::SshAuthSock};
+use lnk_clib::{
+    keys::{self, ssh::SshAuthSock},
+    seed::{
+        store::{self, FileStore, Import},
+        Seed,
+    },
+};
 
 pub mod cli;
 
@@ -32,6 +45,10 @@ pub enum Error {
     #[error(transparent)]
     AddKey(#[from] keys::ssh::Error),
     #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] CjsonError),
+    #[error(transparent)]
     Keystore(Box<dyn error::Error + Send + Sync + 'static>),
     #[error("no active profile was found, perhaps you need to create one")]
     NoActiveProfile,
@@ -40,6 +57,10 @@ pub enum Error {
     #[error(transparent)]
     Profile(#[from] profile::Error),
     #[error(transparent)]
+    SeedsImport(#[from] store::file::error::Import),
+    #[error(transparent)]
+    SeedsIter(#[from] store::file::error::Iter),
+    #[error(transparent)]
     Storage(#[from] storage::error::Init),
     #[error(transparent)]
     ReadOnly(#[from] read::error::Init),
@@ -54,12 +75,18 @@ where
     }
 }
 
+/// Get the profile identified by `id`, which may be either a [`ProfileId`] or
+/// an [`Alias`].
+fn resolve(home: &LnkHome, id: ProfileId) -> Result<Profile, Error> {
+    Profile::resolve(home, id.as_str())?.ok_or(Error::NoProfile(id))
+}
+
 fn get_or_active<P>(home: &LnkHome, id: P) -> Result<Profile, Error>
 where
     P: Into<Option<ProfileId>>,
 {
     match id.into() {
-        Some(id) => Profile::get(home, id.clone())?.ok_or(Error::NoProfile(id)),
+        Some(id) => resolve(home, id),
         None => Profile::active(home)?.ok_or(Error::NoActiveProfile),
     }
 }
@@ -89,7 +116,7 @@ where
 {
     let home = home.into().unwrap_or_default();
     match id {
-        Some(id) => Profile::get(&home, id).map_err(Error::from),
+        Some(id) => Profile::resolve(&home, id.as_str()).map_err(Error::from),
         None => Profile::active(&home).map_err(Error::from),
     }
 }
@@ -100,7 +127,10 @@ where
     H: Into<Option<LnkHome>>,
 {
     let home = home.into().unwrap_or_default();
-    Profile::set(&home, id).map_err(Error::from).map(|_| ())
+    let profile = resolve(&home, id)?;
+    Profile::set(&home, profile.id().clone())
+        .map_err(Error::from)
+        .map(|_| ())
 }
 
 /// List the set of active profiles that exist.
@@ -112,6 +142,45 @@ where
     Profile::list(&home).map_err(Error::from)
 }
 
+/// List the set of profiles that exist, along with their aliases.
+pub fn list_aliased<H>(home: H) -> Result<Vec<(Profile, Option<Alias>)>, Error>
+where
+    H: Into<Option<LnkHome>>,
+{
+    let home = home.into().unwrap_or_default();
+    Profile::list(&home)?
+        .into_iter()
+        .map(|profile| {
+            let alias = Profile::alias(&home, profile.id())?;
+            Ok((profile, alias))
+        })
+        .collect()
+}
+
+/// Get the [`Alias`] of the given profile.
+pub fn alias<H, P>(home: H, id: P) -> Result<(ProfileId, Option<Alias>), Error>
+where
+    H: Into<Option<LnkHome>>,
+    P: Into<Option<ProfileId>>,
+{
+    let home = home.into().unwrap_or_default();
+    let profile = get_or_active(&home, id)?;
+    let alias = Profile::alias(&home, profile.id())?;
+    Ok((profile.id().clone(), alias))
+}
+
+/// Set the [`Alias`] of the given profile.
+pub fn set_alias<H, P>(home: H, id: P, alias: Alias) -> Result<ProfileId, Error>
+where
+    H: Into<Option<LnkHome>>,
+    P: Into<Option<ProfileId>>,
+{
+    let home = home.into().unwrap_or_default();
+    let profile = get_or_active(&home, id)?;
+    let profile = Profile::set_alias(&home, profile.id().clone(), alias)?;
+    Ok(profile.id().clone())
+}
+
 /// Get the `PeerId` associated to the given [`ProfileId`]
 pub fn peer_id<H, P>(home: H, id: P) -> Result<PeerId, Error>
 where
@@ -133,6 +202,43 @@ where
     get_or_active(&home, id).map(|p| p.paths().clone())
 }
 
+/// Export the seeds of a profile as canonical JSON.
+pub fn seeds_export<H, P>(home: H, id: P) -> Result<(ProfileId, Vec<u8>), Error>
+where
+    H: Into<Option<LnkHome>>,
+    P: Into<Option<ProfileId>>,
+{
+    let home = home.into().unwrap_or_default();
+    let profile = get_or_active(&home, id)?;
+    let store = FileStore::<String>::new(profile.paths().seeds_file())?;
+    let seeds = store.iter()?.collect::<Result<Vec<_>, _>>()?;
+    let json = Cjson(seeds).canonical_form()?;
+    Ok((profile.id().clone(), json))
+}
+
+/// Import seeds, in the JSON format produced by [`seeds_export`], into a
+/// profile's seeds.
+///
+/// No seeds are written if any of them are invalid.
+pub fn seeds_import<H, P>(
+    home: H,
+    id: P,
+    json: &[u8],
+    mode: Import,
+) -> Result<(ProfileId, usize), Error>
+where
+    H: Into<Option<LnkHome>>,
+    P: Into<Option<ProfileId>>,
+{
+    let home = home.into().unwrap_or_default();
+    let profile = get_or_active(&home, id)?;
+    let seeds = Cjson::<Vec<Seed<String>>>::from_slice(json)?.into_inner();
+    let imported = seeds.len();
+    let store = FileStore::<String>::new(profile.paths().seeds_file())?;
+    store.import(seeds, mode)?;
+    Ok((profile.id().clone(), imported))
+}
+
 /// Add a profile's [`SecretKey`] to the `ssh-agent`.
 pub fn ssh_add<H, P, C>(
     home: H,
@@ -181,36 +287,81 @@ where
     Ok((profile.id().clone(), present))
 }
 
+/// The presence of a profile's [`SecretKey`] in the `ssh-agent`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SshStatus {
+    pub id: ProfileId,
+    pub alias: Option<Alias>,
+    pub peer_id: PeerId,
+    pub present: bool,
+}
+
+/// See which of the existing profiles' [`SecretKey`]s are present in the
+/// `ssh-agent`.
+pub fn ssh_list<H>(home: H, sock: SshAuthSock) -> Result<Vec<SshStatus>, Error>
+where
+    H: Into<Option<LnkHome>>,
+{
+    let home = home.into().unwrap_or_default();
+    Profile::list(&home)?
+        .into_iter()
+        .map(|profile| {
+            let read = ReadOnly::open(profile.paths())?;
+            let present = keys::ssh::is_signer_present(&profile, sock.clone())?;
+            Ok(SshStatus {
+                id: profile.id().clone(),
+                alias: Profile::alias(&home, profile.id())?,
+                peer_id: *read.peer_id(),
+                present,
+            })
+        })
+        .collect()
+}
+
+/// Read a payload for [`ssh_sign`] or [`ssh_verify`] from the file at `path`,
+/// or from stdin if `path` is `-`.
+pub fn read_payload(path: &Path) -> Result<Vec<u8>, Error> {
+    let mut payload = Vec::new();
+    if path == Path::new("-") {
+        io::stdin().read_to_end(&mut payload)?;
+    } else {
+        fs::File::open(path)?.read_to_end(&mut payload)?;
+    }
+    Ok(payload)
+}
+
 /// Sign a payload with a profile's [`SecretKey`] from the `ssh-agent`.
-pub fn ssh_sign<H, P>(
+pub fn ssh_sign<H, P, B>(
     home: H,
     id: P,
     sock: SshAuthSock,
-    payload: String,
+    payload: B,
 ) -> Result<(ProfileId, Signature), Error>
 where
     H: Into<Option<LnkHome>>,
     P: Into<Option<ProfileId>>,
+    B: AsRef<[u8]>,
 {
     let home = home.into().unwrap_or_default();
     let profile = get_or_active(&home, id)?;
-    let sig = keys::ssh::sign(&profile, sock, payload.as_bytes())?;
+    let sig = keys::ssh::sign(&profile, sock, payload.as_ref())?;
     Ok((profile.id().clone(), sig.into()))
 }
 
 /// Verify a signature and payload with a profile's [`PublicKey`].
-pub fn ssh_verify<H, P>(
+pub fn ssh_verify<H, P, B>(
     home: H,
     id: P,
-    payload: String,
+    payload: B,
     signature: Signature,
 ) -> Result<(ProfileId, bool), Error>
 where
     H: Into<Option<LnkHome>>,
     P: Into<Option<ProfileId>>,
+    B: AsRef<[u8]>,
 {
     let home = home.into().unwrap_or_default();
     let profile = get_or_active(&home, id)?;
-    let verified = keys::ssh::verify(&profile, payload.as_bytes(), &signature)?;
+    let verified = keys::ssh::verify(&profile, payload.as_ref(), &signature)?;
     Ok((profile.id().clone(), verified))
 }
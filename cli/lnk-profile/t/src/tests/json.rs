@@ -0,0 +1,60 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use serde_json::json;
+use tempfile::tempdir;
+
+use librad::{
+    crypto::keystore::{
+        crypto::{Pwhash, KDF_PARAMS_TEST},
+        pinentry::SecUtf8,
+    },
+    profile::LnkHome,
+};
+use lnk_profile::{cli::json as out, create, list_aliased, paths, set_alias};
+
+#[test]
+fn list() -> anyhow::Result<()> {
+    let temp = tempdir()?;
+    let home = LnkHome::Root(temp.path().to_path_buf());
+    let pass = Pwhash::new(SecUtf8::from(b"42".to_vec()), *KDF_PARAMS_TEST);
+    let (aliased, _) = create(home.clone(), pass.clone())?;
+    let (unaliased, _) = create(home.clone(), pass)?;
+    set_alias(home.clone(), aliased.id().clone(), "work".parse()?)?;
+
+    let mut profiles = list_aliased(home)?
+        .iter()
+        .map(|(profile, alias)| out::Profile::new(profile.id(), alias.as_ref()))
+        .collect::<Vec<_>>();
+    profiles.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut expected = vec![
+        json!({ "id": aliased.id().to_string(), "alias": "work" }),
+        json!({ "id": unaliased.id().to_string(), "alias": null }),
+    ];
+    expected.sort_by_key(|profile| profile["id"].as_str().unwrap().to_owned());
+
+    assert_eq!(serde_json::to_value(&profiles)?, json!(expected));
+    Ok(())
+}
+
+#[test]
+fn paths_shape() -> anyhow::Result<()> {
+    let temp = tempdir()?;
+    let home = LnkHome::Root(temp.path().to_path_buf());
+    let pass = Pwhash::new(SecUtf8::from(b"42".to_vec()), *KDF_PARAMS_TEST);
+    let (profile, _) = create(home.clone(), pass)?;
+
+    let paths = paths(home, profile.id().clone())?;
+    assert_eq!(
+        serde_json::to_value(out::Paths::from(&paths))?,
+        json!({
+            "git": paths.git_dir(),
+            "git_includes": paths.git_includes_dir(),
+            "keys": paths.keys_dir(),
+        })
+    );
+    Ok(())
+}
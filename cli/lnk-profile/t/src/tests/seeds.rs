@@ -0,0 +1,163 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use tempfile::tempdir;
+
+use librad::{
+    canonical::Cjson,
+    crypto::keystore::{
+        crypto::{Pwhash, KDF_PARAMS_TEST},
+        pinentry::SecUtf8,
+    },
+    profile::{LnkHome, ProfileId},
+    PeerId,
+    SecretKey,
+};
+use lnk_clib::seed::{
+    store::{file::error, FileStore, Import},
+    Seed,
+};
+use lnk_profile::{create, paths, seeds_export, seeds_import, Error};
+
+fn seed(addrs: &str, label: Option<&str>) -> Seed<String> {
+    Seed {
+        peer: PeerId::from(SecretKey::new()),
+        addrs: addrs.to_string(),
+        label: label.map(ToString::to_string),
+    }
+}
+
+fn json(seeds: &[Seed<String>]) -> Vec<u8> {
+    Cjson(seeds).canonical_form().unwrap()
+}
+
+fn stored(home: &LnkHome, id: &ProfileId) -> Vec<Seed<String>> {
+    let paths = paths(home.clone(), id.clone()).unwrap();
+    FileStore::<String>::new(paths.seeds_file())
+        .unwrap()
+        .iter()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+fn profile(home: &LnkHome) -> ProfileId {
+    let pass = Pwhash::new(SecUtf8::from(b"42".to_vec()), *KDF_PARAMS_TEST);
+    let (profile, _) = create(home.clone(), pass).unwrap();
+    profile.id().clone()
+}
+
+#[test]
+fn export_import_roundtrip() {
+    let temp = tempdir().unwrap();
+    let home = LnkHome::Root(temp.path().to_path_buf());
+    let from = profile(&home);
+    let to = profile(&home);
+
+    let seeds = vec![
+        seed("localhost:8776", Some("local")),
+        seed("seed.example.com:12345", None),
+    ];
+    seeds_import(home.clone(), from.clone(), &json(&seeds), Import::Replace).unwrap();
+
+    let (_, exported) = seeds_export(home.clone(), from).unwrap();
+    assert_eq!(exported, json(&seeds));
+
+    seeds_import(home.clone(), to.clone(), &exported, Import::Replace).unwrap();
+    assert_eq!(stored(&home, &to), seeds);
+}
+
+#[test]
+fn import_merge() {
+    let temp = tempdir().unwrap();
+    let home = LnkHome::Root(temp.path().to_path_buf());
+    let id = profile(&home);
+
+    let kept = seed("localhost:8776", None);
+    let updated = seed("localhost:8777", None);
+    seeds_import(
+        home.clone(),
+        id.clone(),
+        &json(&[kept.clone(), updated.clone()]),
+        Import::Replace,
+    )
+    .unwrap();
+
+    let updated = Seed {
+        label: Some("updated".to_string()),
+        ..updated
+    };
+    let added = seed("localhost:8778", None);
+    seeds_import(
+        home.clone(),
+        id.clone(),
+        &json(&[updated.clone(), added.clone()]),
+        Import::Merge,
+    )
+    .unwrap();
+
+    assert_eq!(stored(&home, &id), vec![kept, updated, added]);
+}
+
+#[test]
+fn import_replace() {
+    let temp = tempdir().unwrap();
+    let home = LnkHome::Root(temp.path().to_path_buf());
+    let id = profile(&home);
+
+    seeds_import(
+        home.clone(),
+        id.clone(),
+        &json(&[seed("localhost:8776", None)]),
+        Import::Replace,
+    )
+    .unwrap();
+
+    let replacement = seed("localhost:8777", None);
+    seeds_import(
+        home.clone(),
+        id.clone(),
+        &json(&[replacement.clone()]),
+        Import::Replace,
+    )
+    .unwrap();
+
+    assert_eq!(stored(&home, &id), vec![replacement]);
+}
+
+#[test]
+fn import_invalid_writes_nothing() {
+    let temp = tempdir().unwrap();
+    let home = LnkHome::Root(temp.path().to_path_buf());
+    let id = profile(&home);
+
+    let existing = vec![seed("localhost:8776", None)];
+    seeds_import(home.clone(), id.clone(), &json(&existing), Import::Replace).unwrap();
+
+    // A label containing `,` cannot be read back from the seeds file
+    let result = seeds_import(
+        home.clone(),
+        id.clone(),
+        &json(&[
+            seed("localhost:8777", None),
+            seed("localhost:8778", Some("a,b")),
+        ]),
+        Import::Replace,
+    );
+    assert!(matches!(
+        result,
+        Err(Error::SeedsImport(error::Import::Invalid { .. }))
+    ));
+
+    let result = seeds_import(
+        home.clone(),
+        id.clone(),
+        b"[{\"peer\":\"nope\"}]",
+        Import::Merge,
+    );
+    assert!(matches!(result, Err(Error::Json(_))));
+
+    assert_eq!(stored(&home, &id), existing);
+}
@@ -0,0 +1,111 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::fs;
+
+use tempfile::tempdir;
+
+use it_helpers::ssh::with_ssh_agent;
+use librad::{
+    crypto::keystore::{
+        crypto::{Pwhash, KDF_PARAMS_TEST},
+        pinentry::SecUtf8,
+    },
+    profile::{Alias, LnkHome},
+};
+use lnk_profile::{
+    create,
+    read_payload,
+    set_alias,
+    ssh_add,
+    ssh_list,
+    ssh_sign,
+    ssh_verify,
+    SshStatus,
+};
+use test_helpers::logging;
+
+#[test]
+fn list_loaded_keys() -> anyhow::Result<()> {
+    logging::init();
+
+    let temp = tempdir()?;
+    let home = LnkHome::Root(temp.path().to_path_buf());
+    let pass = Pwhash::new(SecUtf8::from(b"42".to_vec()), *KDF_PARAMS_TEST);
+    let (loaded, loaded_peer) = create(home.clone(), pass.clone())?;
+    let (unloaded, unloaded_peer) = create(home.clone(), pass.clone())?;
+    let alias = "loaded".parse::<Alias>()?;
+    set_alias(home.clone(), loaded.id().clone(), alias.clone())?;
+
+    let mut statuses = with_ssh_agent(|sock| {
+        ssh_add(
+            home.clone(),
+            loaded.id().clone(),
+            sock.clone(),
+            pass,
+            Vec::new(),
+        )?;
+        Ok(ssh_list(home, sock)?)
+    })?;
+    statuses.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut expected = vec![
+        SshStatus {
+            id: loaded.id().clone(),
+            alias: Some(alias),
+            peer_id: loaded_peer,
+            present: true,
+        },
+        SshStatus {
+            id: unloaded.id().clone(),
+            alias: None,
+            peer_id: unloaded_peer,
+            present: false,
+        },
+    ];
+    expected.sort_by(|a, b| a.id.cmp(&b.id));
+    assert_eq!(statuses, expected);
+
+    Ok(())
+}
+
+#[test]
+fn sign_file() -> anyhow::Result<()> {
+    logging::init();
+
+    let temp = tempdir()?;
+    let home = LnkHome::Root(temp.path().to_path_buf());
+    let pass = Pwhash::new(SecUtf8::from(b"42".to_vec()), *KDF_PARAMS_TEST);
+    let (profile, _) = create(home.clone(), pass.clone())?;
+
+    let file = temp.path().join("payload");
+    fs::write(&file, [0u8, 159, 146, 150, b'\n'])?;
+
+    let sig = with_ssh_agent(|sock| {
+        ssh_add(
+            home.clone(),
+            profile.id().clone(),
+            sock.clone(),
+            pass,
+            Vec::new(),
+        )?;
+        let payload = read_payload(&file)?;
+        let (_, sig) = ssh_sign(home.clone(), profile.id().clone(), sock, payload)?;
+        Ok(sig)
+    })?;
+
+    let (_, verified) = ssh_verify(
+        home.clone(),
+        profile.id().clone(),
+        fs::read(&file)?,
+        sig.clone(),
+    )?;
+    assert!(verified);
+
+    let (_, verified) = ssh_verify(home, profile.id().clone(), b"tampered", sig)?;
+    assert!(!verified);
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use librad::git::Urn;
+use librad::{git::Urn, net::peer::client::config::AddrFamily};
 
 use crate::Mode;
 
@@ -12,9 +12,19 @@ pub enum Args {
         /// The URN we will synchronise
         #[clap(long)]
         urn: Urn,
-        /// Whether to fetch,push or both to seeds
+        /// Whether to fetch, push or both to seeds. `identity-only` fetches
+        /// only the identity refs.
         #[clap(long, default_value_t)]
         mode: Mode,
+        /// The time (in seconds) each replication and request-pull may take
+        /// per seed. If it is not set, the sync waits on each seed
+        /// indefinitely.
+        #[clap(long)]
+        timeout: Option<u64>,
+        /// Which address families to use when connecting to seeds, one of
+        /// `any`, `prefer-v4`, `prefer-v6`, `v4` or `v6`.
+        #[clap(long, default_value_t)]
+        addr_family: AddrFamily,
     },
     /// Attempt to clone a project URN into a local working directory
     ///
@@ -47,5 +57,18 @@ pub enum Args {
         /// A specific peer to clone from
         #[clap(long)]
         peer: Option<librad::PeerId>,
+        /// Which address families to use when connecting to seeds, one of
+        /// `any`, `prefer-v4`, `prefer-v6`, `v4` or `v6`.
+        #[clap(long, default_value_t)]
+        addr_family: AddrFamily,
     },
 }
+
+impl Args {
+    /// Which address families to use when connecting to seeds.
+    pub fn addr_family(&self) -> AddrFamily {
+        match self {
+            Self::Sync { addr_family, .. } | Self::Clone { addr_family, .. } => *addr_family,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use lnk_identities::working_copy_dir::WorkingCopyDir;
 use tokio::runtime::Runtime;
@@ -19,10 +19,10 @@ use librad::{
 use link_async::Spawner;
 use lnk_clib::{
     keys::ssh::{self, SshAuthSock},
-    seed::{self, Seeds},
+    seed,
 };
 
-use crate::{cli::args::Args, forked, sync};
+use crate::{cli::args::Args, forked, load_seeds, sync};
 
 pub fn main(
     args: Args,
@@ -46,21 +46,23 @@ pub fn main(
         let config = client::Config {
             signer: signer.clone(),
             paths: paths.clone(),
-            replication: net::replication::Config::default(),
+            replication: match &args {
+                Args::Sync { mode, .. } => mode.replication_config(),
+                Args::Clone { .. } => net::replication::Config::default(),
+            },
             user_storage: client::config::Storage::default(),
             network: Network::default(),
+            connections: client::config::Connections {
+                family: args.addr_family(),
+                ..Default::default()
+            },
         };
         let endpoint = quic::SendOnly::new(signer.clone(), Network::default()).await?;
         let client = Client::new(config, spawner, endpoint)?;
-        let seeds = {
+        let (seeds, load_failures) = {
             let seeds_file = profile.paths().seeds_file();
             let store = seed::store::FileStore::<String>::new(seeds_file)?;
-            let (seeds, errors) = Seeds::load(&store, None).await?;
-
-            for error in errors {
-                eprintln!("failed to load seed: {}", error);
-                tracing::warn!(error = %error, "failed to load seed")
-            }
+            let (seeds, failures) = load_seeds(&store).await?;
 
             if seeds.is_empty() {
                 eprintln!(
@@ -70,21 +72,27 @@ pub fn main(
                 return Ok(());
             }
 
-            seeds
+            (seeds, failures)
         };
         match args {
-            Args::Sync { urn, mode } => {
-                let synced = sync(&client, urn, seeds, mode).await;
-                println!("{}", serde_json::to_string(&synced)?);
+            Args::Sync {
+                urn, mode, timeout, ..
+            } => {
+                let timeout = timeout.map(Duration::from_secs);
+                let mut report = sync(&client, urn, seeds, mode, timeout).await;
+                report.failures.extend(load_failures);
+                println!("{}", serde_json::to_string(&report)?);
             },
-            Args::Clone { urn, path, peer } => {
+            Args::Clone {
+                urn, path, peer, ..
+            } => {
                 let storage = librad::git::Storage::open(paths, signer.clone())?;
 
                 let already_had_urn = storage.has_urn(&urn)?;
                 let path = WorkingCopyDir::at_or_current_dir(path)?;
                 println!("cloning urn {} into {}", urn, path);
                 println!("syncing monorepo with seeds");
-                sync(&client, urn.clone(), seeds, crate::Mode::Fetch).await;
+                sync(&client, urn.clone(), seeds, crate::Mode::Fetch, None).await;
 
                 if !already_had_urn {
                     // This is the first time we've seen this project, so we set the default head
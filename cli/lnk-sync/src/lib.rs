@@ -1,7 +1,13 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::{fmt, net::SocketAddr, str::FromStr};
+use std::{
+    fmt,
+    future::Future,
+    net::{SocketAddr, ToSocketAddrs},
+    str::FromStr,
+    time::Duration,
+};
 
 use serde::Serialize;
 use thiserror::Error;
@@ -9,12 +15,14 @@ use thiserror::Error;
 use librad::{
     git::Urn,
     net::{
+        self,
         peer::{client, Client},
         quic::ConnectPeer,
     },
+    PeerId,
     Signer,
 };
-use lnk_clib::seed::{Seed, Seeds};
+use lnk_clib::seed::{self, Seed, Seeds};
 
 pub mod cli;
 mod forked;
@@ -27,6 +35,78 @@ pub struct Synced {
     pub seed: Seed<Vec<SocketAddr>>,
     pub replication: Option<replication::Success>,
     pub request_pull: Option<request_pull::Success>,
+    /// Set if only the identity refs were fetched, see [`Mode::IdentityOnly`].
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub identity_only: bool,
+    /// The operations which did not complete within the timeout given to
+    /// [`sync`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub timed_out: Vec<TimedOut>,
+}
+
+/// The outcome of a [`sync`], separating the [`Synced`] seeds from any
+/// failures encountered along the way.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub synced: Vec<Synced>,
+    pub failures: Vec<Failure>,
+}
+
+/// A failure to load or synchronise with a seed.
+#[derive(Debug, Serialize)]
+pub struct Failure {
+    /// The seed the failure relates to, if it is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer: Option<PeerId>,
+    pub error: String,
+}
+
+impl Failure {
+    fn new(peer: PeerId, error: &impl std::error::Error) -> Self {
+        Self {
+            peer: Some(peer),
+            error: error.to_string(),
+        }
+    }
+
+    fn timed_out(peer: PeerId, op: TimedOut) -> Self {
+        Self {
+            peer: Some(peer),
+            error: format!("{} timed out", op),
+        }
+    }
+}
+
+impl From<&seed::error::Load> for Failure {
+    fn from(err: &seed::error::Load) -> Self {
+        let peer = match err {
+            seed::error::Load::Resolve(seed::error::Resolve::DnsLookupFailed { peer, .. }) => {
+                Some(*peer)
+            },
+            _ => None,
+        };
+        Self {
+            peer,
+            error: err.to_string(),
+        }
+    }
+}
+
+/// An operation against a seed which did not complete in time, see [`sync`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimedOut {
+    Replication,
+    RequestPull,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Replication => "replication",
+            Self::RequestPull => "request-pull",
+        })
+    }
 }
 
 #[derive(Debug, Error)]
@@ -45,6 +125,13 @@ pub enum Mode {
     Push,
     /// Perform both replication and request-pull for a seed.
     All,
+    /// Only perform replication from a seed, fetching only the identity refs,
+    /// i.e. `rad/id`, `rad/self` and `rad/signed_refs`.
+    ///
+    /// Note that the [`Client`] must be configured with
+    /// [`librad::net::replication::DataPolicy::Deny`] for this to take effect,
+    /// see [`Mode::replication_config`].
+    IdentityOnly,
 }
 
 impl Mode {
@@ -53,6 +140,7 @@ impl Mode {
             Mode::Fetch => true,
             Mode::Push => false,
             Mode::All => true,
+            Mode::IdentityOnly => true,
         }
     }
 
@@ -61,6 +149,24 @@ impl Mode {
             Mode::Fetch => false,
             Mode::Push => true,
             Mode::All => true,
+            Mode::IdentityOnly => false,
+        }
+    }
+
+    pub fn is_identity_only(&self) -> bool {
+        matches!(self, Mode::IdentityOnly)
+    }
+
+    /// The replication configuration for the [`Client`] used with this mode.
+    pub fn replication_config(&self) -> net::replication::Config {
+        let data = if self.is_identity_only() {
+            net::replication::DataPolicy::Deny
+        } else {
+            net::replication::DataPolicy::Allow
+        };
+        net::replication::Config {
+            data,
+            ..net::replication::Config::default()
         }
     }
 }
@@ -79,7 +185,8 @@ impl FromStr for Mode {
             "fetch" => Ok(Self::Fetch),
             "push" => Ok(Self::Push),
             "all" => Ok(Self::All),
-            _ => Err("invalid mode, exptected one of: ['fetch', 'push', 'all']"),
+            "identity-only" => Ok(Self::IdentityOnly),
+            _ => Err("invalid mode, exptected one of: ['fetch', 'push', 'all', 'identity-only']"),
         }
     }
 }
@@ -90,32 +197,86 @@ impl fmt::Display for Mode {
             Mode::Fetch => "fetch",
             Mode::Push => "push",
             Mode::All => "all",
+            Mode::IdentityOnly => "identity-only",
         })
     }
 }
 
+/// Load the [`Seeds`] from `store`, see [`Seeds::load`].
+///
+/// The seeds which failed to load are returned as [`Failure`]s, to be included
+/// in the [`Report`] of a [`sync`].
+pub async fn load_seeds<S, T>(store: &S) -> Result<(Seeds, Vec<Failure>), S::Scan>
+where
+    S: seed::Store<Addrs = T>,
+    S::Iter: std::error::Error + Send + Sync + 'static,
+    T: Clone + fmt::Display + FromStr + ToSocketAddrs,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let (seeds, errors) = Seeds::load(store, None).await?;
+    let failures = errors
+        .iter()
+        .map(|error| {
+            eprintln!("failed to load seed: {}", error);
+            tracing::warn!(error = %error, "failed to load seed");
+            Failure::from(error)
+        })
+        .collect();
+    Ok((seeds, failures))
+}
+
 /// Synchronise with the provided list of `seeds` for the given `urn`.
 ///
 /// For each seed the [`Mode`] is checked to see if it should replicate and
-/// request-pull.
-pub async fn sync<S, E>(client: &Client<S, E>, urn: Urn, seeds: Seeds, mode: Mode) -> Vec<Synced>
+/// request-pull. Any errors are reported in [`Report::failures`].
+///
+/// If a `timeout` is given, each replication and request-pull must complete
+/// within it. An operation which does not is abandoned and recorded in
+/// [`Synced::timed_out`] as well as [`Report::failures`], and the sync carries
+/// on with the next operation or seed.
+pub async fn sync<S, E>(
+    client: &Client<S, E>,
+    urn: Urn,
+    seeds: Seeds,
+    mode: Mode,
+    timeout: Option<Duration>,
+) -> Report
 where
     S: Signer + Clone,
     E: ConnectPeer + Clone + Send + Sync + 'static,
 {
-    let mut syncs = Vec::with_capacity(seeds.len());
+    let mut report = Report {
+        synced: Vec::with_capacity(seeds.len()),
+        failures: Vec::new(),
+    };
     let is_push = mode.is_push();
     let is_fetch = mode.is_fetch();
     for seed in seeds.0.into_iter() {
+        let mut timed_out = Vec::new();
         let replication = if is_fetch {
-            match replication::replicate(client, urn.clone(), seed.clone()).await {
-                Ok(s) => Some(s),
-                Err(err) => {
+            match within(
+                timeout,
+                replication::replicate(client, urn.clone(), seed.clone()),
+            )
+            .await
+            {
+                None => {
+                    eprintln!("timed out replicating from the seed: {}", seed.peer);
+                    tracing::error!(seed = %seed.peer, "replication timed out");
+                    timed_out.push(TimedOut::Replication);
+                    report
+                        .failures
+                        .push(Failure::timed_out(seed.peer, TimedOut::Replication));
+                    None
+                },
+                Some(Ok(s)) => Some(s),
+                Some(Err(err)) => {
                     eprintln!(
                         "failed to replicate from the seed: {}, reason: {}",
                         seed.peer, err
                     );
                     tracing::error!(seed = %seed.peer, err = %err, "replication error");
+                    report.failures.push(Failure::new(seed.peer, &err));
                     None
                 },
             }
@@ -124,14 +285,29 @@ where
         };
 
         let request_pull = if is_push {
-            match request_pull::request_pull(client, urn.clone(), seed.clone()).await {
-                Ok(s) => s,
-                Err(err) => {
+            match within(
+                timeout,
+                request_pull::request_pull(client, urn.clone(), seed.clone()),
+            )
+            .await
+            {
+                None => {
+                    eprintln!("timed out requesting a pull to the seed: {}", seed.peer);
+                    tracing::error!(seed = %seed.peer, "request-pull timed out");
+                    timed_out.push(TimedOut::RequestPull);
+                    report
+                        .failures
+                        .push(Failure::timed_out(seed.peer, TimedOut::RequestPull));
+                    None
+                },
+                Some(Ok(s)) => s,
+                Some(Err(err)) => {
                     eprintln!(
                         "failed to request-pull to the seed: {}, reason: {}",
                         seed.peer, err
                     );
                     tracing::error!(seed = %seed.peer, err = %err, "request-pull error");
+                    report.failures.push(Failure::new(seed.peer, &err));
                     None
                 },
             }
@@ -139,11 +315,24 @@ where
             None
         };
 
-        syncs.push(Synced {
+        report.synced.push(Synced {
             seed,
             replication,
             request_pull,
+            identity_only: mode.is_identity_only(),
+            timed_out,
         })
     }
-    syncs
+    report
+}
+
+/// Await `f`, giving up with `None` if it does not complete within `timeout`.
+async fn within<F, T>(timeout: Option<Duration>, f: F) -> Option<T>
+where
+    F: Future<Output = T>,
+{
+    match timeout {
+        None => Some(f.await),
+        Some(after) => link_async::timeout(after, f).await.ok(),
+    }
 }
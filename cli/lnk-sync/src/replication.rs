@@ -43,6 +43,7 @@ pub struct Success {
     tracked: Tracked,
     created: Created,
     requires_confirmation: bool,
+    would_prune: Vec<RefString>,
 }
 
 impl From<replication::Success> for Success {
@@ -61,12 +62,18 @@ impl From<replication::Success> for Success {
                 tracked
             });
         let requires_confirmation = s.requires_confirmation;
+        let would_prune = s
+            .would_prune
+            .into_iter()
+            .map(|update| update.refname().to_owned())
+            .collect();
         Self {
             references,
             rejected,
             tracked,
             created,
             requires_confirmation,
+            would_prune,
         }
     }
 }
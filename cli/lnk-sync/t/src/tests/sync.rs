@@ -0,0 +1,167 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use librad::{
+    git::Urn,
+    net::{
+        peer::{client, Client},
+        quic::{ConnectPeer, Ingress},
+        Network,
+    },
+    paths::Paths,
+    PeerId,
+    SecretKey,
+};
+use link_async::Spawner;
+use lnk_clib::seed::{self, Seed, Seeds};
+use lnk_sync::{load_seeds, sync, Failure, Mode, TimedOut};
+
+/// An endpoint whose connection attempts never complete.
+#[derive(Clone)]
+struct Unresponsive;
+
+#[async_trait::async_trait]
+impl ConnectPeer for Unresponsive {
+    async fn connect<'a, Addrs>(&self, _: PeerId, _: Addrs) -> Option<Ingress<'a>>
+    where
+        Addrs: IntoIterator<Item = SocketAddr> + Send,
+        Addrs::IntoIter: Send,
+    {
+        futures::future::pending().await
+    }
+}
+
+/// An endpoint which fails to connect to any peer.
+#[derive(Clone)]
+struct Unreachable;
+
+#[async_trait::async_trait]
+impl ConnectPeer for Unreachable {
+    async fn connect<'a, Addrs>(&self, _: PeerId, _: Addrs) -> Option<Ingress<'a>>
+    where
+        Addrs: IntoIterator<Item = SocketAddr> + Send,
+        Addrs::IntoIter: Send,
+    {
+        None
+    }
+}
+
+fn config(tmp: &tempfile::TempDir) -> anyhow::Result<client::Config<SecretKey>> {
+    Ok(client::Config {
+        signer: SecretKey::new(),
+        paths: Paths::from_root(tmp.path())?,
+        replication: Default::default(),
+        user_storage: Default::default(),
+        network: Network::default(),
+        connections: Default::default(),
+    })
+}
+
+fn urn() -> Urn {
+    "rad:git:hnrkb39fr6f4jj59nfiq7tfd9aznirdu7b59o"
+        .parse()
+        .unwrap()
+}
+
+fn seed() -> Seed<Vec<SocketAddr>> {
+    Seed {
+        peer: PeerId::from(SecretKey::new()),
+        addrs: vec!["127.0.0.1:8776".parse().unwrap()],
+        label: None,
+    }
+}
+
+#[tokio::test]
+async fn unresponsive_seeds_time_out() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let spawner = Arc::new(Spawner::from_current().unwrap());
+    let client = Client::new(config(&tmp)?, spawner, Unresponsive)?;
+
+    let report = sync(
+        &client,
+        urn(),
+        Seeds(vec![seed(), seed()]),
+        Mode::All,
+        Some(Duration::from_millis(100)),
+    )
+    .await;
+
+    assert_eq!(report.synced.len(), 2, "every seed should be attempted");
+    assert_eq!(report.failures.len(), 4, "each timeout is a failure");
+    assert!(report
+        .failures
+        .iter()
+        .all(|failure| failure.error.ends_with("timed out")));
+    for s in report.synced {
+        assert!(s.replication.is_none());
+        assert!(s.request_pull.is_none());
+        assert_eq!(
+            s.timed_out,
+            vec![TimedOut::Replication, TimedOut::RequestPull]
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn report_includes_failures() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let spawner = Arc::new(Spawner::from_current().unwrap());
+    let client = Client::new(config(&tmp)?, spawner, Unreachable)?;
+    let unreachable = seed();
+    let unresolved = PeerId::from(SecretKey::new());
+
+    let mut report = sync(
+        &client,
+        urn(),
+        Seeds(vec![unreachable.clone()]),
+        Mode::Fetch,
+        None,
+    )
+    .await;
+    report
+        .failures
+        .push(Failure::from(&seed::error::Load::Resolve(
+            seed::error::Resolve::DnsLookupFailed {
+                peer: unresolved,
+                addr: "seed.example:8776".to_owned(),
+            },
+        )));
+
+    let json = serde_json::to_value(&report)?;
+    let synced = json["synced"].as_array().unwrap();
+    assert_eq!(synced.len(), 1);
+    assert_eq!(synced[0]["seed"]["peer"], unreachable.peer.to_string());
+
+    let failures = json["failures"].as_array().unwrap();
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0]["peer"], unreachable.peer.to_string());
+    assert_eq!(failures[1]["peer"], unresolved.to_string());
+    assert!(failures.iter().all(|failure| failure["error"].is_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn load_failures_are_reported() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let seeds_file = tmp.path().join("seeds");
+    let peer = PeerId::from(SecretKey::new());
+    std::fs::write(
+        &seeds_file,
+        format!("{}@127.0.0.1:8776\nnot a seed\n", peer),
+    )?;
+    let store = seed::store::FileStore::<String>::new(&seeds_file)?;
+
+    let (seeds, failures) = load_seeds(&store).await?;
+
+    assert_eq!(seeds.len(), 1);
+    assert_eq!(seeds.0[0].peer, peer);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].peer, None);
+
+    Ok(())
+}
@@ -3,7 +3,7 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use crate::{EntryContents, History, ObjectId, TypeName};
+use crate::{ClientId, EntryContents, History, ObjectId, TypeName};
 
 use link_identities::git::Urn;
 
@@ -55,18 +55,27 @@ impl CachedChangeGraph {
         self.refs.clone()
     }
 
+    /// Record the change `new` which was made on top of the current tips. If
+    /// the change is `compacted` it replaces the history, see
+    /// [`crate::UpdateMode::Compact`].
     pub(crate) fn update_ref(
         &mut self,
         previous: Option<git2::Oid>,
         new: git2::Oid,
         author: Urn,
+        client: Option<ClientId>,
         changes: EntryContents,
+        compacted: bool,
     ) {
         if let Some(previous) = previous {
             self.refs.remove(&previous);
         }
         self.refs.insert(new);
-        self.history.extend(new, author, changes);
+        if compacted {
+            self.history = History::new_from_root(new, author, client, changes);
+        } else {
+            self.history.extend(new, author, client, changes);
+        }
     }
 
     pub fn refs(&self) -> &BTreeSet<git2::Oid> {
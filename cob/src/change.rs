@@ -3,13 +3,13 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use super::{trailers, EntryContents, HistoryType, TypeName};
+use super::{trailers, ClientId, EntryContents, HistoryType, TypeName};
 
-use git_trailers::{parse as parse_trailers, OwnedTrailer};
-use link_crypto::BoxedSigner;
+use git_trailers::{parse as parse_trailers, OwnedTrailer, Trailer};
+use link_crypto::{BoxedSigner, PublicKey};
 use link_identities::sign::Signatures;
 
-use std::{convert::TryFrom, fmt};
+use std::{collections::BTreeSet, convert::TryFrom, fmt};
 
 use serde::{Deserialize, Serialize};
 
@@ -35,6 +35,11 @@ pub struct Change {
     /// The OID of the parent commit which points at the identity this change
     /// was authorized with respect to at the time the change was authored.
     authorizing_identity_commit: git2::Oid,
+    /// The identifier of the client which created this change, if any
+    client: Option<ClientId>,
+    /// Whether the contents of this change replace the history it builds on,
+    /// see [`crate::UpdateMode::Compact`]
+    compacted: bool,
     /// The manifest
     manifest: Manifest,
     /// The actual changes this change carries
@@ -50,8 +55,9 @@ impl fmt::Display for Change {
 pub mod error {
     use super::trailers;
     use git_trailers::Error as TrailerError;
-    use link_crypto::BoxedSignError;
+    use link_crypto::{BoxedSignError, PublicKey};
     use link_identities::sign::error::Signatures;
+    use std::collections::BTreeSet;
     use thiserror::Error;
 
     #[derive(Debug, Error)]
@@ -62,6 +68,15 @@ pub mod error {
         Signer(#[from] BoxedSignError),
     }
 
+    /// The signature made by `actual` does not verify against the change, or
+    /// `actual` is not one of the `expected` keys of the change's author.
+    #[derive(Debug, Error)]
+    #[error("the signature by {actual} is not a valid signature by one of {expected:?}")]
+    pub struct Signature {
+        pub expected: BTreeSet<PublicKey>,
+        pub actual: PublicKey,
+    }
+
     #[derive(Debug, Error)]
     pub enum Load {
         #[error(transparent)]
@@ -86,6 +101,10 @@ pub mod error {
         AuthorizingIdentityTrailer(
             #[from] super::trailers::error::InvalidAuthorizingIdentityTrailer,
         ),
+        #[error(transparent)]
+        ClientTrailer(#[from] trailers::error::InvalidClientTrailer),
+        #[error(transparent)]
+        CompactedTrailer(#[from] trailers::error::InvalidCompactedTrailer),
         #[error("non utf-8 characters in commit message")]
         Utf8,
         #[error(transparent)]
@@ -97,6 +116,8 @@ pub struct NewChangeSpec {
     pub(crate) typename: TypeName,
     pub(crate) tips: Option<Vec<git2::Oid>>,
     pub(crate) message: Option<String>,
+    pub(crate) client: Option<ClientId>,
+    pub(crate) compacted: bool,
     pub(crate) contents: EntryContents,
 }
 
@@ -149,11 +170,17 @@ impl Change {
         parent_commits.push(authorizing_identity_commit);
         parent_commits.push(author_commit);
 
-        let trailers = vec![
+        let mut trailers = vec![
             super::trailers::AuthorCommitTrailer::from(author_identity_commit_id).into(),
             super::trailers::AuthorizingIdentityCommitTrailer::from(authorizing_identity_commit_id)
                 .into(),
         ];
+        if let Some(client) = &spec.client {
+            trailers.push(super::trailers::ClientTrailer::from(client.clone()).into());
+        }
+        if spec.compacted {
+            trailers.push(super::trailers::CompactedTrailer.into());
+        }
 
         let commit = repo.commit(
             None,
@@ -173,6 +200,8 @@ impl Change {
 
         Ok(Change {
             schema_commit: None,
+            client: spec.client,
+            compacted: spec.compacted,
             manifest,
             contents: spec.contents,
             commit,
@@ -205,6 +234,12 @@ impl Change {
                 Err(super::trailers::error::InvalidSchemaTrailer::NoTrailer) => None,
                 Err(e) => return Err(e.into()),
             };
+        let client_trailer = super::trailers::ClientTrailer::from_trailers(
+            owned_trailers.iter().map(Trailer::from),
+        )?;
+        let compacted = super::trailers::CompactedTrailer::from_trailers(
+            owned_trailers.iter().map(Trailer::from),
+        )?;
         let signatures = Signatures::try_from(trailers)?;
 
         let tree = commit.tree()?;
@@ -238,6 +273,8 @@ impl Change {
             schema_commit: schema_commit_trailer.map(|s| s.oid()),
             author_commit: author_commit_trailer.oid(),
             authorizing_identity_commit: authorizing_identity_trailer.oid(),
+            client: client_trailer.map(|t| t.into_client()),
+            compacted,
             signatures,
             revision: tree.id(),
         })
@@ -267,13 +304,35 @@ impl Change {
         self.authorizing_identity_commit
     }
 
-    pub fn valid_signatures(&self) -> bool {
+    pub fn client(&self) -> Option<&ClientId> {
+        self.client.as_ref()
+    }
+
+    pub fn compacted(&self) -> bool {
+        self.compacted
+    }
+
+    /// Whether every signature of the change verifies against its tree,
+    /// regardless of who made it.
+    pub fn verify_signatures(&self) -> bool {
+        self.signatures
+            .iter()
+            .all(|(key, sig)| key.verify(sig, self.revision.as_bytes()))
+    }
+
+    /// Check that every signature of the change was made by one of the
+    /// `expected` keys and verifies against its tree, returning the key of
+    /// the first signature which does not.
+    pub fn valid_signatures(&self, expected: &BTreeSet<PublicKey>) -> Result<(), error::Signature> {
         for (key, sig) in self.signatures.iter() {
-            if !key.verify(sig, self.revision.as_bytes()) {
-                return false;
+            if !expected.contains(key) || !key.verify(sig, self.revision.as_bytes()) {
+                return Err(error::Signature {
+                    expected: expected.clone(),
+                    actual: *key,
+                });
             }
         }
-        true
+        Ok(())
     }
 }
 
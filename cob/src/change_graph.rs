@@ -4,12 +4,25 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 use super::{
+    change,
+    history,
+    identity_cache::IdentityCache,
     AuthorizingIdentity,
     Change,
+    ChangeGraphEdge,
+    ChangeGraphJson,
+    ChangeGraphNode,
     CollaborativeObject,
+    History,
     IdentityStorage,
+    Limits,
+    Loading,
     ObjectId,
+    PendingChange,
+    RetrievalProgress,
     TypeName,
+    UnverifiedChange,
+    UnverifiedObject,
 };
 use petgraph::{
     visit::{EdgeRef, Topo, Walker},
@@ -18,7 +31,7 @@ use petgraph::{
 use thiserror::Error as ThisError;
 
 use std::{
-    collections::{hash_map::Entry, BTreeSet, HashMap},
+    collections::{hash_map::Entry, BTreeSet, HashMap, HashSet},
     convert::TryInto,
 };
 
@@ -30,6 +43,29 @@ pub enum Error {
     MissingRevision(git2::Oid),
     #[error(transparent)]
     Git(#[from] git2::Error),
+    #[error(transparent)]
+    TooLarge(#[from] TooLarge),
+    #[error(transparent)]
+    RootMismatch(#[from] RootMismatch),
+}
+
+#[derive(Debug, ThisError)]
+pub enum TooLarge {
+    #[error("the change graph contains more than {max} changes")]
+    Changes { max: usize },
+    #[error("the history of the change graph is larger than {max} bytes")]
+    HistoryBytes { max: usize },
+}
+
+/// The change graph loaded for an object is not rooted at the change which
+/// the object's identifier refers to.
+#[derive(Debug, ThisError)]
+#[error("the change graph of {object} is not rooted at the object's initial change, found roots {roots:?}")]
+pub struct RootMismatch {
+    /// The identifier of the object which was requested
+    pub object: ObjectId,
+    /// The roots of the change graph which was loaded
+    pub roots: Vec<git2::Oid>,
 }
 
 /// The graph of changes for a particular collaborative object
@@ -42,48 +78,107 @@ pub(super) struct ChangeGraph<'a> {
 
 impl<'a> ChangeGraph<'a> {
     /// Load the change graph from the underlying git store by walking
-    /// backwards from references to the object
-    #[tracing::instrument(skip(repo, tip_refs, authorizing_identity))]
+    /// backwards from references to the object.
+    ///
+    /// The walk is aborted with [`Error::TooLarge`] as soon as the graph
+    /// exceeds `limits`, before the remaining changes are loaded.
     pub(super) fn load<'b, 'c>(
         tip_refs: impl Iterator<Item = &'b git2::Reference<'b>>,
         repo: &'c git2::Repository,
         authorizing_identity: &'c dyn AuthorizingIdentity,
         typename: &TypeName,
         oid: &ObjectId,
+        limits: &Limits,
+    ) -> Result<Option<ChangeGraph<'c>>, Error> {
+        Self::load_with_progress(
+            tip_refs,
+            repo,
+            authorizing_identity,
+            typename,
+            oid,
+            limits,
+            &Loading::Sequential,
+            &mut |_| {},
+        )
+    }
+
+    /// As [`Self::load`], reading the changes as configured by `loading` and
+    /// reporting [`RetrievalProgress::LoadingRefs`] and
+    /// [`RetrievalProgress::Walking`] to `progress`.
+    ///
+    /// The graph is walked in rounds, where the changes of all the parents
+    /// discovered in the previous round are read at once. The resulting graph
+    /// is the same regardless of `loading`.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip(repo, tip_refs, authorizing_identity, loading, progress))]
+    pub(super) fn load_with_progress<'b, 'c>(
+        tip_refs: impl Iterator<Item = &'b git2::Reference<'b>>,
+        repo: &'c git2::Repository,
+        authorizing_identity: &'c dyn AuthorizingIdentity,
+        typename: &TypeName,
+        oid: &ObjectId,
+        limits: &Limits,
+        loading: &Loading,
+        progress: &mut dyn FnMut(RetrievalProgress),
     ) -> Result<Option<ChangeGraph<'c>>, Error> {
         let mut builder = GraphBuilder::default();
-        let mut edges_to_process: Vec<(git2::Commit, git2::Oid)> = Vec::new();
+        let mut edges_to_process: Vec<(git2::Oid, git2::Oid)> = Vec::new();
         let tip_refs: Vec<&git2::Reference<'_>> = tip_refs.collect();
         let ref_names: Vec<&str> = tip_refs.iter().filter_map(|r| r.name()).collect();
         tracing::trace!(refs=?ref_names, "loading object from references");
+        progress(RetrievalProgress::LoadingRefs {
+            refs: tip_refs.len(),
+        });
 
         // Populate the initial set of edges_to_process from the refs we have
-        for reference in tip_refs {
-            let commit = reference.peel_to_commit()?;
-            match Change::load(repo, &commit) {
-                Ok(change) => {
-                    let new_edges = builder.add_change(commit, change);
+        let tips = tip_refs
+            .iter()
+            .map(|reference| Ok((*reference, reference.peel_to_commit()?.id())))
+            .collect::<Result<Vec<_>, git2::Error>>()?;
+        let mut loaded = load_changes(repo, loading, tips.iter().map(|(_, tip)| *tip).collect())?;
+        for (reference, tip) in tips {
+            match loaded.remove(&tip) {
+                Some(Ok(change)) => {
+                    let new_edges = builder.add_change(tip, change, limits)?;
                     edges_to_process.extend(new_edges);
                 },
-                Err(e) => {
-                    tracing::warn!(err=?e, commit=?commit.id(), reference=?reference.name(), "unable to load change from reference");
+                Some(Err(e)) => {
+                    tracing::warn!(err=?e, commit=?tip, reference=?reference.name(), "unable to load change from reference");
                 },
+                // Another reference points to the same change
+                None => {},
             }
         }
 
         // Process edges until we have no more to process
-        while let Some((parent_commit, child_commit_id)) = edges_to_process.pop() {
-            tracing::trace!(?parent_commit, ?child_commit_id, "loading change");
-            match Change::load(repo, &parent_commit) {
-                Ok(change) => {
-                    let parent_commit_id = parent_commit.id();
-                    let new_edges = builder.add_change(parent_commit, change);
-                    builder.add_edge(child_commit_id, parent_commit_id);
-                    edges_to_process.extend(new_edges);
-                },
-                Err(e) => {
-                    tracing::warn!(err=?e, commit=?parent_commit.id(), "unable to load changetree from commit");
-                },
+        let mut edges = 0;
+        while !edges_to_process.is_empty() {
+            let round = std::mem::take(&mut edges_to_process);
+            let parents = round
+                .iter()
+                .map(|(parent, _)| *parent)
+                .filter(|parent| !builder.contains(parent))
+                .collect::<BTreeSet<_>>();
+            let mut loaded = load_changes(repo, loading, parents.into_iter().collect())?;
+            for (parent_commit_id, child_commit_id) in round.into_iter().rev() {
+                tracing::trace!(?parent_commit_id, ?child_commit_id, "loading change");
+                edges += 1;
+                progress(RetrievalProgress::Walking { edges });
+                let new_edges = if builder.contains(&parent_commit_id) {
+                    builder.parent_edges(parent_commit_id)
+                } else {
+                    match loaded.remove(&parent_commit_id) {
+                        Some(Ok(change)) => builder.add_change(parent_commit_id, change, limits)?,
+                        Some(Err(e)) => {
+                            tracing::warn!(err=?e, commit=?parent_commit_id, "unable to load changetree from commit");
+                            continue;
+                        },
+                        // The change failed to load for another edge
+                        None => continue,
+                    }
+                };
+                builder.add_edge(child_commit_id, parent_commit_id);
+                edges_to_process.extend(new_edges);
             }
         }
         builder.build(repo, *oid, authorizing_identity)
@@ -93,42 +188,172 @@ impl<'a> ChangeGraph<'a> {
     /// filter out branches of the graph which do not have valid signatures,
     /// or which do not have permission to make a change, or which make a
     /// change which invalidates the schema of the object
-    pub(super) fn evaluate<I: IdentityStorage>(&self, identities: &I) -> CollaborativeObject {
-        let mut roots: Vec<petgraph::graph::NodeIndex<u32>> = self
-            .graph
-            .externals(petgraph::Direction::Incoming)
-            .collect();
-        roots.sort();
-        // This is okay because we check that the graph has a root node in
-        // GraphBuilder::build
-        let root = roots.first().unwrap();
-        let typename = {
-            let first_node = &self.graph[*root];
-            first_node.typename().clone()
-        };
-        let evaluating =
-            evaluation::Evaluating::new(identities, self.authorizing_identity, self.repo);
-        let topo = Topo::new(&self.graph);
-        let items = topo.iter(&self.graph).map(|idx| {
-            let node = &self.graph[idx];
-            let outgoing_edges = self.graph.edges_directed(idx, EdgeDirection::Outgoing);
-            let child_commits: Vec<git2::Oid> = outgoing_edges
-                .map(|e| *self.graph[e.target()].commit())
-                .collect();
-            (node, child_commits)
+    ///
+    /// The history starts at the newest compacted change which all tips build
+    /// on, see [`crate::UpdateMode::Compact`], so the changes before it are
+    /// not evaluated. If that change is rejected, or there is no such change,
+    /// the whole graph is evaluated.
+    ///
+    /// [`RetrievalProgress::Evaluating`] is reported to `progress` as each
+    /// change is evaluated.
+    pub(super) fn evaluate<I: IdentityStorage>(
+        &self,
+        identities: &I,
+        cache: &mut IdentityCache,
+        progress: &mut dyn FnMut(RetrievalProgress),
+    ) -> CollaborativeObject {
+        let root = self.root();
+        let typename = root.typename().clone();
+        let compacted = self.compaction().and_then(|start| {
+            let within = self.descendants(start);
+            self.evaluate_within(
+                identities,
+                cache,
+                progress,
+                *self.graph[start].commit(),
+                Some(&within),
+            )
+            .ok()
         });
-        let history = {
-            let root_change = &self.graph[*root];
-            evaluating.evaluate(*root_change.commit(), items)
+        let history = match compacted {
+            Some(history) => history,
+            None => {
+                // SAFETY: The graph is rooted at `root` and the root change is
+                // always part of the evaluation items
+                self.evaluate_within(identities, cache, progress, *root.commit(), None)
+                    .unwrap()
+            },
         };
         CollaborativeObject {
             authorizing_identity_urn: self.authorizing_identity.urn(),
             typename,
             history,
             id: self.object_id,
+            tips: self.tips(),
+        }
+    }
+
+    /// Evaluate the changes in `within`, or the whole graph, into a history
+    /// starting at `start`.
+    fn evaluate_within<I: IdentityStorage>(
+        &self,
+        identities: &I,
+        cache: &mut IdentityCache,
+        progress: &mut dyn FnMut(RetrievalProgress),
+        start: git2::Oid,
+        within: Option<&HashSet<petgraph::graph::NodeIndex<u32>>>,
+    ) -> Result<History, history::CreateError> {
+        let evaluating = evaluation::Evaluating::new(
+            identities,
+            self.authorizing_identity,
+            self.repo,
+            cache,
+            self.root().schema_commit(),
+        );
+        let total = within.map_or_else(|| self.graph.node_count(), HashSet::len);
+        let items = self
+            .evaluation_items_within(within)
+            .enumerate()
+            .map(|(evaluated, item)| {
+                progress(RetrievalProgress::Evaluating {
+                    evaluated: evaluated + 1,
+                    total,
+                });
+                item
+            });
+        evaluating.evaluate(start, items)
+    }
+
+    /// Evaluate the graph, as in [`Self::evaluate`], returning the changes
+    /// which are not part of the resulting history and why.
+    pub(super) fn pending<I: IdentityStorage>(
+        &self,
+        identities: &I,
+        cache: &mut IdentityCache,
+    ) -> Vec<PendingChange> {
+        evaluation::Evaluating::new(
+            identities,
+            self.authorizing_identity,
+            self.repo,
+            cache,
+            self.root().schema_commit(),
+        )
+        .pending(self.evaluation_items())
+    }
+
+    /// Evaluate the graph, as in [`Self::pending`], returning every change
+    /// annotated with why it was not accepted, if it was not.
+    pub(super) fn unverified<I: IdentityStorage>(
+        &self,
+        identities: &I,
+        cache: &mut IdentityCache,
+    ) -> UnverifiedObject {
+        let root = self.root();
+        let mut pending = self
+            .pending(identities, cache)
+            .into_iter()
+            .map(|change| (change.commit, change.reason))
+            .collect::<HashMap<_, _>>();
+        let changes = self
+            .evaluation_items()
+            .map(|(change, children)| UnverifiedChange {
+                commit: *change.commit(),
+                author_commit: change.author_commit(),
+                author: cache
+                    .person(self.repo, change.author_commit())
+                    .ok()
+                    .flatten()
+                    .map(|person| person.urn()),
+                children,
+                contents: change.contents().clone(),
+                pending: pending.remove(change.commit()),
+            })
+            .collect();
+        UnverifiedObject {
+            id: self.object_id,
+            typename: root.typename().clone(),
+            tips: self.tips(),
+            changes,
         }
     }
 
+    /// The initial change of the object
+    fn root(&self) -> &Change {
+        let mut roots: Vec<petgraph::graph::NodeIndex<u32>> = self
+            .graph
+            .externals(petgraph::Direction::Incoming)
+            .collect();
+        roots.sort();
+        // This is okay because we check that the graph has a root node in
+        // GraphBuilder::build
+        &self.graph[*roots.first().unwrap()]
+    }
+
+    /// The changes of the graph in topological order, along with the commits of
+    /// their children.
+    fn evaluation_items(&self) -> impl Iterator<Item = (&Change, Vec<git2::Oid>)> + '_ {
+        self.evaluation_items_within(None)
+    }
+
+    /// As [`Self::evaluation_items`], only including the changes in `within`
+    /// if it is given.
+    fn evaluation_items_within<'b>(
+        &'b self,
+        within: Option<&'b HashSet<petgraph::graph::NodeIndex<u32>>>,
+    ) -> impl Iterator<Item = (&'b Change, Vec<git2::Oid>)> + 'b {
+        let topo = Topo::new(&self.graph);
+        topo.iter(&self.graph)
+            .filter(move |idx| within.map_or(true, |within| within.contains(idx)))
+            .map(move |idx| {
+                let node = &self.graph[idx];
+                let outgoing_edges = self.graph.edges_directed(idx, EdgeDirection::Outgoing);
+                let child_commits: Vec<git2::Oid> = outgoing_edges
+                    .map(|e| *self.graph[e.target()].commit())
+                    .collect();
+                (node, child_commits)
+            })
+    }
+
     /// Get the tips of the collaborative object
     pub(super) fn tips(&self) -> BTreeSet<git2::Oid> {
         self.graph
@@ -140,6 +365,83 @@ impl<'a> ChangeGraph<'a> {
             .collect()
     }
 
+    /// The most recent change which both the changes in `ours` and in
+    /// `theirs` build on, i.e. the common ancestor of two versions of the
+    /// object identified by their tips. A change counts as its own ancestor.
+    ///
+    /// If there are several such changes, which can happen when changes
+    /// merge each other's branches, the last one in topological order is
+    /// returned. Returns `None` if any of the changes are not part of this
+    /// graph, e.g. because they belong to another object.
+    pub(super) fn merge_base(
+        &self,
+        ours: impl IntoIterator<Item = git2::Oid>,
+        theirs: impl IntoIterator<Item = git2::Oid>,
+    ) -> Option<git2::Oid> {
+        let ours = self.ancestors(ours)?;
+        let theirs = self.ancestors(theirs)?;
+        Topo::new(&self.graph)
+            .iter(&self.graph)
+            .filter(|idx| ours.contains(idx) && theirs.contains(idx))
+            .last()
+            .map(|idx| *self.graph[idx].commit())
+    }
+
+    /// The newest compacted change which every tip of the graph builds on.
+    fn compaction(&self) -> Option<petgraph::graph::NodeIndex<u32>> {
+        let ancestors = self
+            .tips()
+            .into_iter()
+            .map(|tip| self.ancestors(std::iter::once(tip)))
+            .collect::<Option<Vec<_>>>()?;
+        Topo::new(&self.graph)
+            .iter(&self.graph)
+            .filter(|idx| {
+                self.graph[*idx].compacted()
+                    && ancestors.iter().all(|ancestors| ancestors.contains(idx))
+            })
+            .last()
+    }
+
+    /// The changes which build on the change at `start`, including `start`
+    /// itself.
+    fn descendants(
+        &self,
+        start: petgraph::graph::NodeIndex<u32>,
+    ) -> HashSet<petgraph::graph::NodeIndex<u32>> {
+        let mut to_visit = vec![start];
+        let mut descendants = HashSet::new();
+        while let Some(idx) = to_visit.pop() {
+            if descendants.insert(idx) {
+                to_visit.extend(self.graph.neighbors_directed(idx, EdgeDirection::Outgoing));
+            }
+        }
+        descendants
+    }
+
+    /// The changes which the changes in `tips` build on, including `tips`
+    /// themselves. Returns `None` if any of `tips` is not part of the graph.
+    fn ancestors(
+        &self,
+        tips: impl IntoIterator<Item = git2::Oid>,
+    ) -> Option<HashSet<petgraph::graph::NodeIndex<u32>>> {
+        let mut to_visit = tips
+            .into_iter()
+            .map(|tip| {
+                self.graph
+                    .node_indices()
+                    .find(|idx| *self.graph[*idx].commit() == tip)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let mut ancestors = HashSet::new();
+        while let Some(idx) = to_visit.pop() {
+            if ancestors.insert(idx) {
+                to_visit.extend(self.graph.neighbors_directed(idx, EdgeDirection::Incoming));
+            }
+        }
+        Some(ancestors)
+    }
+
     pub(super) fn number_of_nodes(&self) -> u64 {
         self.graph.node_count().try_into().unwrap()
     }
@@ -148,11 +450,46 @@ impl<'a> ChangeGraph<'a> {
         let for_display = self.graph.map(|_ix, n| n.to_string(), |_ix, _e| "");
         petgraph::dot::Dot::new(&for_display).to_string()
     }
+
+    /// A serializable adjacency list of the graph. Edges point from parent
+    /// changes to the changes which depend on them.
+    pub(super) fn to_json(&self) -> ChangeGraphJson {
+        let nodes = self
+            .graph
+            .node_indices()
+            .map(|ix| {
+                let change = &self.graph[ix];
+                ChangeGraphNode {
+                    commit: (*change.commit()).into(),
+                    author_commit: change.author_commit().into(),
+                    schema_commit: change.schema_commit().map(|c| c.into()),
+                    valid_signatures: change.verify_signatures(),
+                }
+            })
+            .collect();
+        let edges = self
+            .graph
+            .edge_references()
+            .map(|e| ChangeGraphEdge {
+                parent: (*self.graph[e.source()].commit()).into(),
+                child: (*self.graph[e.target()].commit()).into(),
+            })
+            .collect();
+        ChangeGraphJson {
+            object_id: self.object_id,
+            nodes,
+            edges,
+        }
+    }
 }
 
 struct GraphBuilder {
     node_indices: HashMap<git2::Oid, petgraph::graph::NodeIndex<u32>>,
     graph: petgraph::Graph<Change, ()>,
+    /// The parent commits of the commits of each change in `graph`
+    parents: HashMap<git2::Oid, Vec<git2::Oid>>,
+    /// The total size of the contents of the changes in `graph`
+    history_bytes: usize,
 }
 
 impl Default for GraphBuilder {
@@ -160,34 +497,56 @@ impl Default for GraphBuilder {
         GraphBuilder {
             node_indices: HashMap::new(),
             graph: petgraph::graph::Graph::new(),
+            parents: HashMap::new(),
+            history_bytes: 0,
         }
     }
 }
 
 impl GraphBuilder {
     /// Add a change to the graph which we are building up, returning any edges
-    /// corresponding to the parents of this node in the change graph
-    fn add_change<'a>(
+    /// corresponding to the parents of this node in the change graph.
+    ///
+    /// If adding the change would make the graph exceed `limits` the change is
+    /// not added and an error is returned.
+    fn add_change(
         &mut self,
-        commit: git2::Commit<'a>,
-        change: Change,
-    ) -> Vec<(git2::Commit<'a>, git2::Oid)> {
-        let author_commit = change.author_commit();
-        let schema_commit = change.schema_commit();
-        let authorizing_identity_commit = change.authorizing_identity_commit();
-        if let Entry::Vacant(e) = self.node_indices.entry(commit.id()) {
+        commit: git2::Oid,
+        loaded: Loaded,
+        limits: &Limits,
+    ) -> Result<Vec<(git2::Oid, git2::Oid)>, TooLarge> {
+        let Loaded { change, parents } = loaded;
+        if let Entry::Vacant(e) = self.node_indices.entry(commit) {
+            let history_bytes = self.history_bytes + change.contents().as_ref().len();
+            check_limits(self.graph.node_count() + 1, history_bytes, limits)?;
+            self.history_bytes = history_bytes;
             let ix = self.graph.add_node(change);
             e.insert(ix);
+            self.parents.insert(commit, parents);
         }
-        commit
-            .parents()
+        Ok(self.parent_edges(commit))
+    }
+
+    fn contains(&self, commit: &git2::Oid) -> bool {
+        self.node_indices.contains_key(commit)
+    }
+
+    /// The edges corresponding to the parents of the change at `commit`, which
+    /// must already be in the graph, which are not yet part of the graph.
+    fn parent_edges(&self, commit: git2::Oid) -> Vec<(git2::Oid, git2::Oid)> {
+        let change = &self.graph[self.node_indices[&commit]];
+        let author_commit = change.author_commit();
+        let schema_commit = change.schema_commit();
+        let authorizing_identity_commit = change.authorizing_identity_commit();
+        self.parents[&commit]
+            .iter()
             .filter_map(|parent| {
-                if parent.id() != author_commit
-                    && Some(parent.id()) != schema_commit
-                    && parent.id() != authorizing_identity_commit
-                    && !self.has_edge(parent.id(), commit.id())
+                if *parent != author_commit
+                    && Some(*parent) != schema_commit
+                    && *parent != authorizing_identity_commit
+                    && !self.has_edge(*parent, commit)
                 {
-                    Some((parent, commit.id()))
+                    Some((*parent, commit))
                 } else {
                     None
                 }
@@ -195,7 +554,7 @@ impl GraphBuilder {
             .collect()
     }
 
-    fn has_edge(&mut self, parent_id: git2::Oid, child_id: git2::Oid) -> bool {
+    fn has_edge(&self, parent_id: git2::Oid, child_id: git2::Oid) -> bool {
         let parent_ix = self.node_indices.get(&parent_id);
         let child_ix = self.node_indices.get(&child_id);
         match (parent_ix, child_ix) {
@@ -217,12 +576,22 @@ impl GraphBuilder {
         object_id: ObjectId,
         authorizing_identity: &'b dyn AuthorizingIdentity,
     ) -> Result<Option<ChangeGraph<'b>>, Error> {
-        if self
+        let roots = self
             .graph
             .externals(petgraph::Direction::Incoming)
-            .next()
-            .is_some()
-        {
+            .map(|ix| *self.graph[ix].commit())
+            .collect::<Vec<_>>();
+        // The object identifier is the oid of the initial change, so the only
+        // root of the graph must be that change. Anything else means the refs
+        // we were given point at some other object.
+        if roots.iter().any(|root| ObjectId::from(root) != object_id) {
+            return Err(RootMismatch {
+                object: object_id,
+                roots,
+            }
+            .into());
+        }
+        if !roots.is_empty() {
             Ok(Some(ChangeGraph {
                 repo,
                 object_id,
@@ -234,3 +603,78 @@ impl GraphBuilder {
         }
     }
 }
+
+/// Check that a graph of `changes` changes, whose contents are
+/// `history_bytes` in total, is within `limits`.
+fn check_limits(changes: usize, history_bytes: usize, limits: &Limits) -> Result<(), TooLarge> {
+    if let Some(max) = limits.max_changes {
+        if changes > max {
+            return Err(TooLarge::Changes { max });
+        }
+    }
+    if let Some(max) = limits.max_history_bytes {
+        if history_bytes > max {
+            return Err(TooLarge::HistoryBytes { max });
+        }
+    }
+    Ok(())
+}
+
+/// A change read from git, along with the parents of its commit.
+struct Loaded {
+    change: Change,
+    parents: Vec<git2::Oid>,
+}
+
+fn load_change(repo: &git2::Repository, commit: git2::Oid) -> Result<Loaded, change::error::Load> {
+    let commit = repo.find_commit(commit)?;
+    Ok(Loaded {
+        change: Change::load(repo, &commit)?,
+        parents: commit.parent_ids().collect(),
+    })
+}
+
+/// Read the changes at `commits`, see [`Loading`].
+fn load_changes(
+    repo: &git2::Repository,
+    loading: &Loading,
+    commits: Vec<git2::Oid>,
+) -> Result<HashMap<git2::Oid, Result<Loaded, change::error::Load>>, git2::Error> {
+    match loading {
+        Loading::Parallel {
+            spawner,
+            concurrency,
+        } if !commits.is_empty() => {
+            // `git2::Repository` can't be shared between threads, so every
+            // task opens its own
+            let path = repo.path().to_path_buf();
+            let concurrency = (*concurrency).max(1);
+            let chunk_size = (commits.len() + concurrency - 1) / concurrency;
+            let tasks = commits
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let path = path.clone();
+                    let chunk = chunk.to_vec();
+                    spawner.blocking(move || {
+                        let repo = git2::Repository::open(path)?;
+                        Ok::<_, git2::Error>(
+                            chunk
+                                .into_iter()
+                                .map(|commit| (commit, load_change(&repo, commit)))
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut loaded = HashMap::new();
+            for chunk in futures::executor::block_on(futures::future::join_all(tasks)) {
+                loaded.extend(chunk?);
+            }
+            Ok(loaded)
+        },
+        _ => Ok(commits
+            .into_iter()
+            .map(|commit| (commit, load_change(repo, commit)))
+            .collect()),
+    }
+}
@@ -8,7 +8,8 @@ use std::{collections::HashMap, ops::ControlFlow};
 use crate::{
     change::Change,
     history,
-    identity_storage::{lookup_authorizing_identity, lookup_person},
+    identity_cache::IdentityCache,
+    pending::{PendingChange, PendingReason, Rejection as RejectionReason},
     pruning_fold,
     AuthDecision,
     AuthorizingIdentity,
@@ -19,6 +20,12 @@ pub struct Evaluating<'a, I: IdentityStorage> {
     identities: &'a I,
     authorizing_identity: &'a dyn AuthorizingIdentity,
     repo: &'a git2::Repository,
+    /// The identities looked up so far, shared with the evaluation of other
+    /// objects
+    cache: &'a mut IdentityCache,
+    /// The schema commit of the root change, which every other change must
+    /// agree with
+    schema_commit: Option<git2::Oid>,
 }
 
 impl<'a, I: IdentityStorage> Evaluating<'a, I> {
@@ -26,42 +33,82 @@ impl<'a, I: IdentityStorage> Evaluating<'a, I> {
         identities: &'a I,
         authorizer: &'a dyn AuthorizingIdentity,
         repo: &'a git2::Repository,
+        cache: &'a mut IdentityCache,
+        schema_commit: Option<git2::Oid>,
     ) -> Evaluating<'a, I> {
         Evaluating {
             identities,
             authorizing_identity: authorizer,
             repo,
+            cache,
+            schema_commit,
         }
     }
 
-    /// # Panics
+    /// Evaluate `items` into a history starting at `root`.
     ///
-    /// If the change corresponding to the root OID is not in `items`
+    /// Fails if the change corresponding to the root OID is not in `items`,
+    /// or it is rejected.
     pub fn evaluate<'b, It: Iterator<Item = (&'b Change, Vec<git2::Oid>)>>(
-        mut self,
+        self,
         root: git2::Oid,
         items: It,
-    ) -> history::History {
-        let entries = pruning_fold::pruning_fold(
-            HashMap::new(),
+    ) -> Result<history::History, history::CreateError> {
+        let (entries, _) = self.fold(items);
+        history::History::new(root, entries)
+    }
+
+    /// The changes in `items` which are rejected, either directly or because
+    /// one of their ancestors was rejected.
+    pub fn pending<'b, It: Iterator<Item = (&'b Change, Vec<git2::Oid>)>>(
+        self,
+        items: It,
+    ) -> Vec<PendingChange> {
+        let (_, pending) = self.fold(items);
+        pending
+    }
+
+    fn fold<'b, It: Iterator<Item = (&'b Change, Vec<git2::Oid>)>>(
+        mut self,
+        items: It,
+    ) -> (
+        HashMap<history::EntryId, history::HistoryEntry>,
+        Vec<PendingChange>,
+    ) {
+        let ((entries, mut pending), rejected) = pruning_fold::pruning_fold_rejected(
+            (HashMap::new(), Vec::new()),
             items.map(|(change, children)| ChangeWithChildren {
                 change,
                 child_commits: children,
             }),
-            |mut entries, c| match self.evaluate_change(c.change, &c.child_commits) {
+            |(mut entries, mut pending), c| match self.evaluate_change(c.change, &c.child_commits) {
                 Err(reason) => {
                     reason.log(c.change);
-                    ControlFlow::Break(entries)
+                    pending.push(PendingChange {
+                        commit: *c.change.commit(),
+                        reason: PendingReason::Rejected(reason),
+                    });
+                    ControlFlow::Break((entries, pending))
                 },
                 Ok(entry) => {
                     tracing::trace!(commit=?c.change.commit(), "change accepted");
                     entries.insert((*c.change.commit()).into(), entry);
-                    ControlFlow::Continue(entries)
+                    ControlFlow::Continue((entries, pending))
                 },
             },
         );
-        // SAFETY: The caller must guarantee that `root` is in `items`
-        history::History::new(root, entries).unwrap()
+        let mut transitive = rejected
+            .transitive()
+            .map(|(commit, ancestor)| PendingChange {
+                commit: *commit,
+                reason: PendingReason::AncestorRejected {
+                    ancestor: *ancestor,
+                },
+            })
+            .collect::<Vec<_>>();
+        transitive.sort_by_key(|change| change.commit);
+        pending.extend(transitive);
+        (entries, pending)
     }
 
     fn evaluate_change(
@@ -69,14 +116,17 @@ impl<'a, I: IdentityStorage> Evaluating<'a, I> {
         change: &Change,
         child_commits: &[git2::Oid],
     ) -> Result<history::HistoryEntry, RejectionReason> {
-        // Check the change signatures are valid
-        if !change.valid_signatures() {
-            return Err(RejectionReason::InvalidSignatures);
+        // Check that the change does not point at a schema other than the one
+        // the object was created with
+        if let Some(schema_commit) = change.schema_commit() {
+            if Some(schema_commit) != self.schema_commit {
+                return Err(RejectionReason::SchemaMismatch { schema_commit });
+            }
         }
 
         // Check that the authorizing identity refernced by the change is a valid
         // version of the identity we are authorizing with respect to
-        let referenced_auth_identity = match lookup_authorizing_identity(
+        let referenced_auth_identity = match self.cache.authorizing_identity(
             self.identities,
             self.repo,
             change.authorizing_identity_commit(),
@@ -90,7 +140,9 @@ impl<'a, I: IdentityStorage> Evaluating<'a, I> {
             return Err(RejectionReason::WrongAuthorizer);
         }
 
-        let author = lookup_person(self.repo, change.author_commit())
+        let author = self
+            .cache
+            .person(self.repo, change.author_commit())
             .map_err(|e| RejectionReason::ErrorFindingAuthor {
                 author_commit_oid: change.author_commit(),
                 error: Box::new(e),
@@ -98,6 +150,11 @@ impl<'a, I: IdentityStorage> Evaluating<'a, I> {
             .ok_or_else(|| RejectionReason::MissingAuthor {
                 missing_author_oid: change.author_commit(),
             })?;
+
+        // Check the change signatures are valid and made by the author
+        change
+            .valid_signatures(&author.delegations().iter().copied().collect())
+            .map_err(RejectionReason::InvalidSignatures)?;
         // Check that the authorizing identity allows this change
         match referenced_auth_identity.check_authorization(&author) {
             AuthDecision::Authorized => {},
@@ -111,7 +168,8 @@ impl<'a, I: IdentityStorage> Evaluating<'a, I> {
             author.urn(),
             child_commits.iter().cloned(),
             change.contents().clone(),
-        ))
+        )
+        .with_client(change.client().cloned()))
     }
 }
 
@@ -132,31 +190,23 @@ impl<'a> pruning_fold::GraphNode for ChangeWithChildren<'a> {
     }
 }
 
-enum RejectionReason {
-    InvalidSignatures,
-    InvalidAuthorizer(Box<dyn std::error::Error>),
-    WrongAuthorizer,
-    MissingAuthor {
-        missing_author_oid: git2::Oid,
-    },
-    ErrorFindingAuthor {
-        author_commit_oid: git2::Oid,
-        error: Box<dyn std::error::Error>,
-    },
-    Unauthorized {
-        reason: &'static str,
-    },
-}
-
 impl RejectionReason {
     fn log(&self, change: &Change) {
         match self {
-            RejectionReason::InvalidSignatures => {
+            RejectionReason::InvalidSignatures(error) => {
                 tracing::warn!(
                     commit=?change.commit(),
+                    actual=%error.actual,
                     "rejecting change because its signatures were invalid"
                 );
             },
+            RejectionReason::SchemaMismatch { schema_commit } => {
+                tracing::warn!(
+                    commit=?change.commit(),
+                    ?schema_commit,
+                    "rejecting change which points at a schema the object was not created with"
+                );
+            },
             RejectionReason::InvalidAuthorizer(error) => {
                 tracing::warn!(
                     commit=?change.commit(),
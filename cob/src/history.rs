@@ -13,7 +13,7 @@ use petgraph::visit::Walker as _;
 use link_identities::git::Urn;
 use radicle_git_ext as ext;
 
-use crate::pruning_fold;
+use crate::{pruning_fold, ClientId};
 
 /// The DAG of changes making up the history of a collaborative object.
 #[derive(Clone, Debug)]
@@ -36,10 +36,17 @@ pub enum CreateError {
     MissingRoot,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum MergeError {
+    #[error("cannot merge histories with different roots")]
+    DifferentRoots,
+}
+
 impl History {
     pub(crate) fn new_from_root<Id: Into<EntryId>>(
         id: Id,
         author: Urn,
+        client: Option<ClientId>,
         contents: EntryContents,
     ) -> Self {
         let id = id.into();
@@ -48,6 +55,7 @@ impl History {
             author,
             children: vec![],
             contents,
+            client,
         };
         let mut entries = HashMap::new();
         entries.insert(id.clone(), root_entry.clone());
@@ -78,6 +86,47 @@ impl History {
         }
     }
 
+    /// The number of changes in this history
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether this history has no changes. A history always contains its
+    /// root change, so this is only ever `false`.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Merge the changes of `other`, a history of the same object, into this
+    /// history.
+    ///
+    /// Changes are identified by their commit, so changes which are already
+    /// in this history are not duplicated. Any dependents they have in
+    /// `other` which are missing here are added to them.
+    pub fn merge(&mut self, other: &History) -> Result<(), MergeError> {
+        if self.root != other.root {
+            return Err(MergeError::DifferentRoots);
+        }
+        for (id, theirs) in &other.entries {
+            match self.entries.get_mut(id) {
+                Some(ours) => {
+                    for child in &theirs.children {
+                        if !ours.children.contains(child) {
+                            ours.children.push(child.clone());
+                        }
+                    }
+                },
+                None => {
+                    self.entries.insert(id.clone(), theirs.clone());
+                },
+            }
+        }
+        let NewGraph { graph, indices } = create_petgraph(&self.root, &self.entries);
+        self.graph = graph;
+        self.indices = indices;
+        Ok(())
+    }
+
     /// A topological (parents before children) traversal of the dependency
     /// graph of this history. This is analagous to
     /// [`std::iter::Iterator::fold`] in that it folds every change into an
@@ -97,12 +146,42 @@ impl History {
         pruning_fold::pruning_fold(init, items, f)
     }
 
+    /// The states this history passed through, one for each accepted change.
+    ///
+    /// Changes are folded into the state in the same order, and with the same
+    /// pruning semantics, as [`History::traverse`]. After each change which
+    /// `f` accepts, `snapshot` is applied to the state and the result is
+    /// paired with the commit of that change. Changes which are pruned -- and
+    /// therefore their descendants -- do not appear in the timeline.
+    ///
+    /// The last entry of the timeline is thus the snapshot of the state
+    /// `traverse` would have produced, unless the root change itself was
+    /// pruned, in which case the timeline is empty.
+    pub fn timeline<F, G, A, S>(&self, init: A, mut f: F, mut snapshot: G) -> Vec<(ext::Oid, S)>
+    where
+        F: for<'r> FnMut(A, &'r HistoryEntry) -> ControlFlow<A, A>,
+        G: FnMut(&A) -> S,
+    {
+        let (_, timeline) =
+            self.traverse((init, Vec::new()), |(state, mut timeline), entry| {
+                match f(state, entry) {
+                    ControlFlow::Continue(state) => {
+                        timeline.push((entry.id.0, snapshot(&state)));
+                        ControlFlow::Continue((state, timeline))
+                    },
+                    ControlFlow::Break(state) => ControlFlow::Break((state, timeline)),
+                }
+            });
+        timeline
+    }
+
     /// Add a new node to this history. The new node will have all the current
     /// tips of the history as its parents.
     pub(crate) fn extend<Id: Into<EntryId>>(
         &mut self,
         new_id: Id,
         new_author: Urn,
+        new_client: Option<ClientId>,
         new_contents: EntryContents,
     ) {
         let tips = self.tips();
@@ -112,7 +191,8 @@ impl History {
             new_author,
             std::iter::empty::<git2::Oid>(),
             new_contents,
-        );
+        )
+        .with_client(new_client);
         let new_ix = self.graph.add_node(new_entry.clone());
         self.entries.insert(new_entry.id().clone(), new_entry);
         for tip in tips {
@@ -124,6 +204,21 @@ impl History {
         }
     }
 
+    /// The entry with the given `id`, if it is part of this history.
+    pub fn entry<Id: Into<EntryId>>(&self, id: Id) -> Option<&HistoryEntry> {
+        self.entries.get(&id.into())
+    }
+
+    /// The tips of the history which were made by `author`.
+    pub fn tips_by_author(&self, author: &Urn) -> Vec<git2::Oid> {
+        self.graph
+            .externals(petgraph::Direction::Outgoing)
+            .map(|n| &self.graph[n])
+            .filter(|entry| entry.author() == author)
+            .map(|entry| git2::Oid::from(entry.id().0))
+            .collect()
+    }
+
     pub(crate) fn tips(&self) -> BTreeSet<EntryId> {
         self.graph
             .externals(petgraph::Direction::Outgoing)
@@ -216,6 +311,8 @@ pub struct HistoryEntry {
     children: Vec<EntryId>,
     #[n(3)]
     contents: EntryContents,
+    #[n(4)]
+    client: Option<ClientId>,
 }
 
 impl HistoryEntry {
@@ -230,9 +327,15 @@ impl HistoryEntry {
             author,
             children: children.into_iter().map(|id| id.into()).collect(),
             contents,
+            client: None,
         }
     }
 
+    /// Set the identifier of the client which authored this change
+    pub fn with_client(self, client: Option<ClientId>) -> Self {
+        Self { client, ..self }
+    }
+
     /// The ids of the changes this change depends on
     pub fn children(&self) -> impl Iterator<Item = &EntryId> {
         self.children.iter()
@@ -248,6 +351,12 @@ impl HistoryEntry {
         &self.contents
     }
 
+    /// The identifier of the client which authored this change, if it
+    /// recorded one
+    pub fn client(&self) -> Option<&ClientId> {
+        self.client.as_ref()
+    }
+
     pub fn id(&self) -> &EntryId {
         &self.id
     }
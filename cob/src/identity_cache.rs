@@ -0,0 +1,156 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+
+use link_identities::git::VerifiedPerson;
+
+use crate::{
+    identity_storage::{lookup_authorizing_identity, lookup_person, LookupError},
+    AuthorizingIdentity,
+    IdentityStorage,
+};
+
+/// A cache of the identities referenced by changes, keyed by the commit of
+/// the identity.
+///
+/// Evaluating a change graph looks up the author and the authorizing identity
+/// of every change, which for objects with many changes are mostly the same
+/// few identities. The cache holds at most `capacity` authors and `capacity`
+/// authorizing identities, evicting the least recently used one when full.
+/// Failed lookups are not cached.
+///
+/// Verifying a project resolves its delegates through the [`IdentityStorage`],
+/// so a cache should not outlive the evaluation of the objects it was created
+/// for.
+pub struct IdentityCache {
+    persons: Lru<VerifiedPerson>,
+    authorizers: Lru<Rc<dyn AuthorizingIdentity>>,
+}
+
+impl Default for IdentityCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdentityCache {
+    /// The capacity used by [`IdentityCache::new`].
+    pub const DEFAULT_CAPACITY: usize = 256;
+
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Create a cache holding at most `capacity` identities of each kind.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "identity cache capacity must be non-zero");
+        Self {
+            persons: Lru::new(capacity),
+            authorizers: Lru::new(capacity),
+        }
+    }
+
+    /// Look up the person at `oid`, verifying it if it is not cached.
+    pub fn person(
+        &mut self,
+        repo: &git2::Repository,
+        oid: git2::Oid,
+    ) -> Result<Option<VerifiedPerson>, LookupError> {
+        if let Some(person) = self.persons.get(&oid) {
+            return Ok(Some(person.clone()));
+        }
+        let person = lookup_person(repo, oid)?;
+        if let Some(person) = &person {
+            self.persons.insert(oid, person.clone());
+        }
+        Ok(person)
+    }
+
+    /// Look up the authorizing identity at `oid`, verifying it if it is not
+    /// cached.
+    pub fn authorizing_identity<I: IdentityStorage>(
+        &mut self,
+        ids: &I,
+        repo: &git2::Repository,
+        oid: git2::Oid,
+    ) -> Result<Rc<dyn AuthorizingIdentity>, LookupError> {
+        if let Some(id) = self.authorizers.get(&oid) {
+            return Ok(id.clone());
+        }
+        let id: Rc<dyn AuthorizingIdentity> = lookup_authorizing_identity(ids, repo, oid)?.into();
+        self.authorizers.insert(oid, id.clone());
+        Ok(id)
+    }
+
+    /// Whether the person at `oid` is currently cached.
+    pub fn contains_person(&self, oid: &git2::Oid) -> bool {
+        self.persons.entries.contains_key(oid)
+    }
+
+    /// Whether the authorizing identity at `oid` is currently cached.
+    pub fn contains_authorizing_identity(&self, oid: &git2::Oid) -> bool {
+        self.authorizers.entries.contains_key(oid)
+    }
+}
+
+/// A least recently used map from commits to `V`.
+struct Lru<V> {
+    capacity: usize,
+    /// Incremented on every access, so that the entry with the lowest tick is
+    /// the least recently used one
+    tick: u64,
+    entries: HashMap<git2::Oid, (V, u64)>,
+    recency: BTreeMap<u64, git2::Oid>,
+}
+
+impl<V> Lru<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, oid: &git2::Oid) -> Option<&V> {
+        let tick = self.next_tick();
+        let (value, last) = self.entries.get_mut(oid)?;
+        self.recency.remove(last);
+        self.recency.insert(tick, *oid);
+        *last = tick;
+        Some(value)
+    }
+
+    fn insert(&mut self, oid: git2::Oid, value: V) {
+        let tick = self.next_tick();
+        if let Some((_, last)) = self.entries.insert(oid, (value, tick)) {
+            self.recency.remove(&last);
+        }
+        self.recency.insert(tick, oid);
+        while self.entries.len() > self.capacity {
+            let oldest = match self.recency.keys().next() {
+                Some(tick) => *tick,
+                None => break,
+            };
+            if let Some(evicted) = self.recency.remove(&oldest) {
+                self.entries.remove(&evicted);
+            }
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
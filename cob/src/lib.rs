@@ -51,7 +51,15 @@
 //! those that were used to generate the cache) then we fall back to evaluating
 //! the full change graph of the object.
 
-use std::{cell::RefCell, collections::BTreeSet, convert::TryFrom, fmt, rc::Rc, str::FromStr};
+use std::{
+    cell::RefCell,
+    collections::BTreeSet,
+    convert::TryFrom,
+    fmt,
+    rc::Rc,
+    str::FromStr,
+    sync::Arc,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -71,6 +79,8 @@ mod change;
 use change::Change;
 
 mod refs_storage;
+#[cfg(feature = "test")]
+pub use refs_storage::memory;
 pub use refs_storage::{ObjectRefs, RefsStorage};
 
 mod cache;
@@ -79,16 +89,29 @@ use cache::{Cache, CachedChangeGraph};
 mod identity_storage;
 pub use identity_storage::IdentityStorage;
 
+mod identity_cache;
+use identity_cache::IdentityCache;
+
 mod history;
 pub use history::{EntryContents, History, HistoryEntry, HistoryType};
 
+mod pending;
+pub use pending::{PendingChange, PendingReason, Rejection};
+
 mod pruning_fold;
 
+mod unverified;
+pub use unverified::{UnverifiedChange, UnverifiedObject};
+
 pub mod internals {
     //! This module exposes implementation details of the collaborative object
     //! crate for use in testing
 
-    pub use super::cache::{Cache, CachedChangeGraph, FileSystemCache};
+    pub use super::{
+        cache::{Cache, CachedChangeGraph, FileSystemCache},
+        identity_cache::IdentityCache,
+        trailers::{error::InvalidClientTrailer, ClientTrailer},
+    };
 }
 
 /// The typename of an object. Valid typenames MUST be sequences of alphanumeric
@@ -127,6 +150,41 @@ impl FromStr for TypeName {
     }
 }
 
+/// An identifier for the client or application which authored a change, e.g.
+/// `radicle-cli 1.2`. This is recorded for attribution only and has no effect
+/// on how a change is evaluated. Valid identifiers are non-empty, contain no
+/// control characters (such as newlines) and do not start or end with
+/// whitespace.
+#[derive(
+    Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, minicbor::Encode, minicbor::Decode,
+)]
+#[cbor(transparent)]
+pub struct ClientId(#[n(0)] String);
+
+impl ClientId {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl fmt::Display for ClientId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+impl FromStr for ClientId {
+    type Err = error::ClientIdParse;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.trim() != s || s.chars().any(char::is_control) {
+            Err(error::ClientIdParse)
+        } else {
+            Ok(ClientId(s.to_string()))
+        }
+    }
+}
+
 /// The id of an object
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ObjectId(git2::Oid);
@@ -191,6 +249,46 @@ impl From<&git2::Oid> for ObjectId {
     }
 }
 
+/// Bounds on the size of the change graph of an object.
+///
+/// Objects are received from untrusted peers, so a change graph may be
+/// arbitrarily large. When an object has to be evaluated from its change graph
+/// (rather than loaded from the cache) and the graph exceeds these bounds, the
+/// walk of the graph is aborted and a `TooLarge` error returned before the
+/// history is evaluated. The default is unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum number of changes in the change graph
+    pub max_changes: Option<usize>,
+    /// The maximum total size, in bytes, of the automerge changes in the change
+    /// graph
+    pub max_history_bytes: Option<usize>,
+}
+
+/// How the changes of a change graph are read from git when an object has to
+/// be evaluated from its change graph. The default is [`Loading::Sequential`].
+#[derive(Clone)]
+pub enum Loading {
+    /// Read the changes one after the other on the current thread
+    Sequential,
+    /// Read the changes in up to `concurrency` batches at once, on the
+    /// blocking thread pool of `spawner`.
+    ///
+    /// The current thread is blocked until the batches are read, so this must
+    /// not be used on an async runtime thread, e.g. call the retrieval function
+    /// via [`link_async::Spawner::blocking`] instead.
+    Parallel {
+        spawner: Arc<link_async::Spawner>,
+        concurrency: usize,
+    },
+}
+
+impl Default for Loading {
+    fn default() -> Self {
+        Self::Sequential
+    }
+}
+
 /// A collaborative object
 #[derive(Debug, Clone)]
 pub struct CollaborativeObject {
@@ -204,6 +302,8 @@ pub struct CollaborativeObject {
     history: History,
     /// The id of the object
     id: ObjectId,
+    /// The tips of the change graph of the object
+    tips: BTreeSet<git2::Oid>,
 }
 
 impl From<Rc<RefCell<CachedChangeGraph>>> for CollaborativeObject {
@@ -214,6 +314,7 @@ impl From<Rc<RefCell<CachedChangeGraph>>> for CollaborativeObject {
             typename: tg.typename().clone(),
             history: tg.history().clone(),
             id: tg.object_id(),
+            tips: tg.tips(),
         }
     }
 }
@@ -230,6 +331,45 @@ impl CollaborativeObject {
     pub fn typename(&self) -> &TypeName {
         &self.typename
     }
+
+    /// The tips of the change graph this object was loaded from, see
+    /// [`UpdateObjectArgs::expected_tips`]
+    pub fn tips(&self) -> &BTreeSet<git2::Oid> {
+        &self.tips
+    }
+
+    /// The [`Self::tips`] which were made by `author`, e.g. to find "your
+    /// latest change" to the object.
+    ///
+    /// The author of a change is only known once the change has been
+    /// evaluated, so tips which were rejected during evaluation are never
+    /// returned. Evaluating every change is already required to produce the
+    /// object, so this is a lookup in the [`Self::history`] rather than
+    /// loading the identity of each author again.
+    pub fn tips_by_author(&self, author: &Urn) -> Vec<git2::Oid> {
+        self.tips
+            .iter()
+            .filter(|tip| {
+                self.history
+                    .entry(**tip)
+                    .map_or(false, |entry| entry.author() == author)
+            })
+            .copied()
+            .collect()
+    }
+}
+
+/// The progress of retrieving an object, see [`retrieve_with_progress`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetrievalProgress {
+    /// The object was found in the cache, nothing else will be reported
+    Cached,
+    /// The change graph is being loaded starting from `refs` references
+    LoadingRefs { refs: usize },
+    /// `edges` edges of the change graph have been walked so far
+    Walking { edges: usize },
+    /// `evaluated` of the `total` changes have been evaluated so far
+    Evaluating { evaluated: usize, total: usize },
 }
 
 /// Additional information about the change graph of an object
@@ -245,16 +385,65 @@ pub struct ChangeGraphInfo {
     pub tips: BTreeSet<git2::Oid>,
 }
 
+/// A serializable description of the change graph of an object. Unlike the
+/// `dotviz` of [`ChangeGraphInfo`] this exposes the structure of the graph so
+/// tooling can render it however it likes.
+#[derive(Clone, Debug, Serialize)]
+pub struct ChangeGraphJson {
+    /// The ID of the object
+    pub object_id: ObjectId,
+    /// Every change in the graph
+    pub nodes: Vec<ChangeGraphNode>,
+    /// The dependencies between changes in the graph
+    pub edges: Vec<ChangeGraphEdge>,
+}
+
+/// A single change in a [`ChangeGraphJson`]
+#[derive(Clone, Debug, Serialize)]
+pub struct ChangeGraphNode {
+    /// The commit the change lives in
+    pub commit: ext::Oid,
+    /// The commit of the identity which authored the change
+    pub author_commit: ext::Oid,
+    /// The schema commit of the change, only present for changes created by
+    /// older implementations
+    pub schema_commit: Option<ext::Oid>,
+    /// Whether the signatures on the change are valid
+    pub valid_signatures: bool,
+}
+
+/// An edge in a [`ChangeGraphJson`], the `child` change depends on the
+/// `parent` change
+#[derive(Clone, Debug, Serialize)]
+pub struct ChangeGraphEdge {
+    /// The commit of the change which is depended on
+    pub parent: ext::Oid,
+    /// The commit of the change which depends on `parent`
+    pub child: ext::Oid,
+}
+
 pub mod error {
+    use std::collections::BTreeSet;
+
     use super::{cache::Error as CacheError, change, change_graph::Error as ChangeGraphError};
     use thiserror::Error;
 
+    pub use super::{
+        change::error::Signature,
+        change_graph::{RootMismatch, TooLarge},
+        history::MergeError,
+    };
+
     use radicle_git_ext::FromMultihashError as ExtOidFromMultiHashError;
 
     #[derive(Error, Debug)]
     #[error("invalid typename")]
     pub struct TypeNameParse;
 
+    #[derive(Error, Debug)]
+    #[error("invalid client identifier")]
+    pub struct ClientIdParse;
+
     #[derive(Debug, Error)]
     pub enum Create<RefsError: std::error::Error> {
         #[error("Invalid automerge history")]
@@ -274,7 +463,11 @@ pub mod error {
     #[derive(Debug, Error)]
     pub enum Retrieve<RefsError: std::error::Error> {
         #[error(transparent)]
-        ChangeGraph(#[from] ChangeGraphError),
+        ChangeGraph(ChangeGraphError),
+        #[error("object too large")]
+        TooLarge(#[source] TooLarge),
+        #[error(transparent)]
+        RootMismatch(RootMismatch),
         #[error(transparent)]
         Git(#[from] git2::Error),
         #[error(transparent)]
@@ -288,7 +481,11 @@ pub mod error {
     #[derive(Debug, Error)]
     pub enum Update<RefsError: std::error::Error> {
         #[error(transparent)]
-        ChangeGraph(#[from] ChangeGraphError),
+        ChangeGraph(ChangeGraphError),
+        #[error("object too large")]
+        TooLarge(#[source] TooLarge),
+        #[error(transparent)]
+        RootMismatch(RootMismatch),
         #[error("no object found")]
         NoSuchObject,
         #[error(transparent)]
@@ -303,6 +500,35 @@ pub mod error {
         Io(#[from] std::io::Error),
         #[error("signer must belong to the author")]
         SignerIsNotAuthor,
+        #[error(
+            "the object was modified concurrently, expected tips {expected:?} but found {found:?}"
+        )]
+        ConcurrentModification {
+            expected: BTreeSet<git2::Oid>,
+            found: BTreeSet<git2::Oid>,
+        },
+        #[error("the compacted changes do not evaluate to the history of the object")]
+        InvalidCompaction,
+    }
+
+    impl<R: std::error::Error> From<ChangeGraphError> for Retrieve<R> {
+        fn from(err: ChangeGraphError) -> Self {
+            match err {
+                ChangeGraphError::TooLarge(err) => Self::TooLarge(err),
+                ChangeGraphError::RootMismatch(err) => Self::RootMismatch(err),
+                err => Self::ChangeGraph(err),
+            }
+        }
+    }
+
+    impl<R: std::error::Error> From<ChangeGraphError> for Update<R> {
+        fn from(err: ChangeGraphError) -> Self {
+            match err {
+                ChangeGraphError::TooLarge(err) => Self::TooLarge(err),
+                ChangeGraphError::RootMismatch(err) => Self::RootMismatch(err),
+                err => Self::ChangeGraph(err),
+            }
+        }
     }
 
     #[derive(Debug, Error)]
@@ -327,6 +553,8 @@ pub struct CreateObjectArgs<'a, R: RefsStorage, P: AsRef<std::path::Path>> {
     /// An optional message to add to the commit message for the commit which
     /// creates this object
     pub message: Option<String>,
+    /// An optional identifier of the client creating this object
+    pub client: Option<ClientId>,
     /// The refs storage used to create a reference to the new object
     pub refs_storage: &'a R,
     /// The repo the new object will be stored in
@@ -348,6 +576,8 @@ impl<'a, R: RefsStorage, P: AsRef<std::path::Path>> CreateObjectArgs<'a, R, P> {
             typename: self.typename.clone(),
             tips: None,
             message: self.message.clone(),
+            client: self.client.clone(),
+            compacted: false,
             contents: self.contents.clone(),
         }
     }
@@ -378,7 +608,12 @@ pub fn create_object<R: RefsStorage, P: AsRef<std::path::Path>>(
     )
     .map_err(error::Create::from)?;
 
-    let history = History::new_from_root(*init_change.commit(), author.urn(), contents.clone());
+    let history = History::new_from_root(
+        *init_change.commit(),
+        author.urn(),
+        init_change.client().cloned(),
+        contents.clone(),
+    );
 
     let object_id = init_change.commit().into();
     refs_storage
@@ -386,6 +621,7 @@ pub fn create_object<R: RefsStorage, P: AsRef<std::path::Path>>(
             &authorizing_identity.urn(),
             typename,
             object_id,
+            None,
             *init_change.commit(),
         )
         .map_err(error::Create::Refs)?;
@@ -404,11 +640,13 @@ pub fn create_object<R: RefsStorage, P: AsRef<std::path::Path>>(
         typename: args.typename,
         history,
         id: init_change.commit().into(),
+        tips: std::iter::once(*init_change.commit()).collect(),
     })
 }
 
 /// Retrieve a collaborative object which is stored in the
 /// `authorizing_identity` person or project identity
+#[allow(clippy::too_many_arguments)]
 pub fn retrieve<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
     refs_storage: &R,
     identity_storage: &I,
@@ -417,6 +655,37 @@ pub fn retrieve<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
     typename: &TypeName,
     oid: &ObjectId,
     cache_dir: Option<P>,
+    limits: &Limits,
+) -> Result<Option<CollaborativeObject>, error::Retrieve<R::Error>> {
+    retrieve_with_progress(
+        refs_storage,
+        identity_storage,
+        repo,
+        authorizing_identity,
+        typename,
+        oid,
+        cache_dir,
+        limits,
+        &Loading::Sequential,
+        &mut |_| {},
+    )
+}
+
+/// As [`retrieve`], reading the changes of the object as configured by
+/// `loading` and reporting the [`RetrievalProgress`] to `progress`, which is
+/// useful for showing feedback when retrieving large objects.
+#[allow(clippy::too_many_arguments)]
+pub fn retrieve_with_progress<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
+    refs_storage: &R,
+    identity_storage: &I,
+    repo: &git2::Repository,
+    authorizing_identity: &dyn AuthorizingIdentity,
+    typename: &TypeName,
+    oid: &ObjectId,
+    cache_dir: Option<P>,
+    limits: &Limits,
+    loading: &Loading,
+    progress: &mut dyn FnMut(RetrievalProgress),
 ) -> Result<Option<CollaborativeObject>, error::Retrieve<R::Error>> {
     let tip_refs = refs_storage
         .object_references(&authorizing_identity.urn(), typename, oid)
@@ -428,8 +697,16 @@ pub fn retrieve<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
         authorizing_identity,
         typename,
         tip_refs,
+        limits,
+        loading,
     }
-    .load_or_materialize::<error::Retrieve<R::Error>, _>(identity_storage, cache.as_mut(), repo)?
+    .load_or_materialize::<error::Retrieve<R::Error>, _>(
+        identity_storage,
+        &mut IdentityCache::new(),
+        cache.as_mut(),
+        repo,
+        progress,
+    )?
     .map(|tg| tg.into()))
 }
 
@@ -441,6 +718,7 @@ pub fn list<R: RefsStorage, P: AsRef<std::path::Path>, I: IdentityStorage>(
     authorizing_identity: &dyn AuthorizingIdentity,
     typename: &TypeName,
     cache_dir: Option<P>,
+    limits: &Limits,
 ) -> Result<Vec<CollaborativeObject>, error::Retrieve<R::Error>> {
     let references = refs_storage
         .type_references(&authorizing_identity.urn(), typename)
@@ -448,6 +726,9 @@ pub fn list<R: RefsStorage, P: AsRef<std::path::Path>, I: IdentityStorage>(
     tracing::trace!(num_objects=?references.len(), "loaded references");
     let mut result = Vec::new();
     let mut cache = open_cache(cache_dir)?;
+    // Objects of the same type are mostly changed by the same people, so the
+    // identities are shared across objects
+    let mut identities = IdentityCache::new();
     for (oid, tip_refs) in references {
         tracing::trace!(object_id=?oid, "loading object");
         let loaded = CobRefs {
@@ -455,11 +736,15 @@ pub fn list<R: RefsStorage, P: AsRef<std::path::Path>, I: IdentityStorage>(
             authorizing_identity,
             typename,
             tip_refs,
+            limits,
+            loading: &Loading::Sequential,
         }
         .load_or_materialize::<error::Retrieve<R::Error>, _>(
             identity_storage,
+            &mut identities,
             cache.as_mut(),
             repo,
+            &mut |_| {},
         )?;
         match loaded {
             Some(obj) => {
@@ -474,7 +759,60 @@ pub fn list<R: RefsStorage, P: AsRef<std::path::Path>, I: IdentityStorage>(
     Ok(result)
 }
 
-/// The data required to create a new object
+/// How the `changes` of an update relate to the existing history of the
+/// object
+pub enum UpdateMode {
+    /// The changes are applied on top of the current tips of the object. This
+    /// is the usual mode of operation.
+    Append,
+    /// The changes are a replacement for the entire history of the object,
+    /// e.g. a single change setting the whole state of the document rather
+    /// than an incremental one. The new change is written with all current tips
+    /// as parents and marked with an `X-Rad-Compacted` trailer. When the
+    /// object is evaluated, its history starts at the newest compacted change
+    /// which all tips build on, so the changes before it are not replayed.
+    /// The prior changes remain in the graph, other peers may have built on
+    /// them and rewriting history would break their references. A change
+    /// made concurrently with a compaction is therefore evaluated along with
+    /// the whole history before it, until a later change builds on both.
+    ///
+    /// Because this crate does not interpret the contents of changes,
+    /// `validate` is called with the current history of the object and the
+    /// compacted contents, and must return `true` if the contents alone
+    /// evaluate to the same value as the history, as they are all that
+    /// readers will evaluate. Otherwise the update fails with
+    /// [`error::Update::InvalidCompaction`].
+    ///
+    /// # Trust
+    ///
+    /// A compacted change is authorized like any other change, i.e. only by
+    /// its author being permitted to change the object. Nothing prevents an
+    /// authorized author from publishing a "compaction" which does not match
+    /// the history, the validation above only protects the local writer.
+    /// Readers evaluating the object from the compacted change are therefore
+    /// trusting its author with the entire state of the object, rather than
+    /// each author with their own changes.
+    Compact {
+        validate: Box<dyn Fn(&History, &EntryContents) -> bool>,
+    },
+}
+
+impl fmt::Debug for UpdateMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Append => f.write_str("Append"),
+            Self::Compact { .. } => f.write_str("Compact { .. }"),
+        }
+    }
+}
+
+impl Default for UpdateMode {
+    fn default() -> Self {
+        Self::Append
+    }
+}
+
+/// The data required to update an object
 pub struct UpdateObjectArgs<'a, R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>> {
     /// The refs storage used to find references to the object, and to update
     /// the local reference
@@ -499,8 +837,21 @@ pub struct UpdateObjectArgs<'a, R: RefsStorage, I: IdentityStorage, P: AsRef<std
     pub typename: TypeName,
     /// An optional message to add to the commit message of the change
     pub message: Option<String>,
+    /// An optional identifier of the client making the change
+    pub client: Option<ClientId>,
     /// The CRDT changes to add to the object
     pub changes: EntryContents,
+    /// Bounds on the size of the change graph of the object being updated
+    pub limits: Limits,
+    /// The tips the caller expects the change graph of the object to have,
+    /// usually [`CollaborativeObject::tips`] of the object the `changes` were
+    /// computed from. If the tips differ the update fails with
+    /// [`error::Update::ConcurrentModification`], rather than writing a change
+    /// which overwrites another writer's.
+    pub expected_tips: Option<Vec<git2::Oid>>,
+    /// Whether `changes` are appended to the history or replace it, see
+    /// [`UpdateMode`]
+    pub mode: UpdateMode,
 }
 
 pub fn update<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
@@ -518,6 +869,10 @@ pub fn update<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
         object_id,
         changes,
         message,
+        client,
+        ref limits,
+        expected_tips,
+        mode,
     } = args;
     if !is_signer_for(signer, author) {
         return Err(error::Update::SignerIsNotAuthor);
@@ -532,6 +887,11 @@ pub fn update<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
     } else {
         None
     };
+    // The ref is only updated if nobody else moved it in the meantime.
+    let previous_target = existing_refs
+        .local
+        .as_ref()
+        .and_then(|local| local.target());
 
     let mut cache = open_cache(cache_dir)?;
     let cached = CobRefs {
@@ -539,34 +899,70 @@ pub fn update<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
         typename,
         oid: object_id,
         tip_refs: existing_refs,
+        limits,
+        loading: &Loading::Sequential,
     }
-    .load_or_materialize::<error::Update<R::Error>, _>(identity_storage, cache.as_mut(), repo)?
+    .load_or_materialize::<error::Update<R::Error>, _>(
+        identity_storage,
+        &mut IdentityCache::new(),
+        cache.as_mut(),
+        repo,
+        &mut |_| {},
+    )?
     .ok_or(error::Update::NoSuchObject)?;
 
+    let tips = cached.borrow().tips();
+    if let Some(expected) = expected_tips {
+        let expected = expected.into_iter().collect::<BTreeSet<_>>();
+        if expected != tips {
+            return Err(error::Update::ConcurrentModification {
+                expected,
+                found: tips,
+            });
+        }
+    }
+
+    let compacted = match mode {
+        UpdateMode::Append => false,
+        UpdateMode::Compact { validate } => {
+            if !validate(cached.borrow().history(), &changes) {
+                return Err(error::Update::InvalidCompaction);
+            }
+            true
+        },
+    };
+
     let change = change::Change::create(
         authorizing_identity.content_id(),
         author.content_id.into(),
         repo,
         signer,
         change::NewChangeSpec {
-            tips: Some(cached.borrow().tips().iter().cloned().collect()),
+            tips: Some(tips.into_iter().collect()),
             contents: changes.clone(),
             typename: typename.clone(),
             message,
+            client,
+            compacted,
         },
     )?;
 
-    cached
-        .borrow_mut()
-        .update_ref(previous_ref, *change.commit(), author.urn(), changes);
+    cached.borrow_mut().update_ref(
+        previous_ref,
+        *change.commit(),
+        author.urn(),
+        change.client().cloned(),
+        changes,
+        change.compacted(),
+    );
     cache.put(object_id, cached.clone())?;
 
-    //let new_commit = *change.commit();
     refs_storage
         .update_ref(
             &authorizing_identity.urn(),
             typename,
             object_id,
+            previous_target,
             *change.commit(),
         )
         .map_err(error::Update::Refs)?;
@@ -574,21 +970,85 @@ pub fn update<R: RefsStorage, I: IdentityStorage, P: AsRef<std::path::Path>>(
     Ok(cached.into())
 }
 
+/// Retrieve the changes which are in the change graph of an object, but which
+/// are not part of its history, along with why each was not accepted.
+///
+/// The rejections are not cached, so this always evaluates the change graph.
+pub fn pending_changes<R: RefsStorage, I: IdentityStorage>(
+    refs_storage: &R,
+    identity_storage: &I,
+    repo: &git2::Repository,
+    authorizing_identity: &dyn AuthorizingIdentity,
+    typename: &TypeName,
+    oid: &ObjectId,
+    limits: &Limits,
+) -> Result<Option<Vec<PendingChange>>, error::Retrieve<R::Error>> {
+    let tip_refs = refs_storage
+        .object_references(&authorizing_identity.urn(), typename, oid)
+        .map_err(error::Retrieve::Refs)?;
+    Ok(ChangeGraph::load(
+        tip_refs.iter(),
+        repo,
+        authorizing_identity,
+        typename,
+        oid,
+        limits,
+    )?
+    .map(|graph| graph.pending(identity_storage, &mut IdentityCache::new())))
+}
+
+/// Retrieve every change in the change graph of an object, annotated with
+/// whether it passed evaluation.
+///
+/// This is NOT safe to use for trust decisions, see [`UnverifiedObject`]. The
+/// result is not cached, so this always evaluates the change graph.
+pub fn retrieve_unverified<R: RefsStorage, I: IdentityStorage>(
+    refs_storage: &R,
+    identity_storage: &I,
+    repo: &git2::Repository,
+    authorizing_identity: &dyn AuthorizingIdentity,
+    typename: &TypeName,
+    oid: &ObjectId,
+    limits: &Limits,
+) -> Result<Option<UnverifiedObject>, error::Retrieve<R::Error>> {
+    let tip_refs = refs_storage
+        .object_references(&authorizing_identity.urn(), typename, oid)
+        .map_err(error::Retrieve::Refs)?;
+    Ok(ChangeGraph::load(
+        tip_refs.iter(),
+        repo,
+        authorizing_identity,
+        typename,
+        oid,
+        limits,
+    )?
+    .map(|graph| graph.unverified(identity_storage, &mut IdentityCache::new())))
+}
+
 /// Retrieve additional information about the change graph of an object. This
 /// is mostly useful for debugging and testing
+///
+/// The change graph is always loaded, so `limits` is applied regardless of
+/// whether the object is cached.
 pub fn changegraph_info_for_object<R: RefsStorage>(
     refs_storage: &R,
     repo: &git2::Repository,
     authorizing_identity: &dyn AuthorizingIdentity,
     typename: &TypeName,
     oid: &ObjectId,
+    limits: &Limits,
 ) -> Result<Option<ChangeGraphInfo>, error::Retrieve<R::Error>> {
     let tip_refs = refs_storage
         .object_references(&authorizing_identity.urn(), typename, oid)
         .map_err(error::Retrieve::Refs)?;
-    if let Some(graph) =
-        ChangeGraph::load(tip_refs.iter(), repo, authorizing_identity, typename, oid)?
-    {
+    if let Some(graph) = ChangeGraph::load(
+        tip_refs.iter(),
+        repo,
+        authorizing_identity,
+        typename,
+        oid,
+        limits,
+    )? {
         Ok(Some(ChangeGraphInfo {
             object_id: *oid,
             dotviz: graph.graphviz(),
@@ -600,6 +1060,61 @@ pub fn changegraph_info_for_object<R: RefsStorage>(
     }
 }
 
+/// Find the common ancestor of two versions of an object, identified by their
+/// tips, e.g. the tips of an object as seen by two peers which have diverged.
+///
+/// Returns `Ok(None)` if the object does not exist, or if any of the tips are
+/// not part of its change graph.
+#[allow(clippy::too_many_arguments)]
+pub fn merge_base<R: RefsStorage>(
+    refs_storage: &R,
+    repo: &git2::Repository,
+    authorizing_identity: &dyn AuthorizingIdentity,
+    typename: &TypeName,
+    oid: &ObjectId,
+    ours: &BTreeSet<git2::Oid>,
+    theirs: &BTreeSet<git2::Oid>,
+    limits: &Limits,
+) -> Result<Option<git2::Oid>, error::Retrieve<R::Error>> {
+    let tip_refs = refs_storage
+        .object_references(&authorizing_identity.urn(), typename, oid)
+        .map_err(error::Retrieve::Refs)?;
+    Ok(ChangeGraph::load(
+        tip_refs.iter(),
+        repo,
+        authorizing_identity,
+        typename,
+        oid,
+        limits,
+    )?
+    .and_then(|graph| graph.merge_base(ours.iter().copied(), theirs.iter().copied())))
+}
+
+/// Retrieve a serializable description of the change graph of an object. This
+/// is the structured counterpart to the `dotviz` in
+/// [`changegraph_info_for_object`]
+pub fn changegraph_json_for_object<R: RefsStorage>(
+    refs_storage: &R,
+    repo: &git2::Repository,
+    authorizing_identity: &dyn AuthorizingIdentity,
+    typename: &TypeName,
+    oid: &ObjectId,
+    limits: &Limits,
+) -> Result<Option<ChangeGraphJson>, error::Retrieve<R::Error>> {
+    let tip_refs = refs_storage
+        .object_references(&authorizing_identity.urn(), typename, oid)
+        .map_err(error::Retrieve::Refs)?;
+    Ok(ChangeGraph::load(
+        tip_refs.iter(),
+        repo,
+        authorizing_identity,
+        typename,
+        oid,
+        limits,
+    )?
+    .map(|graph| graph.to_json()))
+}
+
 fn open_cache<P: AsRef<std::path::Path>>(
     path: Option<P>,
 ) -> Result<Box<dyn cache::Cache>, std::io::Error> {
@@ -623,14 +1138,20 @@ struct CobRefs<'a> {
     typename: &'a TypeName,
     /// The identity which authorizes changes to this object
     authorizing_identity: &'a dyn AuthorizingIdentity,
+    /// Bounds on the size of the change graph, if it needs to be loaded
+    limits: &'a Limits,
+    /// How the changes are read, if the change graph needs to be loaded
+    loading: &'a Loading,
 }
 
 impl<'a> CobRefs<'a> {
     fn load_or_materialize<E, I: IdentityStorage>(
         self,
         identity_storage: &I,
+        identities: &mut IdentityCache,
         cache: &mut dyn Cache,
         repo: &git2::Repository,
+        progress: &mut dyn FnMut(RetrievalProgress),
     ) -> Result<Option<Rc<RefCell<CachedChangeGraph>>>, E>
     where
         E: From<cache::Error>,
@@ -645,18 +1166,22 @@ impl<'a> CobRefs<'a> {
         match cache.load(self.oid, &tip_oids)? {
             Some(obj) => {
                 tracing::trace!(object_id=?self.oid, ?tip_oids, "object found in cache");
+                progress(RetrievalProgress::Cached);
                 Ok(Some(obj))
             },
             None => {
                 tracing::trace!(object_id=?self.oid, ?tip_oids, "object not found in cache");
-                if let Some(graph) = ChangeGraph::load(
+                if let Some(graph) = ChangeGraph::load_with_progress(
                     self.tip_refs.iter(),
                     repo,
                     self.authorizing_identity,
                     self.typename,
                     &self.oid,
+                    self.limits,
+                    self.loading,
+                    progress,
                 )? {
-                    let object = graph.evaluate(identity_storage);
+                    let object = graph.evaluate(identity_storage, identities, progress);
                     let cached = cache::CachedChangeGraph::new(
                         tip_oids,
                         object.history.clone(),
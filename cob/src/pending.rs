@@ -0,0 +1,53 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use thiserror::Error;
+
+use crate::change;
+
+/// A change which is in the change graph of an object, but which is not part
+/// of the object's history.
+#[derive(Debug)]
+pub struct PendingChange {
+    /// The commit of the change
+    pub commit: git2::Oid,
+    /// Why the change is not part of the history
+    pub reason: PendingReason,
+}
+
+#[derive(Debug, Error)]
+pub enum PendingReason {
+    /// The change itself was rejected
+    #[error(transparent)]
+    Rejected(Rejection),
+    /// The change depends on a change which was rejected
+    #[error("the change depends on the rejected change {ancestor}")]
+    AncestorRejected { ancestor: git2::Oid },
+}
+
+/// The reason a change was rejected when evaluating the change graph of an
+/// object.
+#[derive(Debug, Error)]
+pub enum Rejection {
+    #[error("the signatures of the change are invalid: {0}")]
+    InvalidSignatures(change::error::Signature),
+    #[error(
+        "the change points at the schema {schema_commit}, which the object was not created with"
+    )]
+    SchemaMismatch { schema_commit: git2::Oid },
+    #[error("error looking up the authorizing identity: {0}")]
+    InvalidAuthorizer(Box<dyn std::error::Error>),
+    #[error("the change points to an authorizing identity the object is not stored under")]
+    WrongAuthorizer,
+    #[error("the author identity {missing_author_oid} is missing")]
+    MissingAuthor { missing_author_oid: git2::Oid },
+    #[error("error looking up the author identity {author_commit_oid}: {error}")]
+    ErrorFindingAuthor {
+        author_commit_oid: git2::Oid,
+        error: Box<dyn std::error::Error>,
+    },
+    #[error("the change was not authorized: {reason}")]
+    Unauthorized { reason: &'static str },
+}
@@ -23,7 +23,22 @@ pub(crate) trait GraphNode {
 /// ancestors have already been evaluated. Returning `ControlFlow::Break(..)`
 /// from `fold` will omit evaluation of the current node and consequently omit
 /// processing of any nodes who have the current node as an ancestor.
-pub(crate) fn pruning_fold<'a, BN, Node, It, F, O>(init: O, items: It, mut f: F) -> O
+pub(crate) fn pruning_fold<'a, BN, Node, It, F, O>(init: O, items: It, f: F) -> O
+where
+    BN: Borrow<Node> + 'a,
+    Node: 'a + GraphNode,
+    It: Iterator<Item = BN>,
+    F: for<'r> FnMut(O, &'r Node) -> std::ops::ControlFlow<O, O>,
+{
+    pruning_fold_rejected(init, items, f).0
+}
+
+/// Like [`pruning_fold`], but also returns the nodes which were rejected.
+pub(crate) fn pruning_fold_rejected<'a, BN, Node, It, F, O>(
+    init: O,
+    items: It,
+    mut f: F,
+) -> (O, RejectedNodes<Node::Id>)
 where
     BN: Borrow<Node> + 'a,
     Node: 'a + GraphNode,
@@ -55,10 +70,10 @@ where
             },
         };
     }
-    state
+    (state, rejected)
 }
 
-struct RejectedNodes<NodeId> {
+pub(crate) struct RejectedNodes<NodeId> {
     /// Changes which are directly rejected by the fold function
     direct: BTreeSet<NodeId>,
     /// A map from node IDs to the IDs of ancestor nodes which are
@@ -74,6 +89,12 @@ impl<NodeId: Clone + Eq + Ord + std::hash::Hash> RejectedNodes<NodeId> {
         }
     }
 
+    /// The nodes which were rejected because an ancestor was directly
+    /// rejected, paired with that ancestor.
+    pub(crate) fn transitive(&self) -> impl Iterator<Item = (&NodeId, &NodeId)> {
+        self.transitive.iter()
+    }
+
     fn rejected_ancestor(&self, node: &NodeId) -> Option<&NodeId> {
         self.transitive.get(node)
     }
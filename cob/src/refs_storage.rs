@@ -10,6 +10,9 @@ use super::{ObjectId, TypeName};
 use git2::Reference;
 use link_identities::git::Urn;
 
+#[cfg(feature = "test")]
+pub mod memory;
+
 /// References to the tips of a collaborative object
 #[derive(Default)]
 pub struct ObjectRefs<'a> {
@@ -69,11 +72,15 @@ pub trait RefsStorage {
     ) -> Result<HashMap<ObjectId, ObjectRefs<'a>>, Self::Error>;
 
     /// Update a ref to a particular collaborative object
+    ///
+    /// If `previous` is `Some`, the ref is only updated if it still points to
+    /// `previous`. Otherwise, the ref must not exist yet.
     fn update_ref(
         &self,
         identity_urn: &Urn,
         typename: &TypeName,
         object_id: ObjectId,
+        previous: Option<git2::Oid>,
         new_commit: git2::Oid,
     ) -> Result<(), Self::Error>;
 }
@@ -0,0 +1,123 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
+
+use link_identities::git::Urn;
+
+use super::{ObjectRefs, RefsStorage};
+use crate::{ObjectId, TypeName};
+
+/// A [`RefsStorage`] which keeps the tips of collaborative objects in a map,
+/// rather than in the layout used by `librad`.
+///
+/// This is intended for testing the collaborative object logic without a full
+/// `librad` storage. Since [`ObjectRefs`] hands out [`git2::Reference`]s, each
+/// tip is mirrored to a reference in `repo` of the form
+/// `refs/memory/<identity>/<typename>/<object id>`. Only local references are
+/// tracked, so `remote` is always empty.
+pub struct InMemory<'r> {
+    repo: &'r git2::Repository,
+    tips: RefCell<BTreeMap<(Urn, TypeName, ObjectId), git2::Oid>>,
+}
+
+impl<'r> InMemory<'r> {
+    pub fn new(repo: &'r git2::Repository) -> Self {
+        Self {
+            repo,
+            tips: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// The tip of the local changes of the object, if it was ever updated
+    /// through this storage.
+    pub fn tip(&self, urn: &Urn, typename: &TypeName, oid: &ObjectId) -> Option<git2::Oid> {
+        self.tips
+            .borrow()
+            .get(&(urn.clone(), typename.clone(), *oid))
+            .copied()
+    }
+
+    fn refname(urn: &Urn, typename: &TypeName, oid: &ObjectId) -> String {
+        format!("refs/memory/{}/{}/{}", urn.id, typename, oid)
+    }
+
+    fn object_refs(
+        &self,
+        urn: &Urn,
+        typename: &TypeName,
+        oid: &ObjectId,
+    ) -> Result<ObjectRefs<'r>, git2::Error> {
+        let local = match self.tip(urn, typename, oid) {
+            None => None,
+            Some(_) => Some(
+                self.repo
+                    .find_reference(&Self::refname(urn, typename, oid))?,
+            ),
+        };
+        Ok(ObjectRefs {
+            local,
+            remote: Vec::new(),
+        })
+    }
+}
+
+impl<'r> RefsStorage for InMemory<'r> {
+    type Error = git2::Error;
+
+    fn object_references<'a>(
+        &'a self,
+        identity_urn: &Urn,
+        typename: &TypeName,
+        oid: &ObjectId,
+    ) -> Result<ObjectRefs<'a>, Self::Error> {
+        self.object_refs(identity_urn, typename, oid)
+    }
+
+    fn type_references<'a>(
+        &'a self,
+        identity_urn: &Urn,
+        typename: &TypeName,
+    ) -> Result<HashMap<ObjectId, ObjectRefs<'a>>, Self::Error> {
+        let oids = self
+            .tips
+            .borrow()
+            .keys()
+            .filter(|(urn, ty, _)| urn == identity_urn && ty == typename)
+            .map(|(_, _, oid)| *oid)
+            .collect::<Vec<_>>();
+        oids.into_iter()
+            .map(|oid| Ok((oid, self.object_refs(identity_urn, typename, &oid)?)))
+            .collect()
+    }
+
+    fn update_ref(
+        &self,
+        identity_urn: &Urn,
+        typename: &TypeName,
+        object_id: ObjectId,
+        previous: Option<git2::Oid>,
+        new_commit: git2::Oid,
+    ) -> Result<(), Self::Error> {
+        let name = Self::refname(identity_urn, typename, &object_id);
+        match previous {
+            Some(previous) => {
+                self.repo
+                    .reference_matching(&name, new_commit, true, previous, "new change")?
+            },
+            None => self
+                .repo
+                .reference(&name, new_commit, false, "new change")?,
+        };
+        self.tips.borrow_mut().insert(
+            (identity_urn.clone(), typename.clone(), object_id),
+            new_commit,
+        );
+        Ok(())
+    }
+}
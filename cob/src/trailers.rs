@@ -13,9 +13,16 @@ mod schema_commit {
     super::oid_trailer! {SchemaCommitTrailer, "X-Rad-Schema"}
 }
 
+mod client;
+mod compacted;
+
 pub mod error {
     pub use super::author_commit::Error as InvalidAuthorTrailer;
 
+    pub use super::client::Error as InvalidClientTrailer;
+
+    pub use super::compacted::Error as InvalidCompactedTrailer;
+
     pub use super::schema_commit::Error as InvalidSchemaTrailer;
 
     pub use super::authorizing_identity::Error as InvalidAuthorizingIdentityTrailer;
@@ -23,6 +30,8 @@ pub mod error {
 
 pub use author_commit::AuthorCommitTrailer;
 pub use authorizing_identity::AuthorizingIdentityCommitTrailer;
+pub use client::ClientTrailer;
+pub use compacted::CompactedTrailer;
 pub use schema_commit::SchemaCommitTrailer;
 
 /// A macro for generating boilerplate From and TryFrom impls for trailers which
@@ -0,0 +1,85 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::convert::TryFrom;
+
+use git_trailers::{Token, Trailer};
+
+use crate::{error::ClientIdParse, ClientId};
+
+const TOKEN: &str = "X-Rad-Client";
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("multiple values found for X-Rad-Client")]
+    MultipleTrailers,
+    #[error("no value for X-Rad-Client trailer")]
+    NoValue,
+    #[error("multi-line value for X-Rad-Client trailer")]
+    MultiLine,
+    #[error(transparent)]
+    Invalid(#[from] ClientIdParse),
+}
+
+/// The `X-Rad-Client` trailer, recording the client which created a change.
+/// Unlike the other trailers of a change, it is optional.
+pub struct ClientTrailer(ClientId);
+
+impl ClientTrailer {
+    pub fn client(&self) -> &ClientId {
+        &self.0
+    }
+
+    pub fn into_client(self) -> ClientId {
+        self.0
+    }
+
+    /// Find the client trailer in `trailers`, if there is one.
+    pub fn from_trailers<'b, A, I>(trailers: I) -> Result<Option<ClientTrailer>, Error>
+    where
+        A: Into<Trailer<'b>>,
+        I: IntoIterator<Item = A>,
+    {
+        let token = Token::try_from(TOKEN).unwrap();
+        let mut matching = trailers
+            .into_iter()
+            .map(Into::<Trailer<'b>>::into)
+            .filter(|trailer| trailer.token == token)
+            .map(|trailer| ClientTrailer::try_from(&trailer))
+            .collect::<Result<Vec<_>, _>>()?;
+        match matching.len() {
+            0 => Ok(None),
+            1 => Ok(matching.pop()),
+            _ => Err(Error::MultipleTrailers),
+        }
+    }
+}
+
+impl From<ClientId> for ClientTrailer {
+    fn from(client: ClientId) -> Self {
+        Self(client)
+    }
+}
+
+impl From<ClientTrailer> for Trailer<'_> {
+    fn from(containing: ClientTrailer) -> Self {
+        Trailer {
+            token: Token::try_from(TOKEN).unwrap(),
+            values: vec![containing.0.to_string().into()],
+        }
+    }
+}
+
+impl TryFrom<&Trailer<'_>> for ClientTrailer {
+    type Error = Error;
+
+    fn try_from(Trailer { values, .. }: &Trailer<'_>) -> Result<Self, Self::Error> {
+        match values.as_slice() {
+            [] => Err(Error::NoValue),
+            [val] => Ok(Self(val.parse()?)),
+            _ => Err(Error::MultiLine),
+        }
+    }
+}
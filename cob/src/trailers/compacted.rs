@@ -0,0 +1,66 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::convert::TryFrom;
+
+use git_trailers::{Token, Trailer};
+
+const TOKEN: &str = "X-Rad-Compacted";
+const VALUE: &str = "true";
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("multiple values found for X-Rad-Compacted")]
+    MultipleTrailers,
+    #[error("invalid value {0:?} for X-Rad-Compacted trailer")]
+    InvalidValue(String),
+}
+
+/// The `X-Rad-Compacted` trailer, marking a change whose contents replace the
+/// history it builds on, see [`crate::UpdateMode::Compact`]. Like the client
+/// trailer, it is optional.
+pub struct CompactedTrailer;
+
+impl CompactedTrailer {
+    /// Whether `trailers` contain the compacted trailer.
+    pub fn from_trailers<'b, A, I>(trailers: I) -> Result<bool, Error>
+    where
+        A: Into<Trailer<'b>>,
+        I: IntoIterator<Item = A>,
+    {
+        let token = Token::try_from(TOKEN).unwrap();
+        let matching = trailers
+            .into_iter()
+            .map(Into::<Trailer<'b>>::into)
+            .filter(|trailer| trailer.token == token)
+            .map(|trailer| CompactedTrailer::try_from(&trailer))
+            .collect::<Result<Vec<_>, _>>()?;
+        match matching.len() {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::MultipleTrailers),
+        }
+    }
+}
+
+impl From<CompactedTrailer> for Trailer<'_> {
+    fn from(_: CompactedTrailer) -> Self {
+        Trailer {
+            token: Token::try_from(TOKEN).unwrap(),
+            values: vec![VALUE.into()],
+        }
+    }
+}
+
+impl TryFrom<&Trailer<'_>> for CompactedTrailer {
+    type Error = Error;
+
+    fn try_from(Trailer { values, .. }: &Trailer<'_>) -> Result<Self, Self::Error> {
+        match values.as_slice() {
+            [val] if val == VALUE => Ok(Self),
+            _ => Err(Error::InvalidValue(values.join("\n"))),
+        }
+    }
+}
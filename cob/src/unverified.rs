@@ -0,0 +1,58 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::collections::BTreeSet;
+
+use link_identities::git::Urn;
+
+use crate::{EntryContents, ObjectId, PendingReason, TypeName};
+
+/// Every change in the change graph of an object, whether or not it passed
+/// evaluation, see [`crate::retrieve_unverified`].
+///
+/// # Trust
+///
+/// The changes are included regardless of their signatures, authors, or
+/// authorization. This is meant for inspecting what was received from other
+/// peers, e.g. to debug why changes were dropped, and MUST NOT be used to
+/// decide what the state of an object is. Use [`crate::retrieve`] for that.
+#[derive(Debug)]
+pub struct UnverifiedObject {
+    /// The identifier of the object
+    pub id: ObjectId,
+    /// The typename of the object
+    pub typename: TypeName,
+    /// The tips of the change graph of the object
+    pub tips: BTreeSet<git2::Oid>,
+    /// The changes of the change graph, in topological order
+    pub changes: Vec<UnverifiedChange>,
+}
+
+/// A change of an [`UnverifiedObject`], annotated with the outcome of its
+/// evaluation.
+#[derive(Debug)]
+pub struct UnverifiedChange {
+    /// The commit of the change
+    pub commit: git2::Oid,
+    /// The commit of the identity which claims to have authored the change
+    pub author_commit: git2::Oid,
+    /// The author of the change, if their identity could be found
+    pub author: Option<Urn>,
+    /// The commits of the changes which build on this change
+    pub children: Vec<git2::Oid>,
+    /// The CRDT changes of the change
+    pub contents: EntryContents,
+    /// Why the change is not part of the object's history, or `None` if it
+    /// is
+    pub pending: Option<PendingReason>,
+}
+
+impl UnverifiedChange {
+    /// Whether the change passed evaluation, i.e. would be part of the
+    /// object's history.
+    pub fn is_valid(&self) -> bool {
+        self.pending.is_none()
+    }
+}
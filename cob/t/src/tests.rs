@@ -5,6 +5,10 @@
 
 mod cache;
 mod cached_change_graph;
+mod client;
+mod history;
+mod identity_cache;
+mod memory;
 
 use cob::TypeName;
 use std::str::FromStr;
@@ -17,3 +21,12 @@ fn test_valid_typenames() {
     assert!(TypeName::from_str(".abc.123.ghi").is_err());
     assert!(TypeName::from_str("abc.123.ghi.").is_err());
 }
+
+#[test]
+fn test_typenames_are_ref_components() {
+    assert!(TypeName::from_str("xyz.radicle.issue").is_ok());
+    assert!(TypeName::from_str("has/slash").is_err());
+    assert!(TypeName::from_str("has space").is_err());
+    assert!(TypeName::from_str("has..dots").is_err());
+    assert!(TypeName::from_str("").is_err());
+}
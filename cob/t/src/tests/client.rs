@@ -0,0 +1,50 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::str::FromStr;
+
+use cob::{
+    internals::{ClientTrailer, InvalidClientTrailer},
+    ClientId,
+};
+use git_trailers::{parse, Trailer};
+
+#[test]
+fn valid_client_ids() {
+    assert!(ClientId::from_str("radicle-cli 1.2").is_ok());
+    assert!(ClientId::from_str("my-bot").is_ok());
+    assert!(ClientId::from_str("").is_err());
+    assert!(ClientId::from_str("my-bot\n").is_err());
+    assert!(ClientId::from_str("my\nbot").is_err());
+    assert!(ClientId::from_str("my\rbot").is_err());
+    assert!(ClientId::from_str(" my-bot").is_err());
+    assert!(ClientId::from_str("my-bot ").is_err());
+}
+
+#[test]
+fn client_trailer_roundtrip() {
+    let client = ClientId::from_str("radicle-cli 1.2").unwrap();
+    let trailer: Trailer<'_> = ClientTrailer::from(client.clone()).into();
+    let message = format!("a change\n\n{}\n", trailer.display(": "));
+
+    let parsed = parse(&message, ":").unwrap();
+    let found = ClientTrailer::from_trailers(parsed).unwrap().unwrap();
+    assert_eq!(found.client(), &client);
+}
+
+#[test]
+fn client_trailer_absent() {
+    let parsed = parse("a change\n\nX-Rad-Other: foo\n", ":").unwrap();
+    assert!(ClientTrailer::from_trailers(parsed).unwrap().is_none());
+}
+
+#[test]
+fn client_trailer_duplicate() {
+    let parsed = parse("a change\n\nX-Rad-Client: a\nX-Rad-Client: b\n", ":").unwrap();
+    assert!(matches!(
+        ClientTrailer::from_trailers(parsed),
+        Err(InvalidClientTrailer::MultipleTrailers)
+    ));
+}
@@ -0,0 +1,106 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{collections::HashMap, ops::ControlFlow};
+
+use cob::{EntryContents, History, HistoryEntry};
+use link_identities::git::Urn;
+
+fn oid(name: &str) -> git2::Oid {
+    git2::Oid::hash_object(git2::ObjectType::Blob, name.as_bytes()).unwrap()
+}
+
+fn entry(name: &str, children: &[&str]) -> HistoryEntry {
+    authored_entry(name, Urn::new(git2::Oid::zero().into()), children)
+}
+
+fn authored_entry(name: &str, author: Urn, children: &[&str]) -> HistoryEntry {
+    HistoryEntry::new(
+        oid(name),
+        author,
+        children.iter().map(|child| oid(child)),
+        EntryContents::Automerge(name.as_bytes().to_vec()),
+    )
+}
+
+fn history(entries: Vec<HistoryEntry>) -> History {
+    let entries = entries
+        .into_iter()
+        .map(|entry| (entry.id().clone(), entry))
+        .collect::<HashMap<_, _>>();
+    History::new(oid("root"), entries).unwrap()
+}
+
+fn contents(history: &History) -> Vec<Vec<u8>> {
+    history.traverse(Vec::new(), |mut acc, entry| {
+        acc.push(entry.contents().as_ref().to_vec());
+        ControlFlow::Continue(acc)
+    })
+}
+
+#[test]
+fn merge_overlapping() {
+    let mut ours = history(vec![
+        entry("root", &["shared", "ours"]),
+        entry("shared", &[]),
+        entry("ours", &[]),
+    ]);
+    let theirs = history(vec![
+        entry("root", &["shared", "theirs"]),
+        entry("shared", &[]),
+        entry("theirs", &[]),
+    ]);
+    assert_eq!(ours.len(), 3);
+    assert!(!ours.is_empty());
+
+    ours.merge(&theirs).unwrap();
+    assert_eq!(ours.len(), 4);
+
+    let mut applied = contents(&ours);
+    assert_eq!(applied.first().map(Vec::as_slice), Some(&b"root"[..]));
+    applied.sort();
+    assert_eq!(
+        applied,
+        vec![
+            b"ours".to_vec(),
+            b"root".to_vec(),
+            b"shared".to_vec(),
+            b"theirs".to_vec()
+        ]
+    );
+
+    // Merging again changes nothing
+    let merged = ours.clone();
+    ours.merge(&theirs).unwrap();
+    assert_eq!(ours, merged);
+}
+
+#[test]
+fn merge_different_roots() {
+    let mut ours = history(vec![entry("root", &[])]);
+    let theirs = History::new(
+        oid("other"),
+        std::iter::once((oid("other").into(), entry("other", &[]))).collect(),
+    )
+    .unwrap();
+    assert!(ours.merge(&theirs).is_err());
+}
+
+#[test]
+fn tips_by_author() {
+    let alice = Urn::new(oid("alice").into());
+    let bob = Urn::new(oid("bob").into());
+    let history = history(vec![
+        authored_entry("root", alice.clone(), &["alice-1", "bob-1"]),
+        authored_entry("alice-1", alice.clone(), &[]),
+        authored_entry("bob-1", bob.clone(), &[]),
+    ]);
+
+    assert_eq!(history.tips_by_author(&alice), vec![oid("alice-1")]);
+    assert_eq!(history.tips_by_author(&bob), vec![oid("bob-1")]);
+    assert!(history
+        .tips_by_author(&Urn::new(oid("eve").into()))
+        .is_empty());
+}
@@ -0,0 +1,58 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use cob::internals::IdentityCache;
+use link_crypto::SecretKey;
+use link_identities::{
+    git::{Identities, Person},
+    payload,
+};
+use link_identities_test::helpers::Device;
+
+#[test]
+fn evicts_least_recently_used() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init_bare(tmp.path()).unwrap();
+    let keys = [SecretKey::new(), SecretKey::new(), SecretKey::new()];
+    let persons = keys
+        .iter()
+        .zip(["alice", "bob", "carol"])
+        .map(|(key, name)| {
+            Device::new_with(
+                key,
+                Identities::<Person>::from(&repo),
+                payload::Person { name: name.into() },
+            )
+            .unwrap()
+            .current()
+            .clone()
+        })
+        .collect::<Vec<_>>();
+    let oids = persons
+        .iter()
+        .map(|person| git2::Oid::from(person.content_id))
+        .collect::<Vec<_>>();
+
+    let mut cache = IdentityCache::with_capacity(2);
+    let lookup = |cache: &mut IdentityCache, i: usize| {
+        let found = cache.person(&repo, oids[i]).unwrap().unwrap();
+        assert_eq!(found.urn(), persons[i].urn());
+    };
+
+    lookup(&mut cache, 0);
+    lookup(&mut cache, 1);
+    // Touch the first person, so the second is the least recently used one
+    lookup(&mut cache, 0);
+    lookup(&mut cache, 2);
+
+    assert!(cache.contains_person(&oids[0]));
+    assert!(!cache.contains_person(&oids[1]));
+    assert!(cache.contains_person(&oids[2]));
+
+    // Evicted persons are looked up again
+    lookup(&mut cache, 1);
+    assert!(cache.contains_person(&oids[1]));
+    assert!(!cache.contains_person(&oids[0]));
+}
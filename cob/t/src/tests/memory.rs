@@ -0,0 +1,174 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{convert::Infallible, ops::ControlFlow, str::FromStr};
+
+use cob::{
+    memory::InMemory,
+    CreateObjectArgs,
+    EntryContents,
+    History,
+    IdentityStorage,
+    Limits,
+    RefsStorage as _,
+    TypeName,
+    UpdateMode,
+    UpdateObjectArgs,
+};
+use link_crypto::{BoxedSigner, SecretKey};
+use link_identities::git::{Identities, Urn};
+use link_identities_test::helpers::Device;
+
+/// Objects in these tests are authorized by a person, so delegates never need
+/// to be resolved.
+struct NoDelegates;
+
+impl IdentityStorage for NoDelegates {
+    type Error = Infallible;
+
+    fn delegate_oid(&self, urn: Urn) -> Result<git2::Oid, Self::Error> {
+        panic!("unexpected delegate lookup for {}", urn)
+    }
+}
+
+fn change(key: &'static str) -> EntryContents {
+    let mut backend = automerge::Backend::new();
+    let mut frontend = automerge::Frontend::new();
+    let (_, change) = frontend
+        .change::<_, _, automerge::InvalidChangeRequest>(None, |d| {
+            d.add_change(automerge::LocalChange::set(
+                automerge::Path::root().key(key),
+                automerge::Value::Primitive(automerge::Primitive::Str(key.into())),
+            ))?;
+            Ok(())
+        })
+        .unwrap();
+    let (_, change) = backend.apply_local_change(change.unwrap()).unwrap();
+    EntryContents::Automerge(change.raw_bytes().to_vec())
+}
+
+fn contents(history: &History) -> Vec<Vec<u8>> {
+    history.traverse(Vec::new(), |mut acc, entry| {
+        acc.push(entry.contents().as_ref().to_vec());
+        ControlFlow::Continue(acc)
+    })
+}
+
+#[test]
+fn create_then_retrieve() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init_bare(tmp.path()).unwrap();
+    let key = SecretKey::new();
+    let signer = BoxedSigner::from(key.clone());
+    let author = Device::new(&key, Identities::from(&repo))
+        .unwrap()
+        .verify()
+        .unwrap();
+    let refs = InMemory::new(&repo);
+    let typename = TypeName::from_str("xyz.radicle.memory").unwrap();
+
+    let created = cob::create_object(CreateObjectArgs {
+        contents: change("a"),
+        typename: typename.clone(),
+        message: None,
+        client: None,
+        refs_storage: &refs,
+        repo: &repo,
+        signer: &signer,
+        author: &author,
+        authorizing_identity: &author,
+        cache_dir: None::<&std::path::Path>,
+    })
+    .unwrap();
+    assert_eq!(
+        refs.tip(&author.urn(), &typename, created.id()),
+        created.tips().iter().next().copied()
+    );
+
+    let retrieve = || {
+        cob::retrieve(
+            &refs,
+            &NoDelegates,
+            &repo,
+            &author,
+            &typename,
+            created.id(),
+            None::<&std::path::Path>,
+            &Limits::default(),
+        )
+        .unwrap()
+        .unwrap()
+    };
+    let retrieved = retrieve();
+    assert_eq!(retrieved.tips(), created.tips());
+    assert_eq!(contents(retrieved.history()), contents(created.history()));
+
+    let updated = cob::update(UpdateObjectArgs {
+        refs_storage: &refs,
+        identity_storage: &NoDelegates,
+        repo: &repo,
+        signer: &signer,
+        author: &author,
+        authorizing_identity: &author,
+        cache_dir: None::<&std::path::Path>,
+        object_id: *created.id(),
+        typename: typename.clone(),
+        message: None,
+        client: None,
+        changes: change("b"),
+        limits: Limits::default(),
+        expected_tips: None,
+        mode: UpdateMode::Append,
+    })
+    .unwrap();
+    let retrieved = retrieve();
+    assert_eq!(retrieved.tips(), updated.tips());
+    assert_eq!(contents(retrieved.history()), contents(updated.history()));
+    assert_eq!(contents(retrieved.history()).len(), 2);
+
+    let listed = refs.type_references(&author.urn(), &typename).unwrap();
+    assert_eq!(listed.keys().collect::<Vec<_>>(), vec![created.id()]);
+}
+
+#[test]
+fn update_ref_compares_previous_tip() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init_bare(tmp.path()).unwrap();
+    let key = SecretKey::new();
+    let signer = BoxedSigner::from(key.clone());
+    let author = Device::new(&key, Identities::from(&repo))
+        .unwrap()
+        .verify()
+        .unwrap();
+    let refs = InMemory::new(&repo);
+    let typename = TypeName::from_str("xyz.radicle.memory").unwrap();
+
+    let created = cob::create_object(CreateObjectArgs {
+        contents: change("a"),
+        typename: typename.clone(),
+        message: None,
+        client: None,
+        refs_storage: &refs,
+        repo: &repo,
+        signer: &signer,
+        author: &author,
+        authorizing_identity: &author,
+        cache_dir: None::<&std::path::Path>,
+    })
+    .unwrap();
+    let urn = author.urn();
+    let tip = refs.tip(&urn, &typename, created.id()).unwrap();
+
+    let err = refs
+        .update_ref(&urn, &typename, *created.id(), None, tip)
+        .unwrap_err();
+    assert_eq!(err.code(), git2::ErrorCode::Exists);
+    assert!(refs
+        .update_ref(&urn, &typename, *created.id(), Some(git2::Oid::zero()), tip)
+        .is_err());
+    assert!(refs
+        .update_ref(&urn, &typename, *created.id(), Some(tip), tip)
+        .is_ok());
+}
@@ -151,6 +151,7 @@ async fn main() {
                 replication: Default::default(),
                 rate_limits: Default::default(),
                 request_pull,
+                caches: Default::default(),
             },
             storage: Default::default(),
         })
@@ -6,24 +6,40 @@
 use crate::git::{
     identities::{self, any::get as get_identity, local::LocalIdentity, Identities},
     refs::{self, Refs},
-    storage::{read::Error as ReadError, ReadOnlyStorage, Storage},
+    storage::{read::Error as ReadError, ReadOnly, ReadOnlyStorage, Storage},
     types::{Namespace, Reference, RefsCategory},
 };
 
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    str::FromStr,
+};
 
 pub use cob::{
     AuthorizingIdentity,
+    ChangeGraphEdge,
     ChangeGraphInfo,
+    ChangeGraphJson,
+    ChangeGraphNode,
+    ClientId,
     CollaborativeObject,
     CreateObjectArgs,
     EntryContents,
     History,
     IdentityStorage,
+    Limits,
+    Loading,
     ObjectId,
     ObjectRefs,
+    PendingChange,
+    PendingReason,
     RefsStorage,
+    Rejection,
+    RetrievalProgress,
     TypeName,
+    UnverifiedChange,
+    UnverifiedObject,
+    UpdateMode,
 };
 use link_crypto::BoxedSigner;
 use link_identities::git::{SomeIdentity, Urn};
@@ -84,6 +100,9 @@ pub struct NewObjectSpec {
     /// An optional message to add to the commit message for the commit which
     /// creates this object
     pub message: Option<String>,
+    /// An optional identifier of the client creating this object, recorded
+    /// for attribution
+    pub client: Option<ClientId>,
 }
 
 /// The data required to update a collaborative object
@@ -94,14 +113,25 @@ pub struct UpdateObjectSpec {
     pub typename: TypeName,
     /// An optional message to add to the commit message of the change
     pub message: Option<String>,
+    /// An optional identifier of the client making the change, recorded for
+    /// attribution
+    pub client: Option<ClientId>,
     /// The CRDT changes to add to the object
     pub changes: EntryContents,
+    /// The tips the object is expected to have, see
+    /// [`cob::UpdateObjectArgs::expected_tips`]
+    pub expected_tips: Option<Vec<git2::Oid>>,
+    /// Whether `changes` are appended to or replace the history of the
+    /// object, see [`UpdateMode`]
+    pub mode: UpdateMode,
 }
 
 pub struct CollaborativeObjects<'a> {
     signer: BoxedSigner,
     store: &'a Storage,
     cache_dir: Option<std::path::PathBuf>,
+    limits: Limits,
+    loading: Loading,
 }
 
 impl<'a> CollaborativeObjects<'a> {
@@ -114,9 +144,23 @@ impl<'a> CollaborativeObjects<'a> {
             signer,
             store,
             cache_dir,
+            limits: Limits::default(),
+            loading: Loading::default(),
         }
     }
 
+    /// Bound the size of the change graphs of objects loaded by this
+    /// `CollaborativeObjects`. See [`Limits`].
+    pub fn with_limits(self, limits: Limits) -> Self {
+        Self { limits, ..self }
+    }
+
+    /// Read the changes of objects retrieved by this `CollaborativeObjects`
+    /// as configured by `loading`. See [`Loading`].
+    pub fn with_loading(self, loading: Loading) -> Self {
+        Self { loading, ..self }
+    }
+
     pub fn create(
         &self,
         whoami: &LocalIdentity,
@@ -133,6 +177,7 @@ impl<'a> CollaborativeObjects<'a> {
             contents: spec.history,
             typename: spec.typename,
             message: spec.message,
+            client: spec.client,
             cache_dir: self.cache_dir.clone(),
         })
         .map_err(error::Create::from)
@@ -144,7 +189,18 @@ impl<'a> CollaborativeObjects<'a> {
         typename: &cob::TypeName,
         oid: &cob::ObjectId,
     ) -> Result<Option<cob::CollaborativeObject>, error::Retrieve> {
-        cob::retrieve(
+        self.retrieve_with_progress(identity_urn, typename, oid, &mut |_| {})
+    }
+
+    /// See [`cob::retrieve_with_progress`]
+    pub fn retrieve_with_progress(
+        &self,
+        identity_urn: &Urn,
+        typename: &cob::TypeName,
+        oid: &cob::ObjectId,
+        progress: &mut dyn FnMut(RetrievalProgress),
+    ) -> Result<Option<cob::CollaborativeObject>, error::Retrieve> {
+        cob::retrieve_with_progress(
             self,
             &self,
             self.store.as_raw(),
@@ -152,6 +208,9 @@ impl<'a> CollaborativeObjects<'a> {
             typename,
             oid,
             self.cache_dir.clone(),
+            &self.limits,
+            &self.loading,
+            progress,
         )
         .map_err(error::Retrieve::from)
     }
@@ -168,6 +227,7 @@ impl<'a> CollaborativeObjects<'a> {
             resolve_authorizing_identity(self.store, identity_urn)?.as_ref(),
             typename,
             self.cache_dir.clone(),
+            &self.limits,
         )
         .map_err(error::Retrieve::from)
     }
@@ -189,12 +249,241 @@ impl<'a> CollaborativeObjects<'a> {
             object_id: spec.object_id,
             typename: spec.typename,
             message: spec.message,
+            client: spec.client,
             changes: spec.changes,
             cache_dir: self.cache_dir.clone(),
+            limits: self.limits,
+            expected_tips: spec.expected_tips,
+            mode: spec.mode,
         })
         .map_err(error::Update::from)
     }
 
+    /// Replay the history of the object, yielding a snapshot of its state
+    /// after each accepted change paired with the commit of that change.
+    ///
+    /// See [`History::timeline`] for how `apply` and `snapshot` are used.
+    pub fn state_timeline<A, S, F, G>(
+        &self,
+        identity_urn: &Urn,
+        typename: &cob::TypeName,
+        oid: &cob::ObjectId,
+        init: A,
+        apply: F,
+        snapshot: G,
+    ) -> Result<Option<Vec<(git_ext::Oid, S)>>, error::Retrieve>
+    where
+        F: for<'r> FnMut(A, &'r cob::HistoryEntry) -> std::ops::ControlFlow<A, A>,
+        G: FnMut(&A) -> S,
+    {
+        Ok(self
+            .retrieve(identity_urn, typename, oid)?
+            .map(|object| object.history().timeline(init, apply, snapshot)))
+    }
+
+    pub fn pending_changes(
+        &self,
+        identity_urn: &Urn,
+        typename: &cob::TypeName,
+        oid: &cob::ObjectId,
+    ) -> Result<Option<Vec<PendingChange>>, error::Retrieve> {
+        cob::pending_changes(
+            self,
+            &self,
+            self.store.as_raw(),
+            resolve_authorizing_identity(self.store, identity_urn)?.as_ref(),
+            typename,
+            oid,
+            &self.limits,
+        )
+        .map_err(error::Retrieve::from)
+    }
+
+    /// See [`cob::retrieve_unverified`], the result MUST NOT be used for trust
+    /// decisions.
+    pub fn retrieve_unverified(
+        &self,
+        identity_urn: &Urn,
+        typename: &cob::TypeName,
+        oid: &cob::ObjectId,
+    ) -> Result<Option<UnverifiedObject>, error::Retrieve> {
+        cob::retrieve_unverified(
+            self,
+            &self,
+            self.store.as_raw(),
+            resolve_authorizing_identity(self.store, identity_urn)?.as_ref(),
+            typename,
+            oid,
+            &self.limits,
+        )
+        .map_err(error::Retrieve::from)
+    }
+
+    pub fn changegraph_info_for_object(
+        &self,
+        identity_urn: &Urn,
+        typename: &cob::TypeName,
+        oid: &cob::ObjectId,
+    ) -> Result<Option<ChangeGraphInfo>, error::Retrieve> {
+        cob::changegraph_info_for_object(
+            self,
+            self.store.as_raw(),
+            resolve_authorizing_identity(self.store, identity_urn)?.as_ref(),
+            typename,
+            oid,
+            &self.limits,
+        )
+        .map_err(error::Retrieve::from)
+    }
+
+    /// See [`cob::merge_base`]
+    pub fn merge_base(
+        &self,
+        identity_urn: &Urn,
+        typename: &cob::TypeName,
+        oid: &cob::ObjectId,
+        ours: &BTreeSet<git2::Oid>,
+        theirs: &BTreeSet<git2::Oid>,
+    ) -> Result<Option<git2::Oid>, error::Retrieve> {
+        cob::merge_base(
+            self,
+            self.store.as_raw(),
+            resolve_authorizing_identity(self.store, identity_urn)?.as_ref(),
+            typename,
+            oid,
+            ours,
+            theirs,
+            &self.limits,
+        )
+        .map_err(error::Retrieve::from)
+    }
+
+    pub fn changegraph_json_for_object(
+        &self,
+        identity_urn: &Urn,
+        typename: &cob::TypeName,
+        oid: &cob::ObjectId,
+    ) -> Result<Option<ChangeGraphJson>, error::Retrieve> {
+        cob::changegraph_json_for_object(
+            self,
+            self.store.as_raw(),
+            resolve_authorizing_identity(self.store, identity_urn)?.as_ref(),
+            typename,
+            oid,
+            &self.limits,
+        )
+        .map_err(error::Retrieve::from)
+    }
+
+    /// The tips of the local collaborative object references of
+    /// `identity_urn`, as passed to [`Self::update_local_refs`] after a push.
+    pub fn local_tips(
+        &self,
+        identity_urn: &Urn,
+    ) -> Result<BTreeMap<(TypeName, ObjectId), git2::Oid>, RefsError> {
+        let mut tips = BTreeMap::new();
+        for (typename, oid, reference) in self.local_references(identity_urn)? {
+            if let Some(tip) = reference.target() {
+                tips.insert((typename, oid), tip);
+            }
+        }
+        Ok(tips)
+    }
+
+    /// Refresh the local collaborative object references of `identity_urn`
+    /// after a push. `before` are the [`Self::local_tips`] prior to the push.
+    ///
+    /// References which the push changed, or which do not point directly at
+    /// a commit (for example an annotated tag or a symbolic reference), are
+    /// pointed at the change commit they resolve to via
+    /// [`RefsStorage::update_ref`], so that the signed refs pick up the new
+    /// tips. A symbolic reference has no target to compare against, so it is
+    /// removed and replaced by a direct reference. Other references are left
+    /// alone.
+    ///
+    /// Returns the objects whose references were updated.
+    pub fn update_local_refs(
+        &self,
+        identity_urn: &Urn,
+        before: &BTreeMap<(TypeName, ObjectId), git2::Oid>,
+    ) -> Result<Vec<(TypeName, ObjectId)>, RefsError> {
+        let mut changed = Vec::new();
+        for (typename, oid, reference) in self.local_references(identity_urn)? {
+            let commit = reference.peel_to_commit()?.id();
+            let pushed = before.get(&(typename.clone(), oid)) != Some(&commit);
+            if pushed || reference.target() != Some(commit) {
+                changed.push((typename, oid, reference, commit));
+            }
+        }
+
+        let mut updated = Vec::with_capacity(changed.len());
+        for (typename, oid, mut reference, commit) in changed {
+            let previous = reference.target();
+            if reference.kind() == Some(git2::ReferenceType::Symbolic) {
+                reference.delete()?;
+            }
+            self.update_ref(identity_urn, &typename, oid, previous, commit)?;
+            updated.push((typename, oid));
+        }
+        Ok(updated)
+    }
+
+    fn local_references(
+        &self,
+        identity_urn: &Urn,
+    ) -> Result<Vec<(TypeName, ObjectId, git2::Reference<'a>)>, RefsError> {
+        let prefix = format!(
+            "refs/namespaces/{}/refs/{}/",
+            Namespace::from(identity_urn),
+            RefsCategory::Cobs
+        );
+        let glob = globset::Glob::new(&format!("{}**", prefix)).unwrap();
+        let mut references = Vec::new();
+        for reference in self.store.references_glob(glob.compile_matcher())? {
+            let reference = reference?;
+            let object = reference
+                .name()
+                .and_then(|name| name.strip_prefix(prefix.as_str()))
+                .and_then(|name| name.split_once('/'))
+                .and_then(|(typename, oid)| {
+                    Some((
+                        TypeName::from_str(typename).ok()?,
+                        ObjectId::from_str(oid).ok()?,
+                    ))
+                });
+            match object {
+                Some((typename, oid)) => references.push((typename, oid, reference)),
+                None => {
+                    tracing::warn!(reference=?reference.name(), "skipping unrecognised collaborative object reference");
+                },
+            }
+        }
+        Ok(references)
+    }
+}
+
+/// Inspect the change graphs of collaborative objects in a [`ReadOnly`]
+/// storage, which does not require a signer.
+pub struct ChangeGraphs<'a> {
+    store: &'a ReadOnly,
+    limits: Limits,
+}
+
+impl<'a> ChangeGraphs<'a> {
+    pub fn new(store: &'a ReadOnly) -> Self {
+        Self {
+            store,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Bound the size of the change graphs loaded by this `ChangeGraphs`. See
+    /// [`Limits`].
+    pub fn with_limits(self, limits: Limits) -> Self {
+        Self { limits, ..self }
+    }
+
+    /// See [`CollaborativeObjects::changegraph_info_for_object`]
     pub fn changegraph_info_for_object(
         &self,
         identity_urn: &Urn,
@@ -207,11 +496,62 @@ impl<'a> CollaborativeObjects<'a> {
             resolve_authorizing_identity(self.store, identity_urn)?.as_ref(),
             typename,
             oid,
+            &self.limits,
+        )
+        .map_err(error::Retrieve::from)
+    }
+
+    /// See [`CollaborativeObjects::changegraph_json_for_object`]
+    pub fn changegraph_json_for_object(
+        &self,
+        identity_urn: &Urn,
+        typename: &cob::TypeName,
+        oid: &cob::ObjectId,
+    ) -> Result<Option<ChangeGraphJson>, error::Retrieve> {
+        cob::changegraph_json_for_object(
+            self,
+            self.store.as_raw(),
+            resolve_authorizing_identity(self.store, identity_urn)?.as_ref(),
+            typename,
+            oid,
+            &self.limits,
         )
         .map_err(error::Retrieve::from)
     }
 }
 
+impl<'a> RefsStorage for ChangeGraphs<'a> {
+    type Error = RefsError;
+
+    fn object_references<'b>(
+        &'b self,
+        project_urn: &Urn,
+        typename: &TypeName,
+        oid: &ObjectId,
+    ) -> Result<cob::ObjectRefs<'b>, Self::Error> {
+        object_references(self.store, project_urn, typename, oid)
+    }
+
+    fn type_references<'b>(
+        &'b self,
+        project_urn: &Urn,
+        typename: &TypeName,
+    ) -> Result<HashMap<ObjectId, ObjectRefs<'b>>, Self::Error> {
+        type_references(self.store, project_urn, typename)
+    }
+
+    fn update_ref(
+        &self,
+        _project_urn: &Urn,
+        _typename: &TypeName,
+        _object_id: ObjectId,
+        _previous: Option<git2::Oid>,
+        _new_commit: git2::Oid,
+    ) -> Result<(), Self::Error> {
+        Err(RefsError::ReadOnly)
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum RefsError {
     #[error(transparent)]
@@ -220,6 +560,8 @@ pub enum RefsError {
     Read(#[from] ReadError),
     #[error(transparent)]
     Refs(#[from] refs::stored::Error),
+    #[error("collaborative objects cannot be updated in read-only storage")]
+    ReadOnly,
 }
 
 impl<'a> RefsStorage for CollaborativeObjects<'a> {
@@ -231,19 +573,7 @@ impl<'a> RefsStorage for CollaborativeObjects<'a> {
         typename: &TypeName,
         oid: &ObjectId,
     ) -> Result<cob::ObjectRefs<'b>, Self::Error> {
-        let mut local = None;
-        if let Some(local_ref) = local_ref(self.store, project_urn, typename, oid)? {
-            local = Some(local_ref);
-        }
-        let glob = remote_glob(project_urn, typename, oid);
-        let mut remote = Vec::new();
-        let remote_refs: Vec<git2::Reference> = self
-            .store
-            .references_glob(glob.compile_matcher())?
-            .flatten()
-            .collect();
-        remote.extend(remote_refs);
-        Ok(cob::ObjectRefs { local, remote })
+        object_references(self.store.read_only(), project_urn, typename, oid)
     }
 
     fn type_references<'b>(
@@ -251,32 +581,7 @@ impl<'a> RefsStorage for CollaborativeObjects<'a> {
         project_urn: &Urn,
         typename: &TypeName,
     ) -> Result<HashMap<ObjectId, ObjectRefs<'b>>, Self::Error> {
-        let matcher = ObjRefMatcher::new(project_urn, typename);
-
-        let refs: git2::References<'a> = self.store.as_raw().references()?;
-        let mut result = HashMap::new();
-        for reference in refs {
-            let reference = reference?;
-            if let Some(name) = reference.name() {
-                match matcher.match_ref(name) {
-                    ObjRefMatch::Local(oid) => {
-                        result.entry(oid).or_insert_with(|| ObjectRefs {
-                            local: Some(reference),
-                            remote: Vec::new(),
-                        });
-                    },
-                    ObjRefMatch::Remote(oid) => {
-                        let refs = result.entry(oid).or_insert_with(|| ObjectRefs {
-                            local: None,
-                            remote: Vec::new(),
-                        });
-                        refs.remote.push(reference);
-                    },
-                    ObjRefMatch::NoMatch => {},
-                }
-            }
-        }
-        Ok(result)
+        type_references(self.store.read_only(), project_urn, typename)
     }
 
     fn update_ref(
@@ -284,6 +589,7 @@ impl<'a> RefsStorage for CollaborativeObjects<'a> {
         project_urn: &Urn,
         typename: &TypeName,
         object_id: ObjectId,
+        previous: Option<git2::Oid>,
         new_commit: git2::Oid,
     ) -> Result<(), Self::Error> {
         let reference = Reference::rad_collaborative_object(
@@ -294,15 +600,73 @@ impl<'a> RefsStorage for CollaborativeObjects<'a> {
         );
 
         tracing::info!(reference=%reference, commit=?new_commit, "adding change to collaborative object");
-        self.store
-            .as_raw()
-            .reference(&reference.to_string(), new_commit, true, "new change")?;
+        let name = reference.to_string();
+        let raw = self.store.as_raw();
+        match previous {
+            Some(previous) => {
+                raw.reference_matching(&name, new_commit, true, previous, "new change")?
+            },
+            None => raw.reference(&name, new_commit, false, "new change")?,
+        };
 
         Refs::update(self.store, project_urn)?;
         Ok(())
     }
 }
 
+fn object_references<'a>(
+    store: &'a ReadOnly,
+    project_urn: &Urn,
+    typename: &TypeName,
+    oid: &ObjectId,
+) -> Result<cob::ObjectRefs<'a>, RefsError> {
+    let mut local = None;
+    if let Some(local_ref) = local_ref(store, project_urn, typename, oid)? {
+        local = Some(local_ref);
+    }
+    let glob = remote_glob(project_urn, typename, oid);
+    let mut remote = Vec::new();
+    let remote_refs: Vec<git2::Reference> = store
+        .references_glob(glob.compile_matcher())?
+        .flatten()
+        .collect();
+    remote.extend(remote_refs);
+    Ok(cob::ObjectRefs { local, remote })
+}
+
+fn type_references<'a>(
+    store: &'a ReadOnly,
+    project_urn: &Urn,
+    typename: &TypeName,
+) -> Result<HashMap<ObjectId, ObjectRefs<'a>>, RefsError> {
+    let matcher = ObjRefMatcher::new(project_urn, typename);
+
+    let refs: git2::References<'a> = store.as_raw().references()?;
+    let mut result = HashMap::new();
+    for reference in refs {
+        let reference = reference?;
+        if let Some(name) = reference.name() {
+            match matcher.match_ref(name) {
+                ObjRefMatch::Local(oid) => {
+                    result.entry(oid).or_insert_with(|| ObjectRefs {
+                        local: Some(reference),
+                        remote: Vec::new(),
+                    });
+                },
+                ObjRefMatch::Remote(oid) => {
+                    let refs = result.entry(oid).or_insert_with(|| ObjectRefs {
+                        local: None,
+                        remote: Vec::new(),
+                    });
+                    refs.remote.push(reference);
+                },
+                ObjRefMatch::NoMatch => {},
+            }
+        }
+    }
+    Ok(result)
+}
+
 fn local_ref<'a, S: ReadOnlyStorage>(
     store: &'a S,
     project_urn: &Urn,
@@ -335,11 +699,15 @@ fn remote_glob(identity_urn: &Urn, typename: &TypeName, oid: &ObjectId) -> globs
     .unwrap()
 }
 
-fn resolve_authorizing_identity(
-    store: &Storage,
+fn resolve_authorizing_identity<S>(
+    store: &S,
     urn: &Urn,
-) -> Result<Box<dyn AuthorizingIdentity>, error::ResolveAuthorizer> {
-    let identities: Identities<'_, SomeIdentity> = Identities::from(store.as_raw());
+) -> Result<Box<dyn AuthorizingIdentity>, error::ResolveAuthorizer>
+where
+    S: AsRef<ReadOnly>,
+{
+    let store = store.as_ref();
+    let identities: Identities<'_, SomeIdentity> = store.identities();
     let id = get_identity(store, urn)?
         .ok_or_else(|| error::ResolveAuthorizer::NoSuchIdentity { urn: urn.clone() })?;
     match id {
@@ -5,6 +5,11 @@
 
 use std::convert::TryFrom as _;
 
+use link_canonical::{
+    json::{ToCjson, Value},
+    Cjson,
+};
+
 use crate::{
     git::{
         identities,
@@ -35,6 +40,8 @@ pub enum Error {
     UknownIdentity(Urn),
     #[error(transparent)]
     Tracked(#[from] tracking::error::TrackedPeers),
+    #[error(transparent)]
+    IsTracked(#[from] tracking::error::IsTracked),
 }
 
 /// The `rad/self` under a `Project`/`Person`.
@@ -73,12 +80,21 @@ impl Persona {
         Peer: Into<Option<PeerId>>,
     {
         let storage = storage.as_ref();
-        let urn = identity.urn();
         let peer = peer.into();
-        let local = storage.peer_id();
+        Self::load_as(storage, identity, peer, peer.unwrap_or(*storage.peer_id()))
+    }
 
+    /// Like [`Persona::load`], but the delegate status is determined for
+    /// `peer_id` rather than the peer whose `rad/self` is loaded.
+    fn load_as(
+        storage: &storage::ReadOnly,
+        identity: &SomeIdentity,
+        peer: Option<PeerId>,
+        peer_id: PeerId,
+    ) -> Result<Option<Self>, Error> {
+        let urn = identity.urn();
         let refs = Refs::load(storage, &urn, peer)?;
-        let delegate = is_delegate(identity, peer.unwrap_or(*local))?;
+        let delegate = is_delegate(identity, peer_id)?;
         let rad_self = Urn::try_from(Reference::rad_self(Namespace::from(urn), peer))
             .expect("namespace is set");
 
@@ -103,6 +119,32 @@ impl Persona {
     }
 }
 
+/// The JSON representation of a `Persona`, as consumed by external clients.
+///
+/// The shape of this value is part of the public API: the keys are `person`,
+/// `delegate`, and `refs`, where `refs` is `null` if the peer is not
+/// advertising any [`Refs`].
+impl ToCjson for Persona {
+    fn into_cjson(self) -> Value {
+        vec![
+            ("person", via_serde(&self.person)),
+            ("delegate", self.delegate.into_cjson()),
+            ("refs", self.refs.as_ref().map(via_serde).into_cjson()),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// [`Person`] and [`Refs`] only define their canonical form via `serde`, so
+/// convert that into a [`Value`] to keep a single source of truth.
+fn via_serde<T: serde::Serialize>(val: &T) -> Value {
+    let bytes = Cjson(val)
+        .canonical_form()
+        .expect("BUG: `Person` and `Refs` have a canonical JSON form");
+    Value::try_from(bytes.as_slice()).expect("BUG: canonical form must parse as canonical JSON")
+}
+
 fn is_delegate(identity: &SomeIdentity, peer: PeerId) -> Result<bool, Error> {
     match identity {
         SomeIdentity::Project(ref project) => {
@@ -133,15 +175,96 @@ where
         .ok_or_else(|| identities::Error::NotFound(urn.clone()))?;
 
     let mut peers = vec![];
+    remotes(storage, urn, &identity, |_| true, &mut peers)?;
+
+    Ok(peers)
+}
 
+/// Like [`tracked`], but the list starts with a [`Peer::Local`] entry for
+/// `local_peer`, determined by the local `rad/self` of the `urn`. Whether it
+/// is a delegate is determined for `local_peer`.
+///
+/// If the local peer has no `rad/self` for the `urn`, it is
+/// [`Status::NotReplicated`]. Should `local_peer` also appear in the tracking
+/// graph, it is only included as the `Local` entry.
+pub fn tracked_with_local<S>(storage: &S, urn: &Urn, local_peer: PeerId) -> Result<Tracked, Error>
+where
+    S: AsRef<storage::ReadOnly>,
+{
+    let storage = storage.as_ref();
+    let identity = identities::any::get(storage, urn)?
+        .ok_or_else(|| identities::Error::NotFound(urn.clone()))?;
+
+    let status = match Persona::load_as(storage, &identity, None, local_peer)? {
+        Some(persona) => Status::replicated(persona),
+        None => Status::NotReplicated,
+    };
+    let mut peers = vec![Peer::Local {
+        peer_id: local_peer,
+        status,
+    }];
+    remotes(
+        storage,
+        urn,
+        &identity,
+        |peer| peer != local_peer,
+        &mut peers,
+    )?;
+
+    Ok(peers)
+}
+
+fn remotes(
+    storage: &storage::ReadOnly,
+    urn: &Urn,
+    identity: &SomeIdentity,
+    include: impl Fn(PeerId) -> bool,
+    peers: &mut Tracked,
+) -> Result<(), Error> {
     for peer_id in tracking::tracked_peers(storage, Some(urn))? {
         let peer_id = peer_id?;
-        let status = match Persona::load(storage, &identity, peer_id)? {
+        if !include(peer_id) {
+            continue;
+        }
+        let status = match Persona::load(storage, identity, peer_id)? {
             Some(persona) => Status::replicated(persona),
             None => Status::NotReplicated,
         };
         peers.push(Peer::Remote { peer_id, status });
     }
 
-    Ok(peers)
+    Ok(())
+}
+
+/// Determine the relation of a single tracked `peer` to the `urn` provided.
+///
+/// This is equivalent to finding `peer` in the result of [`tracked`], but only
+/// inspects the tracking entry and `rad/self` of `peer` rather than those of
+/// every tracked peer.
+///
+/// If `peer` is not tracked for the `urn`, then `None` is returned.
+pub fn tracked_peer<S>(
+    storage: &S,
+    urn: &Urn,
+    peer: PeerId,
+) -> Result<Option<Peer<Status<Persona>>>, Error>
+where
+    S: AsRef<storage::ReadOnly>,
+{
+    let storage = storage.as_ref();
+    if !tracking::is_tracked(storage, urn, Some(peer))? {
+        return Ok(None);
+    }
+
+    let identity = identities::any::get(storage, urn)?
+        .ok_or_else(|| identities::Error::NotFound(urn.clone()))?;
+    let status = match Persona::load(storage, &identity, peer)? {
+        Some(persona) => Status::replicated(persona),
+        None => Status::NotReplicated,
+    };
+
+    Ok(Some(Peer::Remote {
+        peer_id: peer,
+        status,
+    }))
 }
@@ -28,6 +28,7 @@ pub mod config;
 pub mod glob;
 pub mod pool;
 pub mod read;
+pub mod tree;
 pub mod watch;
 
 pub use config::Config;
@@ -42,6 +43,7 @@ pub use read::{
     References,
     ReferencesGlob,
 };
+pub use tree::NamespaceTree;
 pub use watch::{NamespaceEvent, Watcher};
 
 pub mod error {
@@ -221,7 +223,7 @@ impl Storage {
         watch::Watch { storage: self }
     }
 
-    pub(super) fn signer(&self) -> &BoxedSigner {
+    pub fn signer(&self) -> &BoxedSigner {
         &self.signer
     }
 
@@ -316,6 +318,10 @@ impl ReadOnlyStorage for Storage {
         self.inner.references(reference)
     }
 
+    fn namespace_refs(&self, urn: &Urn) -> Result<NamespaceTree, Error> {
+        self.inner.namespace_refs(urn)
+    }
+
     fn reference_names<'a, 'b, Refs: 'b>(
         &'a self,
         reference: &'b Refs,
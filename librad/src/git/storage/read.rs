@@ -22,6 +22,7 @@ use crate::{
 use super::{
     config::{self, Config},
     glob::{self, Pattern},
+    tree::NamespaceTree,
 };
 
 #[derive(Debug, Error)]
@@ -118,6 +119,12 @@ pub trait ReadOnlyStorage {
         RefspecPattern: From<&'b Refs>,
         Refs: Debug;
 
+    /// All refs in the namespace of `urn`, organised by owner and category.
+    ///
+    /// Symbolic refs are resolved to the object they point to, and omitted if
+    /// dangling.
+    fn namespace_refs(&self, urn: &Urn) -> Result<NamespaceTree, Error>;
+
     fn references_glob<'a, G: 'a>(&'a self, glob: G) -> Result<ReferencesGlob<'a, G>, Error>
     where
         G: Pattern + Debug;
@@ -214,6 +221,10 @@ impl ReadOnly {
     pub fn identities<'a, T: 'a>(&'a self) -> Identities<'a, T> {
         Identities::from(&self.backend)
     }
+
+    pub(crate) fn as_raw(&self) -> &git2::Repository {
+        &self.backend
+    }
 }
 
 impl ReadOnlyStorage for ReadOnly {
@@ -345,6 +356,29 @@ impl ReadOnlyStorage for ReadOnly {
             .map(|inner| References { inner })
     }
 
+    #[tracing::instrument(level = "trace", skip(self, urn), fields(urn = %urn))]
+    fn namespace_refs(&self, urn: &Urn) -> Result<NamespaceTree, Error> {
+        let prefix = format!("refs/namespaces/{}/refs/", urn.encode_id());
+        let mut tree = NamespaceTree::default();
+        for reference in self.backend.references_glob(&format!("{}*", prefix))? {
+            let reference = reference?;
+            let name = match reference.name().and_then(|name| name.strip_prefix(&prefix)) {
+                Some(name) => name.to_owned(),
+                None => continue,
+            };
+            let target = match reference.resolve() {
+                Ok(resolved) => resolved.target(),
+                Err(e) if is_not_found_err(&e) => None,
+                Err(e) => return Err(e.into()),
+            };
+            if let Some(oid) = target {
+                tree.insert(&name, oid.into());
+            }
+        }
+
+        Ok(tree)
+    }
+
     #[tracing::instrument(level = "trace", skip(self))]
     fn reference_names<'a, 'b, Refs: 'b>(
         &'a self,
@@ -0,0 +1,77 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::collections::BTreeMap;
+
+use git_ext as ext;
+
+use crate::{git::types::reference::RefsCategory, PeerId};
+
+/// The refs of a namespace, organised by owner and category.
+///
+/// See [`super::ReadOnlyStorage::namespace_refs`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NamespaceTree {
+    /// The refs owned by the local peer, ie. not under `refs/remotes`.
+    pub local: Refs,
+    /// The refs under `refs/remotes/<peer>`, keyed by the remote peer.
+    pub remotes: BTreeMap<PeerId, Refs>,
+}
+
+impl NamespaceTree {
+    /// Insert the ref `name`, relative to the namespace's `refs/` prefix.
+    pub(super) fn insert(&mut self, name: &str, oid: ext::Oid) {
+        if let Some(rest) = name.strip_prefix("remotes/") {
+            if let Some((remote, rest)) = rest.split_once('/') {
+                if let Ok(peer) = remote.parse::<PeerId>() {
+                    return self.remotes.entry(peer).or_default().insert(rest, oid);
+                }
+            }
+        }
+
+        self.local.insert(name, oid)
+    }
+}
+
+/// The refs of a single owner within a namespace.
+///
+/// Each map is keyed by the ref name relative to its category, eg. a
+/// `refs/heads/feature/x` ref is found in [`Refs::heads`] under `feature/x`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Refs {
+    pub heads: BTreeMap<String, ext::Oid>,
+    pub tags: BTreeMap<String, ext::Oid>,
+    pub notes: BTreeMap<String, ext::Oid>,
+    /// `rad/id`, `rad/self`, `rad/signed_refs`, `rad/ids/*`, etc.
+    pub rad: BTreeMap<String, ext::Oid>,
+    /// Collaborative objects, keyed by `<typename>/<object id>`.
+    pub cobs: BTreeMap<String, ext::Oid>,
+    /// Refs in any other category, keyed by their name relative to `refs/`.
+    pub other: BTreeMap<String, ext::Oid>,
+}
+
+impl Refs {
+    fn insert(&mut self, name: &str, oid: ext::Oid) {
+        let (category, rest) = match name.split_once('/') {
+            Some(split) => split,
+            None => {
+                self.other.insert(name.to_owned(), oid);
+                return;
+            },
+        };
+        let refs = match category.parse::<RefsCategory>() {
+            Ok(RefsCategory::Heads) => &mut self.heads,
+            Ok(RefsCategory::Tags) => &mut self.tags,
+            Ok(RefsCategory::Notes) => &mut self.notes,
+            Ok(RefsCategory::Rad) => &mut self.rad,
+            Ok(RefsCategory::Cobs) => &mut self.cobs,
+            Ok(RefsCategory::Unknown(_)) | Err(_) => {
+                self.other.insert(name.to_owned(), oid);
+                return;
+            },
+        };
+        refs.insert(rest.to_owned(), oid);
+    }
+}
@@ -5,10 +5,13 @@
 
 pub use crate::identities::git::Urn;
 
+mod identities;
 mod odb;
 mod refdb;
 pub mod v1;
 
+pub use refdb::{Lenient, Warning};
+
 pub use link_tracking::{
     config,
     git::{
@@ -17,12 +20,14 @@ pub use link_tracking::{
         tracking::{
             batch::{self, batch, Action, Applied, Updated},
             default_only,
+            effective_config,
             error,
             get,
+            get_many,
             is_tracked,
             modify,
             policy,
-            reference,
+            reference::{self, RefName, Remote},
             track,
             tracked,
             tracked_peers,
@@ -36,6 +41,7 @@ pub use link_tracking::{
             UntrackArgs,
             Untracked,
             UntrackedAll,
+            WithIdentities,
         },
     },
 };
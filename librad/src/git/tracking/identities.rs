@@ -0,0 +1,55 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use git_ext::is_not_found_err;
+use std_ext::Void;
+
+use crate::{
+    git::{
+        identities::{self, SomeIdentity},
+        storage::{self, ReadOnly, ReadOnlyStorage as _, Storage},
+        tracking::git::identities::Read,
+        types::{Namespace, Reference},
+        Urn,
+    },
+    git_ext as ext,
+    PeerId,
+};
+
+impl Read for ReadOnly {
+    type FindError = identities::Error;
+
+    type Oid = ext::Oid;
+    type Identity = SomeIdentity;
+
+    fn rad_self(&self, urn: &Urn, peer: &PeerId) -> Result<Option<Self::Oid>, Self::FindError> {
+        let rad_self = Reference::rad_self(Namespace::from(urn), *peer);
+        match self.reference(&rad_self) {
+            Ok(Some(reference)) => Ok(Some(reference.peel_to_commit()?.id().into())),
+            Ok(None) => Ok(None),
+            Err(storage::Error::Git(e)) if is_not_found_err(&e) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn find_identity(&self, tip: &Self::Oid) -> Result<Self::Identity, Self::FindError> {
+        Ok(self.identities::<Void>().some_identity((*tip).into())?)
+    }
+}
+
+impl Read for Storage {
+    type FindError = identities::Error;
+
+    type Oid = ext::Oid;
+    type Identity = SomeIdentity;
+
+    fn rad_self(&self, urn: &Urn, peer: &PeerId) -> Result<Option<Self::Oid>, Self::FindError> {
+        self.read_only().rad_self(urn, peer)
+    }
+
+    fn find_identity(&self, tip: &Self::Oid) -> Result<Self::Identity, Self::FindError> {
+        self.read_only().find_identity(tip)
+    }
+}
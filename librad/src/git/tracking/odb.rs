@@ -54,6 +54,8 @@ pub mod error {
     pub enum Write {
         #[error(transparent)]
         Git(#[from] git2::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
     }
 }
 
@@ -95,11 +97,9 @@ impl Write for Storage {
     type Oid = ext::Oid;
 
     fn write_config(&self, config: &Config) -> Result<Self::Oid, Self::WriteError> {
-        // unwrap is safe since Error is Infallible
-        Ok(self
-            .as_raw()
-            .blob(&config.canonical_form().unwrap())
-            .map(ext::Oid::from)?)
+        let mut writer = self.as_raw().blob_writer(None)?;
+        config.canonical_form_into(&mut writer)?;
+        Ok(writer.commit().map(ext::Oid::from)?)
     }
 
     fn modify_config<F>(&self, oid: &Self::Oid, f: F) -> Result<Self::Oid, Self::ModifyError>
@@ -3,11 +3,14 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
+use std::{cell::RefCell, collections::HashMap};
+
 use git_ext::reference::name::RefspecPattern;
 use git_ref_format::{refspec, RefString};
 use link_tracking::git::{
+    odb,
     refdb::{self, Applied, PreviousError, Prune, Pruned, PrunedRef, Read, Update, Updated, Write},
-    tracking::reference::RefName,
+    tracking::{reference::RefName, Config},
 };
 
 use crate::{
@@ -120,20 +123,106 @@ fn convert(r: git2::Reference<'_>) -> Result<Ref, error::Conversion> {
 
 type Ref<'a> = refdb::Ref<'a, ext::Oid>;
 
+/// A tracking reference which was skipped by [`Lenient`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// The reference was symbolic, but tracking references are always direct.
+    SymbolicRef { name: String },
+}
+
 pub struct References<'a> {
     inner: read::References<'a>,
+    /// Where to record skipped references. If `None`, symbolic references
+    /// are an error.
+    warnings: Option<&'a RefCell<Vec<Warning>>>,
 }
 
 impl<'a> Iterator for References<'a> {
     type Item = Result<Ref<'a>, error::Iter>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|reference| {
-            reference
-                .map_err(error::Iter::from)
-                .and_then(|r| convert(r).map_err(error::Iter::from))
-        })
+        loop {
+            let r = match self.inner.next()? {
+                Ok(r) => r,
+                Err(err) => return Some(Err(err.into())),
+            };
+            if let Some(warnings) = self.warnings {
+                if r.target().is_none() {
+                    let name = String::from_utf8_lossy(r.name_bytes()).into_owned();
+                    tracing::warn!(name = %name, "skipping symbolic tracking reference");
+                    warnings.borrow_mut().push(Warning::SymbolicRef { name });
+                    continue;
+                }
+            }
+            return Some(convert(r).map_err(error::Iter::from));
+        }
+    }
+}
+
+fn find<'a>(
+    storage: &ReadOnly,
+    reference: &RefName<'_, ext::Oid>,
+    warnings: Option<&RefCell<Vec<Warning>>>,
+) -> Result<Option<Ref<'a>>, error::Find> {
+    let gref = match storage.reference(&RefString::from(reference))? {
+        None => return Ok(None),
+        Some(gref) => gref,
+    };
+    match (gref.target(), warnings) {
+        (Some(target), _) => Ok(Some(Ref {
+            name: reference.clone().into_owned(),
+            target: target.into(),
+        })),
+        (None, Some(warnings)) => {
+            let name = reference.to_string();
+            tracing::warn!(name = %name, "skipping symbolic tracking reference");
+            warnings.borrow_mut().push(Warning::SymbolicRef { name });
+            Ok(None)
+        },
+        (None, None) => Err(error::SymbolicRef.into()),
+    }
+}
+
+/// Find all of `names` while iterating over the references of each urn only
+/// once, rather than looking up each name individually.
+fn find_many<'a>(
+    storage: &ReadOnly,
+    names: &[RefName<'_, ext::Oid>],
+) -> Result<Vec<Option<Ref<'a>>>, error::Find> {
+    let mut found = names.iter().map(|_| None).collect::<Vec<_>>();
+    let mut wanted: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut specs: Vec<refspec::PatternString> = Vec::new();
+    for (i, name) in names.iter().enumerate() {
+        let mut refname = RefString::from(name);
+        wanted.entry(refname.to_string()).or_default().push(i);
+        refname.pop();
+        let spec = refname.with_pattern(refspec::STAR);
+        if !specs.contains(&spec) {
+            specs.push(spec);
+        }
+    }
+
+    for spec in specs {
+        for reference in ReadOnlyStorage::references(storage, &RefspecPattern::from(&*spec))? {
+            let reference = reference?;
+            let indices = match reference.name().and_then(|name| wanted.get(name)) {
+                None => continue,
+                Some(indices) => indices,
+            };
+            let target = reference
+                .target()
+                .map(ext::Oid::from)
+                .ok_or(error::SymbolicRef)?;
+            for i in indices {
+                found[*i] = Some(Ref {
+                    name: names[*i].clone().into_owned(),
+                    target,
+                });
+            }
+        }
     }
+
+    Ok(found)
 }
 
 impl<'a> Read<'a> for ReadOnly {
@@ -148,16 +237,14 @@ impl<'a> Read<'a> for ReadOnly {
         &self,
         reference: &RefName<'_, Self::Oid>,
     ) -> Result<Option<Ref>, Self::FindError> {
-        let gref = self.reference(&RefString::from(reference))?;
-        Ok(gref
-            .map(|gref| {
-                let target = gref.target().map(ext::Oid::from).ok_or(error::SymbolicRef);
-                target.map(|target| Ref {
-                    name: reference.clone().into_owned(),
-                    target,
-                })
-            })
-            .transpose()?)
+        find(self, reference, None)
+    }
+
+    fn find_references(
+        &self,
+        names: &[RefName<'_, Self::Oid>],
+    ) -> Result<Vec<Option<Ref>>, Self::FindError> {
+        find_many(self, names)
     }
 
     fn references(
@@ -165,7 +252,10 @@ impl<'a> Read<'a> for ReadOnly {
         spec: impl AsRef<refspec::PatternStr>,
     ) -> Result<Self::References, Self::ReferencesError> {
         let references = ReadOnlyStorage::references(self, &RefspecPattern::from(spec.as_ref()))?;
-        Ok(References { inner: references })
+        Ok(References {
+            inner: references,
+            warnings: None,
+        })
     }
 }
 
@@ -184,6 +274,13 @@ impl<'a> Read<'a> for Storage {
         self.read_only().find_reference(reference)
     }
 
+    fn find_references(
+        &self,
+        names: &[RefName<'_, Self::Oid>],
+    ) -> Result<Vec<Option<Ref>>, Self::FindError> {
+        self.read_only().find_references(names)
+    }
+
     fn references(
         &'a self,
         spec: impl AsRef<refspec::PatternStr>,
@@ -192,6 +289,81 @@ impl<'a> Read<'a> for Storage {
     }
 }
 
+/// A view of the tracking refdb of `S` which skips symbolic references,
+/// rather than failing on them.
+///
+/// Tracking references are never symbolic, so coming across one means the
+/// refdb is likely corrupted. Reading from `S` directly treats that as an
+/// error, aborting the whole scan. Reading through `Lenient` instead logs the
+/// offending reference, records a [`Warning`] for it, and carries on.
+pub struct Lenient<'s, S> {
+    storage: &'s S,
+    warnings: RefCell<Vec<Warning>>,
+}
+
+impl<'s, S> Lenient<'s, S>
+where
+    S: AsRef<ReadOnly>,
+{
+    pub fn new(storage: &'s S) -> Self {
+        Self {
+            storage,
+            warnings: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The references which were skipped so far.
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.warnings.borrow().clone()
+    }
+}
+
+impl<'a, 's, S> Read<'a> for Lenient<'s, S>
+where
+    S: AsRef<ReadOnly>,
+{
+    type FindError = error::Find;
+    type ReferencesError = read::Error;
+    type IterError = error::Iter;
+
+    type Oid = ext::Oid;
+    type References = References<'a>;
+
+    fn find_reference(
+        &self,
+        reference: &RefName<'_, Self::Oid>,
+    ) -> Result<Option<Ref>, Self::FindError> {
+        find(self.storage.as_ref(), reference, Some(&self.warnings))
+    }
+
+    fn references(
+        &'a self,
+        spec: impl AsRef<refspec::PatternStr>,
+    ) -> Result<Self::References, Self::ReferencesError> {
+        let references = ReadOnlyStorage::references(
+            self.storage.as_ref(),
+            &RefspecPattern::from(spec.as_ref()),
+        )?;
+        Ok(References {
+            inner: references,
+            warnings: Some(&self.warnings),
+        })
+    }
+}
+
+impl<'s, S> odb::Read for Lenient<'s, S>
+where
+    S: AsRef<ReadOnly>,
+{
+    type FindError = <ReadOnly as odb::Read>::FindError;
+
+    type Oid = ext::Oid;
+
+    fn find_config(&self, oid: &Self::Oid) -> Result<Option<Config>, Self::FindError> {
+        odb::Read::find_config(self.storage.as_ref(), oid)
+    }
+}
+
 impl Write for Storage {
     type TxnError = error::Txn;
 
@@ -33,6 +33,10 @@ pub mod x509;
 /// [ALPN]: https://tools.ietf.org/html/rfc7301
 pub const PROTOCOL_VERSION: u8 = 2;
 
+/// The version of the software a peer is running, as reported in response to
+/// an [`protocol::interrogation::Request::GetNodeInfo`].
+pub const SOFTWARE_VERSION: &str = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+
 /// Logical network.
 ///
 /// This may be used to operate "devnets" without physical network isolation:
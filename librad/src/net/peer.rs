@@ -120,7 +120,11 @@ where
         let caches = {
             let store = git::storage::Storage::open(&config.protocol.paths, config.signer.clone())?;
             let phone = phone.clone();
-            let urns = protocol::cache::urns::Filter::new(store, move |ev| phone.emit(ev))?;
+            let urns = protocol::cache::urns::Filter::with_config(
+                store,
+                config.protocol.caches.urns,
+                move |ev| phone.emit(ev),
+            )?;
             protocol::Caches { urns }
         };
 
@@ -227,6 +231,7 @@ where
             urn,
             rev: None,
             origin: None,
+            previous: None,
         }) {
             Ok(()) => providers.boxed(),
             Err(_) => futures::stream::empty().boxed(),
@@ -317,12 +322,39 @@ where
         self.phone.connect(to).await
     }
 
+    /// Drop all connections to `peer`.
+    pub async fn disconnect(&self, peer: PeerId) {
+        self.phone.disconnect(peer).await
+    }
+
+    /// Connect to each of `peers` concurrently, returning as soon as `quorum`
+    /// connections have been established.
+    ///
+    /// The outcome is reported per peer, in the order `peers` were given.
+    pub async fn connect_many(
+        &self,
+        peers: impl IntoIterator<Item = (PeerId, Vec<SocketAddr>)>,
+        quorum: usize,
+    ) -> Vec<(PeerId, Option<Connected>)> {
+        self.phone.connect_many(peers, quorum).await
+    }
+
     pub fn subscribe(
         &self,
     ) -> impl futures::Stream<Item = Result<ProtocolEvent, protocol::RecvError>> {
         self.phone.subscribe()
     }
 
+    /// Subscribe to the events pertaining to `urn`.
+    ///
+    /// See [`protocol::event::upstream::scoped`].
+    pub fn subscribe_urn(
+        &self,
+        urn: Urn,
+    ) -> impl futures::Stream<Item = Result<ProtocolEvent, protocol::RecvError>> {
+        self.phone.subscribe_urn(urn)
+    }
+
     /// Borrow a [`git::storage::Storage`] from the pool, and run a blocking
     /// computation on it.
     pub async fn using_storage<F, T>(&self, blocking: F) -> Result<T, error::Storage>
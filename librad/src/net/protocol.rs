@@ -3,7 +3,7 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{fmt::Debug, future::Future, net::SocketAddr, sync::Arc};
+use std::{fmt::Debug, future::Future, net::SocketAddr, sync::Arc, time::Instant};
 
 use async_stream::stream;
 use futures::{stream::BoxStream, StreamExt};
@@ -72,6 +72,7 @@ pub struct Config<Guard = config::DenyAll> {
     pub replication: replication::Config,
     pub rate_limits: Quota,
     pub request_pull: Guard,
+    pub caches: cache::Config,
     // TODO: transport, ...
 }
 
@@ -207,6 +208,7 @@ where
         Storage::new(storage, config.rate_limits.storage),
         config.paths.clone(),
         config.request_pull,
+        config.rate_limits.request_pull,
     );
     let limits = RateLimits {
         membership: Arc::new(RateLimiter::keyed(
@@ -224,6 +226,7 @@ where
         phone: phone.clone(),
         config: StateConfig {
             paths: Arc::new(config.paths),
+            started: Instant::now(),
         },
         caches,
         spawner,
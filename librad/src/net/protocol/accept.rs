@@ -62,6 +62,22 @@ where
                 )
             },
 
+            membership::Periodic::Reconnect { candidates } => {
+                tracing::info!(peers = candidates.len(), "attempting to reconnect");
+                stream::iter(
+                    candidates
+                        .into_iter()
+                        .map(|info| tick::Tock::AttemptSend {
+                            to: info,
+                            message: state
+                                .membership
+                                .hello(io::peer_advertisement(&state.endpoint)())
+                                .into(),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            },
+
             membership::Periodic::Shuffle(membership::Shuffle {
                 recipient,
                 sample,
@@ -139,6 +155,8 @@ where
                 Downstream::Interrogation(x) => control::interrogation(x).await,
                 Downstream::RequestPull(x) => control::request_pull(x).await,
                 Downstream::Connect(x) => control::connect(&state, x).await,
+                Downstream::ConnectMany(x) => control::connect_many(&state, x),
+                Downstream::Disconnect(x) => control::disconnect(&state, x),
             },
         }
     }
@@ -26,6 +26,11 @@ pub struct Caches {
     pub urns: urns::Filter,
 }
 
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Config {
+    pub urns: urns::Config,
+}
+
 pub mod urns {
     use super::*;
 
@@ -46,11 +51,51 @@ pub mod urns {
     #[derive(Clone, Debug)]
     pub enum Event {
         Error(Arc<Box<dyn std::error::Error + Send + Sync + 'static>>),
+        /// The filter was rebuilt, and the number of elements changed by at
+        /// least [`Config::rebuilt_threshold`].
         Rebuilt {
             built_in: Duration,
             len_old: usize,
             len_new: usize,
         },
+        /// The filter was rebuilt, but the number of elements changed by less
+        /// than [`Config::rebuilt_threshold`].
+        Incremental {
+            built_in: Duration,
+            len_old: usize,
+            len_new: usize,
+        },
+    }
+
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Config {
+        /// The minimum number of elements added or removed by a rebuild for an
+        /// [`Event::Rebuilt`] to be emitted. Rebuilds changing fewer elements
+        /// emit [`Event::Incremental`] instead.
+        ///
+        /// The default of `0` emits [`Event::Rebuilt`] for every rebuild.
+        pub rebuilt_threshold: usize,
+    }
+
+    impl Config {
+        /// The [`Event`] to emit for a rebuild which took `built_in` and
+        /// changed the number of elements from `len_old` to `len_new`.
+        pub fn event(&self, built_in: Duration, len_old: usize, len_new: usize) -> Event {
+            let delta = len_new.max(len_old) - len_new.min(len_old);
+            if delta >= self.rebuilt_threshold {
+                Event::Rebuilt {
+                    built_in,
+                    len_old,
+                    len_new,
+                }
+            } else {
+                Event::Incremental {
+                    built_in,
+                    len_old,
+                    len_new,
+                }
+            }
+        }
     }
 
     #[derive(Clone, Copy, Debug, Default)]
@@ -78,6 +123,17 @@ pub mod urns {
 
     impl Filter {
         pub fn new<F>(storage: storage::Storage, observe: F) -> Result<Self, Error>
+        where
+            F: Fn(Event) + Send + 'static,
+        {
+            Self::with_config(storage, Config::default(), observe)
+        }
+
+        pub fn with_config<F>(
+            storage: storage::Storage,
+            config: Config,
+            observe: F,
+        ) -> Result<Self, Error>
         where
             F: Fn(Event) + Send + 'static,
         {
@@ -89,7 +145,7 @@ pub mod urns {
             let (_watch, events) = storage.watch().namespaces()?;
             thread::spawn({
                 let filter = Arc::clone(&inner);
-                move || recache_thread(storage, filter, events, observe)
+                move || recache_thread(storage, config, filter, events, observe)
             });
 
             Ok(Self { inner, _watch })
@@ -123,6 +179,7 @@ pub mod urns {
 
     fn recache_thread<F>(
         storage: storage::Storage,
+        config: Config,
         filter: Arc<RwLock<FilterInner>>,
         events: impl Iterator<Item = watch::NamespaceEvent>,
         observe: F,
@@ -179,11 +236,7 @@ pub mod urns {
                                 let mut guard = filter.write();
                                 *guard = new;
                                 drop(guard);
-                                observe(Event::Rebuilt {
-                                    built_in: dur,
-                                    len_old,
-                                    len_new,
-                                });
+                                observe(config.event(dur, len_old, len_new));
                             },
                         }
 
@@ -158,3 +158,51 @@ pub(super) async fn connect<S, G>(
         tx.send(conn).ok();
     }
 }
+
+pub(super) fn connect_many<S, G>(
+    state: &State<S, G>,
+    event::downstream::ConnectMany { peers, reply }: event::downstream::ConnectMany,
+) where
+    S: ProtocolStorage<SocketAddr, Update = gossip::Payload> + Clone + 'static,
+    G: RequestPullGuard,
+{
+    let chan = reply.lock().take();
+    if let Some(tx) = chan {
+        // Connect in a separate task, so that slow peers don't hold up other
+        // downstream events
+        let state = state.clone();
+        state
+            .spawner
+            .clone()
+            .spawn(async move {
+                let mut pending = peers
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, (peer, addr_hints))| {
+                        let state = state.clone();
+                        async move { (ix, state.connection(peer, addr_hints).await) }
+                    })
+                    .collect::<stream::FuturesUnordered<_>>();
+                while let Some(outcome) = pending.next().await {
+                    // The caller has stopped listening, e.g. because a quorum
+                    // was reached
+                    if tx.send(outcome).await.is_err() {
+                        break;
+                    }
+                }
+            })
+            .detach();
+    }
+}
+
+pub(super) fn disconnect<S, G>(
+    state: &State<S, G>,
+    event::downstream::Disconnect { peer, reply }: event::downstream::Disconnect,
+) where
+    S: ProtocolStorage<SocketAddr, Update = gossip::Payload> + 'static,
+{
+    state.endpoint.disconnect(&peer);
+    if let Some(tx) = reply.lock().take() {
+        tx.send(()).ok();
+    }
+}
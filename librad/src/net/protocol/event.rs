@@ -15,6 +15,8 @@ pub enum Downstream {
     Interrogation(downstream::Interrogation),
     RequestPull(downstream::RequestPull),
     Connect(downstream::Connect),
+    ConnectMany(downstream::ConnectMany),
+    Disconnect(downstream::Disconnect),
 }
 
 pub mod downstream {
@@ -91,6 +93,20 @@ pub mod downstream {
         pub peer: (PeerId, Vec<SocketAddr>),
         pub reply: Reply<Option<quic::Connection>>,
     }
+
+    /// Connect to each of `peers` concurrently, replying with the index of the
+    /// peer in `peers` and the outcome as each attempt completes.
+    #[derive(Clone)]
+    pub struct ConnectMany {
+        pub peers: Vec<(PeerId, Vec<SocketAddr>)>,
+        pub reply: MultiReply<(usize, Option<quic::Connection>)>,
+    }
+
+    #[derive(Clone)]
+    pub struct Disconnect {
+        pub peer: PeerId,
+        pub reply: Reply<()>,
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -110,7 +126,10 @@ pub mod upstream {
     use futures::{pin_mut, FutureExt as _, StreamExt as _};
     use thiserror::Error;
 
-    use crate::net::protocol::{PeerInfo, RecvError};
+    use crate::{
+        git::Urn,
+        net::protocol::{PeerInfo, RecvError},
+    };
 
     #[derive(Clone, Debug)]
     pub enum Endpoint {
@@ -167,6 +186,29 @@ pub mod upstream {
         }
     }
 
+    /// Filter `events` down to the events pertaining to `urn`.
+    ///
+    /// Only [`Upstream::Gossip`] events carry a URN, so these are the only
+    /// events which are yielded. The events are matched on the identifier of
+    /// `urn`, ignoring its path, so announcements of any branch are included.
+    /// Errors of the underlying stream are passed through, so that a consumer
+    /// can still tell when it lagged behind or the sender went away.
+    pub fn scoped<S>(
+        urn: Urn,
+        events: S,
+    ) -> impl futures::Stream<Item = Result<Upstream, RecvError>>
+    where
+        S: futures::Stream<Item = Result<Upstream, RecvError>>,
+    {
+        let matching = predicate::urn(urn);
+        events.filter(move |event| {
+            futures::future::ready(match event {
+                Ok(event) => matching(event),
+                Err(_) => true,
+            })
+        })
+    }
+
     #[derive(Debug, Error)]
     pub enum ExpectError {
         #[error("timeout waiting for matching event")]
@@ -201,6 +243,81 @@ pub mod upstream {
         }
     }
 
+    /// Like [`expect`], but waits for an event matching any of `predicates`.
+    ///
+    /// The index of the first predicate which matched is returned along with
+    /// the event.
+    pub async fn expect_any<S, P>(
+        events: S,
+        predicates: &[P],
+        timeout: Duration,
+    ) -> Result<(usize, Upstream), ExpectError>
+    where
+        S: futures::Stream<Item = Result<Upstream, RecvError>> + Unpin,
+        P: Fn(&Upstream) -> bool,
+    {
+        let timeout = link_async::sleep(timeout).fuse();
+        pin_mut!(timeout);
+        let mut events = events.fuse();
+        loop {
+            futures::select! {
+                _ = timeout => return Err(ExpectError::Timeout),
+                i = events.next() => match i {
+                    Some(Ok(event)) => {
+                        if let Some(ix) = predicates.iter().position(|matching| matching(&event)) {
+                            return Ok((ix, event));
+                        }
+                    },
+                    Some(Err(RecvError::Closed)) | None => return Err(ExpectError::Lost),
+                    _ => {
+                        continue;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Like [`expect`], but waits until each of `predicates` has matched an
+    /// event.
+    ///
+    /// The first event matching each predicate is returned, in the order of
+    /// `predicates`. A single event may satisfy more than one predicate. The
+    /// `timeout` applies to waiting for all of the events, not to each one
+    /// individually.
+    pub async fn expect_all<S, P>(
+        events: S,
+        predicates: &[P],
+        timeout: Duration,
+    ) -> Result<Vec<Upstream>, ExpectError>
+    where
+        S: futures::Stream<Item = Result<Upstream, RecvError>> + Unpin,
+        P: Fn(&Upstream) -> bool,
+    {
+        let mut matched: Vec<Option<Upstream>> = vec![None; predicates.len()];
+        let timeout = link_async::sleep(timeout).fuse();
+        pin_mut!(timeout);
+        let mut events = events.fuse();
+        while matched.iter().any(Option::is_none) {
+            futures::select! {
+                _ = timeout => return Err(ExpectError::Timeout),
+                i = events.next() => match i {
+                    Some(Ok(event)) => {
+                        for (slot, matching) in matched.iter_mut().zip(predicates) {
+                            if slot.is_none() && matching(&event) {
+                                *slot = Some(event.clone());
+                            }
+                        }
+                    },
+                    Some(Err(RecvError::Closed)) | None => return Err(ExpectError::Lost),
+                    _ => {
+                        continue;
+                    }
+                }
+            }
+        }
+        Ok(matched.into_iter().flatten().collect())
+    }
+
     pub mod predicate {
         use super::*;
 
@@ -213,16 +330,30 @@ pub mod upstream {
             }
         }
 
-        /// Wait for cache `Rebuilt` events where the new length matches the
-        /// predicate.
+        /// Match events pertaining to the identifier of `urn`.
+        ///
+        /// See [`super::scoped`].
+        pub fn urn(urn: Urn) -> impl Fn(&Upstream) -> bool {
+            move |event| match event {
+                Upstream::Gossip(gossip) => match gossip.as_ref() {
+                    Gossip::Put { payload, .. } => payload.urn.id == urn.id,
+                },
+                _ => false,
+            }
+        }
+
+        /// Wait for cache `Rebuilt` or `Incremental` events where the new
+        /// length matches the predicate.
         pub fn urn_cache_len<P>(cmp: P) -> impl Fn(&Upstream) -> bool
         where
             P: Fn(usize) -> bool,
         {
+            use cache::urns::Event::{Incremental, Rebuilt};
+
             move |event| match event {
-                Upstream::Caches(Caches::Urns(cache::urns::Event::Rebuilt { len_new, .. })) => {
-                    cmp(*len_new)
-                },
+                Upstream::Caches(Caches::Urns(
+                    Rebuilt { len_new, .. } | Incremental { len_new, .. },
+                )) => cmp(*len_new),
                 _ => false,
             }
         }
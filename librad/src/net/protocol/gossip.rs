@@ -78,4 +78,12 @@ pub struct Payload {
     /// is, it may map to `remotes/<origin>/<urn.path@rev>`.
     #[n(2)]
     pub origin: Option<PeerId>,
+
+    /// The revision `rev` superseded, if known.
+    ///
+    /// Receivers which already have `previous` may use it to determine what
+    /// changed. Payloads from peers which predate this field decode with
+    /// `None`.
+    #[n(3)]
+    pub previous: Option<Rev>,
 }
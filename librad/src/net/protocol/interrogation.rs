@@ -10,6 +10,6 @@ use crate::identities::xor;
 use super::info::PeerAdvertisement;
 
 mod rpc;
-pub use rpc::{Error, Request, Response};
+pub use rpc::{Error, KnownPeer, NodeInfo, Request, Response};
 
 pub const FRAMED_BUFSIZ: usize = xor::MaxFingerprints::USIZE * 3;
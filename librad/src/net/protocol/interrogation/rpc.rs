@@ -3,12 +3,12 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeSet};
 
 use super::PeerAdvertisement;
-use crate::identities::xor;
+use crate::{identities::xor, net::protocol::Capability, PeerId};
 
-#[derive(Clone, Copy, Debug, minicbor::Encode, minicbor::Decode)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, minicbor::Encode, minicbor::Decode)]
 pub enum Request {
     /// Request the remote peer's [`PeerAdvertisement`]
     #[n(0)]
@@ -27,9 +27,21 @@ pub enum Request {
     #[n(2)]
     #[cbor(array)]
     GetUrns,
+
+    /// Request information about the software the remote peer is running,
+    /// see [`NodeInfo`].
+    #[n(3)]
+    #[cbor(array)]
+    GetNodeInfo,
+
+    /// Request the peers the remote peer knows about, along with their
+    /// addresses, see [`KnownPeer`].
+    #[n(4)]
+    #[cbor(array)]
+    GetPeers,
 }
 
-#[derive(minicbor::Encode, minicbor::Decode)]
+#[derive(Clone, minicbor::Encode, minicbor::Decode)]
 pub enum Response<'a, Addr>
 where
     Addr: Clone + Ord,
@@ -56,6 +68,57 @@ where
     #[n(3)]
     #[cbor(array)]
     Urns(#[n(0)] Cow<'a, xor::Xor>),
+
+    /// Response to a [`Request::GetNodeInfo`].
+    #[n(4)]
+    #[cbor(array)]
+    NodeInfo(#[n(0)] NodeInfo),
+
+    /// Response to a [`Request::GetPeers`].
+    #[n(5)]
+    #[cbor(array)]
+    Peers(#[n(0)] Vec<KnownPeer<Addr>>),
+}
+
+/// A peer in the responder's membership view.
+#[derive(Clone, Debug, PartialEq, Eq, minicbor::Encode, minicbor::Decode)]
+#[cbor(array)]
+pub struct KnownPeer<Addr> {
+    #[n(0)]
+    pub peer_id: PeerId,
+
+    /// The addresses the peer was seen on or advertised, without duplicates.
+    #[n(1)]
+    pub addrs: Vec<Addr>,
+}
+
+impl<Addr> From<KnownPeer<Addr>> for (PeerId, Vec<Addr>) {
+    fn from(KnownPeer { peer_id, addrs }: KnownPeer<Addr>) -> Self {
+        (peer_id, addrs)
+    }
+}
+
+/// Information about the software a peer is running.
+#[derive(Clone, Debug, PartialEq, Eq, minicbor::Encode, minicbor::Decode)]
+#[cbor(array)]
+pub struct NodeInfo {
+    /// The name and version of the software, see
+    /// [`crate::net::SOFTWARE_VERSION`].
+    #[n(0)]
+    pub version: String,
+
+    /// The protocol version, see [`crate::net::PROTOCOL_VERSION`].
+    #[n(1)]
+    pub protocol_version: u8,
+
+    /// The number of seconds since the peer started.
+    #[n(2)]
+    pub uptime_secs: u64,
+
+    /// The capabilities the peer supports, as also advertised in its
+    /// [`PeerAdvertisement`].
+    #[n(3)]
+    pub capabilities: BTreeSet<Capability>,
 }
 
 /// Error response.
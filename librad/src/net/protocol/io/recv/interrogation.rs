@@ -3,7 +3,7 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{borrow::Cow, net::SocketAddr};
+use std::{borrow::Cow, net::SocketAddr, time::Instant};
 
 use futures::{
     io::{AsyncRead, AsyncWrite, AsyncWriteExt as _, BufReader, BufWriter},
@@ -11,16 +11,19 @@ use futures::{
     StreamExt as _,
 };
 use futures_codec::FramedRead;
+use rand_pcg::Pcg64Mcg;
 use thiserror::Error;
 
 use crate::{
     git::storage,
     net::{
+        self,
         connection::Duplex,
         protocol::{
             cache,
             interrogation::{self, Request, Response},
             io::{self, codec},
+            membership,
             Endpoint,
             State,
         },
@@ -59,14 +62,21 @@ pub(in crate::net::protocol) async fn interrogation<S, G, T>(
         match x {
             Err(e) => tracing::warn!(err = ?e, "interrogation recv error"),
             Ok(req) => {
-                let resp = handle_request(&state.endpoint, &state.caches.urns, remote_addr, req)
-                    .map(Cow::from)
-                    .unwrap_or_else(|e| {
-                        tracing::error!(err = ?e, "error handling request");
-                        match e {
-                            Error::Cbor(_) => Cow::from(&*INTERNAL_ERROR),
-                        }
-                    });
+                let resp = handle_request(
+                    &state.endpoint,
+                    &state.membership,
+                    &state.caches.urns,
+                    state.config.started,
+                    remote_addr,
+                    req,
+                )
+                .map(Cow::from)
+                .unwrap_or_else(|e| {
+                    tracing::error!(err = ?e, "error handling request");
+                    match e {
+                        Error::Cbor(_) => Cow::from(&*INTERNAL_ERROR),
+                    }
+                });
 
                 if let Err(e) = send.into_sink().send(resp).await {
                     tracing::warn!(err = ?e, "interrogation send error")
@@ -78,7 +88,9 @@ pub(in crate::net::protocol) async fn interrogation<S, G, T>(
 
 fn handle_request(
     endpoint: &Endpoint,
+    membership: &membership::Hpv<Pcg64Mcg, SocketAddr>,
     urns: &cache::urns::Filter,
+    started: Instant,
     remote_addr: SocketAddr,
     req: interrogation::Request,
 ) -> Result<Vec<u8>, Error> {
@@ -93,6 +105,19 @@ fn handle_request(
             let urns = urns.get();
             Right(encode(&Response::<SocketAddr>::Urns(Cow::Borrowed(&*urns))))
         },
+        Request::GetNodeInfo => Left(Response::NodeInfo(interrogation::NodeInfo {
+            version: net::SOFTWARE_VERSION.to_owned(),
+            protocol_version: net::PROTOCOL_VERSION,
+            uptime_secs: started.elapsed().as_secs(),
+            capabilities: io::peer_advertisement(endpoint)().capabilities,
+        })),
+        Request::GetPeers => Left(Response::Peers(
+            membership
+                .known_addrs()
+                .into_iter()
+                .map(|(peer_id, addrs)| interrogation::KnownPeer { peer_id, addrs })
+                .collect(),
+        )),
     }
     .right_or_else(|resp| encode(&resp))
 }
@@ -112,10 +112,14 @@ where
     }
 }
 
+#[tracing::instrument(
+    skip(state, peer, request, conn, report),
+    fields(urn = %request.urn, peer = %peer)
+)]
 async fn handle_request<'a, S, G, W>(
     state: State<S, G>,
     peer: PeerId,
-    Request { urn }: Request,
+    request: Request,
     conn: quic::Connection,
     report: &mut Reporter<'a, W>,
 ) -> Response
@@ -124,12 +128,36 @@ where
     G: protocol::RequestPullGuard,
     W: AsyncWrite + Unpin,
 {
+    let Request { urn } = request;
     report.progress(progress::authorizing(&urn)).await;
-    match state.request_pull.guard(&peer, &urn) {
+    match state
+        .request_pull
+        .guard(&state.spawner, peer, urn.clone())
+        .await
+    {
         Ok(guard) => report.progress(progress::guard(guard)).await,
-        Err(err) => return error::guard(err).into(),
+        Err(err) if state.request_pull.is_internal(&err) => {
+            tracing::warn!(err = %err, "request-pull guard failed");
+            return error::guard_failed(err).into();
+        },
+        Err(err) => {
+            tracing::info!(err = %err, "request-pull rejected");
+            return error::guard(err).into();
+        },
     }
 
+    let _permit = match state.request_pull.acquire() {
+        request_pull::Slot::Acquired(permit) => permit,
+        request_pull::Slot::Queued(queued) => {
+            report.progress(progress::queued(&urn)).await;
+            queued.wait().await
+        },
+        request_pull::Slot::Busy => {
+            tracing::info!("request-pull rejected, too many in progress");
+            return error::busy().into();
+        },
+    };
+
     report.progress(progress::replicating(&urn)).await;
     match state
         .request_pull
@@ -137,11 +165,19 @@ where
         .await
     {
         Ok(success) => {
+            tracing::info!(
+                updated = success.refs.len(),
+                pruned = success.pruned.len(),
+                "request-pull succeeded"
+            );
             let tips = success.refs.iter().map(|Ref { oid, .. }| oid).copied();
             gossip(&state, peer, &urn, tips).await;
             success.into()
         },
-        Err(err) => error::replication_error(err).into(),
+        Err(err) => {
+            tracing::warn!(err = %err, "request-pull failed");
+            error::replication_error(err).into()
+        },
     }
 }
 
@@ -161,6 +197,7 @@ async fn gossip<S, G>(
                 urn: urn.clone(),
                 rev: Some(rev.into()),
                 origin: None,
+                previous: None,
             }),
             Some(exclude),
         )
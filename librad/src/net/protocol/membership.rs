@@ -24,8 +24,11 @@ pub use partial_view::{PartialView, Transition};
 mod periodic;
 pub use periodic::Periodic;
 
+mod reconnect;
+pub use reconnect::{Backoff, Reconnects};
+
 mod rpc;
-pub use rpc::Message;
+pub use rpc::{Message, Priority};
 
 mod tick;
 pub use tick::Tick;
@@ -8,6 +8,7 @@ use std::{
     iter::{self, FromIterator},
     ops::Mul,
     sync::Arc,
+    time::Instant,
 };
 
 use data::BoundedVec;
@@ -19,6 +20,7 @@ use super::{
     error::Error,
     partial_view::{PartialView, Transition},
     periodic::{periodic_tasks, Periodic},
+    reconnect::Reconnects,
     rpc,
     Params,
     Tick,
@@ -166,6 +168,35 @@ where
         self.0.read().passive().collect()
     }
 
+    /// The known peers along with their addresses, active peers first.
+    ///
+    /// The addresses a peer was seen on come before the ones it advertised.
+    pub fn known_addrs(&self) -> Vec<(PeerId, Vec<Addr>)> {
+        fn dedup<A: PartialEq>(addrs: impl Iterator<Item = A>) -> Vec<A> {
+            addrs.fold(Vec::new(), |mut acc, addr| {
+                if !acc.contains(&addr) {
+                    acc.push(addr)
+                }
+                acc
+            })
+        }
+
+        let guard = self.0.read();
+        let active = guard.view.active_info().map(|info| {
+            let listen_addrs = info
+                .advertised_info
+                .into_iter()
+                .flat_map(|ad| ad.listen_addrs.into_iter());
+            let addrs = dedup(info.seen_addrs.into_iter().chain(listen_addrs));
+            (info.peer_id, addrs)
+        });
+        let passive = guard
+            .view
+            .passive_info()
+            .map(|info| (info.peer_id, dedup(info.addrs().cloned())));
+        active.chain(passive).collect()
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     #[must_use = "ticks must be interpreted"]
     pub fn connection_lost(&self, remote_peer: PeerId) -> TnT<Addr> {
@@ -218,6 +249,12 @@ where
         }
     }
 
+    /// The peers which are due for an attempt to reconnect to them, see
+    /// [`Params::reconnect`].
+    pub fn reconnect_due(&self) -> Vec<PeerInfo<Addr>> {
+        self.0.write().reconnect_due(Instant::now())
+    }
+
     pub(super) fn params(&self) -> Params {
         self.0.read().params.clone()
     }
@@ -228,6 +265,7 @@ struct HpvInner<Rng, Addr> {
     params: Params,
     rng: Rng,
     view: PartialView<Rng, Addr>,
+    reconnects: Reconnects,
 }
 
 impl<Rng, Addr> HpvInner<Rng, Addr>
@@ -237,11 +275,13 @@ where
 {
     pub fn new(local_id: PeerId, rng: Rng, params: Params) -> Self {
         let view = PartialView::new(local_id, rng.clone(), params.max_active, params.max_passive);
+        let reconnects = Reconnects::new(params.reconnect.clone());
         Self {
             local_id,
             params,
             rng,
             view,
+            reconnects,
         }
     }
 
@@ -285,6 +325,7 @@ where
         if demoted.is_empty() {
             TnT::default()
         } else {
+            self.reconnects.lost(remote_peer, Instant::now());
             TnT {
                 trans: demoted,
                 ticks: self
@@ -298,9 +339,22 @@ where
 
     pub fn connection_established(&mut self, info: PartialPeerInfo<Addr>) -> TnT<Addr> {
         tracing::debug!("connection established");
+        self.reconnects.established(&info.peer_id);
         self.view.add_active(info).into_iter().collect()
     }
 
+    /// The peers due for an attempt to reconnect to them at `now`.
+    ///
+    /// Only peers which are still in the passive view are attempted, peers
+    /// which were evicted or promoted in the meantime are skipped.
+    pub fn reconnect_due(&mut self, now: Instant) -> Vec<PeerInfo<Addr>> {
+        let due = self.reconnects.due(now);
+        self.view
+            .passive_info()
+            .filter(|info| due.contains(&info.peer_id))
+            .collect()
+    }
+
     pub fn shuffle(&mut self) -> Option<Shuffle<Addr>> {
         self.random_active().and_then(|recipient| {
             let sample = self
@@ -5,6 +5,8 @@
 
 use std::time::Duration;
 
+use super::Backoff;
+
 #[derive(Debug, Clone)]
 pub struct Params {
     /// Maximum number of active connections.
@@ -22,6 +24,9 @@ pub struct Params {
     pub shuffle_interval: Duration,
     /// Interval in which to attempt to promote a passive peer.
     pub promote_interval: Duration,
+    /// How to reconnect to peers whose connection was lost while they were
+    /// in the active view.
+    pub reconnect: Backoff,
 }
 
 impl Default for Params {
@@ -35,6 +40,7 @@ impl Default for Params {
             shuffle_sample_size: 7,
             shuffle_interval: Duration::from_secs(30),
             promote_interval: Duration::from_secs(30),
+            reconnect: Backoff::default(),
         }
     }
 }
@@ -19,6 +19,7 @@ pub enum Periodic<A> {
     RandomPromotion { candidates: Vec<PeerInfo<A>> },
     Shuffle(Shuffle<A>),
     Tickle,
+    Reconnect { candidates: Vec<PeerInfo<A>> },
 }
 
 #[tracing::instrument(skip(hpv))]
@@ -40,6 +41,22 @@ where
         }
     });
 
+    let reconnect = interval(
+        params.reconnect.check_interval(),
+        Duration::from_millis(100),
+    )
+    .filter_map({
+        let hpv = hpv.clone();
+        move |_| {
+            let candidates = hpv.reconnect_due();
+            if candidates.is_empty() {
+                future::ready(None)
+            } else {
+                future::ready(Some(Periodic::Reconnect { candidates }))
+            }
+        }
+    });
+
     let promote = interval(params.promote_interval, Duration::from_secs(5)).filter_map(move |_| {
         let candidates = hpv.choose_passive_to_promote();
         if candidates.is_empty() {
@@ -53,8 +70,11 @@ where
     let tickle = interval(MAX_IDLE_TIMEOUT.div_f32(2.0), Duration::from_secs(5))
         .filter_map(|_| future::ready(Some(Periodic::Tickle)));
 
-    // Wrapping the `select` calls is the most effective to combine the three
+    // Wrapping the `select` calls is the most effective to combine the
     // interval streams into one. All other means (select macro, select_all)
     // incur significant overhead.
-    stream::select(stream::select(promote, shuffle), tickle)
+    stream::select(
+        stream::select(stream::select(promote, shuffle), tickle),
+        reconnect,
+    )
 }
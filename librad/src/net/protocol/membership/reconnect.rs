@@ -0,0 +1,122 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use crate::PeerId;
+
+/// Parameters for reconnecting to peers which dropped out of the active view.
+///
+/// The `n`th attempt (counting from zero) is made `initial * 2^n` after the
+/// previous one, but no later than `max`. After `max_attempts` the peer is
+/// given up on, and left to the regular promotion of passive peers.
+///
+/// Pending attempts are checked every `initial`, but no more often than
+/// [`Backoff::MIN_CHECK_INTERVAL`].
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub max_attempts: usize,
+}
+
+impl Backoff {
+    pub const MIN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// The interval in which to check for pending attempts.
+    pub fn check_interval(&self) -> Duration {
+        self.initial.max(Self::MIN_CHECK_INTERVAL)
+    }
+
+    /// The delay before making attempt number `attempt`.
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let factor = 1u32.checked_shl(attempt as u32).unwrap_or(u32::MAX);
+        self.initial
+            .checked_mul(factor)
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            max_attempts: 5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pending {
+    attempts: usize,
+    due: Instant,
+}
+
+/// Schedule of reconnection attempts to peers whose connection was lost.
+#[derive(Clone, Debug)]
+pub struct Reconnects {
+    backoff: Backoff,
+    pending: BTreeMap<PeerId, Pending>,
+}
+
+impl Reconnects {
+    pub fn new(backoff: Backoff) -> Self {
+        Self {
+            backoff,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// The connection to `peer` was lost at `now`, schedule the first attempt
+    /// to reconnect.
+    ///
+    /// If `peer` is already scheduled, its schedule is left as is.
+    pub fn lost(&mut self, peer: PeerId, now: Instant) {
+        if self.backoff.max_attempts == 0 {
+            return;
+        }
+        let delay = self.backoff.delay(0);
+        self.pending.entry(peer).or_insert(Pending {
+            attempts: 0,
+            due: now + delay,
+        });
+    }
+
+    /// The connection to `peer` was (re-)established, no further attempts are
+    /// needed.
+    pub fn established(&mut self, peer: &PeerId) {
+        self.pending.remove(peer);
+    }
+
+    /// The peers which are due for a reconnection attempt at `now`.
+    ///
+    /// The next attempt of each returned peer is scheduled, unless it reached
+    /// the maximum number of attempts, in which case it is forgotten.
+    pub fn due(&mut self, now: Instant) -> Vec<PeerId> {
+        let backoff = &self.backoff;
+        let mut due = Vec::new();
+        self.pending.retain(|peer, pending| {
+            if pending.due > now {
+                return true;
+            }
+            due.push(*peer);
+            pending.attempts += 1;
+            pending.due = now + backoff.delay(pending.attempts);
+            pending.attempts < backoff.max_attempts
+        });
+        due
+    }
+
+    /// The time the next attempt to reconnect to `peer` is due, if any.
+    pub fn next_attempt(&self, peer: &PeerId) -> Option<Instant> {
+        self.pending.get(peer).map(|pending| pending.due)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
@@ -1,18 +1,27 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
 use link_async::Spawner;
 use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 use crate::{
     git::{storage, storage::PoolError, Urn},
-    net::{quic, replication},
+    net::{connection::RemotePeer as _, quic, replication},
     paths::Paths,
     PeerId,
 };
 
+pub mod guard;
+pub use guard::{AllowlistGuard, TrackingGuard};
+
 mod rpc;
-pub use rpc::{Error, Progress, Ref, Request, Response, Success};
+pub use rpc::{Error, ErrorCode, Progress, Ref, Request, Response, Success};
 
 /// Buffer size for writing and reading request-pull RPC messages.
 /// It is based on the [`Success`] response which would be considered the
@@ -26,14 +35,129 @@ pub trait Guard {
     type Error: std::error::Error + Send + Sync + 'static;
     /// The `Output` must implement [`std::fmt::Display`] for reporting back to
     /// the client that made the request in the form of a [`Progress`] message.
-    type Output: std::fmt::Display + Send + Sync;
+    type Output: std::fmt::Display + Send + Sync + 'static;
 
     /// Run any checks and effects required for a request-pull.
     ///
     /// For example, an implementation may want to check if the `peer`
     /// and `urn` are authorized to make the request, and also track
     /// the `peer` for the given `urn`.
+    ///
+    /// The guard is run on the blocking thread pool, so it may block, e.g. to
+    /// wait for a storage connection or to read the tracking graph.
     fn guard(&self, peer: &PeerId, urn: &Urn) -> Result<Self::Output, Self::Error>;
+
+    /// Whether `error` means the guard failed to run its checks, as opposed to
+    /// denying the request-pull.
+    ///
+    /// Internal failures are reported to the client with
+    /// [`ErrorCode::Internal`], denials with [`ErrorCode::Unauthorized`]. By
+    /// default, every error is a denial.
+    fn is_internal(&self, _error: &Self::Error) -> bool {
+        false
+    }
+}
+
+/// Bounds on the number of request-pulls served at the same time.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// Number of replications which may run concurrently.
+    ///
+    /// Default: 4
+    pub concurrent: usize,
+    /// Number of requests which may wait for a running replication to
+    /// finish. Requests beyond that are rejected with [`ErrorCode::Busy`].
+    ///
+    /// Default: 16
+    pub queued: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            concurrent: 4,
+            queued: 16,
+        }
+    }
+}
+
+/// Replication slots, bounded by [`Limits`].
+#[derive(Clone)]
+pub struct Slots {
+    queued: usize,
+    running: Arc<Semaphore>,
+    waiting: Arc<AtomicUsize>,
+}
+
+impl Slots {
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            queued: limits.queued,
+            running: Arc::new(Semaphore::new(limits.concurrent)),
+            waiting: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Take a free slot, or a place in the queue if there is none.
+    pub fn acquire(&self) -> Slot {
+        match self.running.clone().try_acquire_owned() {
+            Ok(permit) => Slot::Acquired(Permit(permit)),
+            Err(_) => {
+                let queued = self
+                    .waiting
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                        (n < self.queued).then(|| n + 1)
+                    });
+                match queued {
+                    Ok(_) => Slot::Queued(Queued {
+                        running: self.running.clone(),
+                        waiting: self.waiting.clone(),
+                    }),
+                    Err(_) => Slot::Busy,
+                }
+            },
+        }
+    }
+}
+
+/// The outcome of [`Slots::acquire`].
+#[derive(Debug)]
+pub enum Slot {
+    /// A slot was free.
+    Acquired(Permit),
+    /// All slots are taken, [`Queued::wait`] for one to become free.
+    Queued(Queued),
+    /// All slots are taken and the queue is full.
+    Busy,
+}
+
+/// A replication slot, which is released when dropped.
+#[derive(Debug)]
+pub struct Permit(OwnedSemaphorePermit);
+
+/// A place in the queue for a replication slot.
+#[derive(Debug)]
+pub struct Queued {
+    running: Arc<Semaphore>,
+    waiting: Arc<AtomicUsize>,
+}
+
+impl Queued {
+    pub async fn wait(self) -> Permit {
+        let permit = self
+            .running
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("replication slots are never closed");
+        Permit(permit)
+    }
+}
+
+impl Drop for Queued {
+    fn drop(&mut self) {
+        self.waiting.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// State for serving request-pull calls.
@@ -42,23 +166,46 @@ pub struct State<S, G> {
     storage: S,
     paths: Paths,
     guard: G,
+    slots: Slots,
 }
 
 impl<S, G: Guard> State<S, G> {
-    pub fn new(storage: S, paths: Paths, guard: G) -> Self {
+    pub fn new(storage: S, paths: Paths, guard: G, limits: Limits) -> Self {
         Self {
             storage,
             paths,
             guard,
+            slots: Slots::new(limits),
         }
     }
 
-    pub fn guard(&self, peer: &PeerId, urn: &Urn) -> Result<G::Output, G::Error> {
-        self.guard.guard(peer, urn)
+    /// Run the [`Guard`] on the blocking thread pool of `spawner`, so that a
+    /// guard doing I/O does not hold up the runtime.
+    pub async fn guard(
+        &self,
+        spawner: &Spawner,
+        peer: PeerId,
+        urn: Urn,
+    ) -> Result<G::Output, G::Error>
+    where
+        G: Clone + Send + 'static,
+    {
+        let guard = self.guard.clone();
+        spawner.blocking(move || guard.guard(&peer, &urn)).await
+    }
+
+    /// See [`Guard::is_internal`].
+    pub fn is_internal(&self, error: &G::Error) -> bool {
+        self.guard.is_internal(error)
+    }
+
+    /// See [`Slots::acquire`].
+    pub fn acquire(&self) -> Slot {
+        self.slots.acquire()
     }
 }
 
-pub(in crate::net::protocol) mod error {
+pub mod error {
     use super::*;
 
     #[derive(Debug, Error)]
@@ -76,24 +223,42 @@ pub(in crate::net::protocol) mod error {
     pub fn decode_failed() -> Error {
         Error {
             message: "failed to decode request".into(),
+            code: Some(ErrorCode::DecodeFailed),
         }
     }
 
     pub fn internal_error() -> Error {
         Error {
             message: "internal error".into(),
+            code: Some(ErrorCode::Internal),
         }
     }
 
     pub fn replication_error(err: Replicate) -> Error {
         Error {
             message: format!("request-pull replication error: {}", err),
+            code: Some(ErrorCode::Replication),
+        }
+    }
+
+    pub fn busy() -> Error {
+        Error {
+            message: "too many request-pulls in progress, try again later".into(),
+            code: Some(ErrorCode::Busy),
         }
     }
 
     pub fn guard<E: std::error::Error>(e: E) -> Error {
         Error {
             message: e.to_string(),
+            code: Some(ErrorCode::Unauthorized),
+        }
+    }
+
+    pub fn guard_failed<E: std::error::Error>(e: E) -> Error {
+        Error {
+            message: format!("internal error: {}", e),
+            code: Some(ErrorCode::Internal),
         }
     }
 }
@@ -103,6 +268,11 @@ where
     S: storage::Pooled<storage::Storage> + Send + Sync + 'static,
 {
     /// Run replication and convert the updated tips into [`Ref`]s.
+    #[tracing::instrument(
+        skip(self, spawner, urn, conn),
+        fields(urn = %urn, peer = %conn.remote_peer_id()),
+        err
+    )]
     pub(in crate::net::protocol) async fn replicate(
         &self,
         spawner: &Spawner,
@@ -117,9 +287,9 @@ where
         let succ = repl.replicate(spawner, storage, conn, urn, None).await?;
 
         let storage = self.storage.get().await?;
-        succ.updated_refs()
-            .iter()
-            .try_fold(Success::default(), |mut success, up| match up {
+        let success = succ.updated_refs().iter().try_fold(
+            Success::default(),
+            |mut success, up| match up {
                 Updated::Direct { name, target } => {
                     success.refs.push(Ref {
                         name: name.clone(),
@@ -139,7 +309,14 @@ where
                     success.pruned.push(name.clone());
                     Ok(success)
                 },
-            })
+            },
+        )?;
+        tracing::debug!(
+            updated = success.refs.len(),
+            pruned = success.pruned.len(),
+            "replicated"
+        );
+        Ok(success)
     }
 }
 
@@ -152,6 +329,12 @@ pub mod progress {
         }
     }
 
+    pub fn queued(urn: &Urn) -> Progress {
+        Progress {
+            message: format!("Waiting for a replication slot for `{}`", urn),
+        }
+    }
+
     pub fn authorizing(urn: &Urn) -> Progress {
         Progress {
             message: format!("Checking if request-pull is allowed for `{}`", urn),
@@ -0,0 +1,290 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{collections::BTreeSet, fmt, sync::Arc};
+
+use thiserror::Error;
+
+use crate::{
+    git::{storage, tracking, Urn},
+    PeerId,
+};
+
+use super::Guard;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to access storage for tracking")]
+    Pool(#[from] storage::PoolError),
+    #[error(transparent)]
+    IsTracked(#[from] tracking::error::IsTracked),
+    #[error(transparent)]
+    Track(#[from] tracking::error::Track),
+    #[error("`{urn}` is not tracked, nor is `{peer}` tracked for it")]
+    Untracked { peer: PeerId, urn: Urn },
+}
+
+/// The outcome of a successful [`TrackingGuard::guard`].
+pub struct Tracked {
+    urn: Urn,
+    tracked: Option<Result<tracking::Ref, tracking::PreviousError>>,
+}
+
+impl Tracked {
+    /// `tracked` is the result of tracking the requesting peer, or `None` if
+    /// it was already tracked.
+    pub fn new(urn: Urn, tracked: Option<Result<tracking::Ref, tracking::PreviousError>>) -> Self {
+        Self { urn, tracked }
+    }
+}
+
+impl fmt::Display for Tracked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.tracked {
+            None => write!(f, "Already tracking `{}`", self.urn),
+            Some(Ok(reference)) => write!(f, "Tracked `{}`", reference.name),
+            Some(Err(previous)) => write!(f, "Attempted to track `{}`: {}", self.urn, previous),
+        }
+    }
+}
+
+/// A [`Guard`] which consults the tracking graph.
+///
+/// A request-pull is allowed if either the requesting peer is tracked for the
+/// URN, or the URN itself is tracked (i.e. there is a default entry for it).
+/// In the latter case, the requesting peer is tracked as a side effect, so
+/// that the pulled refs are kept.
+#[derive(Clone)]
+pub struct TrackingGuard<P> {
+    storage: P,
+}
+
+impl<P> TrackingGuard<P> {
+    pub fn new(storage: P) -> Self {
+        Self { storage }
+    }
+}
+
+impl<P> Guard for TrackingGuard<P>
+where
+    P: storage::Pooled<storage::Storage>,
+{
+    type Error = Error;
+    type Output = Tracked;
+
+    fn guard(&self, peer: &PeerId, urn: &Urn) -> Result<Self::Output, Self::Error> {
+        let storage = futures::executor::block_on(self.storage.get())?;
+        let storage = storage.as_ref();
+        if tracking::is_tracked(storage, urn, Some(*peer))? {
+            return Ok(Tracked::new(urn.clone(), None));
+        }
+
+        if !tracking::is_tracked(storage, urn, None)? {
+            return Err(Error::Untracked {
+                peer: *peer,
+                urn: urn.clone(),
+            });
+        }
+
+        let tracked = tracking::track(
+            storage,
+            urn,
+            Some(*peer),
+            tracking::Config::default(),
+            tracking::policy::Track::MustNotExist,
+        )?;
+        Ok(Tracked::new(urn.clone(), Some(tracked)))
+    }
+
+    fn is_internal(&self, error: &Self::Error) -> bool {
+        !matches!(error, Error::Untracked { .. })
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("`{0}` is not in the request-pull allowlist")]
+pub struct NotAllowed(pub PeerId);
+
+/// The outcome of a successful [`AllowlistGuard::guard`].
+pub struct Allowed(PeerId);
+
+impl fmt::Display for Allowed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is allowed", self.0)
+    }
+}
+
+/// A [`Guard`] which only allows request-pulls from a fixed set of peers,
+/// regardless of the URN.
+#[derive(Clone, Debug)]
+pub struct AllowlistGuard {
+    allowed: BTreeSet<PeerId>,
+}
+
+impl AllowlistGuard {
+    pub fn new(allowed: impl IntoIterator<Item = PeerId>) -> Self {
+        Self {
+            allowed: allowed.into_iter().collect(),
+        }
+    }
+}
+
+impl Guard for AllowlistGuard {
+    type Error = NotAllowed;
+    type Output = Allowed;
+
+    fn guard(&self, peer: &PeerId, _: &Urn) -> Result<Self::Output, Self::Error> {
+        if self.allowed.contains(peer) {
+            Ok(Allowed(*peer))
+        } else {
+            Err(NotAllowed(*peer))
+        }
+    }
+}
+
+/// The error of a composed [`Guard`], see [`erase`], [`all`], and [`any`].
+#[derive(Debug, Error)]
+pub enum Denied {
+    #[error(transparent)]
+    One(Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Internal(Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("{}", display_all(.0))]
+    All(Vec<Denied>),
+}
+
+impl Denied {
+    /// Whether any of the composed guards failed internally, see
+    /// [`Guard::is_internal`].
+    pub fn is_internal(&self) -> bool {
+        match self {
+            Self::One(_) => false,
+            Self::Internal(_) => true,
+            Self::All(denied) => denied.iter().any(Self::is_internal),
+        }
+    }
+}
+
+fn display_all(denied: &[Denied]) -> String {
+    if denied.is_empty() {
+        return "no guard allowed the request-pull".to_owned();
+    }
+    denied
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// A [`Guard`] with its error and output types erased, so that guards of
+/// different types can be composed using [`all`] and [`any`].
+#[derive(Clone)]
+pub struct Erased(Arc<dyn Guard<Error = Denied, Output = String> + Send + Sync>);
+
+pub fn erase<G>(guard: G) -> Erased
+where
+    G: Guard + Send + Sync + 'static,
+{
+    Erased(Arc::new(Eraser(guard)))
+}
+
+struct Eraser<G>(G);
+
+impl<G: Guard> Guard for Eraser<G> {
+    type Error = Denied;
+    type Output = String;
+
+    fn guard(&self, peer: &PeerId, urn: &Urn) -> Result<Self::Output, Self::Error> {
+        self.0
+            .guard(peer, urn)
+            .map(|output| output.to_string())
+            .map_err(|err| {
+                if self.0.is_internal(&err) {
+                    Denied::Internal(Box::new(err))
+                } else {
+                    Denied::One(Box::new(err))
+                }
+            })
+    }
+
+    fn is_internal(&self, error: &Self::Error) -> bool {
+        error.is_internal()
+    }
+}
+
+impl Guard for Erased {
+    type Error = Denied;
+    type Output = String;
+
+    fn guard(&self, peer: &PeerId, urn: &Urn) -> Result<Self::Output, Self::Error> {
+        self.0.guard(peer, urn)
+    }
+
+    fn is_internal(&self, error: &Self::Error) -> bool {
+        error.is_internal()
+    }
+}
+
+/// A [`Guard`] which allows a request-pull only if all of its guards do, see
+/// [`all`].
+#[derive(Clone)]
+pub struct All(Vec<Erased>);
+
+/// Allow a request-pull only if all of the `guards` allow it.
+///
+/// The `guards` are consulted in order, stopping at the first one which denies
+/// the request, so any effects of the later guards are not run.
+pub fn all(guards: Vec<Erased>) -> All {
+    All(guards)
+}
+
+impl Guard for All {
+    type Error = Denied;
+    type Output = String;
+
+    fn guard(&self, peer: &PeerId, urn: &Urn) -> Result<Self::Output, Self::Error> {
+        let outputs = self
+            .0
+            .iter()
+            .map(|guard| guard.guard(peer, urn))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(outputs.join("\n"))
+    }
+
+    fn is_internal(&self, error: &Self::Error) -> bool {
+        error.is_internal()
+    }
+}
+
+/// A [`Guard`] which allows a request-pull if any of its guards does, see
+/// [`any`].
+#[derive(Clone)]
+pub struct Any(Vec<Erased>);
+
+/// Allow a request-pull if any of the `guards` allows it.
+///
+/// The `guards` are consulted in order, stopping at the first one which allows
+/// the request. If none do, all of their errors are reported.
+pub fn any(guards: Vec<Erased>) -> Any {
+    Any(guards)
+}
+
+impl Guard for Any {
+    type Error = Denied;
+    type Output = String;
+
+    fn guard(&self, peer: &PeerId, urn: &Urn) -> Result<Self::Output, Self::Error> {
+        let mut denied = Vec::with_capacity(self.0.len());
+        for guard in &self.0 {
+            match guard.guard(peer, urn) {
+                Ok(output) => return Ok(output),
+                Err(err) => denied.push(err),
+            }
+        }
+        Err(Denied::All(denied))
+    }
+
+    fn is_internal(&self, error: &Self::Error) -> bool {
+        error.is_internal()
+    }
+}
@@ -51,6 +51,11 @@ pub struct Request {
 pub struct Error {
     #[n(0)]
     pub message: String,
+    /// Machine-readable classification of the error.
+    ///
+    /// `None` if the responder predates error codes.
+    #[n(1)]
+    pub code: Option<ErrorCode>,
 }
 
 impl fmt::Display for Error {
@@ -61,6 +66,75 @@ impl fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
+/// The kind of a request-pull [`Error`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The request could not be decoded.
+    DecodeFailed,
+
+    /// Some unspecified internal error occurred.
+    Internal,
+
+    /// Replicating from the requester failed.
+    Replication,
+
+    /// The requester is not authorised to request-pull the URN, as
+    /// determined by the responder's [`super::Guard`].
+    Unauthorized,
+
+    /// The responder is serving too many request-pulls and can not queue
+    /// any more. The request may be retried later.
+    Busy,
+
+    /// Catch-all for unknown error codes (forwards-compatibility).
+    ///
+    /// This is for decoding, **do not** construct this variant.
+    Unknown(u8),
+}
+
+impl ErrorCode {
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::DecodeFailed => 0,
+            Self::Internal => 1,
+            Self::Replication => 2,
+            Self::Unauthorized => 3,
+            Self::Busy => 4,
+            Self::Unknown(n) => *n,
+        }
+    }
+}
+
+impl From<u8> for ErrorCode {
+    fn from(n: u8) -> Self {
+        match n {
+            0 => Self::DecodeFailed,
+            1 => Self::Internal,
+            2 => Self::Replication,
+            3 => Self::Unauthorized,
+            4 => Self::Busy,
+            x => Self::Unknown(x),
+        }
+    }
+}
+
+impl minicbor::Encode for ErrorCode {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.u8(self.code())?;
+        Ok(())
+    }
+}
+
+impl<'b> minicbor::Decode<'b> for ErrorCode {
+    fn decode(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        d.u8().map(Self::from)
+    }
+}
+
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq, Encode, Decode)]
 #[cbor(array)]
 pub struct Success {
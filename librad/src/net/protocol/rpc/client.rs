@@ -1,18 +1,20 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::{net::SocketAddr, sync::Arc};
+use std::{iter, net::SocketAddr, sync::Arc};
 
 use crypto::Signer;
+use data::BoundedVec;
 
-use futures::TryFutureExt;
+use futures::{future, FutureExt as _, TryFutureExt};
 
 use link_async::Spawner;
 
 use crate::{
     git::{self, identities::local::LocalIdentity, Urn},
     net::{
-        quic::ConnectPeer,
+        protocol::{broadcast, gossip, io::send_rpc, membership, PeerAdvertisement, PeerInfo},
+        quic::{self, ConnectPeer, ConnectionId, Ingress},
         replication::{self, Replication},
     },
     paths::Paths,
@@ -21,6 +23,7 @@ use crate::{
 
 pub mod config;
 pub use config::Config;
+mod connections;
 pub mod error;
 
 mod interrogation;
@@ -30,6 +33,20 @@ pub use request_pull::RequestPull;
 
 mod streams;
 
+/// The phases of a replication, as reported by
+/// [`Client::replicate_with_progress`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplicationProgress {
+    /// Obtaining a connection to `peer`.
+    Connecting { peer: PeerId },
+    /// Connected, negotiating and fetching the refs of `urn`.
+    Fetching { urn: Urn },
+    /// The fetch completed, creating or updating `updated` refs.
+    Fetched { updated: usize },
+    /// The replication resulted in `tracked` new peers or URNs being tracked.
+    Tracked { tracked: usize },
+}
+
 #[derive(Clone)]
 pub struct Client<Signer, Endpoint: Clone + Send + Sync> {
     config: Config<Signer>,
@@ -39,6 +56,7 @@ pub struct Client<Signer, Endpoint: Clone + Send + Sync> {
     endpoint: Endpoint,
     repl: Replication,
     user_store: git::storage::Pool<git::storage::Storage>,
+    connections: connections::Pool,
 }
 
 impl<S, E: Clone + Send + Sync> Client<S, E>
@@ -50,6 +68,7 @@ where
         let local_id = PeerId::from_signer(&config.signer);
         let user_store = config.storage();
         let repl = Replication::new(&paths, config.replication)?;
+        let connections = connections::Pool::new(config.connections.idle_timeout);
 
         Ok(Self {
             config,
@@ -59,6 +78,7 @@ where
             endpoint,
             repl,
             user_store,
+            connections,
         })
     }
 }
@@ -81,21 +101,48 @@ where
         from: impl Into<(PeerId, Vec<SocketAddr>)>,
         urn: Urn,
         whoami: Option<LocalIdentity>,
+    ) -> Result<replication::Success, error::Replicate> {
+        self.replicate_with_progress(from, urn, whoami, |_| {})
+            .await
+    }
+
+    /// Like [`Client::replicate`], but report the phases of the replication
+    /// to `on_progress` as they happen.
+    ///
+    /// See [`ReplicationProgress`] for the phases reported.
+    pub async fn replicate_with_progress(
+        &self,
+        from: impl Into<(PeerId, Vec<SocketAddr>)>,
+        urn: Urn,
+        whoami: Option<LocalIdentity>,
+        mut on_progress: impl FnMut(ReplicationProgress),
     ) -> Result<replication::Success, error::Replicate> {
         // TODO: errors
         let (remote_peer, addrs) = from.into();
-        let conn = self
-            .endpoint
-            .connect(remote_peer, addrs)
-            .await
-            .ok_or(error::NoConnection(remote_peer))?
-            .connection()
-            .clone();
+        on_progress(ReplicationProgress::Connecting { peer: remote_peer });
+        let conn = self.connect(remote_peer, addrs).await?;
         let store = self.user_store.get().await?;
-        self.repl
-            .replicate(&self.spawner, store, conn, urn, whoami)
-            .err_into()
+        on_progress(ReplicationProgress::Fetching { urn: urn.clone() });
+        let success = self
+            .repl
+            .replicate(&self.spawner, store, conn.clone(), urn, whoami)
+            .err_into::<error::Replicate>()
             .await
+            .map_err(|e| {
+                // We can't tell if the connection is at fault, so play it safe
+                self.connections.evict(&conn);
+                e
+            })?;
+        on_progress(ReplicationProgress::Fetched {
+            updated: success.updated_refs().len(),
+        });
+        if !success.tracked().is_empty() {
+            on_progress(ReplicationProgress::Tracked {
+                tracked: success.tracked().len(),
+            });
+        }
+
+        Ok(success)
     }
 
     pub async fn request_pull(
@@ -105,14 +152,15 @@ where
     ) -> Result<RequestPull, error::RequestPull> {
         let (remote_peer, addrs) = to.into();
 
+        // Not pooled: the responder fetches from us over this connection, so
+        // we need its incoming streams.
         let ingress = self
-            .endpoint
-            .connect(remote_peer, addrs)
+            .connect_fresh(remote_peer, addrs)
             .await
             .ok_or(error::NoConnection(remote_peer))?;
         let (conn, incoming) = match ingress {
-            crate::net::quic::Ingress::Remote(conn) => (conn, None),
-            crate::net::quic::Ingress::Local { conn, streams } => (conn, Some(streams)),
+            Ingress::Remote(conn) => (conn, None),
+            Ingress::Local { conn, streams } => (conn, Some(streams)),
         };
 
         RequestPull::new(conn, incoming, urn, self.paths.clone()).await
@@ -123,16 +171,148 @@ where
         from: impl Into<(PeerId, Vec<SocketAddr>)>,
     ) -> Result<Interrogation, error::NoConnection> {
         let (remote_peer, addrs) = from.into();
-        let ingress = self
-            .endpoint
-            .connect(remote_peer, addrs)
+        let conn = self.connect(remote_peer, addrs.clone()).await?;
+        let connect: interrogation::Connect = {
+            let client = self.clone();
+            Arc::new(move || {
+                let client = client.clone();
+                let addrs = addrs.clone();
+                async move { client.connect(remote_peer, addrs).await }.boxed()
+            })
+        };
+
+        Ok(Interrogation::new(
+            remote_peer,
+            conn,
+            self.connections.clone(),
+            connect,
+        ))
+    }
+
+    /// Announce that `urn` is at `rev` to each of the `seeds`, by sending them
+    /// a gossip `Have`.
+    ///
+    /// Seeds only accept gossip from members of their membership view, so a
+    /// `Neighbour` request is sent ahead of the announcement. Seeds do not
+    /// acknowledge a `Neighbour` they accept, so there is nothing to wait for
+    /// before sending the announcement, and it is dropped as unsolicited if:
+    ///
+    /// * the seed's active view is full, and it refuses the request
+    /// * the announcement, which is sent on a separate stream, is processed by
+    ///   the seed before the request
+    ///
+    /// In either case the seed replies with a `Disconnect`, which is not
+    /// observed here. The results returned only report whether the messages
+    /// could be sent to each seed, the announcement is best-effort.
+    pub async fn announce<Seed>(
+        &self,
+        seeds: impl IntoIterator<Item = Seed>,
+        urn: Urn,
+        rev: Option<gossip::Rev>,
+    ) -> Vec<(PeerId, Result<(), error::Announce>)>
+    where
+        Seed: Into<(PeerId, Vec<SocketAddr>)>,
+    {
+        let payload = gossip::Payload {
+            urn,
+            rev,
+            origin: None,
+            previous: None,
+        };
+        future::join_all(seeds.into_iter().map(|seed| {
+            let (remote_peer, addrs) = seed.into();
+            let payload = payload.clone();
+            async move {
+                let res = self.announce_to(remote_peer, addrs, payload).await;
+                if let Err(e) = &res {
+                    tracing::warn!(peer = %remote_peer, err = %e, "failed to announce");
+                }
+                (remote_peer, res)
+            }
+        }))
+        .await
+    }
+
+    async fn announce_to(
+        &self,
+        peer: PeerId,
+        addrs: Vec<SocketAddr>,
+        payload: gossip::Payload,
+    ) -> Result<(), error::Announce> {
+        let conn = self.connect(peer, addrs).await?;
+        let info = PeerAdvertisement {
+            listen_addrs: BoundedVec::from(iter::empty()),
+            capabilities: Default::default(),
+        };
+        let origin = PeerInfo {
+            peer_id: self.local_id,
+            advertised_info: info.clone(),
+            seen_addrs: BoundedVec::from(iter::empty()),
+        };
+        let neighbour = membership::Message::Neighbour {
+            info,
+            prio: membership::Priority::Normal,
+        };
+        let sent = async {
+            send_rpc::<_, ()>(&conn, neighbour).await?;
+            send_rpc(&conn, broadcast::Message::have(origin, payload)).await
+        }
+        .await;
+        if sent.is_err() {
+            self.connections.evict(&conn);
+        }
+        Ok(sent?)
+    }
+
+    /// The ID of the connection to `peer` which is kept for reuse, if any.
+    ///
+    /// See [`config::Connections`].
+    pub fn pooled_connection(&self, peer: &PeerId) -> Option<ConnectionId> {
+        self.connections.get(peer).map(|conn| conn.id())
+    }
+
+    /// Close the pooled connection to `peer`, if any, as if it broke.
+    #[cfg(feature = "test")]
+    pub fn close_pooled_connection(&self, peer: &PeerId) {
+        if let Some(conn) = self.connections.get(peer) {
+            conn.close(crate::net::connection::CloseReason::ConnectionError)
+        }
+    }
+
+    /// Obtain a connection to `peer`, reusing a pooled one if possible.
+    async fn connect(
+        &self,
+        peer: PeerId,
+        addrs: Vec<SocketAddr>,
+    ) -> Result<quic::Connection, error::NoConnection> {
+        if let Some(conn) = self.connections.get(&peer) {
+            return Ok(conn);
+        }
+
+        let conn = self
+            .connect_fresh(peer, addrs)
             .await
-            .ok_or(error::NoConnection(remote_peer))?;
+            .ok_or(error::NoConnection(peer))?
+            .connection()
+            .clone();
+        self.connections.insert(conn.clone());
+        Ok(conn)
+    }
 
-        Ok(Interrogation {
-            peer: remote_peer,
-            conn: ingress.connection().clone(),
-        })
+    /// Establish a new connection to `peer`, attempting the `addrs` according
+    /// to [`config::AddrFamily`].
+    async fn connect_fresh(
+        &self,
+        peer: PeerId,
+        addrs: Vec<SocketAddr>,
+    ) -> Option<Ingress<'static>> {
+        let (addrs, fallback) = self.config.connections.family.attempts(addrs);
+        let ingress = self.endpoint.connect(peer, addrs).await;
+        if ingress.is_none() && !fallback.is_empty() {
+            tracing::debug!(peer = %peer, "falling back to addresses of the other family");
+            return self.endpoint.connect(peer, fallback).await;
+        }
+        ingress
     }
 
     /// Borrow a [`git::storage::Storage`] from the pool, and run a blocking
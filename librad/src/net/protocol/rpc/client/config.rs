@@ -1,6 +1,8 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::{fmt, net::SocketAddr, str::FromStr, time::Duration};
+
 use crate::{
     crypto::Signer,
     git::{
@@ -18,6 +20,7 @@ pub struct Config<Signer> {
     pub replication: replication::Config,
     pub user_storage: Storage,
     pub network: Network,
+    pub connections: Connections,
 }
 
 impl<S: Clone + Signer> Config<S> {
@@ -44,6 +47,104 @@ impl<S, G> From<peer::Config<S, G>> for Config<S> {
             replication: config.protocol.replication,
             user_storage: UserStorage::from(config.storage.user).into(),
             network: config.protocol.network,
+            connections: Connections::default(),
+        }
+    }
+}
+
+/// Settings for reusing connections across operations of a
+/// [`super::Client`].
+///
+/// A connection to a peer is kept open after an operation completes, and
+/// reused by subsequent operations against the same peer, unless it has been
+/// idle for longer than `idle_timeout`. A zero `idle_timeout` disables reuse.
+///
+/// New connections are attempted according to `family`, see [`AddrFamily`].
+#[derive(Clone, Copy, Debug)]
+pub struct Connections {
+    pub idle_timeout: Duration,
+    pub family: AddrFamily,
+}
+
+impl Default for Connections {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::from_secs(30),
+            family: AddrFamily::default(),
+        }
+    }
+}
+
+/// Which address families to use when connecting to a peer.
+///
+/// The `Prefer` variants attempt the addresses of the given family first, and
+/// only fall back to the remaining addresses if none of those succeed. The
+/// `Only` variants skip addresses of the other family entirely, which is
+/// useful on hosts without routing for that family.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddrFamily {
+    Any,
+    PreferV4,
+    PreferV6,
+    OnlyV4,
+    OnlyV6,
+}
+
+impl AddrFamily {
+    /// Split `addrs` into the addresses to attempt first, and the addresses to
+    /// fall back to if none of the first succeed.
+    ///
+    /// The relative order of `addrs` is preserved. If there are no addresses
+    /// of a preferred family, the remaining addresses are attempted first.
+    pub fn attempts(&self, addrs: Vec<SocketAddr>) -> (Vec<SocketAddr>, Vec<SocketAddr>) {
+        let partition = |addrs: Vec<SocketAddr>| -> (Vec<_>, Vec<_>) {
+            addrs.into_iter().partition(SocketAddr::is_ipv4)
+        };
+        match self {
+            Self::Any => (addrs, vec![]),
+            Self::PreferV4 => match partition(addrs) {
+                (v4, v6) if v4.is_empty() => (v6, vec![]),
+                (v4, v6) => (v4, v6),
+            },
+            Self::PreferV6 => match partition(addrs) {
+                (v4, v6) if v6.is_empty() => (v4, vec![]),
+                (v4, v6) => (v6, v4),
+            },
+            Self::OnlyV4 => (partition(addrs).0, vec![]),
+            Self::OnlyV6 => (partition(addrs).1, vec![]),
+        }
+    }
+}
+
+impl Default for AddrFamily {
+    fn default() -> Self {
+        Self::Any
+    }
+}
+
+impl fmt::Display for AddrFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Any => "any",
+            Self::PreferV4 => "prefer-v4",
+            Self::PreferV6 => "prefer-v6",
+            Self::OnlyV4 => "v4",
+            Self::OnlyV6 => "v6",
+        })
+    }
+}
+
+impl FromStr for AddrFamily {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(Self::Any),
+            "prefer-v4" => Ok(Self::PreferV4),
+            "prefer-v6" => Ok(Self::PreferV6),
+            "v4" => Ok(Self::OnlyV4),
+            "v6" => Ok(Self::OnlyV6),
+            _ => Err("expected one of `any`, `prefer-v4`, `prefer-v6`, `v4` or `v6`"),
         }
     }
 }
@@ -0,0 +1,92 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    net::{connection::RemotePeer as _, quic},
+    PeerId,
+};
+
+struct Entry {
+    conn: quic::Connection,
+    last_used: Instant,
+}
+
+/// Connections to other peers, kept around for reuse by subsequent
+/// operations.
+///
+/// Connections which have not been used for longer than the configured idle
+/// timeout, or which are no longer open, are evicted on the next access, as
+/// are connections which are reported as broken via [`Pool::evict`].
+#[derive(Clone)]
+pub(super) struct Pool {
+    idle_timeout: Duration,
+    conns: Arc<Mutex<HashMap<PeerId, Entry>>>,
+}
+
+impl Pool {
+    pub fn new(idle_timeout: Duration) -> Self {
+        Self {
+            idle_timeout,
+            conns: Default::default(),
+        }
+    }
+
+    /// Get the pooled connection to `peer`, if there is one and it is neither
+    /// idle nor closed.
+    pub fn get(&self, peer: &PeerId) -> Option<quic::Connection> {
+        let mut conns = self.conns.lock();
+        match conns.get_mut(peer) {
+            Some(entry) if !entry.conn.is_open() => {
+                tracing::debug!(peer = %peer, "evicting closed connection");
+                conns.remove(peer);
+                None
+            },
+            Some(entry) if entry.last_used.elapsed() < self.idle_timeout => {
+                entry.last_used = Instant::now();
+                Some(entry.conn.clone())
+            },
+            Some(_) => {
+                tracing::debug!(peer = %peer, "evicting idle connection");
+                conns.remove(peer);
+                None
+            },
+            None => None,
+        }
+    }
+
+    /// Add `conn` to the pool, replacing any previous connection to the same
+    /// peer.
+    pub fn insert(&self, conn: quic::Connection) {
+        if self.idle_timeout.is_zero() {
+            return;
+        }
+        self.conns.lock().insert(
+            conn.remote_peer_id(),
+            Entry {
+                conn,
+                last_used: Instant::now(),
+            },
+        );
+    }
+
+    /// Remove `conn` from the pool, e.g. because it is broken.
+    ///
+    /// If the pool has since been updated with a different connection to the
+    /// same peer, that connection is kept.
+    pub fn evict(&self, conn: &quic::Connection) {
+        let mut conns = self.conns.lock();
+        let peer = conn.remote_peer_id();
+        if matches!(conns.get(&peer), Some(entry) if entry.conn.id() == conn.id()) {
+            tracing::debug!(peer = %peer, "evicting broken connection");
+            conns.remove(&peer);
+        }
+    }
+}
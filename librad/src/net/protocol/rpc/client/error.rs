@@ -32,6 +32,9 @@ pub enum Interrogation {
     #[error("invalid response")]
     InvalidResponse,
 
+    #[error(transparent)]
+    NoConnection(#[from] NoConnection),
+
     #[error(transparent)]
     Rpc(#[from] Box<protocol::error::Rpc<quic::BidiStream>>),
 }
@@ -92,6 +95,22 @@ impl From<storage::PoolError> for Storage {
     }
 }
 
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Announce {
+    #[error(transparent)]
+    NoConnection(#[from] NoConnection),
+
+    #[error(transparent)]
+    Rpc(#[from] Box<protocol::error::Rpc<quic::SendStream>>),
+}
+
+impl From<protocol::error::Rpc<quic::SendStream>> for Announce {
+    fn from(e: protocol::error::Rpc<quic::SendStream>) -> Self {
+        Self::Rpc(Box::new(e))
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("unable to obtain connection to {0}")]
 pub struct NoConnection(pub PeerId);
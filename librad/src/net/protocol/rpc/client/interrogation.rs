@@ -1,7 +1,17 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::net::SocketAddr;
+#[cfg(feature = "test")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
 
 use crate::{
     identities::Xor,
@@ -12,14 +22,70 @@ use crate::{
     PeerId,
 };
 
-use super::error;
+use super::{connections, error};
+
+type Cache =
+    HashMap<interrogation::Request, (Instant, interrogation::Response<'static, SocketAddr>)>;
 
+/// Obtain a new connection to the interrogated peer.
+pub(super) type Connect = Arc<
+    dyn Fn() -> BoxFuture<'static, Result<quic::Connection, error::NoConnection>> + Send + Sync,
+>;
+
+/// A connection to a peer over which any number of interrogation requests
+/// can be made.
+///
+/// Responses can optionally be cached for a short while, so that repeatedly
+/// asking the same question (e.g. for the [`PeerAdvertisement`]) does not
+/// incur a round trip each time. Caching is disabled by default, see
+/// [`Interrogation::cache_for`].
 pub struct Interrogation {
-    pub(super) peer: PeerId,
-    pub(super) conn: quic::Connection,
+    peer: PeerId,
+    conn: Mutex<quic::Connection>,
+    connections: connections::Pool,
+    connect: Connect,
+    ttl: Duration,
+    cache: Mutex<Cache>,
+    #[cfg(feature = "test")]
+    round_trips: AtomicUsize,
 }
 
 impl Interrogation {
+    pub(super) fn new(
+        peer: PeerId,
+        conn: quic::Connection,
+        connections: connections::Pool,
+        connect: Connect,
+    ) -> Self {
+        Self {
+            peer,
+            conn: Mutex::new(conn),
+            connections,
+            connect,
+            ttl: Duration::ZERO,
+            cache: Mutex::new(HashMap::new()),
+            #[cfg(feature = "test")]
+            round_trips: AtomicUsize::new(0),
+        }
+    }
+
+    /// Cache successful responses for `ttl`.
+    ///
+    /// Identical requests made within `ttl` of the first are answered from the
+    /// cache instead of the network. Error responses are never cached. A `ttl`
+    /// of [`Duration::ZERO`], the default, disables caching.
+    pub fn cache_for(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The number of requests which were actually sent to the interrogated
+    /// peer, i.e. not answered from the cache.
+    #[cfg(feature = "test")]
+    pub fn round_trips(&self) -> usize {
+        self.round_trips.load(Ordering::Relaxed)
+    }
+
     /// Ask the interrogated peer to send its [`PeerAdvertisement`].
     pub async fn peer_advertisement(
         &self,
@@ -65,13 +131,96 @@ impl Interrogation {
             })
     }
 
+    /// Ask the interrogated peer to send information about the software it is
+    /// running, see [`interrogation::NodeInfo`].
+    pub async fn node_info(&self) -> Result<interrogation::NodeInfo, error::Interrogation> {
+        use interrogation::{Request, Response};
+
+        self.request(Request::GetNodeInfo)
+            .await
+            .and_then(|resp| match resp {
+                Response::NodeInfo(info) => Ok(info),
+                Response::Error(e) => Err(error::Interrogation::ErrorResponse(e)),
+                _ => Err(error::Interrogation::InvalidResponse),
+            })
+    }
+
+    /// Ask the interrogated peer to send the peers it knows about, along with
+    /// their addresses.
+    ///
+    /// This can be used to discover further seeds from a known one.
+    pub async fn peers(&self) -> Result<Vec<(PeerId, Vec<SocketAddr>)>, error::Interrogation> {
+        use interrogation::{Request, Response};
+
+        self.request(Request::GetPeers)
+            .await
+            .and_then(|resp| match resp {
+                Response::Peers(peers) => Ok(peers.into_iter().map(Into::into).collect()),
+                Response::Error(e) => Err(error::Interrogation::ErrorResponse(e)),
+                _ => Err(error::Interrogation::InvalidResponse),
+            })
+    }
+
     async fn request(
         &self,
         request: interrogation::Request,
     ) -> Result<interrogation::Response<'static, SocketAddr>, error::Interrogation> {
-        match io::send::single_response(&self.conn, request, interrogation::FRAMED_BUFSIZ).await {
+        if let Some(resp) = self.cached(&request) {
+            return Ok(resp);
+        }
+
+        let conn = self.conn.lock().clone();
+        let resp = match self.round_trip(&conn, request).await {
+            // The connection may have broken since it was obtained, so evict
+            // it and retry once over a fresh one.
+            Err(error::Interrogation::Rpc(e)) => {
+                tracing::debug!(peer = %self.peer, err = %e, "interrogation failed, reconnecting");
+                self.connections.evict(&conn);
+                let conn = (self.connect)().await?;
+                *self.conn.lock() = conn.clone();
+                self.round_trip(&conn, request).await
+            },
+            resp => resp,
+        }?;
+
+        if !self.ttl.is_zero() && !matches!(resp, interrogation::Response::Error(_)) {
+            self.cache
+                .lock()
+                .insert(request, (Instant::now(), resp.clone()));
+        }
+
+        Ok(resp)
+    }
+
+    async fn round_trip(
+        &self,
+        conn: &quic::Connection,
+        request: interrogation::Request,
+    ) -> Result<interrogation::Response<'static, SocketAddr>, error::Interrogation> {
+        #[cfg(feature = "test")]
+        self.round_trips.fetch_add(1, Ordering::Relaxed);
+        match io::send::single_response(conn, request, interrogation::FRAMED_BUFSIZ).await {
             Err(e) => Err(e.into()),
             Ok(resp) => resp.ok_or(error::Interrogation::NoResponse(self.peer)),
         }
     }
+
+    fn cached(
+        &self,
+        request: &interrogation::Request,
+    ) -> Option<interrogation::Response<'static, SocketAddr>> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+
+        let mut cache = self.cache.lock();
+        match cache.get(request) {
+            Some((at, resp)) if at.elapsed() < self.ttl => Some(resp.clone()),
+            Some(_) => {
+                cache.remove(request);
+                None
+            },
+            None => None,
+        }
+    }
 }
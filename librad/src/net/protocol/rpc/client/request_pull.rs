@@ -9,16 +9,19 @@ use std::{
 
 use futures::{
     self,
-    future::{self, BoxFuture},
+    future::{self, AbortHandle, Abortable, BoxFuture},
     stream::BoxStream,
     FutureExt as _,
     Stream,
     StreamExt as _,
 };
 
+use tracing::Instrument as _;
+
 use crate::{
     git::Urn,
     net::{
+        connection::RemotePeer as _,
         protocol::{self, request_pull},
         quic,
     },
@@ -36,9 +39,17 @@ use super::{error, streams};
 ///   * A successful response, [`request_pull::Response::Success`]
 ///   * An error response, [`request_pull::Response::Error`]
 ///   * An error,  [`error::RequestPull`]
+///
+/// The request-pull can be aborted using [`RequestPull::cancel`], or an
+/// [`AbortHandle`] obtained via [`RequestPull::abort_handle`].
 pub struct RequestPull {
     resp: BoxStream<'static, Result<request_pull::Response, error::RequestPull>>,
-    repl: BoxFuture<'static, Result<(), error::Incoming>>,
+    // `None` once cancelled
+    repl: Option<Abortable<BoxFuture<'static, Result<(), error::Incoming>>>>,
+    abort: AbortHandle,
+    // Carries the `urn` and `peer` of this request-pull, the outcome is
+    // recorded in it.
+    span: tracing::Span,
 }
 
 trait AssertSend: Send {}
@@ -51,12 +62,22 @@ impl RequestPull {
         urn: Urn,
         paths: Arc<Paths>,
     ) -> Result<Self, error::RequestPull> {
+        let span = tracing::info_span!(
+            "request_pull",
+            urn = %urn,
+            peer = %conn.remote_peer_id()
+        );
         let resp = protocol::io::send::multi_response(
             &conn,
             protocol::request_pull::Request { urn },
             protocol::request_pull::FRAMED_BUFSIZ,
         )
-        .await?
+        .instrument(span.clone())
+        .await
+        .map_err(|e| {
+            span.in_scope(|| tracing::warn!(err = %e, "request-pull failed"));
+            e
+        })?
         .map(|i| i.map_err(error::RequestPull::from))
         .boxed();
 
@@ -65,7 +86,30 @@ impl RequestPull {
             None => future::pending().boxed(),
         };
 
-        Ok(Self { resp, repl })
+        let (abort, registration) = AbortHandle::new_pair();
+        let repl = Some(Abortable::new(repl, registration));
+
+        Ok(Self {
+            resp,
+            repl,
+            abort,
+            span,
+        })
+    }
+
+    /// Cancel the request-pull.
+    ///
+    /// Any replication in progress is aborted, and the next item of the stream
+    /// will be [`error::RequestPull::Cancelled`], after which the stream is
+    /// finished.
+    pub fn cancel(&self) {
+        self.abort.abort()
+    }
+
+    /// Obtain a handle which can be used to [`RequestPull::cancel`] while the
+    /// stream is being polled elsewhere.
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.abort.clone()
     }
 }
 
@@ -73,10 +117,49 @@ impl Stream for RequestPull {
     type Item = Result<request_pull::Response, error::RequestPull>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if let Poll::Ready(Err(e)) = self.repl.poll_unpin(cx) {
-            return Poll::Ready(Some(Err(e.into())));
+        let next = self.as_mut().poll_responses(cx);
+        if let Poll::Ready(Some(item)) = &next {
+            self.span.in_scope(|| record(item));
+        }
+        next
+    }
+}
+
+impl RequestPull {
+    fn poll_responses(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<<Self as Stream>::Item>> {
+        let repl = match self.repl.as_mut() {
+            None => return Poll::Ready(None),
+            Some(repl) => repl,
+        };
+        match repl.poll_unpin(cx) {
+            Poll::Ready(Ok(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+            Poll::Ready(Err(future::Aborted)) => {
+                // Drop the in-flight work, so any outstanding streams are closed
+                self.repl = None;
+                self.resp = futures::stream::empty().boxed();
+                return Poll::Ready(Some(Err(error::RequestPull::Cancelled)));
+            },
+            _ => {},
         }
 
         self.resp.poll_next_unpin(cx)
     }
 }
+
+fn record(item: &Result<request_pull::Response, error::RequestPull>) {
+    match item {
+        Ok(request_pull::Response::Success(success)) => tracing::info!(
+            updated = success.refs.len(),
+            pruned = success.pruned.len(),
+            "request-pull succeeded"
+        ),
+        Ok(request_pull::Response::Error(e)) => {
+            tracing::warn!(err = %e.message, "request-pull failed")
+        },
+        Ok(request_pull::Response::Progress(_)) => {},
+        Err(e) => tracing::warn!(err = %e, "request-pull failed"),
+    }
+}
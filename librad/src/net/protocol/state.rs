@@ -3,7 +3,7 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{net::SocketAddr, ops::Deref, sync::Arc};
+use std::{net::SocketAddr, ops::Deref, sync::Arc, time::Instant};
 
 use link_async::Spawner;
 use nonzero_ext::nonzero;
@@ -34,6 +34,8 @@ use crate::{
 #[derive(Clone)]
 pub(super) struct StateConfig {
     pub paths: Arc<Paths>,
+    /// When the protocol instance was bound, to report its uptime.
+    pub started: Instant,
 }
 
 /// Runtime state of a protocol instance.
@@ -132,6 +134,8 @@ pub struct Quota {
     pub membership: rate_limit::Quota,
     /// See [`StorageQuota`].
     pub storage: StorageQuota,
+    /// See [`request_pull::Limits`].
+    pub request_pull: request_pull::Limits,
 }
 
 impl Default for Quota {
@@ -140,6 +144,7 @@ impl Default for Quota {
             gossip: GossipQuota::default(),
             membership: rate_limit::Quota::per_second(nonzero!(1u32)).allow_burst(nonzero!(10u32)),
             storage: StorageQuota::default(),
+            request_pull: request_pull::Limits::default(),
         }
     }
 }
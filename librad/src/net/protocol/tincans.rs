@@ -194,11 +194,90 @@ impl TinCans {
         rx.await.ok().flatten().map(Connected)
     }
 
+    /// Drop all connections to `peer`.
+    ///
+    /// Returns once the protocol has processed the request, or immediately if
+    /// the protocol is not running.
+    pub async fn disconnect(&self, peer: PeerId) {
+        use event::downstream::Disconnect;
+
+        let (tx, rx) = replier();
+        if self
+            .downstream
+            .send(Downstream::Disconnect(Disconnect { peer, reply: tx }))
+            .is_err()
+        {
+            return;
+        }
+
+        rx.await.ok();
+    }
+
+    /// Connect to each of `peers` concurrently, returning as soon as `quorum`
+    /// connections have been established.
+    ///
+    /// The outcome is reported per peer, in the order `peers` were given. A
+    /// peer is reported as `None` if the connection attempt failed, or if it
+    /// was still outstanding when the quorum was reached.
+    pub async fn connect_many(
+        &self,
+        peers: impl IntoIterator<Item = (PeerId, Vec<SocketAddr>)>,
+        quorum: usize,
+    ) -> Vec<(PeerId, Option<Connected>)> {
+        use event::downstream::ConnectMany;
+
+        let peers = peers.into_iter().collect::<Vec<_>>();
+        let mut results = peers
+            .iter()
+            .map(|(peer, _)| (*peer, None))
+            .collect::<Vec<_>>();
+        if peers.is_empty() {
+            return results;
+        }
+
+        // All attempts are made by a single event, rather than one per peer,
+        // so that many peers can't overflow the downstream channel
+        let (tx, mut rx) = multi_replier();
+        if self
+            .downstream
+            .send(Downstream::ConnectMany(ConnectMany { peers, reply: tx }))
+            .is_err()
+        {
+            return results;
+        }
+
+        let mut connected = 0;
+        while connected < quorum {
+            match rx.recv().await {
+                Some((ix, conn)) => {
+                    if conn.is_some() {
+                        connected += 1;
+                    }
+                    results[ix].1 = conn.map(Connected);
+                },
+                None => break,
+            }
+        }
+
+        results
+    }
+
     pub fn subscribe(&self) -> impl futures::Stream<Item = Result<event::Upstream, RecvError>> {
         let mut r = self.upstream.subscribe();
         async_stream::stream! { loop { yield r.recv().await } }
     }
 
+    /// Like [`TinCans::subscribe`], but only yields the events pertaining to
+    /// `urn`.
+    ///
+    /// See [`event::upstream::scoped`] for which events are included.
+    pub fn subscribe_urn(
+        &self,
+        urn: Urn,
+    ) -> impl futures::Stream<Item = Result<event::Upstream, RecvError>> {
+        event::upstream::scoped(urn, self.subscribe())
+    }
+
     pub(crate) fn emit(&self, evt: impl Into<event::Upstream>) {
         self.upstream.send(evt.into()).ok();
     }
@@ -276,6 +355,36 @@ impl Interrogation {
             })
     }
 
+    /// Ask the interrogated peer to send information about the software it is
+    /// running, see [`interrogation::NodeInfo`].
+    pub async fn node_info(&self) -> Result<interrogation::NodeInfo, error::Interrogation> {
+        use interrogation::{Request, Response};
+
+        self.request(Request::GetNodeInfo)
+            .await
+            .and_then(|resp| match resp {
+                Response::NodeInfo(info) => Ok(info),
+                Response::Error(e) => Err(error::Interrogation::ErrorResponse(e)),
+                _ => Err(error::Interrogation::InvalidResponse),
+            })
+    }
+
+    /// Ask the interrogated peer to send the peers it knows about, along with
+    /// their addresses.
+    ///
+    /// This can be used to discover further seeds from a known one.
+    pub async fn peers(&self) -> Result<Vec<(PeerId, Vec<SocketAddr>)>, error::Interrogation> {
+        use interrogation::{Request, Response};
+
+        self.request(Request::GetPeers)
+            .await
+            .and_then(|resp| match resp {
+                Response::Peers(peers) => Ok(peers.into_iter().map(Into::into).collect()),
+                Response::Error(e) => Err(error::Interrogation::ErrorResponse(e)),
+                _ => Err(error::Interrogation::InvalidResponse),
+            })
+    }
+
     async fn request(
         &self,
         request: interrogation::Request,
@@ -247,6 +247,18 @@ impl Connection {
         Ok(MutexGuard::map(lck, |s| s.as_mut().unwrap()))
     }
 
+    /// Whether the connection is still open, as far as can be told without
+    /// using it.
+    ///
+    /// A connection is considered closed once it was closed explicitly, failed,
+    /// or was reaped for being idle. Untracked connections are always assumed
+    /// to be open.
+    pub fn is_open(&self) -> bool {
+        self.track
+            .as_ref()
+            .map_or(true, |track| track.is_tracked(&self.id()))
+    }
+
     pub fn close(&self, reason: CloseReason) {
         if let Some(track) = self.track.as_ref() {
             track.disconnect(&self.id(), reason)
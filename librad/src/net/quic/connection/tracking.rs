@@ -152,6 +152,12 @@ impl Conntrack {
         }
     }
 
+    /// Whether the given connection is tracked, i.e. it was neither
+    /// disconnected nor reaped.
+    pub fn is_tracked(&self, conn: &ConnectionId) -> bool {
+        self.connections.contains_key(conn)
+    }
+
     /// Indicate activity on the given connection.
     ///
     /// Will prevent the connection from being dropped due to inactivity.
@@ -291,7 +291,8 @@ impl ConnectPeer for SendOnly {
         Addrs: IntoIterator<Item = SocketAddr> + Send,
         Addrs::IntoIter: Send,
     {
-        if peer == self.peer_id {
+        let addrs = addrs.into_iter().collect::<Vec<_>>();
+        if peer == self.peer_id || addrs.is_empty() {
             return None;
         }
 
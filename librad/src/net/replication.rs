@@ -21,7 +21,7 @@ use crate::{
     PeerId,
 };
 
-pub use link_replication::FetchLimit;
+pub use link_replication::{DataPolicy, FetchLimit};
 
 mod context;
 use context::Context;
@@ -55,6 +55,14 @@ pub struct Config {
     pub limit: FetchLimit,
     pub slots: usize,
     pub wait_slot: Duration,
+    /// If [`DataPolicy::Deny`], only the identity refs (`rad/id`, `rad/self`,
+    /// `rad/signed_refs`, etc.) are fetched, regardless of tracking
+    /// configuration.
+    pub data: DataPolicy,
+    /// If `false`, remote-tracking refs which are no longer signed by their
+    /// peer are kept rather than deleted, and reported in
+    /// [`Success::would_prune`].
+    pub prune: bool,
 }
 
 impl Default for Config {
@@ -63,6 +71,8 @@ impl Default for Config {
             limit: FetchLimit::default(),
             slots: 4,
             wait_slot: Duration::from_secs(20),
+            data: DataPolicy::Allow,
+            prune: true,
         }
     }
 }
@@ -102,6 +112,8 @@ impl Replication {
     {
         let slot = timeout(self.config.wait_slot, self.slots.acquire_arc()).await?;
         let limit = self.config.limit;
+        let data = self.config.data;
+        let prune = self.config.prune;
         let odb = self.odb.clone();
         let rdb = self.rdb.clone();
         let res = spawner
@@ -126,6 +138,8 @@ impl Replication {
                     store,
                     refdb,
                     net,
+                    data,
+                    prune,
                 };
                 let whoami = whoami.map(|id| link_replication::LocalIdentity {
                     tip: id.content_id.into(),
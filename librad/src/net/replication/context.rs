@@ -14,7 +14,7 @@ use std::{
 
 use data::NonEmpty;
 use either::{Either, Either::*};
-use git_ref_format::RefString;
+use git_ref_format::{RefStr, RefString};
 use link_git::protocol::Ref;
 use link_replication::{
     io,
@@ -31,6 +31,7 @@ use link_replication::{
     Net,
     ObjectId,
     Odb,
+    RefFilter,
     RefScan,
     Refdb,
     SignedRefs,
@@ -125,6 +126,8 @@ pub struct Context<'a> {
     pub(super) store: &'a Storage,
     pub(super) refdb: io::Refdb<io::Odb>,
     pub(super) net: Network,
+    pub(super) data: link_replication::DataPolicy,
+    pub(super) prune: bool,
 }
 
 impl<'a> Context<'a> {
@@ -462,11 +465,21 @@ impl<'a> Iterator for Tracked<'a> {
     }
 }
 
+/// The [`RefFilter`] of a tracked peer's [`tracking::Config`].
+pub struct TrackedRefs(tracking::Config);
+
+impl RefFilter for TrackedRefs {
+    fn allows(&self, refname: &RefStr) -> bool {
+        self.0.allowed_refs().allows(refname)
+    }
+}
+
 #[allow(clippy::type_complexity)]
 impl<'a> Tracking for Context<'a> {
     type Urn = Urn;
 
     type Tracked = Tracked<'a>;
+    type RefFilter = TrackedRefs;
     type Updated = std::iter::Map<
         std::vec::IntoIter<tracking::batch::Updated>,
         fn(tracking::batch::Updated) -> Either<PeerId, Self::Urn>,
@@ -545,6 +558,19 @@ impl<'a> Tracking for Context<'a> {
     fn tracked(&self) -> Result<Self::Tracked, Self::TrackedError> {
         tracking::tracked(self.store, Some(&self.urn)).map(Tracked)
     }
+
+    fn ref_filter(&self, peer: &PeerId) -> Result<Option<Self::RefFilter>, Self::PolicyError> {
+        Ok(tracking::get(self.store, &self.urn, Some(*peer))?
+            .map(|tracked| TrackedRefs(tracked.config().clone())))
+    }
+
+    fn data_policy(&self) -> link_replication::DataPolicy {
+        self.data
+    }
+
+    fn prune(&self) -> bool {
+        self.prune
+    }
 }
 
 impl<'c> Refdb for Context<'c> {
@@ -12,6 +12,8 @@ use std::{
 
 use crate::paths::{project_dirs, Paths};
 
+pub mod alias;
+pub use alias::Alias;
 pub mod id;
 pub use id::ProfileId;
 
@@ -22,6 +24,12 @@ pub const LNK_PROFILE: &str = "LNK_PROFILE";
 pub enum Error {
     #[error("the profile {0} does not exist")]
     DoesNotExist(ProfileId),
+    #[error("the alias `{alias}` is already used by the profile {id}")]
+    AliasTaken { alias: Alias, id: ProfileId },
+    #[error("the alias `{0}` is the identifier of an existing profile")]
+    AliasIsProfileId(Alias),
+    #[error(transparent)]
+    Alias(#[from] alias::Error),
     #[error(transparent)]
     ProfileId(#[from] id::Error),
     #[error(transparent)]
@@ -127,6 +135,53 @@ impl Profile {
         Ok(profiles)
     }
 
+    /// Get the [`Alias`] of the profile identified by `id`, if it has one.
+    pub fn alias(home: &LnkHome, id: &ProfileId) -> Result<Option<Alias>, Error> {
+        alias::read(home, id).map_err(Error::from)
+    }
+
+    /// Set the [`Alias`] of the profile identified by `id`, replacing any
+    /// previous alias.
+    ///
+    /// This will error if the `id` does not exist under `home`, if `alias` is
+    /// the identifier of an existing profile, or if `alias` is already used
+    /// by another profile.
+    pub fn set_alias(home: &LnkHome, id: ProfileId, alias: Alias) -> Result<Self, Error> {
+        if !exists(home, &id)? {
+            return Err(Error::DoesNotExist(id));
+        }
+        if home.config()?.join(alias.as_str()).is_dir() {
+            return Err(Error::AliasIsProfileId(alias));
+        }
+        for profile in Self::list(home)? {
+            if profile.id != id && Self::alias(home, &profile.id)?.as_ref() == Some(&alias) {
+                return Err(Error::AliasTaken {
+                    alias,
+                    id: profile.id,
+                });
+            }
+        }
+        alias::write(home, &id, &alias)?;
+        Self::from_home(home, Some(id))
+    }
+
+    /// Get the `Profile` to be found under `home`, where `name` is either its
+    /// [`ProfileId`] or its [`Alias`]. If neither exists then `None` is
+    /// returned.
+    pub fn resolve(home: &LnkHome, name: &str) -> Result<Option<Self>, Error> {
+        if let Ok(id) = name.parse::<ProfileId>() {
+            if exists(home, &id)? {
+                return Self::from_home(home, Some(id)).map(Some);
+            }
+        }
+        for profile in Self::list(home)? {
+            if Self::alias(home, &profile.id)?.map_or(false, |alias| alias.as_str() == name) {
+                return Ok(Some(profile));
+            }
+        }
+        Ok(None)
+    }
+
     /// Creates a profile by loading the profile identifier and paths from
     /// the environment variables or well-known file.
     ///
@@ -0,0 +1,83 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{fmt, fs, io, path::PathBuf, str::FromStr};
+
+use thiserror::Error;
+
+use super::{LnkHome, ProfileId};
+
+const ALIAS: &str = "alias";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid profile alias: {0}")]
+    Invalid(String),
+    #[error("invalid profile alias loaded from {path}: {alias}")]
+    FromFile { alias: String, path: PathBuf },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// A human-friendly name for a [`super::Profile`], which can be used in place
+/// of its [`ProfileId`].
+///
+/// A valid alias is a valid [`ProfileId`] which does not contain whitespace.
+/// Aliases are unique amongst the profiles under a [`LnkHome`], and an alias
+/// must not be the [`ProfileId`] of an existing profile.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Alias(String);
+
+impl Alias {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn is_valid(alias: &str) -> bool {
+        !alias.contains(char::is_whitespace) && alias.parse::<ProfileId>().is_ok()
+    }
+}
+
+impl FromStr for Alias {
+    type Err = Error;
+
+    fn from_str(alias: &str) -> Result<Self, Self::Err> {
+        if Self::is_valid(alias) {
+            Ok(Self(alias.to_owned()))
+        } else {
+            Err(Error::Invalid(alias.to_owned()))
+        }
+    }
+}
+
+impl fmt::Display for Alias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Read the alias of the profile `id` from its `alias` file. If the file is
+/// not found then `None` is returned.
+pub(super) fn read(home: &LnkHome, id: &ProfileId) -> Result<Option<Alias>, Error> {
+    let path = home.config()?.join(id).join(ALIAS);
+    match fs::read_to_string(&path) {
+        Ok(content) => {
+            let alias = content.lines().next().unwrap_or("").to_owned();
+            if Alias::is_valid(&alias) {
+                Ok(Some(Alias(alias)))
+            } else {
+                Err(Error::FromFile { alias, path })
+            }
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub(super) fn write(home: &LnkHome, id: &ProfileId, alias: &Alias) -> Result<(), Error> {
+    let path = home.config()?.join(id).join(ALIAS);
+    fs::write(path, alias.as_str())?;
+    Ok(())
+}
@@ -15,6 +15,7 @@ use librad::{
         History,
         NewObjectSpec,
         TypeName,
+        UpdateMode,
         UpdateObjectSpec,
     },
     git::{
@@ -184,6 +185,7 @@ fn collab_object_crud() {
                             NewObjectSpec {
                                 history: init_history(),
                                 message: Some("first change".to_string()),
+                                client: None,
                                 typename: TYPENAME.clone(),
                             },
                         )
@@ -216,8 +218,11 @@ fn collab_object_crud() {
                             UpdateObjectSpec {
                                 typename: TYPENAME.clone(),
                                 message: Some("add first item".to_string()),
+                                client: None,
                                 object_id: id,
                                 changes: add_item(&history, "peer 1 item"),
+                                expected_tips: None,
+                                mode: UpdateMode::Append,
                             },
                         )
                         .unwrap();
@@ -247,6 +252,29 @@ fn collab_object_crud() {
             );
         }
 
+        // The change graph consists of the initial change and the update
+        {
+            let urn = proj.project.urn();
+            let id = *object.id();
+            let graph = peer1
+                .using_storage(move |s| {
+                    s.collaborative_objects(None)
+                        .changegraph_json_for_object(&urn, &TYPENAME, &id)
+                        .unwrap()
+                })
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(graph.nodes.len(), 2);
+            assert_eq!(graph.edges.len(), 1);
+            assert!(graph.nodes.iter().all(|n| n.valid_signatures));
+            assert!(graph
+                .nodes
+                .iter()
+                .any(|n| n.commit == graph.edges[0].parent));
+            assert!(graph.nodes.iter().any(|n| n.commit == graph.edges[0].child));
+        }
+
         proj.pull(peer1, peer2).await.unwrap();
 
         // Check that peer 2
@@ -291,6 +319,9 @@ fn collab_object_crud() {
                                     object_id: id,
                                     changes: add_item(&history, "peer 2 item"),
                                     message: Some("peer 2 change".to_string()),
+                                    client: None,
+                                    expected_tips: None,
+                                    mode: UpdateMode::Append,
                                 },
                             )
                             .unwrap();
@@ -64,6 +64,7 @@ fn can_replicate_from_tracking() {
         peer1
             .announce(gossip::Payload {
                 origin: None,
+                previous: None,
                 urn: proj.project.urn(),
                 rev: None,
             })
@@ -174,6 +174,7 @@ where
 
     peer.announce(gossip::Payload {
         origin: None,
+        previous: None,
         urn: project
             .urn()
             .with_path(Some(master.into_refstring().into())),
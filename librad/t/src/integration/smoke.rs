@@ -4,6 +4,8 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 mod clone;
+mod connect_many;
+mod disconnect;
 mod fetch_limit;
 mod gossip;
 mod interrogation;
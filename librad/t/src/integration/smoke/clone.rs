@@ -13,9 +13,13 @@ use librad::{
     self,
     git::{
         identities,
+        refs,
         storage::ReadOnlyStorage as _,
+        tracking,
         types::{Namespace, Reference},
     },
+    net::{protocol::rpc::client::ReplicationProgress, replication},
+    reflike,
 };
 use test_helpers::logging;
 
@@ -92,6 +96,363 @@ fn not_present() {
     })
 }
 
+#[test]
+fn reports_progress() {
+    logging::init();
+
+    let net = testnet::run(default_config()).unwrap();
+    net.enter(async {
+        let host = Host::init(net.peers().index(0)).await;
+        let leecher = net.peers().index(1);
+        let urn = host.project.project.urn();
+        let host_peer = host.peer.peer_id();
+
+        let mut phases = Vec::new();
+        leecher
+            .client()
+            .unwrap()
+            .replicate_with_progress(
+                (host_peer, host.peer.listen_addrs().to_vec()),
+                urn.clone(),
+                None,
+                |progress| phases.push(progress),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            phases.first(),
+            Some(&ReplicationProgress::Connecting { peer: host_peer })
+        );
+        assert!(phases.contains(&ReplicationProgress::Fetching { urn }));
+        assert!(phases
+            .iter()
+            .any(|p| matches!(p, ReplicationProgress::Fetched { updated } if *updated > 0)));
+    })
+}
+
+/// Replicating with [`DataPolicy::Deny`] should only fetch the identity refs.
+#[test]
+fn identity_only() {
+    logging::init();
+
+    let net = testnet::run(default_config()).unwrap();
+    net.enter(async {
+        let host = Host::init(net.peers().index(0)).await;
+        let urn = host.project.project.urn();
+        let host_peer = host.peer.peer_id();
+        let master = Reference::head(Namespace::from(&urn), None, reflike!("master"));
+        host.peer
+            .using_storage({
+                let urn = urn.clone();
+                let master = master.clone();
+                move |storage| {
+                    let repo = git2::Repository::open(storage.path()).unwrap();
+                    let tree = {
+                        let oid = repo.treebuilder(None).unwrap().write().unwrap();
+                        repo.find_tree(oid).unwrap()
+                    };
+                    let author = git2::Signature::now("The Animal", "animal@muppets.com").unwrap();
+                    repo.commit(
+                        Some(&master.to_string()),
+                        &author,
+                        &author,
+                        "Initial commit",
+                        &tree,
+                        &[],
+                    )
+                    .unwrap();
+                    refs::Refs::update(storage, &urn).unwrap();
+                }
+            })
+            .await
+            .unwrap();
+
+        let client = testnet::TestClient::with_replication(replication::Config {
+            data: replication::DataPolicy::Deny,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        client
+            .replicate(
+                (host_peer, host.peer.listen_addrs().to_vec()),
+                urn.clone(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        client
+            .using_storage(move |storage| {
+                let namespace = Namespace::from(&urn);
+                assert!(storage
+                    .has_ref(&Reference::rad_id(namespace.clone()))
+                    .unwrap());
+                assert!(storage
+                    .has_ref(&Reference::rad_self(namespace.clone(), host_peer))
+                    .unwrap());
+                assert!(storage
+                    .has_ref(&Reference::rad_signed_refs(namespace.clone(), host_peer))
+                    .unwrap());
+                assert!(
+                    !storage
+                        .has_ref(&Reference::head(namespace, host_peer, reflike!("master")))
+                        .unwrap(),
+                    "data refs should not be fetched"
+                );
+            })
+            .await
+            .unwrap();
+    })
+}
+
+/// Replicating with `prune: false` should retain refs which are no longer
+/// signed by the remote, and report them instead.
+#[test]
+fn no_prune_retains_stale_refs() {
+    logging::init();
+
+    let net = testnet::run(default_config()).unwrap();
+    net.enter(async {
+        let host = Host::init(net.peers().index(0)).await;
+        let urn = host.project.project.urn();
+        let host_peer = host.peer.peer_id();
+        let host_addrs = host.peer.listen_addrs().to_vec();
+        let stale = Reference::head(Namespace::from(&urn), None, reflike!("stale"));
+        host.peer
+            .using_storage({
+                let urn = urn.clone();
+                let stale = stale.clone();
+                move |storage| {
+                    let repo = git2::Repository::open(storage.path()).unwrap();
+                    let tree = {
+                        let oid = repo.treebuilder(None).unwrap().write().unwrap();
+                        repo.find_tree(oid).unwrap()
+                    };
+                    let author = git2::Signature::now("The Animal", "animal@muppets.com").unwrap();
+                    repo.commit(
+                        Some(&stale.to_string()),
+                        &author,
+                        &author,
+                        "Soon to be stale",
+                        &tree,
+                        &[],
+                    )
+                    .unwrap();
+                    refs::Refs::update(storage, &urn).unwrap();
+                }
+            })
+            .await
+            .unwrap();
+
+        let client = testnet::TestClient::with_replication(replication::Config {
+            prune: false,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let success = client
+            .replicate((host_peer, host_addrs.clone()), urn.clone(), None)
+            .await
+            .unwrap();
+        assert!(success.would_prune().is_empty());
+
+        host.peer
+            .using_storage({
+                let urn = urn.clone();
+                move |storage| {
+                    let repo = git2::Repository::open(storage.path()).unwrap();
+                    repo.find_reference(&stale.to_string())
+                        .unwrap()
+                        .delete()
+                        .unwrap();
+                    refs::Refs::update(storage, &urn).unwrap();
+                }
+            })
+            .await
+            .unwrap();
+
+        let success = client
+            .replicate((host_peer, host_addrs), urn.clone(), None)
+            .await
+            .unwrap();
+        assert!(success
+            .would_prune()
+            .iter()
+            .any(|update| update.refname().as_str().ends_with("heads/stale")));
+
+        client
+            .using_storage(move |storage| {
+                let stale = Reference::head(Namespace::from(&urn), host_peer, reflike!("stale"));
+                assert!(
+                    storage.has_ref(&stale).unwrap(),
+                    "stale ref should be retained"
+                );
+            })
+            .await
+            .unwrap();
+    })
+}
+
+/// Cloning should only fetch the refs of a tracked non-delegate which its
+/// tracking configuration allows.
+#[test]
+fn respects_tracked_ref_filter() {
+    logging::init();
+
+    let net = testnet::run(default_config()).unwrap();
+    net.enter(async {
+        let host = Host::init(net.peers().index(0)).await;
+        let contributor = net.peers().index(1);
+        let leecher = net.peers().index(2);
+        let urn = host.project.project.urn();
+        let host_peer = host.peer.peer_id();
+        let contributor_id = contributor.peer_id();
+
+        contributor
+            .client()
+            .unwrap()
+            .replicate(
+                (host_peer, host.peer.listen_addrs().to_vec()),
+                urn.clone(),
+                None,
+            )
+            .await
+            .unwrap();
+        let (head, cob) = contributor
+            .using_storage({
+                let urn = urn.clone();
+                move |storage| {
+                    let repo = git2::Repository::open(storage.path()).unwrap();
+                    let tree = {
+                        let oid = repo.treebuilder(None).unwrap().write().unwrap();
+                        repo.find_tree(oid).unwrap()
+                    };
+                    let author = git2::Signature::now("The Animal", "animal@muppets.com").unwrap();
+                    let head = Reference::head(Namespace::from(&urn), None, reflike!("contrib"));
+                    let oid = repo
+                        .commit(
+                            Some(&head.to_string()),
+                            &author,
+                            &author,
+                            "Contribution",
+                            &tree,
+                            &[],
+                        )
+                        .unwrap();
+                    let cob = Reference::rad_collaborative_object(
+                        Namespace::from(&urn),
+                        None,
+                        "xyz.radicle.test".parse().unwrap(),
+                        oid.into(),
+                    );
+                    repo.reference(&cob.to_string(), oid, false, "cob").unwrap();
+                    refs::Refs::update(storage, &urn).unwrap();
+                    (head, cob)
+                }
+            })
+            .await
+            .unwrap();
+
+        host.peer
+            .using_storage({
+                let urn = urn.clone();
+                move |storage| {
+                    assert!(tracking::track(
+                        storage,
+                        &urn,
+                        Some(contributor_id),
+                        tracking::Config::default(),
+                        tracking::policy::Track::Any,
+                    )
+                    .unwrap()
+                    .is_ok());
+                }
+            })
+            .await
+            .unwrap();
+        host.peer
+            .client()
+            .unwrap()
+            .replicate(
+                (contributor_id, contributor.listen_addrs().to_vec()),
+                urn.clone(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        leecher
+            .using_storage({
+                let urn = urn.clone();
+                move |storage| {
+                    assert!(tracking::track(
+                        storage,
+                        &urn,
+                        Some(contributor_id),
+                        tracking::Config {
+                            data: true,
+                            cobs: tracking::config::cobs::Cobs::deny_all(),
+                        },
+                        tracking::policy::Track::Any,
+                    )
+                    .unwrap()
+                    .is_ok());
+                }
+            })
+            .await
+            .unwrap();
+        leecher
+            .client()
+            .unwrap()
+            .replicate((host_peer, host.peer.listen_addrs().to_vec()), urn, None)
+            .await
+            .unwrap();
+
+        leecher
+            .using_storage(move |storage| {
+                assert!(
+                    storage.has_ref(&head.with_remote(contributor_id)).unwrap(),
+                    "allowed refs should be fetched"
+                );
+                assert!(
+                    !storage.has_ref(&cob.with_remote(contributor_id)).unwrap(),
+                    "denied cobs should not be fetched"
+                );
+            })
+            .await
+            .unwrap();
+    })
+}
+
+#[test]
+fn reuses_connection() {
+    logging::init();
+
+    let net = testnet::run(default_config()).unwrap();
+    net.enter(async {
+        let host = Host::init(net.peers().index(0)).await;
+        let urn = host.project.project.urn();
+        let host_peer = host.peer.peer_id();
+        let host_addrs = host.peer.listen_addrs().to_vec();
+
+        let client = testnet::TestClient::init().await.unwrap();
+        client
+            .replicate((host_peer, host_addrs.clone()), urn.clone(), None)
+            .await
+            .unwrap();
+        let first = client
+            .pooled_connection(&host_peer)
+            .expect("connection should be pooled");
+        client
+            .replicate((host_peer, host_addrs), urn, None)
+            .await
+            .unwrap();
+        assert_eq!(Some(first), client.pooled_connection(&host_peer));
+    })
+}
+
 #[test]
 fn when_connected() {
     logging::init();
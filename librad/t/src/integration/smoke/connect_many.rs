@@ -0,0 +1,64 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::ops::Index as _;
+
+use it_helpers::testnet;
+use librad::{PeerId, SecretKey};
+use test_helpers::logging;
+
+fn config() -> testnet::Config {
+    testnet::Config {
+        num_peers: nonzero!(2usize),
+        min_connected: 2,
+        bootstrap: testnet::Bootstrap::from_env(),
+    }
+}
+
+#[test]
+fn reports_partial_success() {
+    logging::init();
+
+    let net = testnet::run(config()).unwrap();
+    net.enter(async {
+        let peer1 = net.peers().index(0);
+        let peer2 = net.peers().index(1);
+        let unknown = PeerId::from(SecretKey::new());
+
+        let connected = peer1
+            .connect_many(
+                vec![
+                    (unknown, vec![]),
+                    (peer2.peer_id(), peer2.listen_addrs().to_vec()),
+                ],
+                2,
+            )
+            .await;
+        assert_eq!(connected.len(), 2);
+        assert_matches!(connected[0], (peer, None) if peer == unknown);
+        assert_matches!(connected[1], (peer, Some(_)) if peer == peer2.peer_id());
+    })
+}
+
+/// More peers than the capacity of the protocol's event channel must not cause
+/// attempts to be dropped.
+#[test]
+fn many_peers() {
+    logging::init();
+
+    let net = testnet::run(config()).unwrap();
+    net.enter(async {
+        let peer1 = net.peers().index(0);
+        let peer2 = net.peers().index(1);
+        let mut peers = (0..32)
+            .map(|_| (PeerId::from(SecretKey::new()), vec![]))
+            .collect::<Vec<_>>();
+        peers.push((peer2.peer_id(), peer2.listen_addrs().to_vec()));
+
+        let connected = peer1.connect_many(peers, 1).await;
+        assert_eq!(connected.len(), 33);
+        assert_matches!(connected[32], (peer, Some(_)) if peer == peer2.peer_id());
+    })
+}
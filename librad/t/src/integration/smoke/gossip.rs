@@ -24,6 +24,7 @@ use librad::{
         protocol::{
             event::{self, upstream::predicate},
             gossip::{self, Rev},
+            membership,
             RequestPullGuard,
         },
     },
@@ -120,6 +121,7 @@ fn fetches_on_gossip_notify() {
         peer1
             .announce(gossip::Payload {
                 origin: None,
+                previous: None,
                 urn: project
                     .urn()
                     .with_path(Some(mastor.into_refstring().into())),
@@ -129,6 +131,7 @@ fn fetches_on_gossip_notify() {
         peer1
             .announce(gossip::Payload {
                 origin: None,
+                previous: None,
                 urn: project.urn().with_path(reflike!("refs/tags/MY-TAG")),
                 rev: Some(Rev::Git(tag_id)),
             })
@@ -267,3 +270,93 @@ fn ask_and_clone() {
         )
     })
 }
+
+/// Given two peers acting as seeds and a client.
+/// When the client announces a URN to both seeds.
+/// Then each seed receives the announcement from the client.
+#[test]
+fn client_announces_to_seeds() {
+    logging::init();
+
+    let net = testnet::run(config()).unwrap();
+    net.enter(async {
+        let seed1 = net.peers().index(0);
+        let seed2 = net.peers().index(1);
+        let client = testnet::TestClient::init().await.unwrap();
+        let seed1_events = seed1.subscribe();
+        let seed2_events = seed2.subscribe();
+
+        let urn = Urn::new(git2::Oid::zero().into());
+        let sent = client
+            .announce(
+                vec![
+                    (seed1.peer_id(), seed1.listen_addrs().to_vec()),
+                    (seed2.peer_id(), seed2.listen_addrs().to_vec()),
+                ],
+                urn,
+                Some(Rev::Git(git2::Oid::zero())),
+            )
+            .await;
+        assert_eq!(sent.len(), 2);
+        for (peer, res) in sent {
+            assert!(res.is_ok(), "failed to announce to {}", peer);
+        }
+
+        for events in [seed1_events, seed2_events] {
+            futures::pin_mut!(events);
+            event::upstream::expect(
+                events,
+                predicate::gossip_from(client.peer_id()),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        }
+    })
+}
+
+/// Given a seed whose active view is full.
+/// When a client announces a URN to the seed.
+/// Then sending the announcement succeeds, but the seed drops it.
+#[test]
+fn client_announcement_dropped_by_full_seed() {
+    logging::init();
+
+    let net = testnet::run(testnet::Config {
+        num_peers: nonzero!(6usize),
+        min_connected: 6,
+        bootstrap: testnet::Bootstrap::First,
+    })
+    .unwrap();
+    net.enter(async {
+        let seed = net.peers().index(0);
+        assert_eq!(
+            seed.membership().await.active.len(),
+            membership::Params::default().max_active,
+            "expected the seed's active view to be full"
+        );
+        let client = testnet::TestClient::init().await.unwrap();
+        let seed_events = seed.subscribe();
+
+        let urn = Urn::new(git2::Oid::zero().into());
+        let sent = client
+            .announce(
+                vec![(seed.peer_id(), seed.listen_addrs().to_vec())],
+                urn,
+                Some(Rev::Git(git2::Oid::zero())),
+            )
+            .await;
+        assert!(sent.into_iter().all(|(_, res)| res.is_ok()));
+
+        futures::pin_mut!(seed_events);
+        assert_matches!(
+            event::upstream::expect(
+                seed_events,
+                predicate::gossip_from(client.peer_id()),
+                Duration::from_secs(2),
+            )
+            .await,
+            Err(event::upstream::ExpectError::Timeout)
+        );
+    })
+}
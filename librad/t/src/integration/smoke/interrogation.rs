@@ -9,9 +9,13 @@ use it_helpers::{fixed::TestProject, testnet};
 use librad::{
     data::BoundedVec,
     identities::SomeUrn,
-    net::protocol::{
-        event::{self, upstream::predicate},
-        PeerAdvertisement,
+    net::{
+        protocol::{
+            event::{self, upstream::predicate},
+            PeerAdvertisement,
+        },
+        PROTOCOL_VERSION,
+        SOFTWARE_VERSION,
     },
 };
 use test_helpers::logging;
@@ -84,3 +88,105 @@ fn responds() {
         }
     })
 }
+
+#[test]
+fn node_info() {
+    logging::init();
+
+    let net = testnet::run(config()).unwrap();
+    net.enter(async {
+        let responder = net.peers().index(0);
+        let requester = net.peers().index(1);
+
+        let interrogation = requester
+            .client()
+            .unwrap()
+            .interrogate((responder.peer_id(), responder.listen_addrs().to_vec()))
+            .await
+            .unwrap();
+        let info = interrogation.node_info().await.unwrap();
+        assert_eq!(info.version, SOFTWARE_VERSION);
+        assert!(info.version.starts_with("librad "));
+        assert_eq!(info.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(info.capabilities, Default::default());
+    })
+}
+
+#[test]
+fn cached() {
+    logging::init();
+
+    let net = testnet::run(config()).unwrap();
+    net.enter(async {
+        let responder = net.peers().index(0);
+        let requester = net.peers().index(1);
+
+        let interrogation = requester
+            .client()
+            .unwrap()
+            .interrogate((responder.peer_id(), responder.listen_addrs().to_vec()))
+            .await
+            .unwrap()
+            .cache_for(Duration::from_secs(60));
+        let first = interrogation.peer_advertisement().await.unwrap();
+        let second = interrogation.peer_advertisement().await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(interrogation.round_trips(), 1);
+
+        // A different request is not answered from the cache
+        interrogation.echo_addr().await.unwrap();
+        assert_eq!(interrogation.round_trips(), 2);
+    })
+}
+
+#[test]
+fn peers() {
+    logging::init();
+
+    let net = testnet::run(config()).unwrap();
+    net.enter(async {
+        let responder = net.peers().index(0);
+        let requester = net.peers().index(1);
+
+        let interrogation = requester
+            .client()
+            .unwrap()
+            .interrogate((responder.peer_id(), responder.listen_addrs().to_vec()))
+            .await
+            .unwrap();
+        let peers = interrogation.peers().await.unwrap();
+        let (_, addrs) = peers
+            .iter()
+            .find(|(peer, _)| *peer == requester.peer_id())
+            .expect("responder should know the requester");
+        assert!(!addrs.is_empty());
+        assert!(peers.iter().all(|(peer, _)| *peer != responder.peer_id()));
+    })
+}
+
+#[test]
+fn reconnects() {
+    logging::init();
+
+    let net = testnet::run(config()).unwrap();
+    net.enter(async {
+        let responder = net.peers().index(0);
+        let requester = net.peers().index(1);
+
+        let client = requester.client().unwrap();
+        let interrogation = client
+            .interrogate((responder.peer_id(), responder.listen_addrs().to_vec()))
+            .await
+            .unwrap();
+        interrogation.node_info().await.unwrap();
+        assert!(client.pooled_connection(&responder.peer_id()).is_some());
+
+        // A closed connection is not handed out of the pool, and the
+        // interrogation retries over a fresh one
+        client.close_pooled_connection(&responder.peer_id());
+        assert!(client.pooled_connection(&responder.peer_id()).is_none());
+        interrogation.node_info().await.unwrap();
+        assert_eq!(interrogation.round_trips(), 3);
+        assert!(client.pooled_connection(&responder.peer_id()).is_some());
+    })
+}
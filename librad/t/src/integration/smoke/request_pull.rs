@@ -1,12 +1,26 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::ops::Index as _;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    ops::Index as _,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use futures::StreamExt as _;
+use futures::{future, StreamExt as _};
 
 use it_helpers::{fixed::TestProject, testnet};
-use librad::{git::storage::ReadOnlyStorage as _, net::protocol::request_pull::Response};
+use librad::{
+    git::storage::ReadOnlyStorage as _,
+    net::protocol::{
+        self,
+        event::{self, upstream::predicate},
+        request_pull::{ErrorCode, Limits, Response},
+        rpc::client::error,
+    },
+};
 use test_helpers::logging;
 
 fn peer_and_client() -> testnet::Config {
@@ -25,6 +39,14 @@ fn peer_and_peer() -> testnet::Config {
     }
 }
 
+fn peer_and_two_peers() -> testnet::Config {
+    testnet::Config {
+        num_peers: nonzero!(3usize),
+        min_connected: 3,
+        bootstrap: testnet::Bootstrap::from_env(),
+    }
+}
+
 #[test]
 fn responds_peer_and_client() {
     logging::init();
@@ -120,3 +142,267 @@ fn responds_peer_and_peer() {
         assert!(pulled, "responder does not have project");
     })
 }
+
+#[test]
+fn rejects_beyond_limits() {
+    logging::init();
+
+    let limits = protocol::Quota {
+        request_pull: Limits {
+            concurrent: 1,
+            queued: 1,
+        },
+        ..Default::default()
+    };
+    let net = testnet::run_with_limits(peer_and_client(), limits).unwrap();
+    net.enter(async {
+        let responder = net.peers().index(0);
+        let requester = testnet::TestClient::init().await.unwrap();
+        let TestProject { project, .. } = requester
+            .using_storage(TestProject::create)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let requests = future::join_all((0..6).map(|_| {
+            requester.request_pull(
+                (responder.peer_id(), responder.listen_addrs().to_vec()),
+                project.urn(),
+            )
+        }))
+        .await;
+        let outcomes = future::join_all(requests.into_iter().map(|rp| async move {
+            let mut rp = rp.unwrap();
+            while let Some(resp) = rp.next().await {
+                match resp.unwrap() {
+                    Response::Error(e) => return Err(e),
+                    Response::Progress(p) => {
+                        tracing::debug!(progress = %p.message, "making progress")
+                    },
+                    Response::Success(_) => return Ok(()),
+                }
+            }
+            panic!("request-pull finished without a response")
+        }))
+        .await;
+
+        let (served, rejected): (Vec<_>, Vec<_>) = outcomes.into_iter().partition(Result::is_ok);
+        assert!(
+            !served.is_empty(),
+            "expected some request-pulls to be served"
+        );
+        assert!(
+            !rejected.is_empty(),
+            "expected request-pulls beyond the limits to be rejected"
+        );
+        for e in rejected {
+            assert_eq!(e.unwrap_err().code, Some(ErrorCode::Busy));
+        }
+    })
+}
+
+#[test]
+fn cancel() {
+    logging::init();
+
+    let net = testnet::run(peer_and_peer()).unwrap();
+    net.enter(async {
+        let responder = net.peers().index(0);
+        let requester = net.peers().index(1);
+        let TestProject { project, .. } = requester
+            .using_storage(TestProject::create)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut rp = requester
+            .client()
+            .unwrap()
+            .request_pull(
+                (responder.peer_id(), responder.listen_addrs().to_vec()),
+                project.urn(),
+            )
+            .await
+            .unwrap();
+
+        let abort = rp.abort_handle();
+        let mut cancelled = false;
+        while let Some(resp) = rp.next().await {
+            match resp {
+                Err(error::RequestPull::Cancelled) => {
+                    cancelled = true;
+                },
+                Err(e) => panic!("request-pull failed: {}", e),
+                Ok(Response::Success(_)) => panic!("request-pull was not cancelled"),
+                Ok(_) => abort.abort(),
+            }
+        }
+        assert!(cancelled, "expected the stream to yield `Cancelled`");
+        assert!(rp.next().await.is_none(), "stream should be finished");
+    })
+}
+
+#[test]
+fn announces_pulled_refs() {
+    logging::init();
+
+    let net = testnet::run(peer_and_two_peers()).unwrap();
+    net.enter(async {
+        let responder = net.peers().index(0);
+        let requester = net.peers().index(1);
+        let observer = net.peers().index(2);
+        let TestProject { project, .. } = requester
+            .using_storage(TestProject::create)
+            .await
+            .unwrap()
+            .unwrap();
+        let observer_events = observer.subscribe();
+
+        let mut rp = requester
+            .client()
+            .unwrap()
+            .request_pull(
+                (responder.peer_id(), responder.listen_addrs().to_vec()),
+                project.urn(),
+            )
+            .await
+            .unwrap();
+
+        while let Some(Ok(resp)) = rp.next().await {
+            match resp {
+                Response::Error(e) => panic!("request-pull failed: {}", e.message),
+                Response::Progress(p) => tracing::debug!(progress = %p.message, "making progress"),
+                Response::Success(success) => {
+                    assert!(!success.refs.is_empty(), "expected updated refs");
+                    break;
+                },
+            }
+        }
+
+        // The responder announces the pulled refs to its other peers
+        let from_responder = predicate::gossip_from(responder.peer_id());
+        let for_urn = predicate::urn(project.urn());
+        futures::pin_mut!(observer_events);
+        event::upstream::expect(
+            observer_events,
+            move |event| from_responder(event) && for_urn(event),
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+    })
+}
+
+type Fields = BTreeMap<&'static str, String>;
+
+/// A [`tracing::Subscriber`] which records the spans created, and the events
+/// emitted, on the thread it is the default for.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Captured>>);
+
+#[derive(Default)]
+struct Captured {
+    spans: Vec<(&'static str, Fields)>,
+    entered: Vec<tracing::span::Id>,
+    events: Vec<(Option<&'static str>, Fields)>,
+}
+
+struct Visitor<'a>(&'a mut Fields);
+
+impl<'a> tracing::field::Visit for Visitor<'a> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl tracing::Subscriber for Capture {
+    fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        let mut fields = Fields::new();
+        span.record(&mut Visitor(&mut fields));
+        let mut captured = self.0.lock().unwrap();
+        captured.spans.push((span.metadata().name(), fields));
+        tracing::span::Id::from_u64(captured.spans.len() as u64)
+    }
+
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut Visitor(&mut fields));
+        let mut captured = self.0.lock().unwrap();
+        let span = captured
+            .entered
+            .last()
+            .map(|id| captured.spans[id.into_u64() as usize - 1].0);
+        captured.events.push((span, fields));
+    }
+
+    fn enter(&self, span: &tracing::span::Id) {
+        self.0.lock().unwrap().entered.push(span.clone());
+    }
+
+    fn exit(&self, _: &tracing::span::Id) {
+        self.0.lock().unwrap().entered.pop();
+    }
+}
+
+#[test]
+fn records_request_pull_span() {
+    let net = testnet::run(peer_and_client()).unwrap();
+    let capture = Capture::default();
+    let (urn, responder_id) = tracing::subscriber::with_default(capture.clone(), || {
+        net.enter(async {
+            let responder = net.peers().index(0);
+            let requester = testnet::TestClient::init().await.unwrap();
+            let TestProject { project, .. } = requester
+                .using_storage(TestProject::create)
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mut rp = requester
+                .request_pull(
+                    (responder.peer_id(), responder.listen_addrs().to_vec()),
+                    project.urn(),
+                )
+                .await
+                .unwrap();
+            while let Some(Ok(resp)) = rp.next().await {
+                match resp {
+                    Response::Error(e) => panic!("request-pull failed: {}", e.message),
+                    Response::Progress(_) => {},
+                    Response::Success(_) => break,
+                }
+            }
+
+            (project.urn(), responder.peer_id())
+        })
+    });
+
+    let captured = capture.0.lock().unwrap();
+    let span = captured
+        .spans
+        .iter()
+        .find(|(name, _)| *name == "request_pull")
+        .map(|(_, fields)| fields)
+        .expect("no request_pull span");
+    assert_eq!(span.get("urn"), Some(&urn.to_string()));
+    assert_eq!(span.get("peer"), Some(&responder_id.to_string()));
+
+    let outcome = captured
+        .events
+        .iter()
+        .find(|(span, fields)| {
+            *span == Some("request_pull")
+                && fields.get("message").map(String::as_str) == Some("request-pull succeeded")
+        })
+        .map(|(_, fields)| fields)
+        .expect("no request-pull outcome recorded");
+    assert!(outcome.contains_key("updated"));
+}
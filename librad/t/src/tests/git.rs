@@ -3,11 +3,13 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
+mod collaborative_objects;
 mod include;
 mod local;
 mod p2p;
 mod project;
 mod refs;
+mod relations;
 mod storage;
 mod tracking;
 mod types;
@@ -0,0 +1,936 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{str::FromStr, sync::Arc};
+
+use git_trailers::Trailer;
+use it_helpers::fixed::TestProject;
+use lazy_static::lazy_static;
+use librad::{
+    collaborative_objects::{
+        error,
+        ChangeGraphs,
+        ClientId,
+        CollaborativeObject,
+        EntryContents,
+        History,
+        Limits,
+        Loading,
+        NewObjectSpec,
+        ObjectId,
+        PendingChange,
+        PendingReason,
+        Rejection,
+        RetrievalProgress,
+        TypeName,
+        UnverifiedChange,
+        UpdateMode,
+        UpdateObjectSpec,
+    },
+    git::{
+        identities::{self, local::LocalIdentity},
+        storage::{ReadOnly, Storage},
+        types::{Namespace, Reference},
+        Urn,
+    },
+    identities::sign::Signature,
+    paths::Paths,
+    PeerId,
+    SecretKey,
+};
+
+lazy_static! {
+    static ref TYPENAME: TypeName = FromStr::from_str("xyz.radicle.testobject").unwrap();
+}
+
+struct Fixture {
+    tmp: tempfile::TempDir,
+    storage: Storage,
+    whoami: LocalIdentity,
+    urn: Urn,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = Paths::from_root(tmp.path()).unwrap();
+        let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+        let proj = TestProject::create(&storage).unwrap();
+        let whoami = identities::local::load(&storage, proj.owner.urn())
+            .unwrap()
+            .unwrap();
+        Self {
+            tmp,
+            storage,
+            whoami,
+            urn: proj.project.urn(),
+        }
+    }
+
+    /// Open the git repository of the storage directly, e.g. to forge changes.
+    fn repo(&self) -> git2::Repository {
+        git2::Repository::open(Paths::from_root(self.tmp.path()).unwrap().git_dir()).unwrap()
+    }
+
+    /// Create an object with an empty list of items and then add each of
+    /// `items` in a separate change.
+    fn create_object(&self, items: &[&str]) -> CollaborativeObject {
+        let cobs = self.storage.collaborative_objects(None);
+        let mut object = cobs
+            .create(
+                &self.whoami,
+                &self.urn,
+                NewObjectSpec {
+                    history: init_history(),
+                    message: Some("create".to_string()),
+                    client: None,
+                    typename: TYPENAME.clone(),
+                },
+            )
+            .unwrap();
+        for item in items {
+            object = cobs
+                .update(
+                    &self.whoami,
+                    &self.urn,
+                    UpdateObjectSpec {
+                        object_id: *object.id(),
+                        typename: TYPENAME.clone(),
+                        message: Some(format!("add {}", item)),
+                        client: None,
+                        changes: add_item(object.history(), *item),
+                        expected_tips: None,
+                        mode: UpdateMode::Append,
+                    },
+                )
+                .unwrap();
+        }
+        object
+    }
+
+    /// Commit a sibling of the local tip change of `object`, with the message
+    /// and tree returned by `forge`, returning the forged commit.
+    ///
+    /// If `remote` is `None` the forged commit replaces the local tip,
+    /// otherwise it becomes the tip of `remote`.
+    fn forge_tip<F>(
+        &self,
+        object: &CollaborativeObject,
+        remote: Option<PeerId>,
+        forge: F,
+    ) -> git2::Oid
+    where
+        F: FnOnce(&git2::Repository, &git2::Commit) -> (String, git2::Oid),
+    {
+        let repo = self.repo();
+        let local_ref = Reference::rad_collaborative_object(
+            Namespace::from(self.urn.clone()),
+            None,
+            TYPENAME.clone(),
+            *object.id(),
+        );
+        let tip = repo
+            .find_commit(repo.refname_to_id(&local_ref.to_string()).unwrap())
+            .unwrap();
+        let (message, tree) = forge(&repo, &tip);
+        let forged = repo
+            .commit(
+                None,
+                &tip.author(),
+                &tip.committer(),
+                &message,
+                &repo.find_tree(tree).unwrap(),
+                &tip.parents().collect::<Vec<_>>().iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+        repo.reference(
+            &local_ref.with_remote(remote).to_string(),
+            forged,
+            true,
+            "forged change",
+        )
+        .unwrap();
+        forged
+    }
+}
+
+/// Forge a tree which the signatures of `tip` were not made over.
+fn forge_tree(repo: &git2::Repository, tip: &git2::Commit) -> (String, git2::Oid) {
+    let mut builder = repo.treebuilder(Some(&tip.tree().unwrap())).unwrap();
+    builder
+        .insert("forged", repo.blob(b"forged").unwrap(), 0o100644)
+        .unwrap();
+    (tip.message().unwrap().to_string(), builder.write().unwrap())
+}
+
+#[test]
+fn retrieve_too_many_changes() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one", "two"]);
+
+    let retrieved = fixture
+        .storage
+        .collaborative_objects(None)
+        .with_limits(Limits {
+            max_changes: Some(3),
+            ..Limits::default()
+        })
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap();
+    assert!(retrieved.is_some());
+
+    let too_large = fixture
+        .storage
+        .collaborative_objects(None)
+        .with_limits(Limits {
+            max_changes: Some(2),
+            ..Limits::default()
+        })
+        .retrieve(&fixture.urn, &TYPENAME, object.id());
+    assert_matches!(
+        too_large,
+        Err(error::Retrieve::Cob(cob::error::Retrieve::TooLarge(
+            cob::error::TooLarge::Changes { max: 2 }
+        )))
+    )
+}
+
+#[test]
+fn retrieve_history_too_large() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one"]);
+
+    let too_large = fixture
+        .storage
+        .collaborative_objects(None)
+        .with_limits(Limits {
+            max_history_bytes: Some(1),
+            ..Limits::default()
+        })
+        .retrieve(&fixture.urn, &TYPENAME, object.id());
+    assert_matches!(
+        too_large,
+        Err(error::Retrieve::Cob(cob::error::Retrieve::TooLarge(
+            cob::error::TooLarge::HistoryBytes { max: 1 }
+        )))
+    )
+}
+
+#[test]
+fn changegraph_info_too_many_changes() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one", "two"]);
+
+    let too_large = fixture
+        .storage
+        .collaborative_objects(None)
+        .with_limits(Limits {
+            max_changes: Some(2),
+            ..Limits::default()
+        })
+        .changegraph_info_for_object(&fixture.urn, &TYPENAME, object.id());
+    assert_matches!(
+        too_large,
+        Err(error::Retrieve::Cob(cob::error::Retrieve::TooLarge(
+            cob::error::TooLarge::Changes { max: 2 }
+        )))
+    )
+}
+
+#[test]
+fn changegraph_read_only() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one", "two"]);
+    let storage = ReadOnly::open(&Paths::from_root(fixture.tmp.path()).unwrap()).unwrap();
+    let graphs = ChangeGraphs::new(&storage);
+
+    let cobs = fixture.storage.collaborative_objects(None);
+    let expected = cobs
+        .changegraph_info_for_object(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    let info = graphs
+        .changegraph_info_for_object(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.number_of_nodes, 3);
+    assert_eq!(info.tips, expected.tips);
+    assert_eq!(info.dotviz, expected.dotviz);
+
+    let json = graphs
+        .changegraph_json_for_object(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(json.nodes.len(), 3);
+    assert_eq!(json.edges.len(), 2);
+
+    let missing = ObjectId::from(git2::Oid::zero());
+    assert!(graphs
+        .changegraph_info_for_object(&fixture.urn, &TYPENAME, &missing)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn retrieve_root_mismatch() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one"]);
+    let other = fixture.create_object(&["two"]);
+
+    // Point the ref of `object` at the history of `other`
+    let repo = fixture.repo();
+    let cob_ref = |id: &ObjectId| {
+        Reference::rad_collaborative_object(
+            Namespace::from(fixture.urn.clone()),
+            None,
+            TYPENAME.clone(),
+            *id,
+        )
+        .to_string()
+    };
+    let other_tip = repo.refname_to_id(&cob_ref(other.id())).unwrap();
+    repo.reference(&cob_ref(object.id()), other_tip, true, "mismatch")
+        .unwrap();
+
+    let mismatch =
+        fixture
+            .storage
+            .collaborative_objects(None)
+            .retrieve(&fixture.urn, &TYPENAME, object.id());
+    assert_matches!(
+        mismatch,
+        Err(error::Retrieve::Cob(cob::error::Retrieve::RootMismatch(
+            cob::error::RootMismatch { object: id, .. }
+        ))) if id == *object.id()
+    )
+}
+
+#[test]
+fn pending_wrong_authorizer() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one"]);
+    let other = TestProject::create(&fixture.storage).unwrap();
+    let other_urn = other.project.urn();
+
+    // Store the object under a project which did not authorize its changes
+    let repo = fixture.repo();
+    let cob_ref = |urn: &Urn| {
+        Reference::rad_collaborative_object(
+            Namespace::from(urn.clone()),
+            None,
+            TYPENAME.clone(),
+            *object.id(),
+        )
+        .to_string()
+    };
+    let tip = repo.refname_to_id(&cob_ref(&fixture.urn)).unwrap();
+    repo.reference(&cob_ref(&other_urn), tip, true, "wrong authorizer")
+        .unwrap();
+
+    let cobs = fixture.storage.collaborative_objects(None);
+    assert!(cobs
+        .pending_changes(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap()
+        .is_empty());
+
+    let pending = cobs
+        .pending_changes(&other_urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(pending.len(), 2);
+    assert_matches!(
+        &pending[0],
+        PendingChange {
+            commit,
+            reason: PendingReason::Rejected(Rejection::WrongAuthorizer),
+        } if ObjectId::from(commit) == *object.id()
+    );
+    assert_matches!(
+        &pending[1],
+        PendingChange {
+            commit,
+            reason: PendingReason::AncestorRejected { ancestor },
+        } if *commit == tip && ObjectId::from(ancestor) == *object.id()
+    );
+}
+
+#[test]
+fn pending_forged_schema() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one"]);
+
+    // Rewrite the tip change so that it points at a schema the object was
+    // not created with. The signatures only cover the tree, so they remain
+    // valid.
+    let forged_schema = fixture.repo().blob(b"forged schema").unwrap();
+    let forged = fixture.forge_tip(&object, None, |_, tip| {
+        let message = format!(
+            "{}\nX-Rad-Schema: {}\n",
+            tip.message().unwrap().trim_end(),
+            ObjectId::from(forged_schema)
+        );
+        (message, tip.tree_id())
+    });
+
+    let cobs = fixture.storage.collaborative_objects(None);
+    let retrieved = cobs
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(retrieved.history().len(), 1);
+
+    let pending = cobs
+        .pending_changes(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_matches!(
+        &pending[0],
+        PendingChange {
+            commit,
+            reason: PendingReason::Rejected(Rejection::SchemaMismatch { schema_commit }),
+        } if *commit == forged && *schema_commit == forged_schema
+    );
+}
+
+#[test]
+fn update_concurrent_modification() {
+    let fixture = Fixture::new();
+    let cobs = fixture.storage.collaborative_objects(None);
+    let object = fixture.create_object(&[]);
+    let update = |object: &CollaborativeObject, item: &str| {
+        cobs.update(
+            &fixture.whoami,
+            &fixture.urn,
+            UpdateObjectSpec {
+                object_id: *object.id(),
+                typename: TYPENAME.clone(),
+                message: None,
+                client: None,
+                changes: add_item(object.history(), item),
+                expected_tips: Some(object.tips().iter().copied().collect()),
+                mode: UpdateMode::Append,
+            },
+        )
+    };
+
+    // Both writers start from the same state, the first one to write wins
+    let first = update(&object, "first").unwrap();
+    assert_matches!(
+        update(&object, "second"),
+        Err(error::Update::Cob(cob::error::Update::ConcurrentModification { expected, found }))
+            if expected == *object.tips() && found == *first.tips()
+    );
+
+    // Retrying from the latest state succeeds
+    let latest = cobs
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    let second = update(&latest, "second").unwrap();
+    assert_eq!(second.history().len(), 3);
+}
+
+#[test]
+fn update_compact_preserves_value() {
+    let fixture = Fixture::new();
+    let cobs = fixture.storage.collaborative_objects(None);
+    let object = fixture.create_object(&["one", "two"]);
+    let (before, _) = evaluate_history(object.history());
+    let compact = |changes: EntryContents| {
+        cobs.update(
+            &fixture.whoami,
+            &fixture.urn,
+            UpdateObjectSpec {
+                object_id: *object.id(),
+                typename: TYPENAME.clone(),
+                message: Some("compact".to_string()),
+                client: None,
+                changes,
+                expected_tips: None,
+                mode: UpdateMode::Compact {
+                    validate: Box::new(compaction_matches),
+                },
+            },
+        )
+    };
+
+    // A compaction which does not reproduce the history is rejected
+    assert_matches!(
+        compact(add_item(object.history(), "three")),
+        Err(error::Update::Cob(cob::error::Update::InvalidCompaction))
+    );
+
+    // The compacted change is the only tip, following all prior ones in the
+    // change graph
+    let compacted = compact(compacted_items(object.history())).unwrap();
+    assert_eq!(compacted.tips().len(), 1);
+    let tip = *compacted.tips().iter().next().unwrap();
+    let unverified = cobs
+        .retrieve_unverified(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(unverified.changes.len(), object.history().len() + 1);
+    for prior in object.tips() {
+        let change = unverified
+            .changes
+            .iter()
+            .find(|change| change.commit == *prior)
+            .unwrap();
+        assert_eq!(change.children, vec![tip]);
+    }
+
+    // The earlier changes are not replayed, the history starts at the
+    // compacted change, both as returned by the update and when retrieved
+    let retrieved = cobs
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    for history in [compacted.history(), retrieved.history()] {
+        assert_eq!(history.len(), 1);
+        assert!(history.entry(tip).is_some());
+        for prior in object.tips() {
+            assert!(history.entry(*prior).is_none());
+        }
+    }
+    let (after, _) = evaluate_history(retrieved.history());
+    assert_eq!(after.state().to_json(), before.state().to_json());
+
+    // Later changes build on the compacted change
+    let updated = cobs
+        .update(
+            &fixture.whoami,
+            &fixture.urn,
+            UpdateObjectSpec {
+                object_id: *object.id(),
+                typename: TYPENAME.clone(),
+                message: Some("add three".to_string()),
+                client: None,
+                changes: add_item(retrieved.history(), "three"),
+                expected_tips: None,
+                mode: UpdateMode::Append,
+            },
+        )
+        .unwrap();
+    let retrieved = cobs
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(retrieved.history(), updated.history());
+    assert_eq!(retrieved.history().len(), 2);
+    let (after, _) = evaluate_history(retrieved.history());
+    assert_eq!(
+        after.state().to_json(),
+        serde_json::json!({"items": ["one", "two", "three"]})
+    );
+}
+
+#[test]
+fn pending_invalid_signature() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one"]);
+
+    let forged = fixture.forge_tip(&object, None, forge_tree);
+
+    let pending = fixture
+        .storage
+        .collaborative_objects(None)
+        .pending_changes(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_matches!(
+        &pending[0],
+        PendingChange {
+            commit,
+            reason: PendingReason::Rejected(Rejection::InvalidSignatures(cob::error::Signature { actual, .. })),
+        } if *commit == forged && actual == fixture.storage.peer_id().as_public_key()
+    );
+}
+
+#[test]
+fn pending_wrong_signer() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one"]);
+
+    // Re-sign the tip change with a key which is not one of the author's. The
+    // signature itself is valid.
+    let key = SecretKey::new();
+    let forged = fixture.forge_tip(&object, None, |_, tip| {
+        let signature = Signature::from((key.public(), key.sign(tip.tree_id().as_bytes())));
+        let message = tip
+            .message()
+            .unwrap()
+            .lines()
+            .filter(|line| !line.starts_with("X-Rad-Signature:"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let message = format!(
+            "{}\n{}\n",
+            message.trim_end(),
+            Trailer::from(signature).display(": ")
+        );
+        (message, tip.tree_id())
+    });
+
+    let pending = fixture
+        .storage
+        .collaborative_objects(None)
+        .pending_changes(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_matches!(
+        &pending[0],
+        PendingChange {
+            commit,
+            reason: PendingReason::Rejected(Rejection::InvalidSignatures(cob::error::Signature { expected, actual })),
+        } if *commit == forged
+            && *actual == key.public()
+            && expected.contains(fixture.storage.peer_id().as_public_key())
+    );
+}
+
+#[test]
+fn retrieve_unverified_invalid_signature() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one"]);
+    let forged = fixture.forge_tip(&object, None, forge_tree);
+    let cobs = fixture.storage.collaborative_objects(None);
+
+    // The verified object does not contain the forged change
+    let verified = cobs
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(verified.history().len(), 1);
+
+    // The unverified object does, along with why it was rejected
+    let unverified = cobs
+        .retrieve_unverified(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    assert_eq!(unverified.changes.len(), 2);
+    assert_eq!(unverified.tips, std::iter::once(forged).collect());
+    let (valid, invalid): (Vec<_>, Vec<_>) = unverified
+        .changes
+        .iter()
+        .partition(|change| change.is_valid());
+    assert_eq!(valid.len(), 1);
+    assert_eq!(ObjectId::from(valid[0].commit), *object.id());
+    assert_eq!(valid[0].author.as_ref(), Some(&fixture.whoami.urn()));
+    assert_matches!(
+        invalid.as_slice(),
+        [UnverifiedChange {
+            commit,
+            pending: Some(PendingReason::Rejected(Rejection::InvalidSignatures(_))),
+            ..
+        }] if *commit == forged
+    );
+}
+
+#[test]
+fn retrieve_reports_progress() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one", "two"]);
+
+    let mut progress = Vec::new();
+    let retrieved = fixture
+        .storage
+        .collaborative_objects(None)
+        .retrieve_with_progress(&fixture.urn, &TYPENAME, object.id(), &mut |p| {
+            progress.push(p)
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(retrieved.history(), object.history());
+
+    assert_eq!(
+        progress.first(),
+        Some(&RetrievalProgress::LoadingRefs { refs: 1 })
+    );
+    assert!(progress
+        .iter()
+        .any(|p| matches!(p, RetrievalProgress::Walking { .. })));
+    assert_eq!(
+        progress.last(),
+        Some(&RetrievalProgress::Evaluating {
+            evaluated: 3,
+            total: 3
+        })
+    );
+}
+
+#[test]
+fn retrieve_parallel() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one", "two", "three", "four"]);
+    let sequential = fixture
+        .storage
+        .collaborative_objects(None)
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let parallel = fixture
+        .storage
+        .collaborative_objects(None)
+        .with_loading(Loading::Parallel {
+            spawner: Arc::new(link_async::Spawner::tokio(rt.handle().clone())),
+            concurrency: 2,
+        })
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(parallel.history(), sequential.history());
+    assert_eq!(parallel.tips(), sequential.tips());
+    assert_eq!(parallel.history().len(), 5);
+}
+
+#[test]
+fn state_timeline() {
+    let fixture = Fixture::new();
+    let object = fixture.create_object(&["one", "two"]);
+
+    let timeline = fixture
+        .storage
+        .collaborative_objects(None)
+        .state_timeline(
+            &fixture.urn,
+            &TYPENAME,
+            object.id(),
+            automerge::Backend::new(),
+            |mut backend, change| match change.contents() {
+                EntryContents::Automerge(bytes) => {
+                    let change = automerge::Change::from_bytes(bytes.clone()).unwrap();
+                    backend.apply_changes(vec![change]).unwrap();
+                    std::ops::ControlFlow::Continue(backend)
+                },
+            },
+            |backend| {
+                let mut frontend = automerge::Frontend::new();
+                frontend.apply_patch(backend.get_patch().unwrap()).unwrap();
+                frontend.state().to_json()
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+    // The creating change plus one change per item
+    assert_eq!(timeline.len(), 3);
+    assert_eq!(
+        timeline.iter().map(|(_, state)| state).collect::<Vec<_>>(),
+        vec![
+            &serde_json::json!({ "items": [] }),
+            &serde_json::json!({ "items": ["one"] }),
+            &serde_json::json!({ "items": ["one", "two"] }),
+        ]
+    );
+
+    let (frontend, _) = evaluate_history(object.history());
+    assert_eq!(timeline.last().unwrap().1, frontend.state().to_json());
+}
+
+#[test]
+fn client_identifier() {
+    let fixture = Fixture::new();
+    let cobs = fixture.storage.collaborative_objects(None);
+    let bot = ClientId::from_str("my-bot 0.1").unwrap();
+
+    let object = cobs
+        .create(
+            &fixture.whoami,
+            &fixture.urn,
+            NewObjectSpec {
+                history: init_history(),
+                message: None,
+                client: Some(bot.clone()),
+                typename: TYPENAME.clone(),
+            },
+        )
+        .unwrap();
+    cobs.update(
+        &fixture.whoami,
+        &fixture.urn,
+        UpdateObjectSpec {
+            object_id: *object.id(),
+            typename: TYPENAME.clone(),
+            message: None,
+            client: None,
+            changes: add_item(object.history(), "one"),
+            expected_tips: None,
+            mode: UpdateMode::Append,
+        },
+    )
+    .unwrap();
+
+    // Retrieve without a cache, so the change graph is evaluated from scratch
+    // and the clients are read back from the commit trailers
+    let retrieved = fixture
+        .storage
+        .collaborative_objects(None)
+        .retrieve(&fixture.urn, &TYPENAME, object.id())
+        .unwrap()
+        .unwrap();
+    let clients = retrieved
+        .history()
+        .traverse(Vec::new(), |mut clients, entry| {
+            clients.push(entry.client().cloned());
+            std::ops::ControlFlow::Continue(clients)
+        });
+    assert_eq!(clients, vec![Some(bot), None]);
+}
+
+#[test]
+fn merge_base() {
+    let fixture = Fixture::new();
+    let cobs = fixture.storage.collaborative_objects(None);
+    let base = fixture.create_object(&["base"]);
+    let ours = cobs
+        .update(
+            &fixture.whoami,
+            &fixture.urn,
+            UpdateObjectSpec {
+                object_id: *base.id(),
+                typename: TYPENAME.clone(),
+                message: Some("add ours".to_string()),
+                client: None,
+                changes: add_item(base.history(), "ours"),
+                expected_tips: None,
+                mode: UpdateMode::Append,
+            },
+        )
+        .unwrap();
+
+    // Fork the object by adding a sibling of our change under a remote
+    let theirs_tip = fixture.forge_tip(&ours, Some(PeerId::from(SecretKey::new())), |_, tip| {
+        ("add theirs".to_string(), tip.tree_id())
+    });
+
+    let theirs = std::iter::once(theirs_tip).collect();
+    assert_eq!(
+        cobs.merge_base(&fixture.urn, &TYPENAME, base.id(), ours.tips(), &theirs)
+            .unwrap(),
+        Some(*base.tips().iter().next().unwrap())
+    );
+
+    // The tips of another object are not part of the change graph
+    let other = fixture.create_object(&[]);
+    assert_eq!(
+        cobs.merge_base(
+            &fixture.urn,
+            &TYPENAME,
+            base.id(),
+            ours.tips(),
+            other.tips()
+        )
+        .unwrap(),
+        None
+    );
+}
+
+fn init_history() -> EntryContents {
+    let mut backend = automerge::Backend::new();
+    let mut frontend = automerge::Frontend::new();
+    let (_, change) = frontend
+        .change::<_, _, automerge::InvalidChangeRequest>(None, |d| {
+            d.add_change(automerge::LocalChange::set(
+                automerge::Path::root().key("items"),
+                automerge::Value::List(Vec::new()),
+            ))?;
+            Ok(())
+        })
+        .unwrap();
+    backend.apply_local_change(change.unwrap()).unwrap();
+    let bytes = backend
+        .get_changes(&[])
+        .iter()
+        .flat_map(|c| c.raw_bytes().to_vec())
+        .collect();
+    EntryContents::Automerge(bytes)
+}
+
+fn add_item<I: Into<automerge::Value>>(history: &History, item: I) -> EntryContents {
+    let (mut frontend, mut backend) = evaluate_history(history);
+    let (_, change) = frontend
+        .change::<_, _, automerge::InvalidChangeRequest>(None, |d| {
+            let num_items = match d.value_at_path(&automerge::Path::root().key("items")) {
+                Some(automerge::Value::List(items)) => items.len() as u32,
+                _ => panic!("no items in doc"),
+            };
+            d.add_change(automerge::LocalChange::insert(
+                automerge::Path::root().key("items").index(num_items),
+                item.into(),
+            ))
+            .unwrap();
+            Ok(())
+        })
+        .unwrap();
+    let (_, change) = backend.apply_local_change(change.unwrap()).unwrap();
+    EntryContents::Automerge(change.raw_bytes().to_vec())
+}
+
+/// A single change which sets the items of the document to their current
+/// value, i.e. the whole state of the object, without depending on the changes
+/// of `history`
+fn compacted_items(history: &History) -> EntryContents {
+    let (current, _) = evaluate_history(history);
+    let items = current
+        .get_value(&automerge::Path::root().key("items"))
+        .expect("no items in doc");
+    let mut frontend = automerge::Frontend::new();
+    let mut backend = automerge::Backend::new();
+    let (_, change) = frontend
+        .change::<_, _, automerge::InvalidChangeRequest>(None, |d| {
+            d.add_change(automerge::LocalChange::set(
+                automerge::Path::root().key("items"),
+                items,
+            ))?;
+            Ok(())
+        })
+        .unwrap();
+    let (_, change) = backend.apply_local_change(change.unwrap()).unwrap();
+    EntryContents::Automerge(change.raw_bytes().to_vec())
+}
+
+/// Whether `compacted` on its own evaluates to the same value as `history`
+fn compaction_matches(history: &History, compacted: &EntryContents) -> bool {
+    let (expected, _) = evaluate_history(history);
+    let mut backend = automerge::Backend::new();
+    match compacted {
+        EntryContents::Automerge(bytes) => {
+            let change = match automerge::Change::from_bytes(bytes.clone()) {
+                Ok(change) => change,
+                Err(_) => return false,
+            };
+            if backend.apply_changes(vec![change]).is_err() {
+                return false;
+            }
+        },
+    }
+    let mut frontend = automerge::Frontend::new();
+    frontend.apply_patch(backend.get_patch().unwrap()).unwrap();
+    frontend.state().to_json() == expected.state().to_json()
+}
+
+fn evaluate_history(history: &History) -> (automerge::Frontend, automerge::Backend) {
+    let backend = history.traverse(
+        automerge::Backend::new(),
+        |mut backend, change| match change.contents() {
+            EntryContents::Automerge(bytes) => {
+                let change = automerge::Change::from_bytes(bytes.clone()).unwrap();
+                backend.apply_changes(vec![change]).unwrap();
+                std::ops::ControlFlow::Continue(backend)
+            },
+        },
+    );
+    let mut frontend = automerge::Frontend::new();
+    let patch = backend.get_patch().unwrap();
+    frontend.apply_patch(patch).unwrap();
+    (frontend, backend)
+}
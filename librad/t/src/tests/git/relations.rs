@@ -0,0 +1,185 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::str::FromStr as _;
+
+use it_helpers::fixed::TestProject;
+use librad::{
+    canonical::json::{ToCjson, Value},
+    git::{
+        identities::relations::{self, Persona},
+        storage::Storage,
+        tracking,
+        Urn,
+    },
+    identities::{
+        relations::{Peer, Status},
+        SomeIdentity,
+    },
+    paths::Paths,
+    PeerId,
+    SecretKey,
+};
+
+fn summary(peer: Peer<Status<Persona>>) -> Peer<Status<Urn>> {
+    peer.map(|status| status.map(|persona| persona.person().urn()))
+}
+
+#[test]
+fn tracked_peer_matches_tracked() {
+    let tmp = tempfile::tempdir().unwrap();
+    let paths = Paths::from_root(tmp.path()).unwrap();
+    let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+    let proj = TestProject::create(&storage).unwrap();
+    let urn = proj.project.urn();
+
+    let peers = [
+        PeerId::from(SecretKey::new()),
+        PeerId::from(SecretKey::new()),
+    ];
+    for peer in peers {
+        assert!(tracking::track(
+            &storage,
+            &urn,
+            Some(peer),
+            tracking::Config::default(),
+            tracking::policy::Track::Any,
+        )
+        .unwrap()
+        .is_ok());
+    }
+
+    let all = relations::tracked(&storage, &urn).unwrap();
+    assert_eq!(all.len(), peers.len());
+    for peer in all {
+        let single = relations::tracked_peer(&storage, &urn, peer.peer_id())
+            .unwrap()
+            .expect("peer should be tracked");
+        assert_eq!(summary(single), summary(peer));
+    }
+
+    let untracked = PeerId::from(SecretKey::new());
+    assert!(relations::tracked_peer(&storage, &urn, untracked)
+        .unwrap()
+        .is_none());
+}
+
+/// The expected shape of a serialized [`Persona`], see [`shape`].
+///
+/// Changing this file means changing the JSON consumed by external clients, so
+/// make sure that is intended.
+const PERSONA_SHAPE: &str = include_str!("relations/persona.json");
+
+/// Replace every leaf of `val` with the name of its type, and every key which
+/// depends on the particular keys or identities involved with a placeholder.
+fn shape(val: Value) -> Value {
+    match val {
+        Value::Object(obj) => obj
+            .into_iter()
+            .map(|(key, val)| {
+                let key = if PeerId::from_str(&key).is_ok() {
+                    "<peer>".to_string()
+                } else if key.starts_with("ids/") {
+                    "ids/<urn>".to_string()
+                } else {
+                    key.as_str().to_string()
+                };
+                (key, shape(val))
+            })
+            .collect(),
+        Value::Array(arr) => arr.into_iter().map(shape).collect::<Vec<_>>().into_cjson(),
+        leaf => leaf.ty_name().into_cjson(),
+    }
+}
+
+#[test]
+fn persona_shape() {
+    let tmp = tempfile::tempdir().unwrap();
+    let paths = Paths::from_root(tmp.path()).unwrap();
+    let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+    let proj = TestProject::create(&storage).unwrap();
+
+    let persona = Persona::load(&storage, &SomeIdentity::Project(proj.project), None)
+        .unwrap()
+        .expect("local persona should exist");
+    assert_eq!(
+        shape(persona.into_cjson()),
+        PERSONA_SHAPE.parse::<Value>().unwrap(),
+        "the JSON representation of `Persona` changed"
+    );
+}
+
+#[test]
+fn tracked_with_local() {
+    let tmp = tempfile::tempdir().unwrap();
+    let paths = Paths::from_root(tmp.path()).unwrap();
+    let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+    let proj = TestProject::create(&storage).unwrap();
+    let urn = proj.project.urn();
+    let local = *storage.peer_id();
+
+    let remote = PeerId::from(SecretKey::new());
+    assert!(tracking::track(
+        &storage,
+        &urn,
+        Some(remote),
+        tracking::Config::default(),
+        tracking::policy::Track::Any,
+    )
+    .unwrap()
+    .is_ok());
+
+    let all = relations::tracked_with_local(&storage, &urn, local).unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(
+        all.iter().filter(|peer| peer.peer_id() == local).count(),
+        1,
+        "local peer should appear exactly once"
+    );
+
+    let (peer_id, status) = all[0].clone().local().expect("first entry is local");
+    assert_eq!(peer_id, local);
+    match status {
+        Status::Replicated(replicated) => {
+            assert!(replicated.user.delegate());
+            assert_eq!(replicated.user.person().urn(), proj.owner.urn());
+        },
+        Status::NotReplicated => panic!("local peer should be replicated"),
+    }
+
+    assert_eq!(
+        summary(all[1].clone()),
+        Peer::Remote {
+            peer_id: remote,
+            status: Status::NotReplicated
+        }
+    );
+}
+
+#[test]
+fn tracked_with_local_non_delegate() {
+    let tmp = tempfile::tempdir().unwrap();
+    let paths = Paths::from_root(tmp.path()).unwrap();
+    let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+    let proj = TestProject::create(&storage).unwrap();
+    let urn = proj.project.urn();
+    let local = PeerId::from(SecretKey::new());
+
+    let all = relations::tracked_with_local(&storage, &urn, local).unwrap();
+    assert_eq!(all.len(), 1);
+
+    let (peer_id, status) = all[0].clone().local().expect("first entry is local");
+    assert_eq!(peer_id, local);
+    match status {
+        Status::Replicated(replicated) => {
+            assert!(
+                !replicated.user.delegate(),
+                "local peer is not a delegate of the project"
+            );
+            assert_eq!(replicated.user.person().urn(), proj.owner.urn());
+        },
+        Status::NotReplicated => panic!("local rad/self should be replicated"),
+    }
+}
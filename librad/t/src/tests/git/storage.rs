@@ -4,4 +4,5 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 mod config;
+mod tree;
 mod watch;
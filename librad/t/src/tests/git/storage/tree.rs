@@ -0,0 +1,65 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use it_helpers::{fixed::TestProject, tmp};
+use librad::{
+    git::{storage::ReadOnlyStorage as _, util::quick_commit},
+    git_ext::tree,
+    reflike,
+    PeerId,
+    SecretKey,
+};
+
+#[test]
+fn namespace_refs() {
+    let store = tmp::storage(SecretKey::new());
+    let TestProject { project, owner } = TestProject::create(&store).unwrap();
+    let urn = project.urn();
+    let remote = PeerId::from(SecretKey::new());
+
+    let head = quick_commit(
+        &store,
+        &urn.clone().with_path(reflike!("refs/heads/main")),
+        vec![("README", tree::blob(b"sup"))].into_iter().collect(),
+        "initial",
+    )
+    .unwrap();
+    {
+        let repo = git2::Repository::open_bare(store.path()).unwrap();
+        let prefix = format!("refs/namespaces/{}/refs", urn.encode_id());
+        for name in [
+            "tags/v1".to_owned(),
+            format!("remotes/{}/heads/main", remote),
+            "cobs/xyz.radicle.issue/hnrkyghsrokxzxpy9pww69xr11dr9q7edbxfo".to_owned(),
+            "stuff/other".to_owned(),
+        ] {
+            repo.reference(&format!("{}/{}", prefix, name), head, false, "test")
+                .unwrap();
+        }
+    }
+
+    let refs = store.namespace_refs(&urn).unwrap();
+    let head = head.into();
+
+    assert_eq!(refs.local.heads.get("main"), Some(&head));
+    assert_eq!(refs.local.tags.get("v1"), Some(&head));
+    assert_eq!(
+        refs.local
+            .cobs
+            .get("xyz.radicle.issue/hnrkyghsrokxzxpy9pww69xr11dr9q7edbxfo"),
+        Some(&head)
+    );
+    assert_eq!(refs.local.other.get("stuff/other"), Some(&head));
+    assert!(refs.local.rad.contains_key("id"));
+    assert!(refs
+        .local
+        .rad
+        .contains_key(&format!("ids/{}", owner.urn().encode_id())));
+
+    assert_eq!(refs.remotes.len(), 1);
+    let remote_refs = &refs.remotes[&remote];
+    assert_eq!(remote_refs.heads.get("main"), Some(&head));
+    assert!(remote_refs.rad.is_empty());
+}
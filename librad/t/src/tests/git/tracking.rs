@@ -3,26 +3,35 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, convert::TryFrom as _};
 
 use git_ref_format::{lit, name, Namespaced, Qualified};
-use it_helpers::git::create_commit;
+use it_helpers::{fixed::TestProject, git::create_commit};
 use librad::{
     git::{
+        identities,
         storage::{ReadOnlyStorage as _, Storage},
         tracking::{
+            effective_config,
+            get,
+            get_many,
             is_tracked,
             migration,
             policy,
             track,
+            tracked,
             tracked_peers,
             untrack,
             v1,
             Config,
+            Lenient,
+            RefName,
             UntrackArgs,
+            Warning,
         },
         Urn,
     },
+    git_ext::RefLike,
     paths::Paths,
     reflike,
     PeerId,
@@ -168,6 +177,136 @@ fn track_yields_tracked() {
     }
 }
 
+#[test]
+fn tracked_agrees_with_tracked_peers() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let paths = Paths::from_root(&tmp).unwrap();
+        let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+        let urns = [
+            Urn::new(git2::Oid::zero().into()),
+            Urn::new(
+                git2::Oid::hash_object(git2::ObjectType::Blob, b"tracked")
+                    .unwrap()
+                    .into(),
+            ),
+        ];
+
+        for urn in &urns {
+            track(&storage, urn, None, Config::default(), policy::Track::Any)
+                .unwrap()
+                .unwrap();
+            for _ in 0..3 {
+                track(
+                    &storage,
+                    urn,
+                    Some(PeerId::from(SecretKey::new())),
+                    Config::default(),
+                    policy::Track::Any,
+                )
+                .unwrap()
+                .unwrap();
+            }
+        }
+
+        let filters = urns.iter().map(Some).chain(std::iter::once(None));
+        for filter in filters {
+            let entries = tracked(&storage, filter)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let peers = tracked_peers(&storage, filter)
+                .unwrap()
+                .collect::<Result<BTreeSet<_>, _>>()
+                .unwrap();
+
+            assert_eq!(
+                entries.iter().filter(|t| t.peer_id().is_none()).count(),
+                filter.map_or(urns.len(), |_| 1),
+                "expected one default entry per urn"
+            );
+            assert_eq!(
+                entries
+                    .iter()
+                    .filter_map(|t| t.peer_id())
+                    .collect::<BTreeSet<_>>(),
+                peers
+            );
+            assert!(!peers.is_empty());
+        }
+    }
+}
+
+#[test]
+fn tracked_with_identities() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let paths = Paths::from_root(&tmp).unwrap();
+        let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+        let proj = TestProject::create(&storage).unwrap();
+        let urn = proj.project.urn();
+        let raw = git2::Repository::open(paths.git_dir()).unwrap();
+
+        // Two peers share the same `rad/self`, the third has none
+        let peers = (0..3)
+            .map(|_| PeerId::from(SecretKey::new()))
+            .collect::<Vec<_>>();
+        for peer in &peers[..2] {
+            raw.reference(
+                &format!(
+                    "refs/namespaces/{}/refs/remotes/{}/rad/self",
+                    urn.encode_id(),
+                    peer
+                ),
+                proj.owner.content_id.into(),
+                false,
+                "rad/self",
+            )
+            .unwrap();
+        }
+        for peer in &peers {
+            track(
+                &storage,
+                &urn,
+                Some(*peer),
+                Config::default(),
+                policy::Track::Any,
+            )
+            .unwrap()
+            .unwrap();
+        }
+
+        let resolved = tracked(&storage, Some(&urn))
+            .unwrap()
+            .with_identities(&storage)
+            .map(|r| r.map(|(tracked, id)| (tracked.peer_id(), id.map(|id| id.content_id()))))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let manual = tracked(&storage, Some(&urn))
+            .unwrap()
+            .map(|r| {
+                let tracked = r.unwrap();
+                let peer = tracked.peer_id().unwrap();
+                let rad_self = urn.clone().with_path(
+                    RefLike::try_from(format!("refs/remotes/{}/rad/self", peer)).unwrap(),
+                );
+                let id = identities::any::get(&storage, &rad_self)
+                    .unwrap()
+                    .map(|id| id.content_id());
+                (Some(peer), id)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(resolved.len(), peers.len());
+        assert_eq!(resolved, manual);
+        assert_eq!(
+            resolved.iter().filter(|(_, id)| id.is_some()).count(),
+            2,
+            "expected two peers with a resolved identity"
+        );
+    }
+}
+
 #[test]
 fn tracked_ignores_urn_path() {
     let tmp = tempfile::tempdir().unwrap();
@@ -198,6 +337,192 @@ fn tracked_ignores_urn_path() {
     }
 }
 
+#[test]
+fn lenient_skips_symbolic_refs() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let paths = Paths::from_root(&tmp).unwrap();
+        let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+        let remote_peer = PeerId::from(SecretKey::new());
+        let bogus_peer = PeerId::from(SecretKey::new());
+        let urn = Urn::new(git2::Oid::zero().into());
+
+        assert!(track(
+            &storage,
+            &urn,
+            Some(remote_peer),
+            Config::default(),
+            policy::Track::Any,
+        )
+        .unwrap()
+        .is_ok());
+
+        let target = git_ref_format::RefString::from(&RefName::new(&urn, remote_peer));
+        let symbolic = git_ref_format::RefString::from(&RefName::new(&urn, bogus_peer));
+        storage
+            .as_raw()
+            .reference_symbolic(
+                symbolic.as_str(),
+                target.as_str(),
+                false,
+                "symbolic tracking ref",
+            )
+            .unwrap();
+
+        assert!(
+            tracked_peers(&storage, Some(&urn))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .is_err(),
+            "strict reads should fail on symbolic refs"
+        );
+
+        let lenient = Lenient::new(&storage);
+        assert_eq!(
+            vec![remote_peer],
+            tracked_peers(&lenient, Some(&urn))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        );
+        assert!(!is_tracked(&lenient, &urn, Some(bogus_peer)).unwrap());
+        assert_eq!(
+            lenient.warnings(),
+            vec![
+                Warning::SymbolicRef {
+                    name: symbolic.to_string()
+                };
+                2
+            ]
+        );
+    }
+}
+
+#[test]
+fn get_many_matches_get() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let paths = Paths::from_root(&tmp).unwrap();
+        let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+        let urn = Urn::new(git2::Oid::zero().into());
+        let other = Urn::new(git2::Oid::from_bytes(&[1; 20]).unwrap().into());
+        let tracked = [
+            PeerId::from(SecretKey::new()),
+            PeerId::from(SecretKey::new()),
+        ];
+        let untracked = PeerId::from(SecretKey::new());
+
+        for peer in tracked.iter().map(|peer| Some(*peer)).chain(Some(None)) {
+            assert!(
+                track(&storage, &urn, peer, Config::default(), policy::Track::Any)
+                    .unwrap()
+                    .is_ok()
+            );
+        }
+        // Tracked for a different urn, so must not show up below
+        assert!(track(
+            &storage,
+            &other,
+            Some(untracked),
+            Config::default(),
+            policy::Track::Any
+        )
+        .unwrap()
+        .is_ok());
+
+        let peers = [Some(tracked[1]), None, Some(untracked), Some(tracked[0])];
+        let summary = |tracked: Option<librad::git::tracking::Tracked>| {
+            tracked.map(|t| (t.urn().clone(), t.peer_id(), t.config().clone()))
+        };
+        let batched = get_many(&storage, &urn, &peers)
+            .unwrap()
+            .into_iter()
+            .map(summary)
+            .collect::<Vec<_>>();
+        let individual = peers
+            .iter()
+            .map(|peer| get(&storage, &urn, *peer).unwrap())
+            .map(summary)
+            .collect::<Vec<_>>();
+
+        assert_eq!(batched, individual);
+        assert_eq!(
+            batched.iter().map(Option::is_some).collect::<Vec<_>>(),
+            vec![true, true, false, true]
+        );
+    }
+}
+
+#[test]
+fn effective_config_prefers_peer() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let paths = Paths::from_root(&tmp).unwrap();
+        let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+        let remote_peer = PeerId::from(SecretKey::new());
+        let urn = Urn::new(git2::Oid::zero().into());
+        let default = Config {
+            data: false,
+            ..Config::default()
+        };
+
+        assert!(track(&storage, &urn, None, default, policy::Track::Any)
+            .unwrap()
+            .is_ok());
+        assert!(track(
+            &storage,
+            &urn,
+            Some(remote_peer),
+            Config::default(),
+            policy::Track::Any,
+        )
+        .unwrap()
+        .is_ok());
+        assert_eq!(
+            effective_config(&storage, &urn, remote_peer).unwrap(),
+            Some(Config::default())
+        );
+    }
+}
+
+#[test]
+fn effective_config_falls_back_to_default() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let paths = Paths::from_root(&tmp).unwrap();
+        let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+        let remote_peer = PeerId::from(SecretKey::new());
+        let urn = Urn::new(git2::Oid::zero().into());
+        let default = Config {
+            data: false,
+            ..Config::default()
+        };
+
+        assert!(
+            track(&storage, &urn, None, default.clone(), policy::Track::Any)
+                .unwrap()
+                .is_ok()
+        );
+        assert_eq!(
+            effective_config(&storage, &urn, remote_peer).unwrap(),
+            Some(default)
+        );
+    }
+}
+
+#[test]
+fn effective_config_none() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let paths = Paths::from_root(&tmp).unwrap();
+        let storage = Storage::open(&paths, SecretKey::new()).unwrap();
+        let remote_peer = PeerId::from(SecretKey::new());
+        let urn = Urn::new(git2::Oid::zero().into());
+
+        assert_eq!(effective_config(&storage, &urn, remote_peer).unwrap(), None);
+    }
+}
+
 #[test]
 fn untrack_with_prune() {
     let tmp = tempfile::tempdir().unwrap();
@@ -0,0 +1,154 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use librad::{
+    git::Urn,
+    net::{
+        peer::{
+            client::{self, config::AddrFamily, error},
+            Client,
+        },
+        quic::{ConnectPeer, Ingress},
+        Network,
+    },
+    paths::Paths,
+    PeerId,
+    SecretKey,
+};
+use link_async::Spawner;
+
+/// An endpoint which fails to connect, recording the addresses of each
+/// attempt.
+#[derive(Clone, Default)]
+struct Recording(Arc<Mutex<Vec<Vec<SocketAddr>>>>);
+
+#[async_trait::async_trait]
+impl ConnectPeer for Recording {
+    async fn connect<'a, Addrs>(&self, _: PeerId, addrs: Addrs) -> Option<Ingress<'a>>
+    where
+        Addrs: IntoIterator<Item = SocketAddr> + Send,
+        Addrs::IntoIter: Send,
+    {
+        self.0.lock().unwrap().push(addrs.into_iter().collect());
+        None
+    }
+}
+
+/// The addresses of each connection attempt made by a [`Client`] configured
+/// with `family`, when replicating from a peer at [`addrs`].
+async fn connect_attempts(family: AddrFamily) -> Vec<Vec<SocketAddr>> {
+    let tmp = tempfile::tempdir().unwrap();
+    let config = client::Config {
+        signer: SecretKey::new(),
+        paths: Paths::from_root(tmp.path()).unwrap(),
+        replication: Default::default(),
+        user_storage: Default::default(),
+        network: Network::default(),
+        connections: client::config::Connections {
+            family,
+            ..Default::default()
+        },
+    };
+    let spawner = Arc::new(Spawner::from_current().unwrap());
+    let endpoint = Recording::default();
+    let client = Client::new(config, spawner, endpoint.clone()).unwrap();
+    let urn = Urn::new(git2::Oid::zero().into());
+
+    let res = client
+        .replicate((PeerId::from(SecretKey::new()), addrs()), urn, None)
+        .await;
+    let attempts = endpoint.0.lock().unwrap().clone();
+    assert!(matches!(res, Err(error::Replicate::NoConnection(_))));
+    attempts
+}
+
+fn addrs() -> Vec<SocketAddr> {
+    vec![
+        "[::1]:8776".parse().unwrap(),
+        "127.0.0.1:8776".parse().unwrap(),
+        "[::2]:8776".parse().unwrap(),
+        "127.0.0.2:8776".parse().unwrap(),
+    ]
+}
+
+#[test]
+fn only_v4_skips_v6() {
+    let (attempt, fallback) = AddrFamily::OnlyV4.attempts(addrs());
+    assert_eq!(
+        attempt,
+        vec![
+            "127.0.0.1:8776".parse().unwrap(),
+            "127.0.0.2:8776".parse().unwrap()
+        ]
+    );
+    assert!(fallback.is_empty());
+}
+
+#[test]
+fn prefer_v4_falls_back_to_v6() {
+    let (attempt, fallback) = AddrFamily::PreferV4.attempts(addrs());
+    assert!(attempt.iter().all(SocketAddr::is_ipv4));
+    assert_eq!(
+        fallback,
+        vec!["[::1]:8776".parse().unwrap(), "[::2]:8776".parse().unwrap()]
+    );
+
+    let v6 = addrs()
+        .into_iter()
+        .filter(SocketAddr::is_ipv6)
+        .collect::<Vec<_>>();
+    assert_eq!(AddrFamily::PreferV4.attempts(v6.clone()), (v6, vec![]));
+}
+
+#[test]
+fn any_keeps_order() {
+    assert_eq!(AddrFamily::Any.attempts(addrs()), (addrs(), vec![]));
+}
+
+#[test]
+fn roundtrip() {
+    for family in [
+        AddrFamily::Any,
+        AddrFamily::PreferV4,
+        AddrFamily::PreferV6,
+        AddrFamily::OnlyV4,
+        AddrFamily::OnlyV6,
+    ] {
+        assert_eq!(family.to_string().parse::<AddrFamily>(), Ok(family))
+    }
+}
+
+#[tokio::test]
+async fn client_attempts_any_at_once() {
+    assert_eq!(connect_attempts(AddrFamily::Any).await, vec![addrs()]);
+}
+
+#[tokio::test]
+async fn client_attempts_only_family() {
+    assert_eq!(
+        connect_attempts(AddrFamily::OnlyV6).await,
+        vec![vec![
+            "[::1]:8776".parse().unwrap(),
+            "[::2]:8776".parse().unwrap()
+        ]]
+    );
+}
+
+#[tokio::test]
+async fn client_falls_back_from_preferred_family() {
+    let v4: Vec<SocketAddr> = vec![
+        "127.0.0.1:8776".parse().unwrap(),
+        "127.0.0.2:8776".parse().unwrap(),
+    ];
+    let v6: Vec<SocketAddr> = vec!["[::1]:8776".parse().unwrap(), "[::2]:8776".parse().unwrap()];
+    assert_eq!(
+        connect_attempts(AddrFamily::PreferV4).await,
+        vec![v4.clone(), v6.clone()]
+    );
+    assert_eq!(connect_attempts(AddrFamily::PreferV6).await, vec![v6, v4]);
+}
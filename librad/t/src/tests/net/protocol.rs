@@ -4,4 +4,8 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 mod broadcast;
+mod cache;
+mod event;
 mod gossip;
+mod membership;
+mod request_pull;
@@ -0,0 +1,54 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::time::Duration;
+
+use librad::net::protocol::cache::urns::{Config, Event};
+
+#[test]
+fn below_threshold_is_incremental() {
+    let config = Config {
+        rebuilt_threshold: 2,
+    };
+    assert_matches!(
+        config.event(Duration::ZERO, 3, 4),
+        Event::Incremental {
+            len_old: 3,
+            len_new: 4,
+            ..
+        }
+    );
+    assert_matches!(
+        config.event(Duration::ZERO, 4, 3),
+        Event::Incremental { .. }
+    );
+    assert_matches!(
+        config.event(Duration::ZERO, 3, 3),
+        Event::Incremental { .. }
+    );
+}
+
+#[test]
+fn at_threshold_is_rebuilt() {
+    let config = Config {
+        rebuilt_threshold: 2,
+    };
+    assert_matches!(
+        config.event(Duration::ZERO, 3, 5),
+        Event::Rebuilt {
+            len_old: 3,
+            len_new: 5,
+            ..
+        }
+    );
+    assert_matches!(config.event(Duration::ZERO, 5, 1), Event::Rebuilt { .. });
+}
+
+#[test]
+fn default_always_rebuilt() {
+    let config = Config::default();
+    assert_matches!(config.event(Duration::ZERO, 3, 3), Event::Rebuilt { .. });
+    assert_matches!(config.event(Duration::ZERO, 3, 4), Event::Rebuilt { .. });
+}
@@ -0,0 +1,159 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{net::SocketAddr, time::Duration};
+
+use futures::StreamExt as _;
+use librad::{
+    data::BoundedVec,
+    git::Urn,
+    git_ext,
+    net::protocol::{
+        broadcast::PutResult,
+        event::{
+            upstream::{self, predicate, ExpectError},
+            Upstream,
+        },
+        gossip::Payload,
+        PeerAdvertisement,
+        PeerInfo,
+    },
+    PeerId,
+    SecretKey,
+};
+
+fn urn(seed: &[u8]) -> Urn {
+    Urn::new(git_ext::Oid::from(
+        git2::Oid::hash_object(git2::ObjectType::Blob, seed).unwrap(),
+    ))
+}
+
+fn put(urn: Urn) -> Upstream {
+    let addr: SocketAddr = ([127, 0, 0, 1], 12345).into();
+    let payload = Payload {
+        urn,
+        rev: None,
+        origin: None,
+        previous: None,
+    };
+    upstream::Gossip::Put {
+        provider: PeerInfo {
+            peer_id: PeerId::from(SecretKey::new()),
+            advertised_info: PeerAdvertisement::new(addr),
+            seen_addrs: BoundedVec::try_from_length(vec![]).unwrap(),
+        },
+        payload: payload.clone(),
+        result: PutResult::Applied(payload),
+    }
+    .into()
+}
+
+fn put_urn(event: &Upstream) -> Urn {
+    match event {
+        Upstream::Gossip(gossip) => match gossip.as_ref() {
+            upstream::Gossip::Put { payload, .. } => payload.urn.clone(),
+        },
+        _ => panic!("expected gossip event"),
+    }
+}
+
+#[tokio::test]
+async fn scoped_to_urn() {
+    let one = urn(b"one");
+    let two = urn(b"two");
+    let events = futures::stream::iter(vec![
+        Ok(put(one.clone())),
+        Ok(put(two.clone())),
+        Ok(upstream::Endpoint::Down.into()),
+        Ok(put(two.clone())),
+        Ok(put(one
+            .clone()
+            .with_path(librad::reflike!("refs/heads/main")))),
+    ]);
+
+    let scoped = upstream::scoped(one.clone(), events)
+        .map(|event| put_urn(&event.unwrap()))
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(scoped.len(), 2);
+    assert!(scoped.iter().all(|urn| urn.id == one.id));
+}
+
+type Predicate = Box<dyn Fn(&Upstream) -> bool>;
+
+fn endpoint_down(event: &Upstream) -> bool {
+    matches!(event, Upstream::Endpoint(upstream::Endpoint::Down))
+}
+
+#[tokio::test]
+async fn expect_any_reports_matching_predicate() {
+    let one = urn(b"one");
+    let two = urn(b"two");
+    let events = futures::stream::iter(vec![
+        Ok(put(one.clone())),
+        Ok(upstream::Endpoint::Down.into()),
+        Ok(put(two.clone())),
+    ]);
+    let predicates: Vec<Predicate> = vec![Box::new(predicate::urn(two)), Box::new(endpoint_down)];
+
+    let (ix, event) = upstream::expect_any(events, &predicates, Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert_eq!(ix, 1);
+    assert!(endpoint_down(&event));
+}
+
+#[tokio::test]
+async fn expect_all_collects_in_predicate_order() {
+    let one = urn(b"one");
+    let two = urn(b"two");
+    let events = futures::stream::iter(vec![
+        Ok(put(two.clone())),
+        Ok(upstream::Endpoint::Down.into()),
+        Ok(put(two.clone())),
+        Ok(put(one.clone())),
+    ]);
+    let predicates: Vec<Predicate> = vec![
+        Box::new(predicate::urn(one.clone())),
+        Box::new(predicate::urn(two.clone())),
+    ];
+
+    let matched = upstream::expect_all(events, &predicates, Duration::from_secs(1))
+        .await
+        .unwrap()
+        .iter()
+        .map(put_urn)
+        .collect::<Vec<_>>();
+    assert_eq!(matched, vec![one, two]);
+}
+
+#[tokio::test]
+async fn expect_all_lost() {
+    let one = urn(b"one");
+    let two = urn(b"two");
+    let events = futures::stream::iter(vec![Ok(put(one.clone()))]);
+    let predicates: Vec<Predicate> =
+        vec![Box::new(predicate::urn(one)), Box::new(predicate::urn(two))];
+
+    assert_matches!(
+        upstream::expect_all(events, &predicates, Duration::from_secs(1)).await,
+        Err(ExpectError::Lost)
+    )
+}
+
+#[tokio::test]
+async fn expect_any_timeout() {
+    let predicates: Vec<Predicate> = vec![Box::new(endpoint_down)];
+
+    assert_matches!(
+        upstream::expect_any(
+            futures::stream::pending(),
+            &predicates,
+            Duration::from_millis(10)
+        )
+        .await,
+        Err(ExpectError::Timeout)
+    )
+}
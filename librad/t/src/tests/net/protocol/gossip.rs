@@ -22,6 +22,7 @@ fn roundtrip_payload() {
         urn: Urn::new(git_ext::Oid::from(git2::Oid::zero())),
         rev: Some(Rev::Git(*OID)),
         origin: Some(PeerId::from(SecretKey::new())),
+        previous: Some(Rev::Git(git2::Oid::zero())),
     };
 
     roundtrip::cbor(payload)
@@ -0,0 +1,123 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::time::{Duration, Instant};
+
+use librad::{
+    net::protocol::membership::{self, Backoff, Hpv, Reconnects},
+    PeerId,
+    SecretKey,
+};
+
+use rand::{rngs::StdRng, SeedableRng as _};
+
+use crate::gen::protocol::blank_peer_info;
+
+fn backoff() -> Backoff {
+    Backoff {
+        initial: Duration::from_secs(1),
+        max: Duration::from_secs(3),
+        max_attempts: 3,
+    }
+}
+
+#[test]
+fn delay_doubles_up_to_max() {
+    let backoff = backoff();
+    assert_eq!(backoff.delay(0), Duration::from_secs(1));
+    assert_eq!(backoff.delay(1), Duration::from_secs(2));
+    assert_eq!(backoff.delay(2), Duration::from_secs(3));
+    assert_eq!(backoff.delay(64), Duration::from_secs(3));
+}
+
+#[test]
+fn lost_connection_schedules_reconnect() {
+    let peer = PeerId::from(SecretKey::new());
+    let mut reconnects = Reconnects::new(backoff());
+    let lost = Instant::now();
+
+    reconnects.lost(peer, lost);
+    assert_eq!(
+        reconnects.next_attempt(&peer),
+        Some(lost + Duration::from_secs(1))
+    );
+    assert!(
+        reconnects.due(lost).is_empty(),
+        "not due before the backoff"
+    );
+    assert_eq!(reconnects.due(lost + Duration::from_secs(1)), vec![peer]);
+
+    let retry = lost + Duration::from_secs(1);
+    assert_eq!(
+        reconnects.next_attempt(&peer),
+        Some(retry + Duration::from_secs(2))
+    );
+}
+
+#[test]
+fn gives_up_after_max_attempts() {
+    let peer = PeerId::from(SecretKey::new());
+    let mut reconnects = Reconnects::new(backoff());
+    let mut now = Instant::now();
+
+    reconnects.lost(peer, now);
+    for _ in 0..3 {
+        now += Duration::from_secs(3);
+        assert_eq!(reconnects.due(now), vec![peer]);
+    }
+    now += Duration::from_secs(3);
+    assert!(reconnects.due(now).is_empty());
+    assert!(reconnects.is_empty());
+}
+
+#[test]
+fn established_cancels_reconnect() {
+    let peer = PeerId::from(SecretKey::new());
+    let mut reconnects = Reconnects::new(backoff());
+    let lost = Instant::now();
+
+    reconnects.lost(peer, lost);
+    reconnects.established(&peer);
+    assert!(reconnects.due(lost + Duration::from_secs(60)).is_empty());
+}
+
+#[test]
+fn check_interval_is_never_zero() {
+    let backoff = Backoff {
+        initial: Duration::ZERO,
+        ..backoff()
+    };
+    assert_eq!(backoff.check_interval(), Backoff::MIN_CHECK_INTERVAL);
+    assert_eq!(
+        self::backoff().check_interval(),
+        Duration::from_secs(1),
+        "an initial delay above the minimum is used as is"
+    );
+}
+
+#[tokio::test]
+async fn lost_active_peer_is_due_for_reconnect() {
+    let params = membership::Params {
+        reconnect: Backoff {
+            initial: Duration::ZERO,
+            ..backoff()
+        },
+        ..Default::default()
+    };
+    let (hpv, _periodic) = Hpv::<_, ()>::new(
+        PeerId::from(SecretKey::new()),
+        StdRng::seed_from_u64(0),
+        params,
+    );
+    let peer = PeerId::from(SecretKey::new());
+
+    hpv.connection_established(blank_peer_info(peer));
+    assert!(hpv.reconnect_due().is_empty());
+
+    hpv.connection_lost(peer);
+    let due = hpv.reconnect_due();
+    assert_eq!(
+        due.iter().map(|info| info.peer_id).collect::<Vec<_>>(),
+        vec![peer]
+    );
+}
@@ -0,0 +1,299 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{sync::Arc, time::Duration};
+
+use librad::{
+    git::{
+        storage::{self, pool, Storage},
+        tracking,
+        Urn,
+    },
+    net::protocol::request_pull::{
+        self,
+        error,
+        guard,
+        AllowlistGuard,
+        ErrorCode,
+        Guard as _,
+        Limits,
+        Slot,
+        Slots,
+        TrackingGuard,
+    },
+    paths::Paths,
+    PeerId,
+    SecretKey,
+};
+use link_async::Spawner;
+
+struct Fixture {
+    _tmp: tempfile::TempDir,
+    paths: Paths,
+    storage: Storage,
+    pool: storage::Pool<Storage>,
+    guard: TrackingGuard<storage::Pool<Storage>>,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = Paths::from_root(tmp.path()).unwrap();
+        let signer = SecretKey::new();
+        let storage = Storage::open(&paths, signer.clone()).unwrap();
+        let pool = storage::Pool::new(
+            pool::ReadWriteConfig::new(paths.clone(), signer, pool::Initialised::no()),
+            1,
+        );
+
+        Self {
+            _tmp: tmp,
+            paths,
+            storage,
+            guard: TrackingGuard::new(pool.clone()),
+            pool,
+        }
+    }
+
+    fn track(&self, urn: &Urn, peer: Option<PeerId>) {
+        tracking::track(
+            &self.storage,
+            urn,
+            peer,
+            tracking::Config::default(),
+            tracking::policy::Track::Any,
+        )
+        .unwrap()
+        .unwrap();
+    }
+}
+
+#[test]
+fn tracked_peer_is_allowed() {
+    let fixture = Fixture::new();
+    let urn = Urn::new(git2::Oid::zero().into());
+    let peer = PeerId::from(SecretKey::new());
+    fixture.track(&urn, Some(peer));
+
+    assert!(fixture.guard.guard(&peer, &urn).is_ok());
+}
+
+#[test]
+fn untracked_peer_is_denied() {
+    let fixture = Fixture::new();
+    let urn = Urn::new(git2::Oid::zero().into());
+    let tracked = PeerId::from(SecretKey::new());
+    let untracked = PeerId::from(SecretKey::new());
+    fixture.track(&urn, Some(tracked));
+
+    assert!(matches!(
+        fixture.guard.guard(&untracked, &urn),
+        Err(guard::Error::Untracked { .. })
+    ));
+    assert!(!tracking::is_tracked(&fixture.storage, &urn, Some(untracked)).unwrap());
+}
+
+#[test]
+fn tracked_urn_tracks_peer() {
+    let fixture = Fixture::new();
+    let urn = Urn::new(git2::Oid::zero().into());
+    let peer = PeerId::from(SecretKey::new());
+    fixture.track(&urn, None);
+
+    assert!(fixture.guard.guard(&peer, &urn).is_ok());
+    assert!(tracking::is_tracked(&fixture.storage, &urn, Some(peer)).unwrap());
+}
+
+#[test]
+fn all_short_circuits_on_denial() {
+    let fixture = Fixture::new();
+    let urn = Urn::new(git2::Oid::zero().into());
+    let allowed = PeerId::from(SecretKey::new());
+    let other = PeerId::from(SecretKey::new());
+    fixture.track(&urn, None);
+
+    let guard = guard::all(vec![
+        guard::erase(AllowlistGuard::new(Some(allowed))),
+        guard::erase(fixture.guard.clone()),
+    ]);
+
+    assert!(guard.guard(&allowed, &urn).is_ok());
+    assert!(tracking::is_tracked(&fixture.storage, &urn, Some(allowed)).unwrap());
+
+    let denied = guard.guard(&other, &urn).unwrap_err();
+    assert!(matches!(denied, guard::Denied::One(err) if err.is::<guard::NotAllowed>()));
+    // The tracking guard never ran, so `other` was not tracked
+    assert!(!tracking::is_tracked(&fixture.storage, &urn, Some(other)).unwrap());
+}
+
+#[test]
+fn any_reports_all_denials() {
+    let fixture = Fixture::new();
+    let urn = Urn::new(git2::Oid::zero().into());
+    let allowed = PeerId::from(SecretKey::new());
+    let tracked = PeerId::from(SecretKey::new());
+    let other = PeerId::from(SecretKey::new());
+    fixture.track(&urn, Some(tracked));
+
+    let guard = guard::any(vec![
+        guard::erase(AllowlistGuard::new(Some(allowed))),
+        guard::erase(fixture.guard.clone()),
+    ]);
+
+    assert!(guard.guard(&allowed, &urn).is_ok());
+    assert!(guard.guard(&tracked, &urn).is_ok());
+    assert_matches!(
+        guard.guard(&other, &urn),
+        Err(guard::Denied::All(denied)) if denied.len() == 2
+    );
+}
+
+#[test]
+fn error_codes() {
+    assert_eq!(error::decode_failed().code, Some(ErrorCode::DecodeFailed));
+    assert_eq!(error::internal_error().code, Some(ErrorCode::Internal));
+    assert_eq!(
+        error::replication_error(error::Replicate::Pool(storage::PoolError::Closed)).code,
+        Some(ErrorCode::Replication)
+    );
+    assert_eq!(
+        error::guard(guard::Error::Untracked {
+            peer: PeerId::from(SecretKey::new()),
+            urn: Urn::new(git2::Oid::zero().into()),
+        })
+        .code,
+        Some(ErrorCode::Unauthorized)
+    );
+    assert_eq!(
+        error::guard_failed(guard::Error::Pool(storage::PoolError::Closed)).code,
+        Some(ErrorCode::Internal)
+    );
+    assert_eq!(error::busy().code, Some(ErrorCode::Busy));
+}
+
+/// A [`request_pull::Guard`] which always fails to run its checks.
+struct Failing;
+
+#[derive(Debug)]
+struct Failed;
+
+impl std::fmt::Display for Failed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("guard failed")
+    }
+}
+
+impl std::error::Error for Failed {}
+
+impl request_pull::Guard for Failing {
+    type Error = Failed;
+    type Output = String;
+
+    fn guard(&self, _: &PeerId, _: &Urn) -> Result<Self::Output, Self::Error> {
+        Err(Failed)
+    }
+
+    fn is_internal(&self, _: &Self::Error) -> bool {
+        true
+    }
+}
+
+#[test]
+fn internal_guard_errors() {
+    let fixture = Fixture::new();
+    let urn = Urn::new(git2::Oid::zero().into());
+    let peer = PeerId::from(SecretKey::new());
+
+    assert!(fixture
+        .guard
+        .is_internal(&guard::Error::Pool(storage::PoolError::Closed)));
+    let untracked = fixture.guard.guard(&peer, &urn).unwrap_err();
+    assert!(!fixture.guard.is_internal(&untracked));
+
+    // A composed guard fails internally if any guard it consulted did
+    let any = guard::any(vec![
+        guard::erase(AllowlistGuard::new(None)),
+        guard::erase(Failing),
+    ]);
+    let denied = any.guard(&peer, &urn).unwrap_err();
+    assert!(any.is_internal(&denied));
+
+    let all = guard::all(vec![
+        guard::erase(AllowlistGuard::new(None)),
+        guard::erase(Failing),
+    ]);
+    let denied = all.guard(&peer, &urn).unwrap_err();
+    assert!(!all.is_internal(&denied));
+}
+
+// The guard waits for a storage connection on the blocking pool, so the
+// runtime can still make progress on the task which releases it.
+#[tokio::test(flavor = "current_thread")]
+async fn guard_waits_for_saturated_pool() {
+    let fixture = Fixture::new();
+    let urn = Urn::new(git2::Oid::zero().into());
+    let peer = PeerId::from(SecretKey::new());
+    fixture.track(&urn, Some(peer));
+
+    let spawner = Arc::new(Spawner::from_current().unwrap());
+    let state = request_pull::State::new(
+        fixture.pool.clone(),
+        fixture.paths.clone(),
+        fixture.guard.clone(),
+        Limits::default(),
+    );
+    let held = fixture.pool.get().await.unwrap();
+    let guarding = spawner.spawn({
+        let spawner = spawner.clone();
+        async move { state.guard(&spawner, peer, urn).await }
+    });
+
+    link_async::sleep(Duration::from_millis(100)).await;
+    drop(held);
+    let tracked = link_async::timeout(Duration::from_secs(10), guarding)
+        .await
+        .expect("guard did not finish")
+        .unwrap();
+    assert!(tracked.is_ok());
+}
+
+#[tokio::test]
+async fn slots_queue_then_reject() {
+    let slots = Slots::new(Limits {
+        concurrent: 2,
+        queued: 1,
+    });
+
+    let first = assert_matches!(slots.acquire(), Slot::Acquired(permit) => permit);
+    let _second = assert_matches!(slots.acquire(), Slot::Acquired(permit) => permit);
+    let queued = assert_matches!(slots.acquire(), Slot::Queued(queued) => queued);
+    assert_matches!(slots.acquire(), Slot::Busy);
+
+    // Releasing a running slot lets the queued request through, and frees its
+    // place in the queue
+    drop(first);
+    let _third = queued.wait().await;
+    assert_matches!(slots.acquire(), Slot::Queued(_));
+}
+
+#[test]
+fn error_without_code_decodes() {
+    #[derive(minicbor::Encode)]
+    #[cbor(array)]
+    struct LegacyError {
+        #[n(0)]
+        message: String,
+    }
+
+    let bytes = minicbor::to_vec(LegacyError {
+        message: "internal error".to_owned(),
+    })
+    .unwrap();
+    assert_eq!(
+        minicbor::decode::<request_pull::Error>(&bytes).unwrap(),
+        request_pull::Error {
+            message: "internal error".to_owned(),
+            code: None,
+        }
+    );
+}
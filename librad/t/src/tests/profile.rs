@@ -6,7 +6,7 @@
 use std::{collections::BTreeSet, fs};
 use tempfile::TempDir;
 
-use librad::profile::{id, Error, LnkHome, Profile, ProfileId};
+use librad::profile::{id, Alias, Error, LnkHome, Profile, ProfileId};
 
 pub struct TempHome {
     tmp: TempDir,
@@ -154,3 +154,82 @@ fn set_profile() {
     let err = Profile::set(&tmp_home.home, "i-dont-exist".parse().unwrap()).unwrap_err();
     assert!(matches!(err, Error::DoesNotExist { .. }));
 }
+
+#[test]
+fn set_and_resolve_alias() {
+    let tmp_home = temp();
+
+    let p = Profile::new(&tmp_home.home).unwrap();
+    assert_eq!(Profile::alias(&tmp_home.home, p.id()).unwrap(), None);
+
+    let alias: Alias = "work".parse().unwrap();
+    Profile::set_alias(&tmp_home.home, p.id().clone(), alias.clone()).unwrap();
+    assert_eq!(
+        Profile::alias(&tmp_home.home, p.id()).unwrap(),
+        Some(alias.clone())
+    );
+
+    let by_alias = Profile::resolve(&tmp_home.home, alias.as_str()).unwrap();
+    assert_eq!(Some(p.id()), by_alias.as_ref().map(|p| p.id()));
+    let by_id = Profile::resolve(&tmp_home.home, p.id().as_str()).unwrap();
+    assert_eq!(Some(p.id()), by_id.as_ref().map(|p| p.id()));
+    assert!(Profile::resolve(&tmp_home.home, "home").unwrap().is_none());
+}
+
+#[test]
+fn list_aliases() {
+    let tmp_home = temp();
+
+    let p1 = Profile::new(&tmp_home.home).unwrap();
+    let p2 = Profile::new(&tmp_home.home).unwrap();
+    Profile::set_alias(&tmp_home.home, p1.id().clone(), "work".parse().unwrap()).unwrap();
+
+    let aliases = Profile::list(&tmp_home.home)
+        .unwrap()
+        .into_iter()
+        .map(|p| {
+            let alias = Profile::alias(&tmp_home.home, p.id()).unwrap();
+            (p.id().clone(), alias)
+        })
+        .collect::<BTreeSet<_>>();
+    let expected = vec![
+        (p1.id().clone(), Some("work".parse().unwrap())),
+        (p2.id().clone(), None),
+    ]
+    .into_iter()
+    .collect::<BTreeSet<_>>();
+
+    assert_eq!(expected, aliases);
+}
+
+#[test]
+fn alias_is_unique() {
+    let tmp_home = temp();
+
+    let p1 = Profile::new(&tmp_home.home).unwrap();
+    let p2 = Profile::new(&tmp_home.home).unwrap();
+    let alias: Alias = "work".parse().unwrap();
+    Profile::set_alias(&tmp_home.home, p1.id().clone(), alias.clone()).unwrap();
+
+    // Re-setting the same alias on the same profile is fine
+    Profile::set_alias(&tmp_home.home, p1.id().clone(), alias.clone()).unwrap();
+
+    let err = Profile::set_alias(&tmp_home.home, p2.id().clone(), alias).unwrap_err();
+    assert!(matches!(err, Error::AliasTaken { id, .. } if &id == p1.id()));
+
+    let err = Profile::set_alias(
+        &tmp_home.home,
+        p2.id().clone(),
+        p1.id().as_str().parse().unwrap(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::AliasIsProfileId(_)));
+}
+
+#[test]
+fn invalid_alias() {
+    assert!("".parse::<Alias>().is_err());
+    assert!("foo/bar".parse::<Alias>().is_err());
+    assert!("foo bar".parse::<Alias>().is_err());
+    assert!("..".parse::<Alias>().is_err());
+}
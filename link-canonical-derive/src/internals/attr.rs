@@ -3,7 +3,7 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use syn::{Attribute, DeriveInput, Lit, Meta, MetaNameValue, NestedMeta};
+use syn::{Attribute, DeriveInput, Field, Lit, Meta, MetaNameValue, NestedMeta, Variant};
 
 use crate::internals::case::Case;
 
@@ -11,6 +11,8 @@ pub const CJSON: &str = "cjson";
 pub const RENAME_ALL: &str = "rename_all";
 pub const TAGGED: &str = "tag";
 pub const CONTENT: &str = "content";
+pub const OTHER: &str = "other";
+pub const FLATTEN: &str = "flatten";
 
 /// The rules given by `cjson` attributes.
 #[derive(Clone, Debug)]
@@ -119,6 +121,23 @@ impl Rules {
     }
 }
 
+/// Whether the `variant` is marked with `#[cjson(other)]`.
+pub fn is_other(variant: &Variant) -> bool {
+    has_path(&variant.attrs, OTHER)
+}
+
+fn has_path(attrs: &[Attribute], ident: &str) -> bool {
+    attrs
+        .iter()
+        .flat_map(get_meta_items)
+        .any(|meta| matches!(meta, NestedMeta::Meta(Meta::Path(p)) if p.is_ident(ident)))
+}
+
+/// Whether the `field` is marked with `#[cjson(flatten)]`.
+pub fn is_flatten(field: &Field) -> bool {
+    has_path(&field.attrs, FLATTEN)
+}
+
 pub fn get_meta_items(attr: &Attribute) -> Vec<NestedMeta> {
     if !attr.path.is_ident(CJSON) {
         return Vec::new();
@@ -13,6 +13,7 @@ use syn::{
     DataEnum,
     DataStruct,
     DeriveInput,
+    Field,
     Fields,
     GenericParam,
     Generics,
@@ -23,7 +24,7 @@ use syn::{
 
 mod internals;
 use internals::{
-    attr::{Rules, Tagged},
+    attr::{self, Rules, Tagged},
     case,
 };
 
@@ -103,6 +104,30 @@ fn cjson(ident: &Ident, data: &Data, rules: &Rules) -> TokenStream {
 /// { "x": 42 }
 /// ```
 ///
+/// A field marked with `#[cjson(flatten)]` has the entries of its own object
+/// merged into the parent object, rather than being nested under the field
+/// name. Since `into_cjson` cannot fail, the generated code panics if the
+/// field does not produce an object, or if one of its keys clashes with
+/// another field.
+///
+/// ```rust,ignore
+/// #[derive(ToCjson)]
+/// struct Bar {
+///   x: usize,
+///   #[cjson(flatten)]
+///   meta: Meta,
+/// }
+///
+/// #[derive(ToCjson)]
+/// struct Meta {
+///   y: bool,
+/// }
+/// ```
+///
+/// ```json
+/// { "x": 42, "y": true }
+/// ```
+///
 /// # Unnamed Fields
 ///
 /// Similar to named fields, we first alias the fields by their position. For
@@ -146,13 +171,26 @@ fn cjson_struct(data: &DataStruct, rules: &Rules) -> TokenStream {
             let alias = names.clone().map(|name| {
                 quote! { let #name = self.#name; }
             });
-            let imp = product::named_fields(names, rules);
+            let flatten = fields
+                .named
+                .iter()
+                .filter(|field| attr::is_flatten(field))
+                .cloned()
+                .map(|field| field.ident.unwrap());
+            let names = fields
+                .named
+                .iter()
+                .filter(|field| !attr::is_flatten(field))
+                .cloned()
+                .map(|field| field.ident.unwrap());
+            let imp = product::named_fields(names, flatten, rules);
             quote! {
                 #(#alias)*
                 #imp
             }
         },
         Fields::Unnamed(ref fields) => {
+            guard_flatten(fields.unnamed.iter());
             let names = fields
                 .unnamed
                 .iter()
@@ -221,6 +259,23 @@ fn cjson_struct(data: &DataStruct, rules: &Rules) -> TokenStream {
 /// { "type": "Baz", "payload": [true] }
 /// ```
 ///
+/// # Renaming
+///
+/// If `rename_all` is given, it applies to the variant names used as the value
+/// of the `tag`, as well as to the names of any named fields.
+///
+/// ```rust,ignore
+/// #[derive(ToCjson)]
+/// #[cjson(rename_all = "camelCase", tag = "type")]
+/// enum Foo {
+///   BarBaz { quux_x: usize },
+/// }
+/// ```
+///
+/// ```json
+/// { "type": "barBaz", "quuxX": 42 }
+/// ```
+///
 /// # Named Fields
 ///
 /// If the `enum` has named fields, we match on their identifiers. If the `enum`
@@ -267,6 +322,32 @@ fn cjson_struct(data: &DataStruct, rules: &Rules) -> TokenStream {
 /// ```json
 /// { "type": "Quux" }
 /// ```
+///
+/// # Other
+///
+/// A single variant may be marked with `#[cjson(other)]` to act as the
+/// catch-all for tags which are not otherwise known. It must be either a unit
+/// variant or a newtype variant, typically holding the unknown tag as a
+/// `String`.
+///
+/// A newtype `other` variant is output as its bare inner value, without a
+/// `tag` or `content` wrapper, regardless of whether the `enum` is internally
+/// or adjacently tagged. A unit `other` variant is output like any other unit
+/// variant. The `enum` must still specify a `tag`.
+///
+/// ```rust,ignore
+/// #[derive(ToCjson)]
+/// #[cjson(tag = "type")]
+/// enum Foo {
+///   Quux,
+///   #[cjson(other)]
+///   Unknown(String),
+/// }
+/// ```
+///
+/// ```json
+/// "frobnicate"
+/// ```
 #[rustfmt::skip::macros(quote)]
 fn cjson_enum(ident: &Ident, data: &DataEnum, rules: &Rules) -> TokenStream {
     let tagged = match &rules.tagged {
@@ -275,6 +356,9 @@ fn cjson_enum(ident: &Ident, data: &DataEnum, rules: &Rules) -> TokenStream {
         },
         Some(tagged) => tagged,
     };
+    if data.variants.iter().filter(|v| attr::is_other(v)).count() > 1 {
+        panic!("only one variant can be marked with #[cjson(other)]")
+    }
     let arms = data
         .variants
         .iter()
@@ -283,24 +367,68 @@ fn cjson_enum(ident: &Ident, data: &DataEnum, rules: &Rules) -> TokenStream {
     quote! { match self { #(#arms),* } }
 }
 
+fn guard_flatten<'a>(mut fields: impl Iterator<Item = &'a Field>) {
+    if fields.any(attr::is_flatten) {
+        panic!("#[cjson(flatten)] can only be used on the named fields of a struct")
+    }
+}
+
 mod product {
     use super::*;
 
     #[rustfmt::skip::macros(quote)]
-    pub fn named_fields(names: impl Iterator<Item = Ident>, rules: &Rules) -> TokenStream {
+    pub fn named_fields(
+        names: impl Iterator<Item = Ident>,
+        mut flatten: impl Iterator<Item = Ident>,
+        rules: &Rules,
+    ) -> TokenStream {
         let kvs = names.map(|name| {
             let cased = case::convert(&format!("{}", name), rules.casing);
             quote! { (#cased, link_canonical::json::ToCjson::into_cjson(#name)) }
         });
-        quote! {
-	    use std::iter::FromIterator as _;
-	    let kvs = vec![#(#kvs),*].into_iter();
-	    link_canonical::json::Value::from_iter(
-		kvs.map(|(key, value)| {
-		    (link_canonical::Cstring::from(key), value)
-		})
-	    )
-	}
+        match flatten.next() {
+            None => quote! {
+		use std::iter::FromIterator as _;
+		let kvs = vec![#(#kvs),*].into_iter();
+		link_canonical::json::Value::from_iter(
+		    kvs.map(|(key, value)| {
+			(link_canonical::Cstring::from(key), value)
+		    })
+		)
+	    },
+            Some(first) => {
+                let merges = std::iter::once(first).chain(flatten).map(|name| {
+                    let field = format!("{}", name);
+                    quote! {
+			match link_canonical::json::ToCjson::into_cjson(#name) {
+			    link_canonical::json::Value::Object(map) => {
+				for (key, value) in map {
+				    if val.insert(key.clone(), value).is_some() {
+					panic!(
+					    "flattened field `{}` contains duplicate key `{}`",
+					    #field, key
+					)
+				    }
+				}
+			    },
+			    other => panic!(
+				"cannot flatten field `{}`, expected an object but found {}",
+				#field, other.ty_name()
+			    ),
+			}
+		    }
+                });
+                quote! {
+		    let kvs: Vec<(&str, link_canonical::json::Value)> = vec![#(#kvs),*];
+		    let mut val = link_canonical::json::Map::new();
+		    for (key, value) in kvs {
+			val.insert(link_canonical::Cstring::from(key), value);
+		    }
+		    #(#merges)*
+		    link_canonical::json::Value::Object(val)
+		}
+            },
+        }
     }
 
     #[rustfmt::skip::macros(quote)]
@@ -334,17 +462,33 @@ mod coproduct {
         variant: &Variant,
     ) -> TokenStream {
         let name = &variant.ident;
+        let value = case::convert(&format!("{}", name), casing);
+        if attr::is_other(variant) {
+            match &variant.fields {
+                Fields::Unit => {},
+                Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                    return quote! {
+			#ident::#name(__field0) => {
+			    link_canonical::json::ToCjson::into_cjson(__field0)
+			}
+		    };
+                },
+                _ => panic!("#[cjson(other)] can only be used on a unit or newtype variant"),
+            }
+        }
         match &variant.fields {
             Fields::Named(ref fields) => {
+                guard_flatten(fields.named.iter());
                 let named = fields.named.iter().cloned().map(|f| f.ident.unwrap());
                 tagged.guard_fields(named.clone());
-                let body = named_fields(variant, named.clone(), tagged, casing);
+                let body = named_fields(&value, named.clone(), tagged, casing);
                 quote! { #ident::#name { #(#named),* } => { #body } }
             },
             Fields::Unnamed(ref fields) => {
+                guard_flatten(fields.unnamed.iter());
                 let named = (0..fields.unnamed.len())
                     .map(|i| Ident::new(&format!("__field{}", i), Span::call_site()));
-                let body = unnamed_fields(variant, named.clone(), tagged);
+                let body = unnamed_fields(&value, named.clone(), tagged);
                 quote! { #ident::#name ( #(#named),* ) => { #body } }
             },
             Fields::Unit => {
@@ -354,7 +498,7 @@ mod coproduct {
 			let mut val = link_canonical::json::Map::new();
 			val.insert(
 			    link_canonical::Cstring::from(#tag),
-			    link_canonical::json::ToCjson::into_cjson(stringify!(#name))
+			    link_canonical::json::ToCjson::into_cjson(#value)
 			);
 			link_canonical::json::Value::Object(val)
 		    }
@@ -365,14 +509,13 @@ mod coproduct {
 
     #[rustfmt::skip::macros(quote)]
     fn unnamed_fields(
-        variant: &Variant,
+        value: &str,
         names: impl ExactSizeIterator<Item = Ident>,
         tagged: &Tagged,
     ) -> TokenStream {
         let mut vs = names.map(|name| {
             quote! { link_canonical::json::ToCjson::into_cjson(#name) }
         });
-        let name = &variant.ident;
 
         if vs.len() == 1 {
             let v = vs.next().unwrap();
@@ -382,7 +525,7 @@ mod coproduct {
 		let mut val = link_canonical::json::Map::new();
 		val.insert(
 		    link_canonical::Cstring::from(#tag),
-		    link_canonical::json::ToCjson::into_cjson(stringify!(#name))
+		    link_canonical::json::ToCjson::into_cjson(#value)
 		);
 		val.insert(
 		    link_canonical::Cstring::from(#content),
@@ -402,7 +545,7 @@ mod coproduct {
                         .chain(std::iter::once(quote! {
 			(
 			    link_canonical::Cstring::from(#tag),
-			    link_canonical::json::ToCjson::into_cjson(stringify!(#name))
+			    link_canonical::json::ToCjson::into_cjson(#value)
 			)
 		    }));
                     quote! {
@@ -417,7 +560,7 @@ mod coproduct {
 			let mut val = link_canonical::json::Map::new();
 			val.insert(
 			    link_canonical::Cstring::from(#tag),
-			    link_canonical::json::ToCjson::into_cjson(stringify!(#name))
+			    link_canonical::json::ToCjson::into_cjson(#value)
 			);
 			let vs = vec![#(#vs),*].into_iter();
 			val.insert(
@@ -435,7 +578,7 @@ mod coproduct {
 
     #[rustfmt::skip::macros(quote)]
     fn named_fields(
-        variant: &Variant,
+        value: &str,
         names: impl Iterator<Item = Ident>,
         tagged: &Tagged,
         casing: Option<case::Case>,
@@ -444,12 +587,11 @@ mod coproduct {
             let cased = case::convert(&format!("{}", name), casing);
             quote! { (#cased, link_canonical::json::ToCjson::into_cjson(#name)) }
         });
-        let name = &variant.ident;
 
         match tagged {
             Tagged::Internally(tag) => {
                 let kvs = kvs.chain(std::iter::once(
-                    quote! { (#tag, link_canonical::json::ToCjson::into_cjson(stringify!(#name))) },
+                    quote! { (#tag, link_canonical::json::ToCjson::into_cjson(#value)) },
                 ));
                 quote! {
 		    use std::iter::FromIterator as _;
@@ -467,7 +609,7 @@ mod coproduct {
 		    let mut val = link_canonical::json::Map::new();
 		    val.insert(
 			link_canonical::Cstring::from(#tag),
-			link_canonical::json::ToCjson::into_cjson(stringify!(#name))
+			link_canonical::json::ToCjson::into_cjson(#value)
 		    );
 		    let kvs = vec![#(#kvs),*].into_iter();
 		    val.insert(
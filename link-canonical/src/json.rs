@@ -6,6 +6,7 @@
 use std::{
     collections::{btree_map, BTreeMap, BTreeSet},
     convert::{Infallible, TryFrom},
+    io,
     iter::FromIterator,
     slice,
     str::{self, FromStr},
@@ -14,7 +15,16 @@ use std::{
 use crate::{Canonical, Cstring};
 
 mod parser;
+pub use parser::Error;
 mod ser;
+#[cfg(feature = "serde-json")]
+mod serde;
+#[cfg(feature = "serde-json")]
+pub use self::serde::ConversionError;
+mod timestamp;
+pub use timestamp::{Timestamp, TimestampError};
+mod bytes;
+pub use bytes::{Bytes, BytesError};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Value {
@@ -37,6 +47,99 @@ impl Value {
             Value::Null => "null",
         }
     }
+
+    /// The value of `key`, if this is an object which contains it.
+    pub fn get(&self, key: &Cstring) -> Option<&Value> {
+        match self {
+            Value::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    /// The element at `index`, if this is an array which is long enough.
+    pub fn index(&self, index: usize) -> Option<&Value> {
+        match self {
+            Value::Array(array) => array.get(index),
+            _ => None,
+        }
+    }
+
+    /// Follow `path` into nested objects and arrays, starting from this value.
+    ///
+    /// Returns `None` if any segment of `path` does not exist. An empty `path`
+    /// yields this value.
+    pub fn get_path(&self, path: &[PathSegment]) -> Option<&Value> {
+        path.iter().try_fold(self, |val, segment| match segment {
+            PathSegment::Key(key) => val.get(key),
+            PathSegment::Index(index) => val.index(*index),
+        })
+    }
+
+    /// Compare by canonical form rather than by structure.
+    ///
+    /// Keys are always sorted and strings always NFC-normalised, so this only
+    /// differs from `==` for [`Number`]s which were not constructed in their
+    /// smallest representation: for example, `Number::I64(1)` and
+    /// `Number::U64(1)` are not `==`, but both are written as `1`, so they
+    /// are canonically equal. Parsing never produces such numbers, but
+    /// [`ToCjson`] for signed integers and direct construction can.
+    pub fn canonically_eq(&self, other: &Value) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+
+    /// Encode binary data as a [`Value::String`].
+    ///
+    /// The encoding is [multibase] base32-z, ie. the string is prefixed with
+    /// `h`, followed by the unpadded z-base-32 encoding of `bytes`. This is
+    /// the same encoding used for URNs and peer ids, and only ever yields
+    /// ASCII characters, so the string is unaffected by normalisation.
+    ///
+    /// [multibase]: https://github.com/multiformats/multibase
+    pub fn bytes(bytes: &[u8]) -> Value {
+        Value::String(multibase::encode(multibase::Base::Base32Z, bytes).into())
+    }
+
+    /// Decode binary data encoded by [`Value::bytes`].
+    ///
+    /// Returns `None` if this is not a string, or if it is not in exactly
+    /// that encoding. Other multibase encodings are rejected, so that the
+    /// same bytes always have the same canonical form.
+    pub fn as_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Value::String(s) => match multibase::decode(s.as_str()) {
+                Ok((multibase::Base::Base32Z, bytes)) => Some(bytes),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A step into a nested [`Value`], see [`Value::get_path`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PathSegment {
+    /// The value of a key of an object.
+    Key(Cstring),
+    /// The element at an index of an array.
+    Index(usize),
+}
+
+impl From<Cstring> for PathSegment {
+    fn from(key: Cstring) -> Self {
+        Self::Key(key)
+    }
+}
+
+impl From<&str> for PathSegment {
+    fn from(key: &str) -> Self {
+        Self::Key(key.into())
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
 }
 
 impl<K: Into<Cstring>, A: ToCjson> FromIterator<(K, A)> for Value {
@@ -49,35 +152,38 @@ impl<K: Into<Cstring>, A: ToCjson> FromIterator<(K, A)> for Value {
 }
 
 impl FromStr for Value {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         use nom::{
-            error::{convert_error, VerboseError},
-            Err::{Error, Failure, Incomplete},
+            error::convert_error,
+            Err::{Error as Fail, Failure, Incomplete},
         };
 
-        match parser::json::<VerboseError<&str>>(s) {
+        match parser::json::<parser::Verbose>(s) {
             Ok((rem, value)) => {
                 if rem.trim().is_empty() {
                     Ok(value)
                 } else {
-                    Err(format!("expected EOF, found: {}", rem))
+                    Err(Error::TrailingInput(rem.to_string()))
                 }
             },
-            Err(Error(e)) | Err(Failure(e)) => Err(convert_error(s, e)),
-            Err(Incomplete(_)) => Err("unexpected end of input".to_string()),
+            Err(Fail(e)) | Err(Failure(e)) => match e.error {
+                Some(err) => Err(err),
+                None => Err(Error::Syntax(convert_error(s, e.verbose))),
+            },
+            Err(Incomplete(_)) => Err(Error::Incomplete),
         }
     }
 }
 
 impl TryFrom<&[u8]> for Value {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
         // XXX: could make the `parser` generic over input
         str::from_utf8(bytes)
-            .map_err(|err| err.to_string())
+            .map_err(Error::from)
             .and_then(|s| s.parse())
     }
 }
@@ -88,12 +194,26 @@ impl Canonical for Value {
     fn canonical_form(&self) -> Result<Vec<u8>, Self::Error> {
         Ok(self.to_bytes())
     }
+
+    fn canonical_form_into<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_to(w)
+    }
 }
 
+/// A canonical JSON number.
+///
+/// The parser represents integers by the smallest variant which can hold
+/// them: non-negative integers are `U64` (or `U128` if they exceed
+/// `u64::MAX`), and negative integers are `I64` (or `I128` if they are below
+/// `i64::MIN`). [`ToCjson`] for the signed integer types keeps non-negative
+/// values as `I64`, so such a [`Value`] is not `==` to the result of parsing
+/// its canonical form, see [`Value::canonically_eq`].
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Number {
     U64(u64),
     I64(i64),
+    U128(u128),
+    I128(i128),
 }
 
 impl Canonical for Number {
@@ -102,12 +222,26 @@ impl Canonical for Number {
     fn canonical_form(&self) -> Result<Vec<u8>, Self::Error> {
         Ok(self.to_bytes())
     }
+
+    fn canonical_form_into<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_to(w)
+    }
 }
 
 pub trait ToCjson {
     fn into_cjson(self) -> Value;
 }
 
+/// Types which can be recovered from a [`Value`].
+///
+/// This is the counterpart to [`ToCjson`]: for any `t`,
+/// `T::from_cjson(t.into_cjson())` should yield a value equal to `t`.
+pub trait FromCjson: Sized {
+    type Error;
+
+    fn from_cjson(value: Value) -> Result<Self, Self::Error>;
+}
+
 // Identity
 
 impl ToCjson for Value {
@@ -219,6 +353,28 @@ impl ToCjson for i8 {
     }
 }
 
+impl ToCjson for u128 {
+    fn into_cjson(self) -> Value {
+        match u64::try_from(self) {
+            Ok(x) => x.into_cjson(),
+            Err(_) => Value::Number(Number::U128(self)),
+        }
+    }
+}
+
+impl ToCjson for i128 {
+    fn into_cjson(self) -> Value {
+        if self >= 0 {
+            (self as u128).into_cjson()
+        } else {
+            match i64::try_from(self) {
+                Ok(x) => x.into_cjson(),
+                Err(_) => Value::Number(Number::I128(self)),
+            }
+        }
+    }
+}
+
 // Bool
 
 impl ToCjson for bool {
@@ -371,6 +527,10 @@ impl Array {
         self.0.push(val)
     }
 
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.0.get(index)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
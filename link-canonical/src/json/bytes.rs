@@ -0,0 +1,61 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::ops::Deref;
+
+use thiserror::Error;
+
+use super::{FromCjson, ToCjson, Value};
+
+#[derive(Debug, Error)]
+pub enum BytesError {
+    #[error("expected a string of encoded bytes, found {0}")]
+    Type(&'static str),
+    #[error("string is not base32-z multibase encoded")]
+    Encoding,
+}
+
+/// Binary data, represented in canonical JSON as a string.
+///
+/// See [`Value::bytes`] for the encoding.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(Bytes(bytes): Bytes) -> Self {
+        bytes
+    }
+}
+
+impl ToCjson for Bytes {
+    fn into_cjson(self) -> Value {
+        Value::bytes(&self.0)
+    }
+}
+
+impl FromCjson for Bytes {
+    type Error = BytesError;
+
+    fn from_cjson(value: Value) -> Result<Self, Self::Error> {
+        match &value {
+            Value::String(_) => value.as_bytes().map(Self).ok_or(BytesError::Encoding),
+            other => Err(BytesError::Type(other.ty_name())),
+        }
+    }
+}
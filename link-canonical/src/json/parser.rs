@@ -3,43 +3,127 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::ops::Neg;
-
 use nom::{
     branch::alt,
     bytes::streaming::{tag, take_while},
     character::streaming::{char, digit0, one_of},
-    combinator::{cut, map, recognize, value},
-    error::{context, ContextError, FromExternalError, ParseError},
+    combinator::{cut, map, map_opt, map_res, recognize, value},
+    error::{context, ContextError, ErrorKind, FromExternalError, ParseError, VerboseError},
     multi::separated_list0,
     sequence::{self, preceded, separated_pair, terminated},
 };
 
 use crate::{
-    json::{ToCjson as _, Value},
+    json::{Map, ToCjson as _, Value},
     Cstring,
 };
 
 mod string;
 
+/// The reasons parsing a [`Value`] may fail.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("duplicate key `{0}` in object")]
+    DuplicateKey(Cstring),
+    #[error("expected EOF, found: {0}")]
+    TrailingInput(String),
+    #[error("unexpected end of input")]
+    Incomplete,
+    #[error(transparent)]
+    Utf8(#[from] std::str::Utf8Error),
+    /// Any other syntax error, as a trace of the parser's context
+    #[error("{0}")]
+    Syntax(String),
+}
+
+/// A [`VerboseError`] which additionally keeps the [`Error`] that caused
+/// parsing to fail, if any, so that it can be reported as is.
+pub(super) struct Verbose<'a> {
+    pub(super) verbose: VerboseError<&'a str>,
+    pub(super) error: Option<Error>,
+}
+
+impl<'a> From<VerboseError<&'a str>> for Verbose<'a> {
+    fn from(verbose: VerboseError<&'a str>) -> Self {
+        Self {
+            verbose,
+            error: None,
+        }
+    }
+}
+
+impl<'a> ParseError<&'a str> for Verbose<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        VerboseError::from_error_kind(input, kind).into()
+    }
+
+    fn append(input: &'a str, kind: ErrorKind, other: Self) -> Self {
+        Self {
+            verbose: VerboseError::append(input, kind, other.verbose),
+            error: other.error,
+        }
+    }
+
+    fn from_char(input: &'a str, c: char) -> Self {
+        VerboseError::from_char(input, c).into()
+    }
+}
+
+impl<'a> ContextError<&'a str> for Verbose<'a> {
+    fn add_context(input: &'a str, ctx: &'static str, other: Self) -> Self {
+        Self {
+            verbose: VerboseError::add_context(input, ctx, other.verbose),
+            error: other.error,
+        }
+    }
+}
+
+impl<'a> FromExternalError<&'a str, string::Error> for Verbose<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, e: string::Error) -> Self {
+        VerboseError::from_external_error(input, kind, e).into()
+    }
+}
+
+impl<'a> FromExternalError<&'a str, Error> for Verbose<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, e: Error) -> Self {
+        Self {
+            verbose: VerboseError::from_error_kind(input, kind),
+            error: Some(e),
+        }
+    }
+}
+
 pub fn json<'a, E>(i: &'a str) -> nom::IResult<&'a str, Value, E>
 where
-    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, string::Error>,
+    E: ParseError<&'a str>
+        + ContextError<&'a str>
+        + FromExternalError<&'a str, string::Error>
+        + FromExternalError<&'a str, Error>,
 {
     preceded(sp, alt((string, number, object, array, boolean, null)))(i)
 }
 
 fn object<'a, E>(i: &'a str) -> nom::IResult<&'a str, Value, E>
 where
-    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, string::Error>,
+    E: ParseError<&'a str>
+        + ContextError<&'a str>
+        + FromExternalError<&'a str, string::Error>
+        + FromExternalError<&'a str, Error>,
 {
     context(
         "object",
         preceded(
             char('{'),
             cut(terminated(
-                map(members, |members| {
-                    Value::Object(members.into_iter().collect())
+                map_res(members, |members| {
+                    let mut map = Map::new();
+                    for (key, val) in members {
+                        if map.get(&key).is_some() {
+                            return Err(Error::DuplicateKey(key));
+                        }
+                        map.insert(key, val);
+                    }
+                    Ok(Value::Object(map))
                 }),
                 preceded(sp, char('}')),
             )),
@@ -49,21 +133,30 @@ where
 
 fn members<'a, E>(i: &'a str) -> nom::IResult<&'a str, Vec<(Cstring, Value)>, E>
 where
-    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, string::Error>,
+    E: ParseError<&'a str>
+        + ContextError<&'a str>
+        + FromExternalError<&'a str, string::Error>
+        + FromExternalError<&'a str, Error>,
 {
     separated_list0(preceded(sp, char(',')), pair)(i)
 }
 
 fn pair<'a, E>(i: &'a str) -> nom::IResult<&'a str, (Cstring, Value), E>
 where
-    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, string::Error>,
+    E: ParseError<&'a str>
+        + ContextError<&'a str>
+        + FromExternalError<&'a str, string::Error>
+        + FromExternalError<&'a str, Error>,
 {
     separated_pair(preceded(sp, cstring), cut(preceded(sp, char(':'))), json)(i)
 }
 
 fn array<'a, E>(i: &'a str) -> nom::IResult<&'a str, Value, E>
 where
-    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, string::Error>,
+    E: ParseError<&'a str>
+        + ContextError<&'a str>
+        + FromExternalError<&'a str, string::Error>
+        + FromExternalError<&'a str, Error>,
 {
     map(
         context(
@@ -135,11 +228,12 @@ fn signed<'a, E>(i: &'a str) -> nom::IResult<&'a str, Value, E>
 where
     E: ParseError<&'a str>,
 {
-    preceded(
-        minus,
-        map(digits, |digits: &'a str| {
-            digits.parse::<i64>().unwrap().neg().into_cjson()
-        }),
+    map_opt(
+        recognize(preceded(minus, digits)),
+        |signed: &'a str| match signed.parse::<i64>() {
+            Ok(x) => Some(x.into_cjson()),
+            Err(_) => signed.parse::<i128>().ok().map(|x| x.into_cjson()),
+        },
     )(i)
 }
 
@@ -147,8 +241,8 @@ fn unsigned<'a, E>(i: &'a str) -> nom::IResult<&'a str, Value, E>
 where
     E: ParseError<&'a str>,
 {
-    map(digits, |digits: &'a str| {
-        digits.parse::<u64>().unwrap().into_cjson()
+    map_opt(digits, |digits: &'a str| {
+        digits.parse::<u128>().ok().map(|x| x.into_cjson())
     })(i)
 }
 
@@ -3,79 +3,156 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
+use std::io::{self, Write};
+
 use crate::Cstring;
 
 use super::{Number, Value};
 
 impl Value {
     pub(super) fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        self.write_to(&mut buf)
+            .expect("BUG: writing to a Vec cannot fail");
+        buf
+    }
+
+    /// Write the canonical form of the value to `w`, without building it up in
+    /// memory first.
+    pub(super) fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
         match self {
-            Value::Object(obj) => {
-                let mut buf = vec![];
-                between(&mut buf, b'{', b'}', |buf| {
-                    intercalate(buf, obj.iter(), |buf, (key, val)| {
-                        string(buf, key);
-                        buf.push(b':');
-                        buf.extend(val.to_bytes());
-                    })
-                });
-                buf
-            },
-            Value::Array(array) => {
-                let mut buf = vec![];
-                between(&mut buf, b'[', b']', |buf| {
-                    intercalate(buf, array.iter(), |buf, v| buf.extend(v.to_bytes()))
-                });
-                buf
-            },
-            Value::String(s) => {
-                let mut buf = vec![];
-                string(&mut buf, s);
-                buf
-            },
-            Value::Number(n) => n.to_bytes(),
+            Value::Object(obj) => between(w, b'{', b'}', |w| {
+                intercalate(w, obj.iter(), |w, (key, val)| {
+                    string(w, key)?;
+                    w.write_all(b":")?;
+                    val.write_to(w)
+                })
+            }),
+            Value::Array(array) => between(w, b'[', b']', |w| {
+                intercalate(w, array.iter(), |w, v| v.write_to(w))
+            }),
+            Value::String(s) => string(w, s),
+            Value::Number(n) => n.write_to(w),
             Value::Bool(b) => match b {
-                true => b"true".to_vec(),
-                false => b"false".to_vec(),
+                true => w.write_all(b"true"),
+                false => w.write_all(b"false"),
             },
-            Value::Null => b"null".to_vec(),
+            Value::Null => w.write_all(b"null"),
+        }
+    }
+
+    /// Render the value with newlines and `indent` spaces of indentation per
+    /// level of nesting, for human consumption.
+    ///
+    /// Keys are in the same order as the canonical form, but the whitespace
+    /// means the output is **not** canonical JSON. It must never be used where
+    /// the canonical form is expected, e.g. when signing or hashing. Parsing
+    /// the output yields a value equal to `self`, whose
+    /// [`crate::Canonical::canonical_form`] is the canonical form.
+    pub fn to_pretty(&self, indent: usize) -> String {
+        let mut buf = vec![];
+        self.pretty(&mut buf, indent, 0)
+            .expect("BUG: writing to a Vec cannot fail");
+        String::from_utf8(buf).expect("BUG: JSON is valid UTF-8")
+    }
+
+    fn pretty(&self, buf: &mut Vec<u8>, indent: usize, depth: usize) -> io::Result<()> {
+        match self {
+            Value::Object(obj) if !obj.is_empty() => between(buf, b'{', b'}', |buf| {
+                buf.push(b'\n');
+                intercalate_lines(buf, obj.iter(), |buf, (key, val)| {
+                    pad(buf, indent * (depth + 1));
+                    string(buf, key)?;
+                    buf.extend(b": ");
+                    val.pretty(buf, indent, depth + 1)
+                })?;
+                pad(buf, indent * depth);
+                Ok(())
+            }),
+            Value::Array(array) if !array.is_empty() => between(buf, b'[', b']', |buf| {
+                buf.push(b'\n');
+                intercalate_lines(buf, array.iter(), |buf, val| {
+                    pad(buf, indent * (depth + 1));
+                    val.pretty(buf, indent, depth + 1)
+                })?;
+                pad(buf, indent * depth);
+                Ok(())
+            }),
+            _ => self.write_to(buf),
         }
     }
 }
 
 impl Number {
     pub(super) fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        self.write_to(&mut buf)
+            .expect("BUG: writing to a Vec cannot fail");
+        buf
+    }
+
+    pub(super) fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
         match self {
-            Self::U64(x) => format!("{}", x).as_bytes().to_vec(),
-            Self::I64(x) => format!("{}", x).as_bytes().to_vec(),
+            Self::U64(x) => write!(w, "{}", x),
+            Self::I64(x) => write!(w, "{}", x),
+            Self::U128(x) => write!(w, "{}", x),
+            Self::I128(x) => write!(w, "{}", x),
         }
     }
 }
 
-fn between<F>(buf: &mut Vec<u8>, before: u8, after: u8, callback: F)
+fn between<W, F>(w: &mut W, before: u8, after: u8, callback: F) -> io::Result<()>
 where
-    F: FnOnce(&mut Vec<u8>),
+    W: Write,
+    F: FnOnce(&mut W) -> io::Result<()>,
 {
-    buf.push(before);
-    callback(buf);
-    buf.push(after);
+    w.write_all(&[before])?;
+    callback(w)?;
+    w.write_all(&[after])
+}
+
+fn string<W: Write>(w: &mut W, string: &Cstring) -> io::Result<()> {
+    between(w, b'"', b'"', |w| w.write_all(string.as_bytes()))
 }
 
-fn string(buf: &mut Vec<u8>, string: &Cstring) {
-    between(buf, b'"', b'"', |buf| {
-        buf.extend(string.as_bytes());
-    });
+fn intercalate<W, F, T>(
+    w: &mut W,
+    collection: impl ExactSizeIterator<Item = T>,
+    callback: F,
+) -> io::Result<()>
+where
+    W: Write,
+    F: Fn(&mut W, T) -> io::Result<()>,
+{
+    let length = collection.len();
+    for (i, v) in collection.enumerate() {
+        callback(w, v)?;
+        if i + 1 != length {
+            w.write_all(b",")?;
+        }
+    }
+    Ok(())
 }
 
-fn intercalate<F, T>(buf: &mut Vec<u8>, collection: impl ExactSizeIterator<Item = T>, callback: F)
+fn intercalate_lines<F, T>(
+    buf: &mut Vec<u8>,
+    collection: impl ExactSizeIterator<Item = T>,
+    callback: F,
+) -> io::Result<()>
 where
-    F: Fn(&mut Vec<u8>, T),
+    F: Fn(&mut Vec<u8>, T) -> io::Result<()>,
 {
     let length = collection.len();
     for (i, v) in collection.enumerate() {
-        callback(buf, v);
+        callback(buf, v)?;
         if i + 1 != length {
             buf.push(b',');
         }
+        buf.push(b'\n');
     }
+    Ok(())
+}
+
+fn pad(buf: &mut Vec<u8>, width: usize) {
+    buf.extend(std::iter::repeat(b' ').take(width));
 }
@@ -0,0 +1,102 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+//! Conversions between [`Value`] and [`serde_json::Value`].
+
+use std::convert::TryFrom;
+
+use thiserror::Error;
+
+use super::{Array, Map, Number, Value};
+use crate::Cstring;
+
+#[derive(Debug, Error)]
+pub enum ConversionError {
+    /// Canonical JSON does not permit floating point numbers.
+    ///
+    /// Note that `serde_json` cannot represent `NaN` or infinities, they are
+    /// converted to `null` before they reach a [`serde_json::Value`].
+    #[error("canonical JSON does not permit floating point numbers, found {0}")]
+    Float(serde_json::Number),
+    /// `serde_json` numbers are at most 64 bits wide.
+    #[error("{0:?} does not fit in a serde_json number")]
+    OutOfRange(Number),
+}
+
+impl TryFrom<serde_json::Value> for Value {
+    type Error = ConversionError;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        Ok(match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Number(n) => Value::Number(Number::try_from(n)?),
+            serde_json::Value::String(s) => Value::String(Cstring::from(s)),
+            serde_json::Value::Array(xs) => Value::Array(Array(
+                xs.into_iter()
+                    .map(Value::try_from)
+                    .collect::<Result<_, _>>()?,
+            )),
+            serde_json::Value::Object(kvs) => Value::Object(Map(kvs
+                .into_iter()
+                .map(|(k, v)| Ok((Cstring::from(k), Value::try_from(v)?)))
+                .collect::<Result<_, ConversionError>>()?)),
+        })
+    }
+}
+
+impl TryFrom<serde_json::Number> for Number {
+    type Error = ConversionError;
+
+    fn try_from(n: serde_json::Number) -> Result<Self, Self::Error> {
+        if let Some(u) = n.as_u64() {
+            Ok(Number::U64(u))
+        } else if let Some(i) = n.as_i64() {
+            Ok(Number::I64(i))
+        } else {
+            Err(ConversionError::Float(n))
+        }
+    }
+}
+
+impl TryFrom<Value> for serde_json::Value {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        Ok(match value {
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(b) => serde_json::Value::Bool(b),
+            Value::Number(n) => serde_json::Value::Number(serde_json::Number::try_from(n)?),
+            Value::String(s) => serde_json::Value::String(s.into()),
+            Value::Array(xs) => serde_json::Value::Array(
+                xs.into_iter()
+                    .map(serde_json::Value::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Object(kvs) => serde_json::Value::Object(
+                kvs.into_iter()
+                    .map(|(k, v)| Ok((k.into(), serde_json::Value::try_from(v)?)))
+                    .collect::<Result<_, ConversionError>>()?,
+            ),
+        })
+    }
+}
+
+impl TryFrom<Number> for serde_json::Number {
+    type Error = ConversionError;
+
+    fn try_from(n: Number) -> Result<Self, Self::Error> {
+        match n {
+            Number::U64(u) => Ok(u.into()),
+            Number::I64(i) => Ok(i.into()),
+            Number::U128(u) => u64::try_from(u)
+                .map(Into::into)
+                .map_err(|_| ConversionError::OutOfRange(n)),
+            Number::I128(i) => i64::try_from(i)
+                .map(Into::into)
+                .map_err(|_| ConversionError::OutOfRange(n)),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{
+    convert::TryFrom,
+    time::{Duration, SystemTime, SystemTimeError, UNIX_EPOCH},
+};
+
+use thiserror::Error;
+
+use super::{FromCjson, Number, ToCjson, Value};
+
+#[derive(Debug, Error)]
+pub enum TimestampError {
+    #[error("expected a non-negative integer timestamp, found {0}")]
+    Type(&'static str),
+    #[error("timestamp {0:?} is out of range")]
+    OutOfRange(Number),
+    #[error("timestamp {0} is not representable as a system time")]
+    SystemTime(u64),
+}
+
+/// A point in time, as whole seconds since the Unix epoch
+/// (`1970-01-01T00:00:00Z`).
+///
+/// Timestamps are always in UTC, and are represented in canonical JSON as a
+/// non-negative integer. Sub-second precision is deliberately dropped, so that
+/// the same instant always has exactly one canonical form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    pub fn from_secs(secs: u64) -> Self {
+        Self(secs)
+    }
+
+    /// The current system time, truncated to whole seconds.
+    ///
+    /// # Panics
+    ///
+    /// If the system clock is set to before the Unix epoch.
+    pub fn now() -> Self {
+        Self::try_from(SystemTime::now()).expect("system clock is set before the Unix epoch")
+    }
+
+    pub fn as_secs(&self) -> u64 {
+        self.0
+    }
+}
+
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = SystemTimeError;
+
+    /// Truncates `time` to whole seconds.
+    fn try_from(time: SystemTime) -> Result<Self, Self::Error> {
+        time.duration_since(UNIX_EPOCH)
+            .map(|since| Self(since.as_secs()))
+    }
+}
+
+impl TryFrom<Timestamp> for SystemTime {
+    type Error = TimestampError;
+
+    fn try_from(Timestamp(secs): Timestamp) -> Result<Self, Self::Error> {
+        UNIX_EPOCH
+            .checked_add(Duration::from_secs(secs))
+            .ok_or(TimestampError::SystemTime(secs))
+    }
+}
+
+impl ToCjson for Timestamp {
+    fn into_cjson(self) -> Value {
+        self.0.into_cjson()
+    }
+}
+
+impl FromCjson for Timestamp {
+    type Error = TimestampError;
+
+    fn from_cjson(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(Number::U64(secs)) => Ok(Self(secs)),
+            Value::Number(Number::I64(secs)) if secs >= 0 => Ok(Self(secs as u64)),
+            Value::Number(n) => Err(TimestampError::OutOfRange(n)),
+            other => Err(TimestampError::Type(other.ty_name())),
+        }
+    }
+}
@@ -6,6 +6,7 @@
 use std::{
     convert::{Infallible, TryFrom},
     fmt::{self, Display},
+    io,
     ops::{Deref, DerefMut},
     str::FromStr,
 };
@@ -29,6 +30,23 @@ pub trait Canonical {
     type Error;
 
     fn canonical_form(&self) -> Result<Vec<u8>, Self::Error>;
+
+    /// Write the canonical form to `w`.
+    ///
+    /// The default implementation writes the result of
+    /// [`Canonical::canonical_form`], reporting its errors as
+    /// [`io::ErrorKind::Other`]. Implementations which can produce their
+    /// canonical form incrementally should override this to avoid allocating
+    /// the whole output up front.
+    fn canonical_form_into<W: io::Write>(&self, w: &mut W) -> io::Result<()>
+    where
+        Self::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let bytes = self
+            .canonical_form()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        w.write_all(&bytes)
+    }
 }
 
 #[derive(Debug, Error)]
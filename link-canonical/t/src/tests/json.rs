@@ -3,8 +3,26 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
+use std::{
+    convert::TryFrom,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
 use link_canonical::{
-    json::{Array, Map, ToCjson, Value},
+    json::{
+        Array,
+        Bytes,
+        BytesError,
+        ConversionError,
+        Error,
+        FromCjson,
+        Map,
+        Number,
+        Timestamp,
+        TimestampError,
+        ToCjson,
+        Value,
+    },
     Canonical,
     Cstring,
 };
@@ -16,6 +34,12 @@ struct Foo {
     y_foo: Option<Cstring>,
 }
 
+#[derive(ToCjson)]
+struct Big {
+    neg: i128,
+    pos: u128,
+}
+
 #[derive(ToCjson)]
 struct Bar(bool, bool);
 
@@ -43,19 +67,73 @@ enum F {
     O,
 }
 
-fn roundtrip(s: &str) -> Result<(), String> {
+#[derive(ToCjson)]
+#[cjson(rename_all = "camelCase", tag = "type")]
+enum G {
+    LongName { some_field: u64 },
+    OtherName,
+}
+
+#[derive(ToCjson)]
+#[cjson(tag = "type")]
+enum Other<T> {
+    Known {
+        x: T,
+    },
+    #[cjson(other)]
+    Unknown(String),
+}
+
+#[derive(ToCjson)]
+#[cjson(tag = "type", content = "payload")]
+enum AdjacentOther {
+    Known(u64),
+    #[cjson(other)]
+    Unknown(String),
+}
+
+#[derive(ToCjson)]
+#[cjson(tag = "type")]
+enum UnitOther {
+    Known(u64),
+    #[cjson(other)]
+    Unknown,
+}
+
+#[derive(ToCjson)]
+#[cjson(rename_all = "camelCase")]
+struct Flattened {
+    some_name: String,
+    #[cjson(flatten)]
+    meta: Meta,
+}
+
+#[derive(ToCjson)]
+#[cjson(rename_all = "camelCase")]
+struct Meta {
+    created_at: u64,
+    author: Option<Cstring>,
+}
+
+#[derive(ToCjson)]
+struct FlattenedScalar {
+    #[cjson(flatten)]
+    x: u64,
+}
+
+fn roundtrip(s: &str) -> Result<(), Error> {
     let val = s.parse::<Value>()?;
     assert_eq!(val.canonical_form().unwrap(), s.as_bytes());
     Ok(())
 }
 
-fn encode_string(s: &str) -> Result<String, String> {
+fn encode_string(s: &str) -> Result<String, Error> {
     let bs = s.parse::<Value>()?.canonical_form().unwrap();
     Ok(std::str::from_utf8(&bs).unwrap().to_string())
 }
 
 #[test]
-fn securesystemslib_asserts() -> Result<(), String> {
+fn securesystemslib_asserts() -> Result<(), Error> {
     roundtrip("[1,2,3]")?;
     roundtrip("[]")?;
     roundtrip("{}")?;
@@ -73,7 +151,63 @@ fn securesystemslib_asserts() -> Result<(), String> {
 }
 
 #[test]
-fn ascii_control_characters() -> Result<(), String> {
+fn large_integers() -> Result<(), Error> {
+    let values = vec![
+        u128::MAX.into_cjson(),
+        (u64::MAX as u128 + 1).into_cjson(),
+        i128::MAX.into_cjson(),
+        i128::MIN.into_cjson(),
+        (i64::MIN as i128 - 1).into_cjson(),
+        i64::MIN.into_cjson(),
+        u64::MAX.into_cjson(),
+    ];
+    // Wrapped in an array, since the parser is streaming and a bare number may
+    // be incomplete
+    for val in values {
+        let val = vec![val].into_cjson();
+        let bytes = val.canonical_form().unwrap();
+        assert_eq!(std::str::from_utf8(&bytes).unwrap().parse::<Value>()?, val);
+    }
+
+    assert_eq!(
+        u128::MAX.into_cjson().canonical_form().unwrap(),
+        u128::MAX.to_string().as_bytes()
+    );
+    assert_eq!(
+        i128::MIN.into_cjson().canonical_form().unwrap(),
+        i128::MIN.to_string().as_bytes()
+    );
+
+    // Values which fit in 64 bits have the same representation
+    assert_eq!(42i128.into_cjson(), 42u64.into_cjson());
+    assert_eq!((-42i128).into_cjson(), (-42i64).into_cjson());
+    assert_eq!(42u128.into_cjson(), 42u64.into_cjson());
+
+    // Values beyond 128 bits are rejected rather than truncated
+    assert!("[340282366920938463463374607431768211456]"
+        .parse::<Value>()
+        .is_err());
+    assert!("[-170141183460469231731687303715884105729]"
+        .parse::<Value>()
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn big_canon() {
+    let val = Big {
+        neg: i128::MIN,
+        pos: u128::MAX,
+    };
+    assert_eq!(
+        val.into_cjson().canonical_form().unwrap(),
+        format!(r#"{{"neg":{},"pos":{}}}"#, i128::MIN, u128::MAX).into_bytes()
+    );
+}
+
+#[test]
+fn ascii_control_characters() -> Result<(), Error> {
     for i in 0x00..0x80 {
         assert!(encode_string(&format!("\\x{:02x}", i)).is_err());
     }
@@ -86,7 +220,7 @@ fn ascii_control_characters() -> Result<(), String> {
 }
 
 #[test]
-fn ordered_nested_object() -> Result<(), String> {
+fn ordered_nested_object() -> Result<(), Error> {
     roundtrip(
         r#"{"a":1,"b":2,"c":{"a":null,"h":{"h":-5,"i":3},"x":{}},"nested":{"bad":true,"good":false},"zzz":"I have a newline\n"}"#,
     )?;
@@ -115,6 +249,120 @@ fn ordered_nested_object() -> Result<(), String> {
     Ok(())
 }
 
+#[test]
+fn pretty_reparses() -> Result<(), Error> {
+    let value = r#"{"b":[1,{"d":[],"c":"x\ny"}],"a":{},"e":-5}"#.parse::<Value>()?;
+    let pretty = value.to_pretty(2);
+
+    pretty_assertions::assert_eq!(
+        pretty,
+        r#"{
+  "a": {},
+  "b": [
+    1,
+    {
+      "c": "x\ny",
+      "d": []
+    }
+  ],
+  "e": -5
+}"#
+    );
+    assert_eq!(pretty.parse::<Value>()?, value);
+
+    Ok(())
+}
+
+#[test]
+fn duplicate_keys() -> Result<(), Error> {
+    let duplicate = Error::DuplicateKey(Cstring::from("a"));
+    assert_eq!(r#"{"a":1,"a":2}"#.parse::<Value>(), Err(duplicate.clone()));
+    assert_eq!(r#"[{"b":{"a":1,"a":1}}]"#.parse::<Value>(), Err(duplicate));
+
+    roundtrip(r#"{"a":1,"b":{"a":2}}"#)?;
+
+    Ok(())
+}
+
+#[test]
+fn canonical_form_into() -> Result<(), Error> {
+    let value = r#"{"b":[1,-2,{"d":[],"c":"x\ny"}],"a":{},"e":null,"f":true}"#.parse::<Value>()?;
+
+    let mut streamed = vec![];
+    value.canonical_form_into(&mut streamed).unwrap();
+    assert_eq!(streamed, value.canonical_form().unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn get_path() -> Result<(), Error> {
+    use link_canonical::json::PathSegment;
+
+    let value = r#"{"a":{"b":[1,{"c":"x"}]},"d":null}"#.parse::<Value>()?;
+
+    assert_eq!(value.get_path(&[]), Some(&value));
+    assert_eq!(
+        value.get_path(&["a".into(), "b".into(), 1.into(), "c".into()]),
+        Some(&"x".into_cjson())
+    );
+    assert_eq!(
+        value.get_path(&["a".into(), "b".into(), 0.into()]),
+        Some(&1u64.into_cjson())
+    );
+    assert_eq!(
+        value.get(&Cstring::from("d")).and_then(|d| d.get_path(&[])),
+        Some(&Value::Null)
+    );
+    assert_eq!(
+        value
+            .get(&Cstring::from("a"))
+            .and_then(|a| a.get(&Cstring::from("b"))),
+        value.get_path(&[PathSegment::Key(Cstring::from("a")), "b".into()])
+    );
+
+    // missing key
+    assert_eq!(value.get_path(&["a".into(), "z".into()]), None);
+    // index out of bounds
+    assert_eq!(value.get_path(&["a".into(), "b".into(), 2.into()]), None);
+    // key into an array
+    assert_eq!(value.get_path(&["a".into(), "b".into(), "c".into()]), None);
+    // index into an object
+    assert_eq!(value.get_path(&["a".into(), 0.into()]), None);
+    // through a scalar
+    assert_eq!(value.get_path(&["d".into(), "e".into()]), None);
+    assert_eq!(value.index(0), None);
+
+    Ok(())
+}
+
+#[test]
+fn canonically_eq() -> Result<(), Error> {
+    // Signed integers are not normalised to their smallest representation.
+    let signed = vec![("a", 1i64), ("b", -1i64)]
+        .into_iter()
+        .collect::<Value>();
+    let parsed = r#"{"b":-1,"a":1}"#.parse::<Value>()?;
+    assert_ne!(signed, parsed);
+    assert!(signed.canonically_eq(&parsed));
+    assert!(parsed.canonically_eq(&signed));
+
+    // Key order and unicode normalisation don't matter either way.
+    let reordered = vec![("b", -1i64), ("a", 1i64)]
+        .into_iter()
+        .collect::<Value>();
+    assert_eq!(signed, reordered);
+    assert!(signed.canonically_eq(&reordered));
+    assert!("\u{e9}"
+        .into_cjson()
+        .canonically_eq(&"e\u{301}".into_cjson()));
+
+    assert!(!signed.canonically_eq(&r#"{"a":1,"b":1}"#.parse::<Value>()?));
+    assert!(!1u64.into_cjson().canonically_eq(&"1".into_cjson()));
+
+    Ok(())
+}
+
 #[test]
 fn foo_canon() {
     let val = Foo {
@@ -240,3 +488,224 @@ fn f_canon() {
         vec![("t", "O".into_cjson())].into_iter().collect::<Value>()
     );
 }
+
+#[test]
+fn g_canon() {
+    let val = G::LongName { some_field: 42 };
+    assert_eq!(
+        val.into_cjson(),
+        vec![
+            ("type", "longName".into_cjson()),
+            ("someField", 42u64.into_cjson()),
+        ]
+        .into_iter()
+        .collect::<Value>()
+    );
+
+    assert_eq!(
+        G::OtherName.into_cjson(),
+        vec![("type", "otherName".into_cjson())]
+            .into_iter()
+            .collect::<Value>()
+    );
+}
+
+#[test]
+fn from_serde_json() {
+    let json = serde_json::json!({
+        "name": "radicle",
+        "tags": ["link", -1, 2, null, true],
+        "nested": { "n": 18446744073709551615u64 },
+    });
+    let val = Value::try_from(json.clone()).unwrap();
+    assert_eq!(
+        val,
+        vec![
+            ("name", "radicle".into_cjson()),
+            (
+                "tags",
+                vec![
+                    "link".into_cjson(),
+                    Value::Number(Number::I64(-1)),
+                    2u64.into_cjson(),
+                    Value::Null,
+                    true.into_cjson(),
+                ]
+                .into_cjson()
+            ),
+            (
+                "nested",
+                vec![("n", u64::MAX.into_cjson())]
+                    .into_iter()
+                    .collect::<Value>()
+            ),
+        ]
+        .into_iter()
+        .collect::<Value>()
+    );
+    assert_eq!(serde_json::Value::try_from(val).unwrap(), json);
+}
+
+#[test]
+fn from_serde_json_rejects_floats() {
+    let json = serde_json::json!({ "tags": [1, 2.5] });
+    assert!(matches!(
+        Value::try_from(json),
+        Err(ConversionError::Float(_))
+    ));
+}
+
+#[test]
+fn into_serde_json_rejects_wide_numbers() {
+    let val = Value::Number(Number::U128(u128::from(u64::MAX) + 1));
+    assert!(matches!(
+        serde_json::Value::try_from(val),
+        Err(ConversionError::OutOfRange(Number::U128(_)))
+    ));
+    assert_eq!(
+        serde_json::Value::try_from(Value::Number(Number::I128(-3))).unwrap(),
+        serde_json::json!(-3)
+    );
+}
+
+#[test]
+fn timestamp_roundtrip() {
+    for secs in [0, 1_650_000_000, u64::MAX] {
+        let ts = Timestamp::from_secs(secs);
+        let val = ts.into_cjson();
+        assert_eq!(val, Value::Number(Number::U64(secs)));
+
+        let parsed = Value::try_from(val.canonical_form().unwrap().as_slice()).unwrap();
+        assert_eq!(Timestamp::from_cjson(parsed).unwrap(), ts);
+    }
+}
+
+#[test]
+fn timestamp_truncates_system_time() {
+    let time = UNIX_EPOCH + Duration::from_millis(1_650_000_000_999);
+    let ts = Timestamp::try_from(time).unwrap();
+    assert_eq!(ts.as_secs(), 1_650_000_000);
+    assert_eq!(
+        SystemTime::try_from(ts).unwrap(),
+        UNIX_EPOCH + Duration::from_secs(1_650_000_000)
+    );
+}
+
+#[test]
+fn timestamp_overflows_system_time() {
+    assert!(matches!(
+        SystemTime::try_from(Timestamp::from_secs(u64::MAX)),
+        Err(TimestampError::SystemTime(u64::MAX))
+    ));
+}
+
+#[test]
+fn timestamp_accepts_non_negative_i64() {
+    assert_eq!(
+        Timestamp::from_cjson(Value::Number(Number::I64(1_650_000_000))).unwrap(),
+        Timestamp::from_secs(1_650_000_000)
+    );
+}
+
+#[test]
+fn timestamp_rejects_non_integers() {
+    assert!("1650000000.5".parse::<Value>().is_err());
+    assert!(matches!(
+        Timestamp::from_cjson("1650000000".into_cjson()),
+        Err(TimestampError::Type("string"))
+    ));
+    assert!(matches!(
+        Timestamp::from_cjson(Value::Number(Number::I64(-1))),
+        Err(TimestampError::OutOfRange(Number::I64(-1)))
+    ));
+}
+
+#[test]
+fn other_canon() {
+    assert_eq!(
+        Other::Known { x: 42u64 }.into_cjson(),
+        vec![("type", "Known".into_cjson()), ("x", 42u64.into_cjson())]
+            .into_iter()
+            .collect::<Value>()
+    );
+    assert_eq!(
+        Other::<u64>::Unknown("frobnicate".to_string()).into_cjson(),
+        "frobnicate".into_cjson()
+    );
+
+    assert_eq!(
+        AdjacentOther::Known(42).into_cjson(),
+        vec![
+            ("type", "Known".into_cjson()),
+            ("payload", 42u64.into_cjson())
+        ]
+        .into_iter()
+        .collect::<Value>()
+    );
+    assert_eq!(
+        AdjacentOther::Unknown("frobnicate".to_string()).into_cjson(),
+        "frobnicate".into_cjson()
+    );
+
+    assert_eq!(
+        UnitOther::Unknown.into_cjson(),
+        vec![("type", "Unknown".into_cjson())]
+            .into_iter()
+            .collect::<Value>()
+    );
+}
+
+#[test]
+fn flatten_canon() {
+    let val = Flattened {
+        some_name: "radicle".to_string(),
+        meta: Meta {
+            created_at: 1_650_000_000,
+            author: None,
+        },
+    };
+    assert_eq!(
+        val.into_cjson(),
+        vec![
+            ("someName", "radicle".into_cjson()),
+            ("createdAt", 1_650_000_000u64.into_cjson()),
+            ("author", Value::Null),
+        ]
+        .into_iter()
+        .collect::<Value>()
+    );
+}
+
+#[test]
+#[should_panic(expected = "cannot flatten field `x`")]
+fn flatten_non_object() {
+    FlattenedScalar { x: 42 }.into_cjson();
+}
+
+#[test]
+fn bytes_roundtrip() {
+    let large = (0..=u8::MAX).cycle().take(64 * 1024).collect::<Vec<_>>();
+    for bytes in [vec![], vec![0], b"radicle".to_vec(), large] {
+        let val = Bytes(bytes.clone()).into_cjson();
+        assert!(matches!(val, Value::String(_)));
+        assert_eq!(val, Value::bytes(&bytes));
+        assert_eq!(val.as_bytes(), Some(bytes.clone()));
+
+        let parsed = Value::try_from(val.canonical_form().unwrap().as_slice()).unwrap();
+        assert_eq!(Bytes::from_cjson(parsed).unwrap(), Bytes(bytes));
+    }
+}
+
+#[test]
+fn bytes_rejects_other_encodings() {
+    // multibase base16
+    assert_eq!("f7261646963616c".into_cjson().as_bytes(), None);
+    assert!(matches!(
+        Bytes::from_cjson("not bytes".into_cjson()),
+        Err(BytesError::Encoding)
+    ));
+    assert!(matches!(
+        Bytes::from_cjson(42u64.into_cjson()),
+        Err(BytesError::Type("number"))
+    ));
+}
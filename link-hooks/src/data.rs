@@ -6,19 +6,64 @@ use std::{fmt, str::FromStr};
 use link_identities::urn::{HasProtocol, Urn};
 use multihash::Multihash;
 
-use super::{sealed, Display, IsZero, Updated};
+use super::{sealed, Display, IsZero, Kind, Updated};
 
+/// A notification that the reference, identified by `urn`, was updated from
+/// `old` to `new`.
+///
+/// The wire form is `<urn> <old> <new> LF`, see the [`fmt::Display`] and
+/// [`FromStr`] implementations.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Data<R> {
+    /// The URN of the project, including the path of the updated reference.
     pub urn: Urn<R>,
+    /// The revision before the update, or the zero revision if the reference
+    /// was created.
     pub old: R,
+    /// The revision after the update, or the zero revision if the reference
+    /// was deleted.
     pub new: R,
 }
 
+impl<R> Data<R> {
+    pub fn new(urn: Urn<R>, old: R, new: R) -> Self {
+        Self { urn, old, new }
+    }
+
+    /// The URN of the project, including the path of the updated reference.
+    pub fn urn(&self) -> &Urn<R> {
+        &self.urn
+    }
+
+    /// The revision before the update.
+    pub fn old_rev(&self) -> &R {
+        &self.old
+    }
+
+    /// The revision after the update.
+    pub fn new_rev(&self) -> &R {
+        &self.new
+    }
+}
+
 impl<R> Data<R>
 where
     R: IsZero + PartialEq,
 {
+    /// Whether the update was a creation, update, or deletion of the
+    /// reference.
+    ///
+    /// Returns `None` if both `old` and `new` are the zero revision, in which
+    /// case there is nothing to classify.
+    pub fn kind(&self) -> Option<Kind> {
+        match self.updated() {
+            Updated::Zero => None,
+            Updated::Created => Some(Kind::Create),
+            Updated::Deleted => Some(Kind::Delete),
+            Updated::Changed | Updated::NoChange => Some(Kind::Update),
+        }
+    }
+
     pub fn updated(&self) -> Updated {
         match (self.old.is_zero(), self.new.is_zero()) {
             (true, true) => Updated::Zero,
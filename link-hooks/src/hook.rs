@@ -1,9 +1,28 @@
 // Copyright © 2022 The Radicle Link Contributors
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::{collections::HashMap, ffi::OsStr, fmt, path::PathBuf, str::FromStr, time::Duration};
-
-use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt as _};
+use std::{
+    collections::{HashMap, VecDeque},
+    ffi::{OsStr, OsString},
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+        RwLock,
+    },
+    time::Duration,
+};
+
+use futures::{
+    future::BoxFuture,
+    stream::FuturesUnordered,
+    Future,
+    FutureExt,
+    Stream,
+    StreamExt as _,
+};
 use multihash::Multihash;
 use tokio::sync::mpsc;
 
@@ -14,6 +33,11 @@ use super::{Data, Display, Track};
 pub mod config;
 pub use config::Config;
 
+pub mod frame;
+
+pub mod in_process;
+pub use in_process::InProcess;
+
 /// End of transimission character.
 pub const EOT: u8 = 0x04;
 
@@ -22,6 +46,31 @@ pub const EOT: u8 = 0x04;
 pub enum Notification<R> {
     Track(Track<R>),
     Data(Data<R>),
+    Batch(Batch<R>),
+}
+
+/// A set of notifications that are delivered to a hook as a single message,
+/// see [`config::Config::coalesce`].
+///
+/// A batch only ever contains notifications of the same kind, since [`Data`]
+/// and [`Track`] notifications are delivered to different hooks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Batch<R> {
+    Track(Vec<Track<R>>),
+    Data(Vec<Data<R>>),
+}
+
+impl<R> fmt::Display for Batch<R>
+where
+    R: HasProtocol + fmt::Display,
+    for<'a> &'a R: Into<Multihash>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Track(tracks) => tracks.iter().try_for_each(|track| write!(f, "{}", track)),
+            Self::Data(data) => data.iter().try_for_each(|data| write!(f, "{}", data)),
+        }
+    }
 }
 
 impl<R> fmt::Display for Notification<R>
@@ -33,6 +82,7 @@ where
         match self {
             Self::Track(track) => write!(f, "{}", track),
             Self::Data(data) => write!(f, "{}", data),
+            Self::Batch(batch) => write!(f, "{}", batch),
         }
     }
 }
@@ -49,12 +99,60 @@ impl<R> From<Data<R>> for Notification<R> {
     }
 }
 
+impl<R> From<Batch<R>> for Notification<R> {
+    fn from(b: Batch<R>) -> Self {
+        Self::Batch(b)
+    }
+}
+
+/// Statistics about the delivery of [`Notification`]s to each [`Hook`],
+/// obtained via [`Hooks::stats`].
+///
+/// The statistics are shared, i.e. they keep updating while [`Hooks::run`] is
+/// processing notifications.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    dropped: Arc<RwLock<HashMap<PathBuf, Arc<AtomicUsize>>>>,
+}
+
+impl Stats {
+    /// The number of messages dropped for the hook at `path`, because it was
+    /// processing them too slowly.
+    pub fn dropped(&self, path: &Path) -> usize {
+        self.dropped
+            .read()
+            .expect("BUG: stats lock poisoned")
+            .get(path)
+            .map_or(0, |count| count.load(Ordering::Relaxed))
+    }
+
+    /// The number of messages dropped for each hook.
+    pub fn all_dropped(&self) -> HashMap<PathBuf, usize> {
+        self.dropped
+            .read()
+            .expect("BUG: stats lock poisoned")
+            .iter()
+            .map(|(path, count)| (path.clone(), count.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    fn counter(&self, path: &Path) -> Arc<AtomicUsize> {
+        self.dropped
+            .write()
+            .expect("BUG: stats lock poisoned")
+            .entry(path.to_path_buf())
+            .or_default()
+            .clone()
+    }
+}
+
 /// Executor for a set of [`Hook`]s that will receive and process
 /// [`Notification`]s via a channel.
 pub struct Hooks<P: Process> {
     data_hooks: Vec<Hook<P>>,
     track_hooks: Vec<Hook<P>>,
     config: Config,
+    stats: Stats,
 }
 
 impl<P: Process + Send + Sync + 'static> Hooks<P> {
@@ -70,9 +168,16 @@ impl<P: Process + Send + Sync + 'static> Hooks<P> {
             data_hooks,
             track_hooks,
             config,
+            stats: Stats::default(),
         }
     }
 
+    /// A handle to the delivery [`Stats`] of the hooks, which can be used while
+    /// they [`Hooks::run`].
+    pub fn stats(&self) -> Stats {
+        self.stats.clone()
+    }
+
     /// The `incoming` [`Notification`]s are sent to each respective hook,
     /// depending on the notification variant, until the stream is exhausted.
     ///
@@ -89,30 +194,45 @@ impl<P: Process + Send + Sync + 'static> Hooks<P> {
         use senders::{Event, Senders};
 
         let mut routines = FuturesUnordered::new();
-        let mut data_senders: Senders<Data<R>> = Senders::new(Event::Data);
-        let mut track_senders: Senders<Track<R>> = Senders::new(Event::Track);
+        let overflow = self.config.hook.overflow;
+        let mut data_senders: Senders<Data<R>> = Senders::new(Event::Data, overflow);
+        let mut track_senders: Senders<Track<R>> = Senders::new(Event::Track, overflow);
 
         for hook in self.data_hooks {
             let path = hook.path.clone();
             tracing::debug!(hook = %path.display(), "starting data hook");
             let (sender, routine) = hook.start(self.config.hook);
-            data_senders.insert(path, sender);
+            data_senders.insert(path.clone(), sender, self.stats.counter(&path));
             routines.push(routine);
         }
         for hook in self.track_hooks {
             let path = hook.path.clone();
             tracing::debug!(hook = %path.display(), "starting track hook");
             let (sender, routine) = hook.start(self.config.hook);
-            track_senders.insert(path, sender);
+            track_senders.insert(path.clone(), sender, self.stats.counter(&path));
             routines.push(routine);
         }
+        let mut pending_data = Vec::new();
+        let mut pending_track = Vec::new();
+        let mut deadline = None;
         loop {
+            let flush = match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).boxed(),
+                None => futures::future::pending().boxed(),
+            };
             futures::select! {
+                () = flush.fuse() => {
+                    deadline = None;
+                    let failed = drive(
+                        flush_pending(&data_senders, &track_senders, &mut pending_data, &mut pending_track),
+                        &mut routines,
+                    )
+                    .await;
+                    remove_failed(failed, &mut data_senders, &mut track_senders);
+                },
                 failed_hook_path = routines.next().fuse() => {
                     if let Some(failed_hook_path) = failed_hook_path {
-                        tracing::warn!(hook = %failed_hook_path.display(), "hook failed, removing from hooks set");
-                        data_senders.remove(&failed_hook_path);
-                        track_senders.remove(&failed_hook_path);
+                        remove_failed(Some(failed_hook_path), &mut data_senders, &mut track_senders);
                     } else {
                         tracing::error!("all hook routines have stopped");
                         break;
@@ -120,13 +240,37 @@ impl<P: Process + Send + Sync + 'static> Hooks<P> {
                 }
                 n = incoming.next().fuse() => {
                     match n {
+                        Some(n) if self.config.coalesce.is_some() => {
+                            if deadline.is_none() {
+                                deadline = self
+                                    .config
+                                    .coalesce
+                                    .map(|window| tokio::time::Instant::now() + window);
+                            }
+                            match n {
+                                Notification::Data(d) => pending_data.push(d),
+                                Notification::Track(t) => pending_track.push(t),
+                                Notification::Batch(Batch::Data(ds)) => pending_data.extend(ds),
+                                Notification::Batch(Batch::Track(ts)) => pending_track.extend(ts),
+                            }
+                        },
                         Some(Notification::Data(d)) => {
                             tracing::trace!(data = %d, "received data notification");
-                            data_senders.send(d)
+                            let failed = drive(data_senders.send(d.into()), &mut routines).await;
+                            remove_failed(failed, &mut data_senders, &mut track_senders);
                         },
                         Some(Notification::Track(t)) => {
                             tracing::trace!(track = %t, "received track notification");
-                            track_senders.send(t)
+                            let failed = drive(track_senders.send(t.into()), &mut routines).await;
+                            remove_failed(failed, &mut data_senders, &mut track_senders);
+                        },
+                        Some(Notification::Batch(batch)) => {
+                            tracing::trace!(batch = %batch, "received batch notification");
+                            let failed = match batch {
+                                Batch::Data(ds) => drive(data_senders.send(HookMessage::Batch(ds)), &mut routines).await,
+                                Batch::Track(ts) => drive(track_senders.send(HookMessage::Batch(ts)), &mut routines).await,
+                            };
+                            remove_failed(failed, &mut data_senders, &mut track_senders);
                         },
                         None => {
                             tracing::trace!("finished notifications stream");
@@ -137,6 +281,19 @@ impl<P: Process + Send + Sync + 'static> Hooks<P> {
             }
         }
 
+        // Deliver anything still waiting for the coalescing window
+        let failed = drive(
+            flush_pending(
+                &data_senders,
+                &track_senders,
+                &mut pending_data,
+                &mut pending_track,
+            ),
+            &mut routines,
+        )
+        .await;
+        remove_failed(failed, &mut data_senders, &mut track_senders);
+
         // Send EOTs to all senders
         data_senders.eot().await;
         track_senders.eot().await;
@@ -149,6 +306,65 @@ impl<P: Process + Send + Sync + 'static> Hooks<P> {
     }
 }
 
+/// Stop delivering notifications to the `failed` hooks.
+fn remove_failed<R>(
+    failed: impl IntoIterator<Item = PathBuf>,
+    data_senders: &mut senders::Senders<Data<R>>,
+    track_senders: &mut senders::Senders<Track<R>>,
+) {
+    for path in failed {
+        tracing::warn!(hook = %path.display(), "hook failed, removing from hooks set");
+        data_senders.remove(&path);
+        track_senders.remove(&path);
+    }
+}
+
+/// Deliver the coalesced notifications as batches, leaving the buffers empty.
+async fn flush_pending<R>(
+    data_senders: &senders::Senders<Data<R>>,
+    track_senders: &senders::Senders<Track<R>>,
+    pending_data: &mut Vec<Data<R>>,
+    pending_track: &mut Vec<Track<R>>,
+) where
+    R: Clone,
+{
+    if !pending_data.is_empty() {
+        data_senders
+            .send(HookMessage::Batch(std::mem::take(pending_data)))
+            .await;
+    }
+    if !pending_track.is_empty() {
+        track_senders
+            .send(HookMessage::Batch(std::mem::take(pending_track)))
+            .await;
+    }
+}
+
+/// Complete `send` while making progress on the hook `routines`, since
+/// [`config::Overflow::Block`] waits on them to free up their channels.
+///
+/// Returns the paths of the hooks which finished in the meantime.
+async fn drive<F>(send: F, routines: &mut FuturesUnordered<BoxFuture<'_, PathBuf>>) -> Vec<PathBuf>
+where
+    F: Future<Output = ()>,
+{
+    let mut failed = Vec::new();
+    let send = send.fuse();
+    futures::pin_mut!(send);
+    loop {
+        futures::select! {
+            () = send => return failed,
+            path = routines.next() => match path {
+                Some(path) => failed.push(path),
+                None => {
+                    send.await;
+                    return failed;
+                },
+            },
+        }
+    }
+}
+
 /// A communication medium for a hook process.
 ///
 /// # Cancel Safety
@@ -179,14 +395,19 @@ pub trait Process: Sized {
 /// A spawned hook process.
 pub struct Hook<P: Process> {
     path: PathBuf,
+    args: Vec<OsString>,
     child: P,
 }
 
+#[derive(Clone)]
 pub enum HookMessage<T> {
     /// End of transmission message.
     EOT,
     /// The payload to be sent to a hook, usually [`Data`] or [`Track`].
     Payload(T),
+    /// A set of payloads to be sent to a hook as a single message, see
+    /// [`frame`] for how its end is marked.
+    Batch(Vec<T>),
 }
 
 impl<T> From<T> for HookMessage<T> {
@@ -208,8 +429,40 @@ impl<T: FromStr> FromStr for HookMessage<T> {
 }
 
 impl<P: Process + Send + Sync + 'static> Hook<P> {
+    /// Wrap the `child` process of the hook at `path`.
+    ///
+    /// If the hook fails it is respawned from `path` without any arguments, so
+    /// `child` should have been spawned that way too. Use [`Process::spawn`]
+    /// for a hook which takes arguments.
     pub fn new(path: PathBuf, child: P) -> Self {
-        Self { path, child }
+        Self {
+            path,
+            args: Vec::new(),
+            child,
+        }
+    }
+
+    /// Respawn the hook process and replay the `recent` messages to it.
+    ///
+    /// The failed process is waited for, or killed after `timeout`, first.
+    async fn restart(
+        &mut self,
+        recent: &VecDeque<Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<(), error::Restart> {
+        if let Err(err) = self.child.wait_or_kill(timeout).await {
+            tracing::warn!(err = %err, "failed to terminate hook");
+        }
+        self.child = P::spawn(self.path.clone(), self.args.iter())
+            .await
+            .map_err(|err| error::Restart::Spawn(Box::new(err)))?;
+        for bytes in recent {
+            self.child
+                .write(bytes)
+                .await
+                .map_err(|err| error::Restart::Replay(Box::new(err)))?;
+        }
+        Ok(())
     }
 
     #[tracing::instrument(skip(self), fields(hook = ?self.path))]
@@ -222,9 +475,11 @@ impl<P: Process + Send + Sync + 'static> Hook<P> {
     {
         let (sx, mut rx) = mpsc::channel::<HookMessage<D>>(config.buffer);
         let routine = async move {
+            let mut restarts = config.restarts;
+            let mut recent = VecDeque::with_capacity(config.replay);
             tracing::trace!("waiting for notification");
             while let Some(msg) = rx.recv().await {
-                match msg {
+                let payload = match msg {
                     HookMessage::EOT => {
                         if let Err(err) = self.write(&[EOT]).await {
                             tracing::warn!(err = %err, "failed to write EOT to hook");
@@ -234,12 +489,42 @@ impl<P: Process + Send + Sync + 'static> Hook<P> {
                         }
                         return self.path;
                     },
-                    HookMessage::Payload(msg) => {
-                        if let Err(err) = self.write(msg.display().as_bytes()).await {
-                            tracing::warn!(err = %err, "failed to write to hook");
+                    HookMessage::Payload(msg) => msg.display(),
+                    HookMessage::Batch(msgs) => msgs
+                        .iter()
+                        .map(Display::display)
+                        .chain(Some(String::from("\n")))
+                        .collect(),
+                };
+                let bytes = match frame::encode(config.framing, payload.as_bytes()) {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        tracing::warn!(err = %err, "failed to frame message for hook");
+                        continue;
+                    },
+                };
+                if config.replay > 0 {
+                    if recent.len() == config.replay {
+                        recent.pop_front();
+                    }
+                    recent.push_back(bytes.clone());
+                }
+                if let Err(err) = self.write(&bytes).await {
+                    tracing::warn!(err = %err, "failed to write to hook");
+                    loop {
+                        if restarts == 0 {
+                            if let Err(err) = self.wait_or_kill(config.timeout).await {
+                                tracing::warn!(err = %err, "failed to terminate hook");
+                            }
                             return self.path;
                         }
-                    },
+                        restarts -= 1;
+                        tracing::info!(replay = recent.len(), "restarting hook");
+                        match self.restart(&recent, config.timeout).await {
+                            Ok(()) => break,
+                            Err(err) => tracing::warn!(err = %err, "failed to restart hook"),
+                        }
+                    }
                 }
             }
             self.path
@@ -263,9 +548,14 @@ where
         I: IntoIterator<Item = S> + Send,
         S: AsRef<OsStr>,
     {
+        let args = args
+            .into_iter()
+            .map(|arg| arg.as_ref().to_os_string())
+            .collect::<Vec<_>>();
         Ok(Self {
             path: path.clone(),
-            child: P::spawn(path, args).await?,
+            child: P::spawn(path, args.iter()).await?,
+            args,
         })
     }
 
@@ -288,39 +578,53 @@ pub(super) mod senders {
     }
 
     pub struct Senders<P> {
-        senders: HashMap<PathBuf, mpsc::Sender<HookMessage<P>>>,
+        senders: HashMap<PathBuf, (mpsc::Sender<HookMessage<P>>, Arc<AtomicUsize>)>,
         kind: Event,
+        overflow: config::Overflow,
     }
 
     impl<P> Senders<P> {
-        pub fn new(kind: Event) -> Self {
+        pub fn new(kind: Event, overflow: config::Overflow) -> Self {
             Self {
                 senders: HashMap::new(),
                 kind,
+                overflow,
             }
         }
 
-        pub fn insert(&mut self, path: PathBuf, sender: mpsc::Sender<HookMessage<P>>) {
-            self.senders.insert(path, sender);
+        pub fn insert(
+            &mut self,
+            path: PathBuf,
+            sender: mpsc::Sender<HookMessage<P>>,
+            dropped: Arc<AtomicUsize>,
+        ) {
+            self.senders.insert(path, (sender, dropped));
         }
 
         pub fn remove(&mut self, path: &PathBuf) {
             self.senders.remove(path);
         }
 
-        pub fn send(&self, p: P)
+        pub async fn send(&self, msg: HookMessage<P>)
         where
             P: Clone,
         {
-            for (path, sender) in self.senders.iter() {
-                if sender.try_send(p.clone().into()).is_err() {
+            for (path, (sender, dropped)) in self.senders.iter() {
+                let sent = match self.overflow {
+                    config::Overflow::Drop => sender.try_send(msg.clone()).is_ok(),
+                    config::Overflow::Block { timeout } => {
+                        matches!(sender.send_timeout(msg.clone(), timeout).await, Ok(()))
+                    },
+                };
+                if !sent {
+                    dropped.fetch_add(1, Ordering::Relaxed);
                     tracing::warn!(hook=%path.display(), kind=?self.kind, "dropping message for hook which is running too slowly");
                 }
             }
         }
 
         pub async fn eot(&self) {
-            for (path, sender) in self.senders.iter() {
+            for (path, (sender, _)) in self.senders.iter() {
                 if let Err(err) = sender.send(HookMessage::EOT).await {
                     tracing::warn!(hook=%path.display(), kind=?self.kind, err=%err, "failed to send EOT");
                 }
@@ -329,6 +633,18 @@ pub(super) mod senders {
     }
 }
 
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    pub enum Restart {
+        #[error("failed to respawn hook")]
+        Spawn(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error("failed to replay recent messages to hook")]
+        Replay(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    }
+}
+
 mod tokio_impl {
     use std::{ffi::OsStr, io, path::PathBuf, process::Stdio, time::Duration};
     use tokio::{
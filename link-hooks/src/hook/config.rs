@@ -7,6 +7,10 @@ use std::time::Duration;
 pub struct Config {
     /// Configuration for the set of [`super::Hooks`]
     pub hook: Hook,
+    /// If set, notifications are buffered for this window, starting from the
+    /// first notification received, and are delivered to the hooks as a
+    /// single [`super::Notification::Batch`].
+    pub coalesce: Option<Duration>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -16,6 +20,20 @@ pub struct Hook {
     /// The duration to wait for a hook to complete after the
     /// end-of-transmission message before it is forcefully killed.
     pub timeout: Duration,
+    /// How messages are framed when written to the hook. See
+    /// [`super::frame`] for the wire format.
+    pub framing: Framing,
+    /// What to do with a message when the hook's channel is full.
+    pub overflow: Overflow,
+    /// The number of times a hook is respawned after failing to receive a
+    /// message. If it is `0`, a failed hook is removed from the set of hooks.
+    pub restarts: usize,
+    /// The number of most recent messages replayed to a respawned hook before
+    /// it resumes receiving new messages.
+    ///
+    /// Note that this is best-effort: the messages are only kept in memory,
+    /// and the hook may receive messages it already processed before failing.
+    pub replay: usize,
 }
 
 impl Default for Hook {
@@ -23,6 +41,44 @@ impl Default for Hook {
         Self {
             buffer: 10,
             timeout: Duration::from_secs(2),
+            framing: Framing::default(),
+            overflow: Overflow::default(),
+            restarts: 0,
+            replay: 0,
         }
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Drop the message, see [`super::Stats::dropped`].
+    Drop,
+    /// Wait for up to `timeout` for the hook to catch up, which holds up the
+    /// delivery of further notifications. If the hook does not catch up in
+    /// time, the message is dropped.
+    Block { timeout: Duration },
+}
+
+impl Default for Overflow {
+    fn default() -> Self {
+        Self::Drop
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Framing {
+    /// Payloads are written as is, delimited by newlines.
+    Raw,
+    /// Payloads are written with a length prefix.
+    LengthPrefixed {
+        /// Payloads larger than this number of bytes are gzip compressed. If
+        /// it is `None` payloads are never compressed.
+        compress_above: Option<usize>,
+    },
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Self::Raw
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Framing of the messages written to the stdin of a hook.
+//!
+//! How messages are framed is chosen by [`Framing`] in the hook's
+//! [`super::config::Hook`].
+//!
+//! # Raw
+//!
+//! The default. Each payload is written as is -- a [`crate::Data`] or
+//! [`crate::Track`] line, terminated by a newline -- and the end of
+//! transmission is signalled by the single byte [`EOT`].
+//!
+//! # Length-prefixed
+//!
+//! Each payload is written as a frame consisting of a one byte kind, followed
+//! by the length of the body as a big-endian `u32`, followed by the body:
+//!
+//! ```text
+//! +--------+------------------+----------------+
+//! | kind   | length           | body           |
+//! | 1 byte | 4 bytes, u32 BE  | `length` bytes |
+//! +--------+------------------+----------------+
+//! ```
+//!
+//! The kind is one of:
+//!   * [`RAW`] (`0x00`) -- the body is the payload as it would be written with
+//!     raw framing, including the trailing newline.
+//!   * [`GZIP`] (`0x01`) -- the body is the same payload compressed with gzip
+//!     ([RFC 1952]). Payloads are only compressed if they are larger than the
+//!     configured threshold.
+//!   * [`EOT`] (`0x04`) -- the end of transmission. It is a single byte, with
+//!     no length or body following it, so it is the same in both framings.
+//!
+//! # Batches
+//!
+//! Several [`crate::Data`] or [`crate::Track`] lines may be delivered as a
+//! single batch, e.g. when notifications are coalesced. The payload of a batch
+//! is its lines followed by an empty line, so that the end of the batch is
+//! explicit in either framing.
+//!
+//! [RFC 1952]: https://www.rfc-editor.org/rfc/rfc1952
+
+use std::{
+    convert::TryFrom as _,
+    io::{self, Read as _, Write as _},
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+pub use super::{config::Framing, EOT};
+
+/// The frame kind of an uncompressed payload.
+pub const RAW: u8 = 0x00;
+/// The frame kind of a gzip compressed payload.
+pub const GZIP: u8 = 0x01;
+
+/// The length of the header of a length-prefixed frame.
+const HEADER_LEN: usize = 5;
+
+/// A decoded length-prefixed frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+    /// The payload, decompressed if necessary.
+    Payload(Vec<u8>),
+    /// The end of transmission.
+    Eot,
+}
+
+/// Encode `payload` according to `framing`.
+pub fn encode(framing: Framing, payload: &[u8]) -> Result<Vec<u8>, error::Encode> {
+    match framing {
+        Framing::Raw => Ok(payload.to_vec()),
+        Framing::LengthPrefixed { compress_above } => {
+            let (kind, body) = match compress_above {
+                Some(threshold) if payload.len() > threshold => {
+                    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                    encoder.write_all(payload)?;
+                    (GZIP, encoder.finish()?)
+                },
+                _ => (RAW, payload.to_vec()),
+            };
+            let len = u32::try_from(body.len())
+                .map_err(|_| error::Encode::TooLarge { len: body.len() })?;
+            let mut frame = Vec::with_capacity(HEADER_LEN + body.len());
+            frame.push(kind);
+            frame.extend_from_slice(&len.to_be_bytes());
+            frame.extend(body);
+            Ok(frame)
+        },
+    }
+}
+
+/// Decode the first length-prefixed frame in `bytes`, returning it along with
+/// the remaining bytes.
+pub fn decode(bytes: &[u8]) -> Result<(Frame, &[u8]), error::Decode> {
+    let (kind, rest) = bytes.split_first().ok_or(error::Decode::Incomplete)?;
+    if *kind == EOT {
+        return Ok((Frame::Eot, rest));
+    }
+    if rest.len() < HEADER_LEN - 1 {
+        return Err(error::Decode::Incomplete);
+    }
+    let (len, rest) = rest.split_at(HEADER_LEN - 1);
+    let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+    if rest.len() < len {
+        return Err(error::Decode::Incomplete);
+    }
+    let (body, rest) = rest.split_at(len);
+    let payload = match *kind {
+        RAW => body.to_vec(),
+        GZIP => {
+            let mut payload = Vec::new();
+            GzDecoder::new(body).read_to_end(&mut payload)?;
+            payload
+        },
+        kind => return Err(error::Decode::UnknownKind(kind)),
+    };
+    Ok((Frame::Payload(payload), rest))
+}
+
+pub mod error {
+    use std::io;
+
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    pub enum Encode {
+        #[error("payload of {len} bytes is too large to be framed")]
+        TooLarge { len: usize },
+        #[error(transparent)]
+        Io(#[from] io::Error),
+    }
+
+    #[derive(Debug, Error)]
+    pub enum Decode {
+        #[error("incomplete frame")]
+        Incomplete,
+        #[error("unknown frame kind {0:#04x}")]
+        UnknownKind(u8),
+        #[error(transparent)]
+        Io(#[from] io::Error),
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! A [`Process`] which runs within the current process.
+//!
+//! Instead of writing to the stdin of a subprocess, the bytes written to an
+//! [`InProcess`] hook are delivered via a channel, which allows embedding hook
+//! logic, or observing what a hook would receive in tests.
+
+use std::{convert::Infallible, ffi::OsStr, path::PathBuf, time::Duration};
+
+use tokio::sync::mpsc;
+
+use super::Process;
+
+/// The receiving end of an [`InProcess`] hook. Each item is the bytes of a
+/// single write, i.e. a framed payload or the final [`super::EOT`].
+///
+/// Dropping the receiver signals that the hook has finished, which is what
+/// [`Process::wait_or_kill`] waits for.
+pub type Receiver = mpsc::UnboundedReceiver<Vec<u8>>;
+
+/// A hook which delivers the bytes written to it over a channel.
+pub struct InProcess {
+    sender: Option<mpsc::UnboundedSender<Vec<u8>>>,
+}
+
+impl InProcess {
+    /// Create an [`InProcess`] hook along with the [`Receiver`] the hook logic
+    /// should consume.
+    pub fn channel() -> (Self, Receiver) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (
+            Self {
+                sender: Some(sender),
+            },
+            receiver,
+        )
+    }
+}
+
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    #[error("in-process hooks cannot be spawned, use `InProcess::channel`")]
+    pub struct Spawn;
+
+    #[derive(Debug, Error)]
+    pub enum Write {
+        #[error("the in-process hook was killed")]
+        Killed,
+        #[error("the in-process hook has finished")]
+        Finished,
+    }
+}
+
+#[async_trait]
+impl Process for InProcess {
+    type SpawnError = error::Spawn;
+    type WriteError = error::Write;
+    type DieError = Infallible;
+
+    async fn spawn<I, S>(_path: PathBuf, _args: I) -> Result<Self, Self::SpawnError>
+    where
+        I: IntoIterator<Item = S> + Send,
+        S: AsRef<OsStr>,
+    {
+        Err(error::Spawn)
+    }
+
+    async fn write(&mut self, bs: &[u8]) -> Result<(), Self::WriteError> {
+        let sender = self.sender.as_ref().ok_or(error::Write::Killed)?;
+        sender.send(bs.to_vec()).map_err(|_| error::Write::Finished)
+    }
+
+    /// Wait for the [`Receiver`] to be dropped. If that does not happen within
+    /// `duration`, the hook is killed by closing the channel, i.e. the
+    /// [`Receiver`] will see the end of the stream.
+    async fn wait_or_kill(&mut self, duration: Duration) -> Result<(), Self::DieError> {
+        let sender = match self.sender.as_ref() {
+            None => return Ok(()),
+            Some(sender) => sender,
+        };
+        if tokio::time::timeout(duration, sender.closed())
+            .await
+            .is_err()
+        {
+            self.sender = None;
+        }
+        Ok(())
+    }
+}
@@ -11,7 +11,7 @@ pub mod track;
 pub use track::Track;
 
 pub mod hook;
-pub use hook::{Hooks, Notification};
+pub use hook::{Batch, Hooks, Notification, Stats};
 
 mod sealed;
 
@@ -38,6 +38,17 @@ pub enum Updated {
     NoChange,
 }
 
+/// The kind of change a notification describes, see [`Data::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// The reference was created, i.e. `old` is the zero revision.
+    Create,
+    /// The reference was updated from one non-zero revision to another.
+    Update,
+    /// The reference was deleted, i.e. `new` is the zero revision.
+    Delete,
+}
+
 #[cfg(feature = "git")]
 mod git {
     use git2::Oid;
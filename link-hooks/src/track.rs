@@ -9,14 +9,56 @@ use multihash::Multihash;
 
 use super::{sealed, Display, IsZero, Updated};
 
+/// A notification that the tracking entry for `peer` in the project
+/// identified by `urn` was updated from `old` to `new`.
+///
+/// The wire form is `<urn> <peer>|default <old> <new> LF`, see the
+/// [`fmt::Display`] and [`FromStr`] implementations.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Track<R> {
+    /// The URN of the project being tracked.
     pub urn: Urn<R>,
+    /// The tracked peer, or `None` for the default entry.
     pub peer: Option<PeerId>,
+    /// The revision of the tracking configuration before the update, or the
+    /// zero revision if the entry was created.
     pub old: R,
+    /// The revision of the tracking configuration after the update, or the
+    /// zero revision if the entry was removed.
     pub new: R,
 }
 
+impl<R> Track<R> {
+    pub fn new(urn: Urn<R>, peer: Option<PeerId>, old: R, new: R) -> Self {
+        Self {
+            urn,
+            peer,
+            old,
+            new,
+        }
+    }
+
+    /// The URN of the project being tracked.
+    pub fn urn(&self) -> &Urn<R> {
+        &self.urn
+    }
+
+    /// The tracked peer, or `None` for the default entry.
+    pub fn peer(&self) -> Option<PeerId> {
+        self.peer
+    }
+
+    /// The revision of the tracking configuration before the update.
+    pub fn old_rev(&self) -> &R {
+        &self.old
+    }
+
+    /// The revision of the tracking configuration after the update.
+    pub fn new_rev(&self) -> &R {
+        &self.new
+    }
+}
+
 impl<R> Track<R>
 where
     R: IsZero + PartialEq,
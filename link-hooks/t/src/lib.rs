@@ -8,3 +8,5 @@ pub mod gen;
 mod integration;
 #[cfg(test)]
 mod properties;
+#[cfg(test)]
+mod tests;
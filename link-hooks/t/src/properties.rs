@@ -3,7 +3,7 @@
 
 use proptest::prelude::*;
 
-use link_hooks::{Data, IsZero, Track, Updated};
+use link_hooks::{Data, IsZero, Kind, Track, Updated};
 use radicle_git_ext as ext;
 use test_helpers::roundtrip;
 
@@ -33,6 +33,29 @@ proptest! {
         prop_data_deleted(data.clone());
         prop_data_changed(data);
     }
+
+    #[test]
+    fn data_kind(data in gen_data()) {
+        prop_data_kind(data)
+    }
+}
+
+fn prop_data_kind(data: Data<ext::Oid>) {
+    let zero: ext::Oid = git2::Oid::zero().into();
+    let created = Data::new(data.urn.clone(), zero, data.new);
+    let deleted = Data::new(data.urn.clone(), data.old, zero);
+
+    if !data.new.is_zero() {
+        assert_eq!(created.kind(), Some(Kind::Create));
+    }
+    if !data.old.is_zero() {
+        assert_eq!(deleted.kind(), Some(Kind::Delete));
+    }
+    match (data.old.is_zero(), data.new.is_zero()) {
+        (false, false) => assert_eq!(data.kind(), Some(Kind::Update)),
+        (true, true) => assert_eq!(data.kind(), None),
+        _ => {},
+    }
 }
 
 fn prop_track_created(track: Track<ext::Oid>) {
@@ -0,0 +1,7 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod frame;
+mod in_process;
+mod overflow;
+mod restart;
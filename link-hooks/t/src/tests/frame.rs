@@ -0,0 +1,139 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    convert::Infallible,
+    ffi::OsStr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use link_hooks::{
+    hook::{
+        self,
+        config,
+        frame::{self, Frame, Framing},
+        Hook,
+        Process,
+    },
+    Data,
+    Hooks,
+    Notification,
+};
+use radicle_git_ext::Oid;
+
+/// A hook which records everything written to it.
+#[derive(Clone, Default)]
+struct Mock {
+    written: Arc<Mutex<Vec<u8>>>,
+}
+
+#[async_trait::async_trait]
+impl Process for Mock {
+    type SpawnError = Infallible;
+    type WriteError = Infallible;
+    type DieError = Infallible;
+
+    async fn spawn<I, S>(_path: PathBuf, _args: I) -> Result<Self, Self::SpawnError>
+    where
+        I: IntoIterator<Item = S> + Send,
+        S: AsRef<OsStr>,
+    {
+        Ok(Self::default())
+    }
+
+    async fn write(&mut self, bs: &[u8]) -> Result<(), Self::WriteError> {
+        self.written.lock().unwrap().extend_from_slice(bs);
+        Ok(())
+    }
+
+    async fn wait_or_kill(&mut self, _duration: Duration) -> Result<(), Self::DieError> {
+        Ok(())
+    }
+}
+
+fn data() -> Data<Oid> {
+    "rad:git:hnrkyzfpih4pqsw3cp1donkmwsgh9w5fwfdwo/refs/heads/main 0c3b4502a83a309b19123adc60a23e4e92bb13fb aeff7e8e964c47ba67a0c6eeba3beb62e29379d4\n"
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn compress_above_threshold() {
+    let payload = data().to_string().into_bytes();
+
+    let raw = frame::encode(Framing::Raw, &payload).unwrap();
+    assert_eq!(raw, payload);
+
+    for (threshold, kind) in [
+        (None, frame::RAW),
+        (Some(payload.len()), frame::RAW),
+        (Some(payload.len() - 1), frame::GZIP),
+    ] {
+        let framed = frame::encode(
+            Framing::LengthPrefixed {
+                compress_above: threshold,
+            },
+            &payload,
+        )
+        .unwrap();
+        assert_eq!(framed[0], kind);
+        let (decoded, rest) = frame::decode(&framed).unwrap();
+        assert_eq!(decoded, Frame::Payload(payload.clone()));
+        assert!(rest.is_empty());
+    }
+}
+
+#[test]
+fn decode_incomplete() {
+    let framed = frame::encode(
+        Framing::LengthPrefixed {
+            compress_above: None,
+        },
+        b"payload\n",
+    )
+    .unwrap();
+    for len in 0..framed.len() {
+        assert!(matches!(
+            frame::decode(&framed[..len]),
+            Err(frame::error::Decode::Incomplete)
+        ));
+    }
+}
+
+#[tokio::test]
+async fn compressed_roundtrip() {
+    let mock = Mock::default();
+    let config = hook::Config {
+        hook: config::Hook {
+            framing: Framing::LengthPrefixed {
+                compress_above: Some(16),
+            },
+            ..config::Hook::default()
+        },
+        ..hook::Config::default()
+    };
+    let hooks = Hooks::new(
+        config,
+        vec![Hook::new(PathBuf::from("mock-data"), mock.clone())],
+        vec![],
+    );
+    hooks
+        .run(futures::stream::iter(vec![Notification::from(data())]))
+        .await;
+
+    let written = mock.written.lock().unwrap().clone();
+    assert_eq!(written[0], frame::GZIP);
+
+    let (payload, rest) = frame::decode(&written).unwrap();
+    let payload = match payload {
+        Frame::Payload(payload) => String::from_utf8(payload).unwrap(),
+        Frame::Eot => panic!("expected a payload"),
+    };
+    assert_eq!(payload.parse::<Data<Oid>>().unwrap(), data());
+
+    let (eot, rest) = frame::decode(rest).unwrap();
+    assert_eq!(eot, Frame::Eot);
+    assert!(rest.is_empty());
+}
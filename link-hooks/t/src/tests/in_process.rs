@@ -0,0 +1,102 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{path::PathBuf, time::Duration};
+
+use link_hooks::{
+    hook::{self, in_process, Hook, InProcess, Process as _, EOT},
+    Data,
+    Hooks,
+    Notification,
+    Track,
+};
+use radicle_git_ext::Oid;
+
+const DATA: &str = "rad:git:hnrkyzfpih4pqsw3cp1donkmwsgh9w5fwfdwo/refs/heads/main 0c3b4502a83a309b19123adc60a23e4e92bb13fb aeff7e8e964c47ba67a0c6eeba3beb62e29379d4\n";
+const TRACK: &str = "rad:git:hnrkyzfpih4pqsw3cp1donkmwsgh9w5fwfdwo default 0000000000000000000000000000000000000000 aeff7e8e964c47ba67a0c6eeba3beb62e29379d4\n";
+
+/// Consume the writes to an in-process hook until the EOT, returning the
+/// received payloads.
+async fn consume(mut rx: in_process::Receiver) -> (Vec<String>, bool) {
+    let mut payloads = Vec::new();
+    while let Some(bs) = rx.recv().await {
+        if bs == [EOT] {
+            return (payloads, true);
+        }
+        payloads.push(String::from_utf8(bs).unwrap());
+    }
+    (payloads, false)
+}
+
+#[tokio::test]
+async fn in_process_hooks_receive_notifications() {
+    let data: Data<Oid> = DATA.parse().unwrap();
+    let track: Track<Oid> = TRACK.parse().unwrap();
+
+    let (data_hook, data_rx) = InProcess::channel();
+    let (track_hook, track_rx) = InProcess::channel();
+    let hooks = Hooks::new(
+        hook::Config::default(),
+        vec![Hook::new(PathBuf::from("data"), data_hook)],
+        vec![Hook::new(PathBuf::from("track"), track_hook)],
+    );
+
+    let ((), (data_payloads, data_eot), (track_payloads, track_eot)) = futures::join!(
+        hooks.run(futures::stream::iter(vec![
+            Notification::from(data.clone()),
+            Notification::from(track.clone()),
+        ])),
+        consume(data_rx),
+        consume(track_rx),
+    );
+
+    assert_eq!(data_payloads, vec![DATA.to_string()]);
+    assert!(data_eot);
+    assert_eq!(track_payloads, vec![TRACK.to_string()]);
+    assert!(track_eot);
+}
+
+#[tokio::test]
+async fn in_process_hook_is_killed_after_timeout() {
+    let (mut hook, mut rx) = InProcess::channel();
+    hook.write(&[EOT]).await.unwrap();
+    hook.wait_or_kill(Duration::from_millis(10)).await.unwrap();
+
+    // The hook was killed, so the receiver sees the end of the stream without
+    // having to be dropped.
+    assert_eq!(rx.recv().await, Some(vec![EOT]));
+    assert_eq!(rx.recv().await, None);
+    assert!(hook.write(b"late").await.is_err());
+}
+
+#[tokio::test]
+async fn coalesced_notifications_are_batched() {
+    let data: Data<Oid> = DATA.parse().unwrap();
+    let track: Track<Oid> = TRACK.parse().unwrap();
+
+    let (data_hook, data_rx) = InProcess::channel();
+    let (track_hook, track_rx) = InProcess::channel();
+    let hooks = Hooks::new(
+        hook::Config {
+            coalesce: Some(Duration::from_millis(100)),
+            ..hook::Config::default()
+        },
+        vec![Hook::new(PathBuf::from("data"), data_hook)],
+        vec![Hook::new(PathBuf::from("track"), track_hook)],
+    );
+
+    let ((), (data_payloads, _), (track_payloads, _)) = futures::join!(
+        hooks.run(futures::stream::iter(vec![
+            Notification::from(data.clone()),
+            Notification::from(track.clone()),
+            Notification::from(data.clone()),
+            Notification::from(data.clone()),
+        ])),
+        consume(data_rx),
+        consume(track_rx),
+    );
+
+    // Each batch is terminated by an empty line
+    assert_eq!(data_payloads, vec![DATA.repeat(3) + "\n"]);
+    assert_eq!(track_payloads, vec![format!("{}\n", TRACK)]);
+}
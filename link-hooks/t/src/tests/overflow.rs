@@ -0,0 +1,116 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    convert::Infallible,
+    ffi::OsStr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use link_hooks::{
+    hook::{self, config, Hook, Process},
+    Data,
+    Hooks,
+    Notification,
+};
+use radicle_git_ext::Oid;
+
+/// A hook which takes `delay` to process each write.
+#[derive(Clone)]
+struct Slow {
+    delay: Duration,
+    writes: Arc<AtomicUsize>,
+}
+
+impl Slow {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            writes: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Process for Slow {
+    type SpawnError = Infallible;
+    type WriteError = Infallible;
+    type DieError = Infallible;
+
+    async fn spawn<I, S>(_path: PathBuf, _args: I) -> Result<Self, Self::SpawnError>
+    where
+        I: IntoIterator<Item = S> + Send,
+        S: AsRef<OsStr>,
+    {
+        Ok(Self::new(Duration::ZERO))
+    }
+
+    async fn write(&mut self, _bs: &[u8]) -> Result<(), Self::WriteError> {
+        tokio::time::sleep(self.delay).await;
+        self.writes.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn wait_or_kill(&mut self, _duration: Duration) -> Result<(), Self::DieError> {
+        Ok(())
+    }
+}
+
+fn notifications(n: usize) -> Vec<Notification<Oid>> {
+    let data: Data<Oid> = "rad:git:hnrkyzfpih4pqsw3cp1donkmwsgh9w5fwfdwo/refs/heads/main 0c3b4502a83a309b19123adc60a23e4e92bb13fb aeff7e8e964c47ba67a0c6eeba3beb62e29379d4\n"
+        .parse()
+        .unwrap();
+    (0..n).map(|_| Notification::from(data.clone())).collect()
+}
+
+fn config(overflow: config::Overflow) -> hook::Config {
+    hook::Config {
+        hook: config::Hook {
+            buffer: 1,
+            overflow,
+            ..config::Hook::default()
+        },
+        ..hook::Config::default()
+    }
+}
+
+#[tokio::test]
+async fn slow_hook_drops() {
+    let path = PathBuf::from("slow-data");
+    let slow = Slow::new(Duration::from_millis(50));
+    let hooks = Hooks::new(
+        config(config::Overflow::Drop),
+        vec![Hook::new(path.clone(), slow.clone())],
+        vec![],
+    );
+    let stats = hooks.stats();
+    hooks.run(futures::stream::iter(notifications(10))).await;
+
+    let dropped = stats.dropped(&path);
+    assert!(dropped > 0);
+    // Everything that wasn't dropped was written, plus the EOT
+    assert_eq!(slow.writes.load(Ordering::SeqCst) + dropped, 10 + 1);
+}
+
+#[tokio::test]
+async fn slow_hook_blocks() {
+    let path = PathBuf::from("slow-data");
+    let slow = Slow::new(Duration::from_millis(10));
+    let hooks = Hooks::new(
+        config(config::Overflow::Block {
+            timeout: Duration::from_secs(5),
+        }),
+        vec![Hook::new(path.clone(), slow.clone())],
+        vec![],
+    );
+    let stats = hooks.stats();
+    hooks.run(futures::stream::iter(notifications(10))).await;
+
+    assert_eq!(stats.dropped(&path), 0);
+    assert_eq!(slow.writes.load(Ordering::SeqCst), 10 + 1);
+}
@@ -0,0 +1,155 @@
+// Copyright © 2022 The Radicle Link Contributors
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    ffi::OsStr,
+    fs::OpenOptions,
+    io::{self, Write as _},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use link_hooks::{
+    hook::{self, config, Hook, Process, EOT},
+    Data,
+    Hooks,
+    Notification,
+};
+use radicle_git_ext::Oid;
+use tempfile::NamedTempFile;
+
+/// A hook which appends everything written to it to the file at `path`, and
+/// fails when writing the `fail_on`th message. The number of times it was
+/// waited for is counted in `reaped`.
+struct Flaky {
+    path: PathBuf,
+    fail_on: Option<usize>,
+    writes: usize,
+    reaped: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl Process for Flaky {
+    type SpawnError = io::Error;
+    type WriteError = io::Error;
+    type DieError = io::Error;
+
+    /// A respawned hook never fails.
+    async fn spawn<I, S>(path: PathBuf, _args: I) -> Result<Self, Self::SpawnError>
+    where
+        I: IntoIterator<Item = S> + Send,
+        S: AsRef<OsStr>,
+    {
+        Ok(Self {
+            path,
+            fail_on: None,
+            writes: 0,
+            reaped: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    async fn write(&mut self, bs: &[u8]) -> Result<(), Self::WriteError> {
+        let n = self.writes;
+        self.writes += 1;
+        if self.fail_on == Some(n) {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "flaky hook died"));
+        }
+        OpenOptions::new()
+            .append(true)
+            .open(&self.path)?
+            .write_all(bs)
+    }
+
+    async fn wait_or_kill(&mut self, _duration: Duration) -> Result<(), Self::DieError> {
+        self.reaped.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+fn data(new: &str) -> Data<Oid> {
+    format!(
+        "rad:git:hnrkyzfpih4pqsw3cp1donkmwsgh9w5fwfdwo/refs/heads/main 0c3b4502a83a309b19123adc60a23e4e92bb13fb {}\n",
+        new
+    )
+    .parse()
+    .unwrap()
+}
+
+#[tokio::test]
+async fn restarted_hook_receives_replay() {
+    let out = NamedTempFile::new().unwrap();
+    let reaped = Arc::new(AtomicUsize::new(0));
+    let hook = Flaky {
+        path: out.path().to_path_buf(),
+        fail_on: Some(1),
+        writes: 0,
+        reaped: reaped.clone(),
+    };
+    let config = hook::Config {
+        hook: config::Hook {
+            restarts: 1,
+            replay: 1,
+            ..config::Hook::default()
+        },
+        ..hook::Config::default()
+    };
+    let first = data("aeff7e8e964c47ba67a0c6eeba3beb62e29379d4");
+    let missed = data("0c3b4502a83a309b19123adc60a23e4e92bb13fb");
+    let last = data("b6fe4e6a5e13a2d2d1bfbfe4bd4eba42dc3e0a17");
+
+    let hooks = Hooks::new(
+        config,
+        vec![Hook::new(out.path().to_path_buf(), hook)],
+        vec![],
+    );
+    hooks
+        .run(futures::stream::iter(vec![
+            Notification::from(first.clone()),
+            Notification::from(missed.clone()),
+            Notification::from(last.clone()),
+        ]))
+        .await;
+
+    let written = std::fs::read(out.path()).unwrap();
+    let expected = [first.to_string(), missed.to_string(), last.to_string()]
+        .concat()
+        .into_bytes()
+        .into_iter()
+        .chain(Some(EOT))
+        .collect::<Vec<_>>();
+    assert_eq!(written, expected);
+    assert_eq!(
+        reaped.load(Ordering::SeqCst),
+        1,
+        "the failed hook should be waited for before it is respawned"
+    );
+}
+
+#[tokio::test]
+async fn failed_hook_without_restarts_is_removed() {
+    let out = NamedTempFile::new().unwrap();
+    let reaped = Arc::new(AtomicUsize::new(0));
+    let hook = Flaky {
+        path: out.path().to_path_buf(),
+        fail_on: Some(0),
+        writes: 0,
+        reaped: reaped.clone(),
+    };
+    let hooks = Hooks::new(
+        hook::Config::default(),
+        vec![Hook::new(out.path().to_path_buf(), hook)],
+        vec![],
+    );
+    hooks
+        .run(futures::stream::iter(vec![Notification::from(data(
+            "aeff7e8e964c47ba67a0c6eeba3beb62e29379d4",
+        ))]))
+        .await;
+
+    assert!(std::fs::read(out.path()).unwrap().is_empty());
+    assert_eq!(reaped.load(Ordering::SeqCst), 1);
+}
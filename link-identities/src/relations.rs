@@ -10,6 +10,10 @@
 //! `Replicated`, and it can be a `Tracker`, `Contributor`, or `Maintainer` of
 //! the project.
 
+use canonical::{
+    json::{ToCjson, Value},
+    ToCjson,
+};
 use crypto::PeerId;
 use serde::Serialize;
 
@@ -47,6 +51,24 @@ pub enum Peer<S> {
     },
 }
 
+/// Mirrors the `serde` representation. [`PeerId`] has no canonical JSON form
+/// of its own, so it is written out in its string form.
+impl<S: ToCjson> ToCjson for Peer<S> {
+    fn into_cjson(self) -> Value {
+        let (ty, peer_id, status) = match self {
+            Self::Local { peer_id, status } => ("local", peer_id, status),
+            Self::Remote { peer_id, status } => ("remote", peer_id, status),
+        };
+        vec![
+            ("type", ty.into_cjson()),
+            ("peerId", peer_id.to_string().into_cjson()),
+            ("status", status.into_cjson()),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
 impl<U> Peer<Status<U>> {
     /// Refine a `Peer` into a `Replicated` peer.
     ///
@@ -150,8 +172,9 @@ impl<S> Peer<S> {
 /// A newtype to represent that the user's data has been replicated locally.
 ///
 /// The payload is left generic, but is generally represented by `Person`.
-#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, ToCjson)]
 #[serde(rename_all = "camelCase")]
+#[cjson(rename_all = "camelCase")]
 pub struct Replicated<U> {
     /// The user metadata the peer used with the project.
     pub user: U,
@@ -188,6 +211,25 @@ pub enum Status<U> {
     Replicated(Replicated<U>),
 }
 
+/// Mirrors the `serde` representation, ie. the fields of [`Replicated`] sit
+/// alongside the `type` tag. The derive would nest them under a `"0"` key
+/// instead, so this is written out by hand.
+impl<U: ToCjson> ToCjson for Status<U> {
+    fn into_cjson(self) -> Value {
+        match self {
+            Self::NotReplicated => vec![("type", "notReplicated".into_cjson())]
+                .into_iter()
+                .collect(),
+            Self::Replicated(Replicated { user }) => vec![
+                ("type", "replicated".into_cjson()),
+                ("user", user.into_cjson()),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
+
 impl<U> Status<U> {
     /// Helper for constructing the `Status::Replicated` variant.
     pub const fn replicated(user: U) -> Self {
@@ -26,6 +26,7 @@ use crate::{
     Net,
     Odb,
     PeerId,
+    RefFilter as _,
     RefScan,
     Refdb,
     SignedRefs,
@@ -61,6 +62,8 @@ where
 
     let scx = state.as_shim(cx);
     let local_id = *LocalPeer::id(&scx);
+    let data = Tracking::data_policy(&scx);
+    let prune = Tracking::prune(&scx);
     let delegates = VerifiedIdentity::delegate_ids(&anchor);
     let delegates_sans_local = delegates
         .iter()
@@ -140,6 +143,11 @@ where
             }
         }
     }
+    if data == DataPolicy::Deny {
+        transitive
+            .values_mut()
+            .for_each(|policy| *policy = DataPolicy::Deny);
+    }
 
     let requires_confirmation = {
         info!("setting up local rad/ hierarchy");
@@ -198,7 +206,21 @@ where
         Refdb::update(cx, tips)?
     };
 
-    let signed_refs = signed_refs.flattened();
+    let mut signed_refs = signed_refs.flattened();
+    // Only fetch the refs of non-delegates which their tracking configuration
+    // allows, or no refs at all if data is denied.
+    for (id, refs) in signed_refs.refs.iter_mut() {
+        if data == DataPolicy::Deny {
+            refs.refs.clear();
+            continue;
+        }
+        if delegates.contains(id) {
+            continue;
+        }
+        if let Some(filter) = Tracking::ref_filter(cx, id)? {
+            refs.refs.retain(|name, _| filter.allows(name));
+        }
+    }
     // Clear rad tips so far. Fetch will ask the remote to advertise
     // all rad refs from the transitive trackings, so we can inspect
     // the state afterwards to see if we got any.
@@ -228,7 +250,19 @@ where
                 .collect(),
             cutoff: 0,
         };
-        let trans_sigrefs = sigrefs::combined(&state.as_shim(cx), selector)?;
+        let mut trans_sigrefs = sigrefs::combined(&state.as_shim(cx), selector)?;
+        // As above, the refs of transitively tracked non-delegates are subject
+        // to their tracking configuration.
+        let peers = trans_sigrefs
+            .keys()
+            .filter(|id| !delegates.contains(id))
+            .copied()
+            .collect::<Vec<_>>();
+        for id in peers {
+            if let Some(filter) = Tracking::ref_filter(cx, &id)? {
+                trans_sigrefs.retain_refs(&id, |name, _| filter.allows(name));
+            }
+        }
         let trans_ids = state.id_tips().keys().copied().collect();
         debug!(?trans_sigrefs);
         let trans_fetch = fetch::Transitive {
@@ -251,7 +285,14 @@ where
     }
 
     info!("updating tips");
-    applied.append(&mut Refdb::update(cx, state.updates_mut().drain(..))?);
+    let (would_prune, tips): (Vec<_>, Vec<_>) = state
+        .updates_mut()
+        .drain(..)
+        .partition(|up| !prune && matches!(up, Update::Prune { .. }));
+    if !would_prune.is_empty() {
+        info!(n = would_prune.len(), "pruning disabled, retaining refs");
+    }
+    applied.append(&mut Refdb::update(cx, tips)?);
     for u in &applied.updated {
         debug!("applied {:?}", u);
     }
@@ -300,6 +341,7 @@ where
         tracked: newly_tracked,
         requires_confirmation,
         validation: warnings,
+        would_prune,
         _marker: PhantomData,
     })
 }
@@ -49,7 +49,7 @@ mod success;
 pub use success::Success;
 
 mod track;
-pub use track::{DataPolicy, Rel as TrackingRel, Tracking};
+pub use track::{DataPolicy, RefFilter, Rel as TrackingRel, Tracking};
 
 mod transmit;
 pub use transmit::{FilteredRef, LsRefs, Negotiation, Net, RefPrefix, WantsHaves};
@@ -80,6 +80,16 @@ impl<T> Default for Flattened<T> {
 pub struct Combined<Oid>(BTreeMap<PeerId, Sigrefs<Oid>>);
 
 impl<Oid> Combined<Oid> {
+    /// Retain only the signed refs of `id` for which `f` returns `true`.
+    pub fn retain_refs<F>(&mut self, id: &PeerId, f: F)
+    where
+        F: FnMut(&RefString, &mut Oid) -> bool,
+    {
+        if let Some(sigrefs) = self.0.get_mut(id) {
+            sigrefs.refs.retain(f)
+        }
+    }
+
     pub fn flattened(self) -> Flattened<Oid> {
         let mut refs = BTreeMap::new();
         let mut remotes = BTreeSet::new();
@@ -271,6 +271,7 @@ where
     type Urn = U;
 
     type Tracked = T::Tracked;
+    type RefFilter = T::RefFilter;
     #[allow(clippy::type_complexity)]
     type Updated =
         std::iter::Map<std::vec::IntoIter<track::Rel<U>>, fn(track::Rel<U>) -> Either<PeerId, U>>;
@@ -296,6 +297,18 @@ where
     fn tracked(&self) -> Result<Self::Tracked, Self::TrackedError> {
         self.inner.tracked()
     }
+
+    fn ref_filter(&self, peer: &PeerId) -> Result<Option<Self::RefFilter>, Self::PolicyError> {
+        self.inner.ref_filter(peer)
+    }
+
+    fn data_policy(&self) -> track::DataPolicy {
+        self.inner.data_policy()
+    }
+
+    fn prune(&self) -> bool {
+        self.inner.prune()
+    }
 }
 
 impl<T, U> Identities for Shim<'_, T, U>
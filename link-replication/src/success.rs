@@ -15,6 +15,7 @@ pub struct Success<Urn> {
     pub tracked: Vec<Either<PeerId, Urn>>,
     pub requires_confirmation: bool,
     pub validation: Vec<error::Validation>,
+    pub would_prune: Vec<Update<'static>>,
     pub(crate) _marker: PhantomData<Urn>,
 }
 
@@ -74,6 +75,12 @@ where
         self.requires_confirmation
     }
 
+    /// Prunes which were not applied, because pruning is disabled by
+    /// [`crate::Tracking::prune`].
+    pub fn would_prune(&self) -> &[Update<'static>] {
+        &self.would_prune
+    }
+
     /// Any post-validation errors.
     pub fn validation_errors(&self) -> &[error::Validation] {
         &self.validation
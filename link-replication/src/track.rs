@@ -4,6 +4,7 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 use either::Either;
+use git_ref_format::RefStr;
 
 use crate::{PeerId, Urn};
 
@@ -31,11 +32,18 @@ impl Default for DataPolicy {
     }
 }
 
+/// A predicate over the refs signed by a tracked peer, determining which of
+/// them are fetched.
+pub trait RefFilter {
+    fn allows(&self, refname: &RefStr) -> bool;
+}
+
 pub trait Tracking {
     type Urn: Urn;
 
     type Updated: Iterator<Item = Either<PeerId, Self::Urn>>;
     type Tracked: Iterator<Item = Result<(PeerId, DataPolicy), Self::TrackedError>>;
+    type RefFilter: RefFilter;
 
     type TrackError: std::error::Error + Send + Sync + 'static;
     type TrackedError: std::error::Error + Send + Sync + 'static;
@@ -48,4 +56,30 @@ pub trait Tracking {
 
     /// All tracked [`PeerId`]s in the context of the current [`Urn`].
     fn tracked(&self) -> Result<Self::Tracked, Self::TrackedError>;
+
+    /// The [`RefFilter`] for the refs signed by `peer` in the context of the
+    /// current [`Urn`].
+    ///
+    /// If `None` is returned, all of the refs are fetched.
+    fn ref_filter(&self, peer: &PeerId) -> Result<Option<Self::RefFilter>, Self::PolicyError>;
+
+    /// The [`DataPolicy`] applying to all peers, including delegates.
+    ///
+    /// If [`DataPolicy::Deny`], only the `rad` refs (identities and signed
+    /// refs) are fetched. Otherwise, [`Tracking::tracked`] and
+    /// [`Tracking::ref_filter`] decide.
+    fn data_policy(&self) -> DataPolicy {
+        DataPolicy::Allow
+    }
+
+    /// Whether remote-tracking refs which are no longer signed by their peer
+    /// are deleted.
+    ///
+    /// If `false`, such refs are kept and reported in
+    /// [`crate::Success::would_prune`] instead. `rad` refs are never pruned
+    /// either way, and since only signed refs are validated, the retained
+    /// refs do not affect signed refs verification.
+    fn prune(&self) -> bool {
+        true
+    }
 }
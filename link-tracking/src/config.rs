@@ -3,7 +3,7 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::{convert::TryFrom, str::FromStr};
+use std::{convert::TryFrom, io, str::FromStr};
 
 use thiserror::Error;
 
@@ -51,6 +51,10 @@ impl<Ty: Clone + Ord + Into<Cstring> + Ord, Id: Clone + Ord + ToCjson> Canonical
     fn canonical_form(&self) -> Result<Vec<u8>, Self::Error> {
         self.clone().into_cjson().canonical_form()
     }
+
+    fn canonical_form_into<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.clone().into_cjson().canonical_form_into(w)
+    }
 }
 
 impl<Ty: Ord, Id: Ord> Default for Config<Ty, Id> {
@@ -16,13 +16,14 @@ pub mod cjson;
 /// ```ignore
 /// ("*" | <typename>): {
 ///   "policy": ("allow" | "deny")
-///   "pattern": ("*" | [<object id>])
+///   "pattern": ("*" | [<object id>] | { "except": [<object id>] })
 /// }
 /// ```
 ///
 /// The `<typename>` is the type identifier for the collaborative object, the
 /// `<object id>` is the identifier for a particular object of the given type,
-/// and `*` signifies a wildcard.
+/// and `*` signifies a wildcard. An `except` pattern matches every object but
+/// the ones listed.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Cobs<Type, ObjectId: Ord>(BTreeMap<TypeName<Type>, Filter<ObjectId>>);
 
@@ -50,6 +51,8 @@ pub struct Filter<ObjectId: Ord> {
 pub enum Pattern<ObjectId> {
     Wildcard,
     Objects(BTreeSet<ObjectId>),
+    /// Matches all objects except for the given ones.
+    Except(BTreeSet<ObjectId>),
 }
 
 impl<ObjectId: Ord> Pattern<ObjectId> {
@@ -57,6 +60,7 @@ impl<ObjectId: Ord> Pattern<ObjectId> {
         match self {
             Self::Wildcard => true,
             Self::Objects(objs) => objs.contains(oid),
+            Self::Except(objs) => !objs.contains(oid),
         }
     }
 }
@@ -141,6 +145,121 @@ impl<Ty: Ord, Id: Ord> Cobs<Ty, Id> {
     }
 }
 
+impl<Ty: Ord + Clone, Id: Ord + Clone> Cobs<Ty, Id> {
+    /// The most restrictive combination of `self` and `other`, ie. an object
+    /// is allowed by the result iff it is allowed by both.
+    ///
+    /// For each type name, the [`Filter`]s of either side are intersected as
+    /// in [`Filter::intersect`]. A type name missing from one side falls back
+    /// to that side's `Wildcard` entry, whose pattern is ignored, the same way
+    /// as when evaluating a ref against the filters. A side without a
+    /// `Wildcard` entry allows all objects of unlisted types.
+    ///
+    /// The result only has a `Wildcard` entry if either side does, and it
+    /// denies if either side's `Wildcard` entry denies.
+    pub fn intersect(&self, other: &Cobs<Ty, Id>) -> Cobs<Ty, Id> {
+        let mut cobs = Self::empty();
+        if self.wildcard().is_some() || other.wildcard().is_some() {
+            let policy = match (self.wildcard_policy(), other.wildcard_policy()) {
+                (Policy::Allow, Policy::Allow) => Policy::Allow,
+                _ => Policy::Deny,
+            };
+            cobs.insert(
+                TypeName::Wildcard,
+                Filter {
+                    policy,
+                    pattern: Pattern::Wildcard,
+                },
+            );
+        }
+
+        let types = self
+            .0
+            .keys()
+            .chain(other.0.keys())
+            .filter_map(|ty| match ty {
+                TypeName::Wildcard => None,
+                TypeName::Type(ty) => Some(ty),
+            })
+            .collect::<BTreeSet<_>>();
+        for ty in types {
+            let filter = self.effective(ty).intersect(&other.effective(ty));
+            cobs.insert(TypeName::Type(ty.clone()), filter);
+        }
+
+        cobs
+    }
+
+    fn wildcard_policy(&self) -> Policy {
+        self.wildcard()
+            .map(|filter| filter.policy)
+            .unwrap_or(Policy::Allow)
+    }
+
+    /// The [`Filter`] applying to `ty`, falling back to the `Wildcard` entry.
+    fn effective(&self, ty: &Ty) -> Filter<Id> {
+        self.get(ty.clone()).cloned().unwrap_or_else(|| Filter {
+            policy: self.wildcard_policy(),
+            pattern: Pattern::Wildcard,
+        })
+    }
+}
+
+impl<Id: Ord + Clone> Filter<Id> {
+    /// The [`Filter`] allowing exactly the objects which are allowed by both
+    /// `self` and `other`.
+    ///
+    /// Denying all objects wins over anything else, and allowing all objects
+    /// yields the other side. Otherwise, the allowed object sets are
+    /// intersected, where a denied set of objects allows everything but
+    /// those objects.
+    ///
+    /// The result never uses [`Pattern::Except`]: allowing all objects except
+    /// some is expressed as denying those objects instead.
+    pub fn intersect(&self, other: &Filter<Id>) -> Filter<Id> {
+        use Pattern::{Except, Objects, Wildcard};
+
+        let allowed = match (self.allowed(), other.allowed()) {
+            (None, _) | (_, None) => None,
+            (Some(Wildcard), Some(pattern)) | (Some(pattern), Some(Wildcard)) => Some(pattern),
+            (Some(Objects(a)), Some(Objects(b))) => {
+                Some(Objects(a.intersection(&b).cloned().collect()))
+            },
+            (Some(Objects(allowed)), Some(Except(denied)))
+            | (Some(Except(denied)), Some(Objects(allowed))) => {
+                Some(Objects(allowed.difference(&denied).cloned().collect()))
+            },
+            (Some(Except(a)), Some(Except(b))) => Some(Except(a.union(&b).cloned().collect())),
+        };
+
+        match allowed {
+            None => Filter {
+                policy: Policy::Deny,
+                pattern: Wildcard,
+            },
+            Some(Except(denied)) => Filter {
+                policy: Policy::Deny,
+                pattern: Objects(denied),
+            },
+            Some(pattern) => Filter {
+                policy: Policy::Allow,
+                pattern,
+            },
+        }
+    }
+
+    /// The [`Pattern`] matching the objects this filter allows, or `None` if
+    /// it allows none.
+    fn allowed(&self) -> Option<Pattern<Id>> {
+        match (self.policy, &self.pattern) {
+            (Policy::Allow, pattern) => Some(pattern.clone()),
+            (Policy::Deny, Pattern::Wildcard) => None,
+            (Policy::Deny, Pattern::Objects(objs)) => Some(Pattern::Except(objs.clone())),
+            (Policy::Deny, Pattern::Except(objs)) => Some(Pattern::Objects(objs.clone())),
+        }
+    }
+}
+
 impl<Ty: Ord, Id: Ord> FromIterator<(TypeName<Ty>, Filter<Id>)> for Cobs<Ty, Id> {
     fn from_iter<T: IntoIterator<Item = (TypeName<Ty>, Filter<Id>)>>(iter: T) -> Self {
         Self(iter.into_iter().collect())
@@ -170,8 +289,10 @@ impl<'a, Ty: Ord, Id: Ord> Entry<'a, Ty, Id> {
         })
     }
 
-    /// Insert the given `Id`s for the given `Entry`. If the previous
-    /// [`Pattern`] was a `Wildcard` then this operation is a no-op.
+    /// Insert the given `Id`s for the given `Entry`, so that the [`Pattern`]
+    /// matches them. If the previous [`Pattern`] was a `Wildcard` then this
+    /// operation is a no-op. If it was `Except`, the `Id`s are no longer
+    /// excepted.
     pub fn insert_objects<I>(self, ids: I) -> Self
     where
         I: IntoIterator<Item = Id>,
@@ -180,12 +301,19 @@ impl<'a, Ty: Ord, Id: Ord> Entry<'a, Ty, Id> {
             match &mut filter.pattern {
                 Pattern::Wildcard => { /* no-op */ },
                 Pattern::Objects(objs) => objs.extend(ids),
+                Pattern::Except(objs) => {
+                    for id in ids {
+                        objs.remove(&id);
+                    }
+                },
             }
         })
     }
 
-    /// Remove the given `Id`s for the given `Entry`. If the previous
-    /// [`Pattern`] was a `Wildcard` then this operation is a no-op.
+    /// Remove the given `Id`s for the given `Entry`, so that the [`Pattern`]
+    /// no longer matches them. If the previous [`Pattern`] was a `Wildcard`
+    /// then this operation is a no-op. If it was `Except`, the `Id`s are
+    /// excepted.
     pub fn remove_objects<I>(self, ids: I) -> Self
     where
         I: IntoIterator<Item = Id>,
@@ -198,6 +326,7 @@ impl<'a, Ty: Ord, Id: Ord> Entry<'a, Ty, Id> {
                         objs.remove(&id);
                     }
                 },
+                Pattern::Except(objs) => objs.extend(ids),
             }
         })
     }
@@ -3,10 +3,13 @@
 // This file is part of radicle-link, distributed under the GPLv3 with Radicle
 // Linking Exception. For full terms see the included LICENSE file.
 
-use std::convert::{TryFrom, TryInto};
+use std::{
+    collections::BTreeSet,
+    convert::{TryFrom, TryInto},
+};
 
 use link_canonical::{
-    json::{ToCjson, Value},
+    json::{Array, ToCjson, Value},
     Cstring,
 };
 
@@ -14,6 +17,7 @@ use super::{Cobs, Filter, Pattern, Policy, TypeName};
 
 const POLICY: &str = "policy";
 const PATTERN: &str = "pattern";
+const EXCEPT: &str = "except";
 
 pub mod error {
     use thiserror::Error;
@@ -32,6 +36,8 @@ pub mod error {
     pub enum Pattern {
         #[error("expected wildcard `*`")]
         ExpectedWildcard,
+        #[error("expected an object with the single key 'except'")]
+        ExpectedExcept,
         #[error("expected type {expected}, but found {found}")]
         MismatchedTy { expected: String, found: String },
         #[error("failed to parse the object identifier")]
@@ -92,6 +98,7 @@ impl<Id: Ord + ToCjson> ToCjson for Pattern<Id> {
         match self {
             Self::Wildcard => "*".into_cjson(),
             Self::Objects(objs) => objs.into_cjson(),
+            Self::Except(objs) => vec![(EXCEPT, objs.into_cjson())].into_iter().collect(),
         }
     }
 }
@@ -161,31 +168,54 @@ where
 
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
-            Value::Array(objs) => objs
-                .into_iter()
-                .map(|val| match val {
-                    Value::String(s) => s
-                        .try_into()
-                        .map_err(|err| error::Pattern::Identifier(err.into())),
+            Value::Array(objs) => objects(objs).map(Self::Objects),
+            Value::Object(mut map) => {
+                let except = map
+                    .remove(&EXCEPT.into())
+                    .ok_or(error::Pattern::ExpectedExcept)?;
+                if !map.is_empty() {
+                    return Err(error::Pattern::ExpectedExcept);
+                }
+                match except {
+                    Value::Array(objs) => objects(objs).map(Self::Except),
                     val => Err(error::Pattern::MismatchedTy {
-                        expected: "<object id>".into(),
+                        expected: "[<object id> ..]".into(),
                         found: val.ty_name().to_string(),
                     }),
-                })
-                .collect::<Result<_, _>>()
-                .map(Self::Objects),
+                }
+            },
             Value::String(s) => match s.as_str() {
                 "*" => Ok(Self::Wildcard),
                 _ => Err(error::Pattern::ExpectedWildcard),
             },
             val => Err(error::Pattern::MismatchedTy {
-                expected: "string of '*' or '[<object id> ..]'".into(),
+                expected: "string of '*', '[<object id> ..]', or '{\"except\": [<object id> ..]}'"
+                    .into(),
                 found: val.ty_name().to_string(),
             }),
         }
     }
 }
 
+fn objects<Id>(objs: Array) -> Result<BTreeSet<Id>, error::Pattern>
+where
+    Id: Ord,
+    Cstring: TryInto<Id>,
+    <Cstring as TryInto<Id>>::Error: std::error::Error + Send + Sync + 'static,
+{
+    objs.into_iter()
+        .map(|val| match val {
+            Value::String(s) => s
+                .try_into()
+                .map_err(|err| error::Pattern::Identifier(err.into())),
+            val => Err(error::Pattern::MismatchedTy {
+                expected: "<object id>".into(),
+                found: val.ty_name().to_string(),
+            }),
+        })
+        .collect()
+}
+
 impl<Ty, Id> TryFrom<Value> for Cobs<Ty, Id>
 where
     Ty: Ord,
@@ -4,6 +4,7 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 pub mod config;
+pub mod identities;
 pub mod odb;
 pub mod refdb;
 pub mod tracking;
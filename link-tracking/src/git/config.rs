@@ -71,6 +71,37 @@ impl Config {
             },
         }
     }
+
+    /// The [`RefFilter`] determining which refs may be fetched under this
+    /// [`Config`].
+    pub fn allowed_refs(&self) -> RefFilter<'_> {
+        RefFilter { config: self }
+    }
+}
+
+/// A predicate over refnames, combining the `data` flag and `cobs` filters of a
+/// [`Config`]. See [`Config::allowed_refs`].
+#[derive(Clone, Copy, Debug)]
+pub struct RefFilter<'a> {
+    config: &'a Config,
+}
+
+impl RefFilter<'_> {
+    /// Whether `refname` may be fetched.
+    ///
+    /// Refs under `refs/rad` are always allowed, since they are needed to
+    /// verify the identity being fetched. Any other ref must be qualified and
+    /// is allowed iff [`Config::policy_for`] is [`Policy::Allow`].
+    pub fn allows(&self, refname: &RefStr) -> bool {
+        match Qualified::from_refstr(refname) {
+            None => false,
+            Some(q) => {
+                let (_refs, cat, _, _) = q.non_empty_components();
+                refs::name::RAD == cat.as_ref()
+                    || matches!(self.config.policy_for(&q), Policy::Allow)
+            },
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -0,0 +1,26 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use link_crypto::PeerId;
+use link_identities::urn::Urn;
+
+pub trait Read {
+    type FindError: std::error::Error + Send + Sync + 'static;
+
+    type Oid;
+    type Identity;
+
+    /// Find the tip of `rad/self` for the given `peer` under the namespace of
+    /// `urn`. If the reference does not exist, then the result should be
+    /// `None`.
+    fn rad_self(
+        &self,
+        urn: &Urn<Self::Oid>,
+        peer: &PeerId,
+    ) -> Result<Option<Self::Oid>, Self::FindError>;
+
+    /// Find the identity whose history ends at `tip`.
+    fn find_identity(&self, tip: &Self::Oid) -> Result<Self::Identity, Self::FindError>;
+}
@@ -36,6 +36,19 @@ pub trait Read<'a> {
         name: &RefName<'_, Self::Oid>,
     ) -> Result<Option<Ref<Self::Oid>>, Self::FindError>;
 
+    /// Get a [`Ref`] for each of the `names`, in the same order, where an entry
+    /// is `None` if no such reference exists.
+    ///
+    /// The default implementation calls [`Read::find_reference`] for each name.
+    /// Implementations should override it if they can look up many references
+    /// more cheaply in one go.
+    fn find_references(
+        &self,
+        names: &[RefName<'_, Self::Oid>],
+    ) -> Result<Vec<Option<Ref<Self::Oid>>>, Self::FindError> {
+        names.iter().map(|name| self.find_reference(name)).collect()
+    }
+
     /// Get all [`Ref`]s that match the given `refspec`.
     #[allow(clippy::type_complexity)]
     fn references(
@@ -16,6 +16,7 @@ use crate::tracking;
 
 use super::{
     config::{self, Config},
+    identities,
     odb,
     refdb,
 };
@@ -519,6 +520,87 @@ where
     }
 }
 
+impl<'a, Db, R> TrackedEntries<'a, Db, R> {
+    /// Pair each tracked entry with the identity found at the `rad/self` of
+    /// its peer.
+    ///
+    /// Identities are resolved lazily, as the entries are iterated, and are
+    /// cached by the tip of `rad/self`, so peers pointing at the same history
+    /// only have it loaded once. The `default` entry, and any peer that does
+    /// not have a `rad/self`, is paired with `None`.
+    pub fn with_identities<Ids>(self, ids: &'a Ids) -> WithIdentities<'a, Self, Ids>
+    where
+        Ids: identities::Read<Oid = Oid>,
+    {
+        WithIdentities {
+            entries: self,
+            ids,
+            seen: BTreeMap::new(),
+        }
+    }
+}
+
+/// Iterator of [`Tracked`] entries paired with the identities of their peers.
+///
+/// See [`TrackedEntries::with_identities`].
+pub struct WithIdentities<'a, I, Ids: identities::Read> {
+    entries: I,
+    ids: &'a Ids,
+    seen: BTreeMap<Oid, Ids::Identity>,
+}
+
+impl<'a, I, Ids> WithIdentities<'a, I, Ids>
+where
+    Ids: identities::Read<Oid = Oid>,
+    Ids::Identity: Clone,
+{
+    fn resolve(
+        &mut self,
+        tracked: &Tracked,
+    ) -> Result<Option<Ids::Identity>, error::WithIdentities> {
+        let (urn, peer) = match tracked {
+            tracking::Tracked::Default { .. } => return Ok(None),
+            tracking::Tracked::Peer { urn, peer, .. } => (urn, peer),
+        };
+        let identity_err = |err: Ids::FindError| error::WithIdentities::Identity {
+            urn: urn.clone(),
+            peer: *peer,
+            source: err.into(),
+        };
+
+        let tip = match self.ids.rad_self(urn, peer).map_err(identity_err)? {
+            None => return Ok(None),
+            Some(tip) => tip,
+        };
+
+        // We may have seen this identity already
+        if let Some(identity) = self.seen.get(&tip) {
+            return Ok(Some(identity.clone()));
+        }
+
+        let identity = self.ids.find_identity(&tip).map_err(identity_err)?;
+        self.seen.insert(tip, identity.clone());
+        Ok(Some(identity))
+    }
+}
+
+impl<'a, I, Ids> Iterator for WithIdentities<'a, I, Ids>
+where
+    I: Iterator<Item = Result<Tracked, error::Tracked>>,
+    Ids: identities::Read<Oid = Oid>,
+    Ids::Identity: Clone,
+{
+    type Item = Result<(Tracked, Option<Ids::Identity>), error::WithIdentities>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|tracked| {
+            let tracked = tracked?;
+            let identity = self.resolve(&tracked)?;
+            Ok((tracked, identity))
+        })
+    }
+}
+
 /// Return all tracked entries, optionally filtering by an [`Urn`].
 pub fn tracked<'a, Db>(
     db: &'a Db,
@@ -628,6 +710,78 @@ where
     }
 }
 
+/// Return the tracking entries for a given `urn` and each of the `peers`, in
+/// the same order.
+///
+/// This is equivalent to calling [`get`] for each of the `peers`, but the
+/// references are looked up together using [`refdb::Read::find_references`].
+pub fn get_many<'a, Db>(
+    db: &Db,
+    urn: &'_ Urn<Oid>,
+    peers: &[Option<PeerId>],
+) -> Result<Vec<Option<Tracked>>, error::Get>
+where
+    Db: odb::Read<Oid = Oid> + refdb::Read<'a, Oid = Oid>,
+{
+    let names = peers
+        .iter()
+        .map(|peer| RefName::new(urn, *peer))
+        .collect::<Vec<_>>();
+    let references = db
+        .find_references(&names)
+        .map_err(|err| error::Get::FindRefs {
+            urn: urn.clone(),
+            source: err.into(),
+        })?;
+
+    names
+        .iter()
+        .zip(references)
+        .map(|(name, reference)| match reference {
+            None => Ok(None),
+            Some(reference) => {
+                let config =
+                    db.find_config(&reference.target)
+                        .map_err(|err| error::Get::FindObj {
+                            name: reference.name.into_owned(),
+                            target: reference.target,
+                            source: err.into(),
+                        })?;
+                Ok(config.map(|config| from_reference(name, config)))
+            },
+        })
+        .collect()
+}
+
+/// Return the [`Config`] that applies to `peer` for the given `urn`.
+///
+/// The precedence is:
+///
+///   1. the config of the peer's own entry, `refs/rad/remotes/<urn>/<peer>`, if
+///      it exists
+///   2. otherwise, the config of the default entry,
+///      `refs/rad/remotes/<urn>/default`, if it exists
+///   3. otherwise, `None`
+///
+/// Note that the default entry existing does not mean `peer` is tracked, see
+/// [`is_tracked`].
+pub fn effective_config<'a, Db>(
+    db: &Db,
+    urn: &'_ Urn<Oid>,
+    peer: PeerId,
+) -> Result<Option<Config>, error::Get>
+where
+    Db: odb::Read<Oid = Oid> + refdb::Read<'a, Oid = Oid>,
+{
+    Ok(get_many(db, urn, &[Some(peer), None])?
+        .into_iter()
+        .flatten()
+        .next()
+        .map(|tracked| match tracked {
+            Tracked::Default { config, .. } | Tracked::Peer { config, .. } => config,
+        }))
+}
+
 /// Check if a tracking entry for a given `urn` and `peer` exists.
 pub fn is_tracked<'a, Db>(
     db: &Db,
@@ -653,12 +807,27 @@ where
 /// This will return false if there are either:
 ///   * No tracking entries for the `urn`
 ///   * There is at least one tracked peer for the `urn`
+///
+/// The existence of the default entry is checked directly, so the tracking
+/// entries are only scanned if it exists, and the scan stops at the first
+/// tracked peer.
 pub fn default_only<'a, Db>(db: &'a Db, urn: &Urn<Oid>) -> Result<bool, error::DefaultOnly>
 where
     Db: refdb::Read<'a, Oid = Oid>,
 {
+    let default = RefName::new(urn, Remote::Default);
+    if db
+        .find_reference(&default)
+        .map_err(|err| error::DefaultOnly::FindRef {
+            name: default.clone().into_owned(),
+            source: err.into(),
+        })?
+        .is_none()
+    {
+        return Ok(false);
+    }
+
     let spec = remotes_refspec(Some(urn));
-    let mut seen_default = false;
     for reference in db
         .references(&spec)
         .map_err(|err| error::DefaultOnly::References {
@@ -666,22 +835,16 @@ where
             source: err.into(),
         })?
     {
-        match reference
-            .map_err(|err| error::DefaultOnly::Iter {
-                spec: spec.clone(),
-                source: err.into(),
-            })?
-            .name
-            .remote
-        {
-            Remote::Default => {
-                seen_default = true;
-            },
-            Remote::Peer(_) => return Ok(false),
+        let reference = reference.map_err(|err| error::DefaultOnly::Iter {
+            spec: spec.clone(),
+            source: err.into(),
+        })?;
+        if let Remote::Peer(_) = reference.name.remote {
+            return Ok(false);
         }
     }
 
-    Ok(seen_default)
+    Ok(true)
 }
 
 fn from_reference(name: &RefName<'_, Oid>, config: Config) -> Tracked {
@@ -4,6 +4,8 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 use git_ref_format::refspec;
+use link_crypto::PeerId;
+use link_identities::urn::Urn;
 use radicle_git_ext::Oid;
 use thiserror::Error;
 
@@ -141,6 +143,19 @@ pub enum Tracked {
     },
 }
 
+#[derive(Debug, Error)]
+pub enum WithIdentities {
+    #[error("failed to resolve the identity of `{peer}` for `{urn}`")]
+    Identity {
+        urn: Urn<Oid>,
+        peer: PeerId,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    Tracked(#[from] Tracked),
+}
+
 #[derive(Debug, Error)]
 pub enum TrackedPeers {
     #[error("failed to unpack a reference entry while getting tracked entries for `{spec}`")]
@@ -172,6 +187,12 @@ pub enum Get {
         #[source]
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+    #[error("failed while attempting to find the entries of `{urn}` during get")]
+    FindRefs {
+        urn: Urn<Oid>,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -186,6 +207,12 @@ pub enum IsTracked {
 
 #[derive(Debug, Error)]
 pub enum DefaultOnly {
+    #[error("failed while attempting to find `{name}` during default_only")]
+    FindRef {
+        name: RefName<'static, Oid>,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
     #[error("failed to unpack a reference entry while getting tracked entries for `{spec}`")]
     Iter {
         spec: refspec::PatternString,
@@ -20,12 +20,18 @@ pub fn base() -> RefString {
 /// ```ignore
 /// (default | <peer>)
 /// ```
+///
+/// This is the canonical representation of a tracked remote, and is
+/// re-exported by `librad::git::tracking`. Code which deals in `Option<PeerId>`
+/// -- where `None` denotes the default entry -- should convert using the `From`
+/// implementations rather than matching by hand.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Remote {
     Default,
     Peer(PeerId),
 }
 
+/// [`Remote::Default`] maps to `None`, and [`Remote::Peer`] to `Some`.
 impl From<Remote> for Option<PeerId> {
     fn from(remote: Remote) -> Self {
         match remote {
@@ -35,6 +41,7 @@ impl From<Remote> for Option<PeerId> {
     }
 }
 
+/// `None` maps to [`Remote::Default`], and `Some` to [`Remote::Peer`].
 impl From<Option<PeerId>> for Remote {
     fn from(peer: Option<PeerId>) -> Self {
         peer.map_or(Self::Default, Self::Peer)
@@ -95,7 +102,7 @@ impl<'a, R: ToOwned + Clone> RefName<'a, R> {
         P: Into<Option<PeerId>>,
     {
         Self {
-            remote: peer.into().map(Remote::Peer).unwrap_or_default(),
+            remote: Remote::from(peer.into()),
             urn: {
                 let urn = urn.into();
                 if urn.path.is_some() {
@@ -85,7 +85,10 @@ pub mod gen {
     }
 
     pub fn non_wildcard_pattern() -> impl Strategy<Value = Pattern<ObjectId>> {
-        prop::collection::btree_set(object_id(), 1..5).prop_map(Pattern::Objects)
+        prop_oneof![
+            prop::collection::btree_set(object_id(), 1..5).prop_map(Pattern::Objects),
+            prop::collection::btree_set(object_id(), 1..5).prop_map(Pattern::Except),
+        ]
     }
 
     pub fn filter() -> impl Strategy<Value = Filter<ObjectId>> {
@@ -168,6 +171,18 @@ pub mod filter {
                 prop_default_is_fallback(ty, id, cobs, filter)
             }
 
+            #[test]
+            fn except_pattern_inverts(
+                ty in gen::type_name(),
+                id in gen::object_id(),
+                other in gen::object_id(),
+                policy in gen::policy(),
+            )
+            {
+                prop_assume!(id != other);
+                prop_except_pattern_inverts(ty, id, other, policy)
+            }
+
             #[test]
             fn empty_cobs_is_allow(
                 ty in gen::type_name(),
@@ -229,6 +244,28 @@ pub mod filter {
             assert_eq!(policy, c.policy_for(&q))
         }
 
+        fn prop_except_pattern_inverts(
+            ty: TypeName,
+            id: ObjectId,
+            other: ObjectId,
+            policy: Policy,
+        ) {
+            let c = Config {
+                cobs: [(
+                    cobs::TypeName::Type(ty.clone()),
+                    Filter {
+                        policy,
+                        pattern: Pattern::Except(iter::once(id.clone()).collect()),
+                    },
+                )]
+                .into(),
+                ..Config::default()
+            };
+
+            assert_eq!(policy.inverse(), c.policy_for(&refs_cobs(&ty, &id)));
+            assert_eq!(policy, c.policy_for(&refs_cobs(&ty, &other)))
+        }
+
         fn prop_empty_cobs_is_allow(ty: TypeName, id: ObjectId) {
             let q = refs_cobs(&ty, &id);
             let c = Config {
@@ -4,4 +4,6 @@
 // Linking Exception. For full terms see the included LICENSE file.
 
 mod config;
+mod default_only;
 mod fusion;
+mod reference;
@@ -27,6 +27,22 @@ fn parse_commutes() {
     );
 }
 
+#[test]
+fn canonical_form_into() {
+    let mut config: Config<&str, &str> = Config::default();
+    config.cobs.insert(
+        TypeName::Type("discussion"),
+        Filter {
+            policy: Policy::Deny,
+            pattern: Pattern::Wildcard,
+        },
+    );
+
+    let mut streamed = vec![];
+    config.canonical_form_into(&mut streamed).unwrap();
+    assert_eq!(streamed, config.canonical_form().unwrap());
+}
+
 #[test]
 fn can_insert() {
     let mut config: Config<&str, &str> = Config::default();
@@ -199,3 +215,249 @@ fn can_remove_objects() {
         }
     )
 }
+
+#[test]
+fn except_roundtrip() {
+    let id = git::config::ObjectId(cob::ObjectId::from(git2::Oid::zero()));
+    let config = git::config::Config {
+        data: true,
+        cobs: [(
+            TypeName::Type("discussion".parse().map(git::config::TypeName).unwrap()),
+            Filter {
+                policy: Policy::Allow,
+                pattern: Pattern::Except(Some(id.clone()).into_iter().collect()),
+            },
+        )]
+        .into(),
+    };
+
+    let canonical = String::from_utf8(config.canonical_form().unwrap()).unwrap();
+    assert_eq!(
+        canonical,
+        format!(
+            r#"{{"cobs":{{"discussion":{{"pattern":{{"except":["{}"]}},"policy":"allow"}}}},"data":true}}"#,
+            id.0
+        )
+    );
+    assert_eq!(
+        git::config::Config::try_from(canonical.as_str()).unwrap(),
+        config
+    );
+}
+
+#[test]
+fn except_objects() {
+    let mut config: Config<&str, u8> = Config {
+        data: true,
+        cobs: [(
+            TypeName::Type("discussion"),
+            Filter {
+                policy: Policy::Allow,
+                pattern: Pattern::Except(vec![1, 2].into_iter().collect()),
+            },
+        )]
+        .into(),
+    };
+    config
+        .cobs
+        .entry(TypeName::Type("discussion"))
+        .insert_objects(vec![1])
+        .remove_objects(vec![3]);
+
+    assert_eq!(
+        config,
+        Config {
+            data: true,
+            cobs: [(
+                TypeName::Type("discussion"),
+                Filter {
+                    policy: Policy::Allow,
+                    pattern: Pattern::Except(vec![2, 3].into_iter().collect()),
+                }
+            )]
+            .into()
+        }
+    )
+}
+
+mod intersect {
+    use super::*;
+
+    fn objects(ids: &[&'static str]) -> Pattern<&'static str> {
+        Pattern::Objects(ids.iter().copied().collect())
+    }
+
+    #[test]
+    fn allow_all_deny_all() {
+        let allow: Cobs<&str, &str> = Cobs::allow_all();
+        let deny = Cobs::deny_all();
+        assert_eq!(allow.intersect(&deny), Cobs::deny_all());
+        assert_eq!(deny.intersect(&allow), Cobs::deny_all());
+        assert_eq!(allow.intersect(&allow), Cobs::allow_all());
+    }
+
+    #[test]
+    fn explicit_objects() {
+        let mut a: Cobs<&str, &str> = Cobs::empty();
+        a.insert(
+            TypeName::Type("discussion"),
+            Filter {
+                policy: Policy::Allow,
+                pattern: objects(&["1", "2", "3"]),
+            },
+        );
+        let mut b = Cobs::empty();
+        b.insert(
+            TypeName::Type("discussion"),
+            Filter {
+                policy: Policy::Allow,
+                pattern: objects(&["2", "3", "4"]),
+            },
+        );
+
+        assert_eq!(
+            a.intersect(&b),
+            [(
+                TypeName::Type("discussion"),
+                Filter {
+                    policy: Policy::Allow,
+                    pattern: objects(&["2", "3"]),
+                }
+            )]
+            .into()
+        );
+    }
+
+    #[test]
+    fn denied_objects_are_removed() {
+        let allowed = Filter {
+            policy: Policy::Allow,
+            pattern: objects(&["1", "2"]),
+        };
+        let denied = Filter {
+            policy: Policy::Deny,
+            pattern: objects(&["2", "3"]),
+        };
+
+        let expected = Filter {
+            policy: Policy::Allow,
+            pattern: objects(&["1"]),
+        };
+        assert_eq!(allowed.intersect(&denied), expected);
+        assert_eq!(denied.intersect(&allowed), expected);
+        assert_eq!(
+            denied.intersect(&Filter {
+                policy: Policy::Deny,
+                pattern: objects(&["4"]),
+            }),
+            Filter {
+                policy: Policy::Deny,
+                pattern: objects(&["2", "3", "4"]),
+            }
+        );
+    }
+
+    #[test]
+    fn except_is_normalised() {
+        let except = Filter {
+            policy: Policy::Allow,
+            pattern: Pattern::Except(["1", "2"].into_iter().collect()),
+        };
+
+        assert_eq!(
+            except.intersect(&Filter {
+                policy: Policy::Allow,
+                pattern: objects(&["2", "3"]),
+            }),
+            Filter {
+                policy: Policy::Allow,
+                pattern: objects(&["3"]),
+            }
+        );
+        assert_eq!(
+            except.intersect(&Filter {
+                policy: Policy::Deny,
+                pattern: objects(&["3"]),
+            }),
+            Filter {
+                policy: Policy::Deny,
+                pattern: objects(&["1", "2", "3"]),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_type_falls_back_to_wildcard() {
+        let mut a: Cobs<&str, &str> = Cobs::deny_all();
+        a.insert(
+            TypeName::Type("discussion"),
+            Filter {
+                policy: Policy::Allow,
+                pattern: Pattern::Wildcard,
+            },
+        );
+        let b = Cobs::allow_all();
+
+        assert_eq!(a.intersect(&b), a);
+        assert_eq!(b.intersect(&a), a);
+    }
+}
+
+mod allowed_refs {
+    use git_ref_format::{refname, RefString};
+
+    use super::*;
+
+    fn config(
+        data: bool,
+        cobs: Cobs<git::config::TypeName, git::config::ObjectId>,
+    ) -> git::config::Config {
+        Config { data, cobs }
+    }
+
+    #[test]
+    fn data_refs_follow_data_flag() {
+        let allow = config(true, Cobs::allow_all());
+        let deny = config(false, Cobs::allow_all());
+        for name in [
+            refname!("refs/heads/main"),
+            refname!("refs/tags/v1"),
+            refname!("refs/notes/n"),
+        ] {
+            assert!(allow.allowed_refs().allows(&name));
+            assert!(!deny.allowed_refs().allows(&name));
+        }
+    }
+
+    #[test]
+    fn rad_refs_are_always_allowed() {
+        let config = config(false, Cobs::deny_all());
+        for name in [
+            refname!("refs/rad/id"),
+            refname!("refs/rad/self"),
+            refname!("refs/rad/signed_refs"),
+        ] {
+            assert!(config.allowed_refs().allows(&name))
+        }
+    }
+
+    #[test]
+    fn cob_refs_follow_cobs_filter() {
+        let name = RefString::try_from(format!(
+            "refs/cobs/xyz.radicle.issue/{}",
+            cob::ObjectId::from(git2::Oid::zero())
+        ))
+        .unwrap();
+        assert!(config(false, Cobs::allow_all())
+            .allowed_refs()
+            .allows(&name));
+        assert!(!config(true, Cobs::deny_all()).allowed_refs().allows(&name));
+    }
+
+    #[test]
+    fn unqualified_and_unknown_refs_are_denied() {
+        let config = config(true, Cobs::allow_all());
+        assert!(!config.allowed_refs().allows(&refname!("main")));
+        assert!(!config.allowed_refs().allows(&refname!("refs/unknown/main")));
+    }
+}
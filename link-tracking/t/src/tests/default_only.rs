@@ -0,0 +1,93 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use std::{cell::Cell, convert::Infallible};
+
+use git_ref_format::refspec;
+use link_crypto::{PeerId, SecretKey};
+use link_identities::urn::Urn;
+use link_tracking::git::{
+    refdb::{self, Ref},
+    tracking::{default_only, RefName, Remote},
+};
+use radicle_git_ext::Oid;
+
+/// An in-memory refdb of tracking entries for a single `urn`, which counts how
+/// many references were yielded while scanning.
+struct Remotes {
+    urn: Urn<Oid>,
+    remotes: Vec<Remote>,
+    scanned: Cell<usize>,
+}
+
+impl Remotes {
+    fn new(remotes: Vec<Remote>) -> Self {
+        Self {
+            urn: Urn::new(git2::Oid::zero().into()),
+            remotes,
+            scanned: Cell::new(0),
+        }
+    }
+
+    fn target() -> Oid {
+        git2::Oid::zero().into()
+    }
+}
+
+impl<'a> refdb::Read<'a> for Remotes {
+    type FindError = Infallible;
+    type ReferencesError = Infallible;
+    type IterError = Infallible;
+
+    type Oid = Oid;
+    type References = Box<dyn Iterator<Item = Result<Ref<'a, Oid>, Infallible>> + 'a>;
+
+    fn find_reference(&self, name: &RefName<'_, Oid>) -> Result<Option<Ref<Oid>>, Infallible> {
+        Ok(
+            (*name.urn == self.urn && self.remotes.contains(&name.remote)).then(|| Ref {
+                name: name.clone().into_owned(),
+                target: Self::target(),
+            }),
+        )
+    }
+
+    fn references(
+        &'a self,
+        _: impl AsRef<refspec::PatternStr>,
+    ) -> Result<Self::References, Infallible> {
+        Ok(Box::new(self.remotes.iter().map(move |remote| {
+            self.scanned.set(self.scanned.get() + 1);
+            Ok(Ref {
+                name: RefName::new(&self.urn, *remote),
+                target: Self::target(),
+            })
+        })))
+    }
+}
+
+fn peers(n: usize) -> impl Iterator<Item = Remote> {
+    (0..n).map(|_| Remote::Peer(PeerId::from(SecretKey::new())))
+}
+
+#[test]
+fn default_only_without_default_does_not_scan() {
+    let db = Remotes::new(peers(100).collect());
+    assert!(!default_only(&db, &db.urn).unwrap());
+    assert_eq!(db.scanned.get(), 0);
+}
+
+#[test]
+fn default_only_short_circuits_on_peer() {
+    let db = Remotes::new(std::iter::once(Remote::Default).chain(peers(100)).collect());
+    assert!(!default_only(&db, &db.urn).unwrap());
+    assert_eq!(db.scanned.get(), 2);
+}
+
+#[test]
+fn default_only_with_only_default() {
+    let db = Remotes::new(vec![Remote::Default]);
+    assert!(default_only(&db, &db.urn).unwrap());
+    assert_eq!(db.scanned.get(), 1);
+}
@@ -0,0 +1,31 @@
+// Copyright © 2022 The Radicle Link Contributors
+//
+// This file is part of radicle-link, distributed under the GPLv3 with Radicle
+// Linking Exception. For full terms see the included LICENSE file.
+
+use link_crypto::{PeerId, SecretKey};
+use link_tracking::git::tracking::Remote;
+
+#[test]
+fn default_roundtrip() {
+    let peer: Option<PeerId> = Remote::Default.into();
+    assert_eq!(peer, None);
+    assert_eq!(Remote::from(peer), Remote::Default);
+    assert_eq!(Remote::default(), Remote::Default);
+}
+
+#[test]
+fn peer_roundtrip() {
+    let peer = PeerId::from(SecretKey::new());
+    let remote = Remote::from(Some(peer));
+    assert_eq!(remote, Remote::Peer(peer));
+    assert_eq!(Option::<PeerId>::from(remote), Some(peer));
+}
+
+#[test]
+fn display_roundtrip() {
+    let peer = PeerId::from(SecretKey::new());
+    for remote in [Remote::Default, Remote::Peer(peer)] {
+        assert_eq!(remote.to_string().parse::<Remote>().unwrap(), remote);
+    }
+}
@@ -32,6 +32,7 @@ use librad::{
             rpc::client::{self, Client},
         },
         quic,
+        replication,
         Network,
     },
     paths::Paths,
@@ -70,6 +71,10 @@ impl Deref for TestClient {
 
 impl TestClient {
     pub async fn init() -> anyhow::Result<TestClient> {
+        Self::with_replication(Default::default()).await
+    }
+
+    pub async fn with_replication(replication: replication::Config) -> anyhow::Result<TestClient> {
         let spawner = Spawner::from_current()
             .map(Arc::new)
             .ok_or_else(|| anyhow::anyhow!("failed to get Spawner for TestClient"))?;
@@ -81,9 +86,10 @@ impl TestClient {
         let config = client::Config {
             signer: key,
             paths,
-            replication: Default::default(),
+            replication,
             user_storage: Default::default(),
             network,
+            connections: Default::default(),
         };
         Ok(TestClient {
             client: Client::new(config, spawner, endpoint)?,
@@ -168,7 +174,7 @@ impl LocalAddr for RunningTestPeer {
     }
 }
 
-async fn boot<I, J>(seeds: I) -> anyhow::Result<BoundTestPeer>
+async fn boot<I, J>(seeds: I, rate_limits: protocol::Quota) -> anyhow::Result<BoundTestPeer>
 where
     I: IntoIterator<Item = (PeerId, J)>,
     J: IntoIterator<Item = SocketAddr>,
@@ -188,8 +194,9 @@ where
         membership: Default::default(),
         network: Network::Custom(b"localtestnet".as_ref().into()),
         replication: Default::default(),
-        rate_limits: Default::default(),
+        rate_limits,
         request_pull: Default::default(),
+        caches: Default::default(),
     };
     let disco = seeds.into_iter().collect::<discovery::Static>();
     let peer = Peer::new(peer::Config {
@@ -290,20 +297,23 @@ pub struct Config {
     pub bootstrap: Bootstrap,
 }
 
-async fn bootstrap(config: Config) -> anyhow::Result<Vec<BoundTestPeer>> {
+async fn bootstrap(
+    config: Config,
+    rate_limits: protocol::Quota,
+) -> anyhow::Result<Vec<BoundTestPeer>> {
     let num_peers = config.num_peers.get();
     let mut peers = Vec::with_capacity(num_peers);
 
     match config.bootstrap {
         Bootstrap::None => {
             for _ in 0..num_peers {
-                let peer = boot::<Option<_>, Option<_>>(None).await?;
+                let peer = boot::<Option<_>, Option<_>>(None, rate_limits.clone()).await?;
                 peers.push(peer);
             }
         },
 
         Bootstrap::First => {
-            let bootstrap_node = boot::<Option<_>, Option<_>>(None).await?;
+            let bootstrap_node = boot::<Option<_>, Option<_>>(None, rate_limits.clone()).await?;
             let bootstrap = Some((
                 bootstrap_node.bound.peer_id(),
                 bootstrap_node.listen_addrs(),
@@ -311,7 +321,7 @@ async fn bootstrap(config: Config) -> anyhow::Result<Vec<BoundTestPeer>> {
             peers.push(bootstrap_node);
 
             for _ in 1..num_peers {
-                let peer = boot(bootstrap.clone()).await?;
+                let peer = boot(bootstrap.clone(), rate_limits.clone()).await?;
                 peers.push(peer);
             }
         },
@@ -319,7 +329,7 @@ async fn bootstrap(config: Config) -> anyhow::Result<Vec<BoundTestPeer>> {
         Bootstrap::Prev => {
             let mut bootstrap: Option<(PeerId, Vec<SocketAddr>)> = None;
             for _ in 0..num_peers {
-                let peer = boot(bootstrap.take()).await?;
+                let peer = boot(bootstrap.take(), rate_limits.clone()).await?;
                 bootstrap = Some((peer.bound.peer_id(), peer.bound.listen_addrs()));
                 peers.push(peer);
             }
@@ -327,7 +337,7 @@ async fn bootstrap(config: Config) -> anyhow::Result<Vec<BoundTestPeer>> {
 
         Bootstrap::Fixed(bootstrap) => {
             for _ in 0..num_peers {
-                let peer = boot(bootstrap.clone()).await?;
+                let peer = boot(bootstrap.clone(), rate_limits.clone()).await?;
                 peers.push(peer);
             }
         },
@@ -373,12 +383,17 @@ impl Drop for Testnet {
 }
 
 pub fn run(config: Config) -> anyhow::Result<Testnet> {
+    run_with_limits(config, protocol::Quota::default())
+}
+
+/// Like [`run`], but every peer is configured with `rate_limits`.
+pub fn run_with_limits(config: Config, rate_limits: protocol::Quota) -> anyhow::Result<Testnet> {
     let rt = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()?;
 
     let min_connected = config.min_connected;
-    let bootstrapped = rt.block_on(bootstrap(config))?;
+    let bootstrapped = rt.block_on(bootstrap(config, rate_limits))?;
     let num_peers = bootstrapped.len();
 
     let mut sig = Vec::with_capacity(num_peers);